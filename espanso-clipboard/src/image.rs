@@ -0,0 +1,134 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+  Png,
+  Jpeg,
+  Gif,
+  Bmp,
+  Webp,
+  Tiff,
+  Svg,
+}
+
+impl ImageFormat {
+  pub fn mime_type(&self) -> &'static str {
+    match self {
+      ImageFormat::Png => "image/png",
+      ImageFormat::Jpeg => "image/jpeg",
+      ImageFormat::Gif => "image/gif",
+      ImageFormat::Bmp => "image/bmp",
+      ImageFormat::Webp => "image/webp",
+      ImageFormat::Tiff => "image/tiff",
+      ImageFormat::Svg => "image/svg+xml",
+    }
+  }
+}
+
+// The format is detected from the file signature, as the extension might be
+// missing or wrong. The extension is only used for text-based formats (SVG)
+// and as a last resort. PNG is assumed when the format can't be determined,
+// which is what the backends use when the format is not preserved.
+pub fn detect_image_format(image_path: &Path, data: &[u8]) -> ImageFormat {
+  if let Some(format) = detect_format_from_signature(data) {
+    return format;
+  }
+
+  let extension = image_path
+    .extension()
+    .unwrap_or_default()
+    .to_string_lossy()
+    .to_ascii_lowercase();
+  match extension.as_str() {
+    "svg" => ImageFormat::Svg,
+    "jpg" | "jpeg" => ImageFormat::Jpeg,
+    "gif" => ImageFormat::Gif,
+    "bmp" => ImageFormat::Bmp,
+    "webp" => ImageFormat::Webp,
+    "tif" | "tiff" => ImageFormat::Tiff,
+    _ => ImageFormat::Png,
+  }
+}
+
+fn detect_format_from_signature(data: &[u8]) -> Option<ImageFormat> {
+  if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+    Some(ImageFormat::Png)
+  } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+    Some(ImageFormat::Jpeg)
+  } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+    Some(ImageFormat::Gif)
+  } else if data.starts_with(b"BM") {
+    Some(ImageFormat::Bmp)
+  } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+    Some(ImageFormat::Webp)
+  } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+    Some(ImageFormat::Tiff)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn detect_image_format_uses_signature() {
+    let path = Path::new("image.png");
+    assert_eq!(
+      detect_image_format(path, b"\x89PNG\r\n\x1a\n1234"),
+      ImageFormat::Png
+    );
+    assert_eq!(
+      detect_image_format(path, &[0xFF, 0xD8, 0xFF, 0xE0]),
+      ImageFormat::Jpeg
+    );
+    assert_eq!(detect_image_format(path, b"GIF89a1234"), ImageFormat::Gif);
+    assert_eq!(
+      detect_image_format(path, b"RIFF\0\0\0\0WEBPVP8 "),
+      ImageFormat::Webp
+    );
+    assert_eq!(detect_image_format(path, b"II*\0"), ImageFormat::Tiff);
+  }
+
+  #[test]
+  fn detect_image_format_falls_back_to_extension() {
+    assert_eq!(
+      detect_image_format(Path::new("logo.SVG"), b"<svg></svg>"),
+      ImageFormat::Svg
+    );
+    assert_eq!(
+      detect_image_format(Path::new("photo.jpeg"), b""),
+      ImageFormat::Jpeg
+    );
+    assert_eq!(
+      detect_image_format(Path::new("unknown"), b"data"),
+      ImageFormat::Png
+    );
+  }
+
+  #[test]
+  fn mime_type_matches_format() {
+    assert_eq!(ImageFormat::Png.mime_type(), "image/png");
+    assert_eq!(ImageFormat::Svg.mime_type(), "image/svg+xml");
+  }
+}
@@ -20,7 +20,9 @@
 use std::path::Path;
 
 use anyhow::Result;
-use log::info;
+use log::{info, warn};
+
+pub mod image;
 
 #[cfg(target_os = "windows")]
 mod win32;
@@ -29,10 +31,15 @@ mod win32;
 #[cfg(not(feature = "wayland"))]
 mod x11;
 
+// The Wayland backend is also available on X11 builds, and is selected
+// at runtime when running in a Wayland session
 #[cfg(target_os = "linux")]
-#[cfg(feature = "wayland")]
 mod wayland;
 
+#[cfg(target_os = "linux")]
+#[cfg(not(feature = "wayland"))]
+mod session;
+
 #[cfg(target_os = "macos")]
 mod cocoa;
 
@@ -40,6 +47,12 @@ pub trait Clipboard {
   fn get_text(&self) -> Option<String>;
   fn set_text(&self, text: &str) -> Result<()>;
   fn set_image(&self, image_path: &Path) -> Result<()>;
+
+  // Set the image keeping its original encoding (and so its transparency),
+  // on the platforms that support it. Otherwise, it behaves as `set_image`
+  fn set_image_preserving_format(&self, image_path: &Path) -> Result<()> {
+    self.set_image(image_path)
+  }
   fn set_html(&self, html: &str, fallback_text: Option<&str>) -> Result<()>;
 }
 
@@ -73,7 +86,25 @@ pub fn get_clipboard(_: ClipboardOptions) -> Result<Box<dyn Clipboard>> {
 
 #[cfg(target_os = "linux")]
 #[cfg(not(feature = "wayland"))]
-pub fn get_clipboard(_: ClipboardOptions) -> Result<Box<dyn Clipboard>> {
+pub fn get_clipboard(options: ClipboardOptions) -> Result<Box<dyn Clipboard>> {
+  if session::detect_session_type() == session::SessionType::Wayland {
+    // Through XWayland, the X11 clipboard is not always synchronized with
+    // the Wayland one, so we prefer the native tools if available
+    match wayland::fallback::WaylandFallbackClipboard::new(options) {
+      Ok(clipboard) => {
+        info!("detected Wayland session, using WaylandFallbackClipboard");
+        return Ok(Box::new(clipboard));
+      }
+      Err(err) => {
+        warn!(
+          "detected Wayland session, but unable to initialize WaylandFallbackClipboard: {}",
+          err
+        );
+        warn!("falling back to X11NativeClipboard, clipboard operations might not work");
+      }
+    }
+  }
+
   info!("using X11NativeClipboard");
   Ok(Box::new(x11::native::X11NativeClipboard::new()?))
 }
@@ -0,0 +1,88 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SessionType {
+  X11,
+  Wayland,
+}
+
+pub(crate) fn detect_session_type() -> SessionType {
+  session_type_from_env(
+    std::env::var("XDG_SESSION_TYPE").ok().as_deref(),
+    std::env::var("WAYLAND_DISPLAY").ok().as_deref(),
+  )
+}
+
+// XDG_SESSION_TYPE is the most reliable source, but it's not set by every
+// login manager, so we also check whether a Wayland display is available.
+fn session_type_from_env(
+  xdg_session_type: Option<&str>,
+  wayland_display: Option<&str>,
+) -> SessionType {
+  let xdg_session_type = xdg_session_type.map(|value| value.trim().to_lowercase());
+  match xdg_session_type.as_deref() {
+    Some("wayland") => SessionType::Wayland,
+    Some("x11") => SessionType::X11,
+    _ => match wayland_display {
+      Some(display) if !display.trim().is_empty() => SessionType::Wayland,
+      _ => SessionType::X11,
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn xdg_session_type_is_preferred() {
+    assert_eq!(
+      session_type_from_env(Some("wayland"), None),
+      SessionType::Wayland
+    );
+    assert_eq!(
+      session_type_from_env(Some("x11"), Some("wayland-0")),
+      SessionType::X11
+    );
+    assert_eq!(
+      session_type_from_env(Some("Wayland"), None),
+      SessionType::Wayland
+    );
+  }
+
+  #[test]
+  fn wayland_display_is_used_as_fallback() {
+    assert_eq!(
+      session_type_from_env(Some("tty"), Some("wayland-0")),
+      SessionType::Wayland
+    );
+    assert_eq!(
+      session_type_from_env(None, Some("wayland-1")),
+      SessionType::Wayland
+    );
+  }
+
+  #[test]
+  fn x11_is_the_default() {
+    assert_eq!(session_type_from_env(None, None), SessionType::X11);
+    assert_eq!(session_type_from_env(None, Some("")), SessionType::X11);
+    assert_eq!(session_type_from_env(Some("tty"), None), SessionType::X11);
+  }
+}
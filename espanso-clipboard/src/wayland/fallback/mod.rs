@@ -24,7 +24,7 @@ use std::{
   process::Stdio,
 };
 
-use crate::{Clipboard, ClipboardOptions};
+use crate::{image::detect_image_format, Clipboard, ClipboardOptions};
 use anyhow::Result;
 use log::{error, warn};
 use std::process::Command;
@@ -131,7 +131,27 @@ impl Clipboard for WaylandFallbackClipboard {
     file.read_to_end(&mut data)?;
 
     self.invoke_command_with_timeout(
-      &mut Command::new("wl-copy").arg("--type").arg("image/png"),
+      Command::new("wl-copy").arg("--type").arg("image/png"),
+      &data,
+      "wl-copy",
+    )
+  }
+
+  fn set_image_preserving_format(&self, image_path: &std::path::Path) -> anyhow::Result<()> {
+    if !image_path.exists() || !image_path.is_file() {
+      return Err(WaylandFallbackClipboardError::ImageNotFound(image_path.to_path_buf()).into());
+    }
+
+    // Load the image data
+    let mut file = std::fs::File::open(image_path)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+
+    let format = detect_image_format(image_path, &data);
+    self.invoke_command_with_timeout(
+      Command::new("wl-copy")
+        .arg("--type")
+        .arg(format.mime_type()),
       &data,
       "wl-copy",
     )
@@ -139,7 +159,7 @@ impl Clipboard for WaylandFallbackClipboard {
 
   fn set_html(&self, html: &str, _fallback_text: Option<&str>) -> anyhow::Result<()> {
     self.invoke_command_with_timeout(
-      &mut Command::new("wl-copy").arg("--type").arg("text/html"),
+      Command::new("wl-copy").arg("--type").arg("text/html"),
       html.as_bytes(),
       "wl-copy",
     )
@@ -25,4 +25,9 @@ extern "C" {
   pub fn clipboard_x11_set_text(text: *const c_char) -> i32;
   pub fn clipboard_x11_set_html(html: *const c_char, fallback_text: *const c_char) -> i32;
   pub fn clipboard_x11_set_image(buffer: *const u8, buffer_size: i32) -> i32;
+  pub fn clipboard_x11_set_image_with_format(
+    buffer: *const u8,
+    buffer_size: i32,
+    mime_type: *const c_char,
+  ) -> i32;
 }
@@ -23,7 +23,7 @@ use std::{
   path::PathBuf,
 };
 
-use crate::Clipboard;
+use crate::{image::detect_image_format, Clipboard};
 use anyhow::Result;
 use std::os::raw::c_char;
 use thiserror::Error;
@@ -80,6 +80,29 @@ impl Clipboard for X11NativeClipboard {
     }
   }
 
+  fn set_image_preserving_format(&self, image_path: &std::path::Path) -> anyhow::Result<()> {
+    if !image_path.exists() || !image_path.is_file() {
+      return Err(X11NativeClipboardError::ImageNotFound(image_path.to_path_buf()).into());
+    }
+
+    // Load the image data
+    let mut file = std::fs::File::open(image_path)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+
+    let format = detect_image_format(image_path, &data);
+    let mime_type = CString::new(format.mime_type())?;
+    let native_result = unsafe {
+      ffi::clipboard_x11_set_image_with_format(data.as_ptr(), data.len() as i32, mime_type.as_ptr())
+    };
+
+    if native_result > 0 {
+      Ok(())
+    } else {
+      Err(X11NativeClipboardError::SetOperationFailed().into())
+    }
+  }
+
   fn set_html(&self, html: &str, fallback_text: Option<&str>) -> anyhow::Result<()> {
     let html_string = CString::new(html)?;
     let fallback_string = CString::new(fallback_text.unwrap_or_default())?;
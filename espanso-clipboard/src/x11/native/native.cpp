@@ -73,4 +73,19 @@ int32_t clipboard_x11_set_image(char * buffer, int32_t size) {
   }
 
   return 1;
-}
\ No newline at end of file
+}
+
+int32_t clipboard_x11_set_image_with_format(char * buffer, int32_t size, char * mime_type) {
+  clip::format format = clip::register_format(mime_type);
+
+  clip::lock l;
+  if (!l.clear()) {
+    return 0;
+  }
+
+  if (!l.set_data(format, buffer, size)) {
+    return 0;
+  }
+
+  return 1;
+}
@@ -27,5 +27,6 @@ extern "C" int32_t clipboard_x11_get_text(char * buffer, int32_t buffer_size);
 extern "C" int32_t clipboard_x11_set_text(char * text);
 extern "C" int32_t clipboard_x11_set_html(char * html, char * fallback_text);
 extern "C" int32_t clipboard_x11_set_image(char * buffer, int32_t buffer_size);
+extern "C" int32_t clipboard_x11_set_image_with_format(char * buffer, int32_t buffer_size, char * mime_type);
 
 #endif //ESPANSO_X11_CLIPBOARD_H
\ No newline at end of file
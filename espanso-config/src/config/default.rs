@@ -18,6 +18,17 @@
  */
 
 pub(crate) const DEFAULT_CLIPBOARD_THRESHOLD: usize = 100;
+pub(crate) const DEFAULT_KEYS_CLIPBOARD_FALLBACK_THRESHOLD: usize = 2000;
 pub(crate) const DEFAULT_PRE_PASTE_DELAY: usize = 100;
 pub(crate) const DEFAULT_SHORTCUT_EVENT_DELAY: usize = 10;
 pub(crate) const DEFAULT_RESTORE_CLIPBOARD_DELAY: usize = 300;
+pub(crate) const DEFAULT_PASTE_FOCUS_RETRIES: usize = 1;
+pub(crate) const DEFAULT_MAX_GROUP_MATCHES: usize = 100_000;
+pub(crate) const DEFAULT_MAX_TOTAL_MATCHES: usize = 1_000_000;
+pub(crate) const DEFAULT_MAX_GROUP_FILE_SIZE: u64 = 64 * 1024 * 1024;
+pub(crate) const DEFAULT_REMOTE_IMPORTS_TTL: u64 = 24 * 60 * 60;
+pub(crate) const DEFAULT_REGEX_MAX_LOOKBACK: usize = 30;
+pub(crate) const DEFAULT_MAX_BACKSPACES_PER_EXPANSION: usize = 1000;
+pub(crate) const DEFAULT_SHELL_TIMEOUT: usize = 0;
+pub(crate) const DEFAULT_WATCHER_QUIET_PERIOD: u64 = 1000;
+pub(crate) const DEFAULT_INJECT_CHUNK_DELAY: usize = 50;
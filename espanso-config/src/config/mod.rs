@@ -20,7 +20,10 @@
 use anyhow::Result;
 use indoc::formatdoc;
 use std::sync::Arc;
-use std::{collections::HashSet, path::Path};
+use std::{
+  collections::{BTreeMap, HashSet},
+  path::{Path, PathBuf},
+};
 use thiserror::Error;
 
 pub(crate) mod default;
@@ -28,7 +31,7 @@ mod parse;
 mod path;
 mod resolve;
 pub(crate) mod store;
-mod util;
+pub(crate) mod util;
 
 #[cfg(test)]
 use mockall::{automock, predicate::*};
@@ -60,6 +63,15 @@ pub trait Config: Send + Sync {
   // slow for long strings.
   fn clipboard_threshold(&self) -> usize;
 
+  // If true, matches with `force_mode: keys` are injected with the clipboard
+  // anyway when their text is longer than `keys_clipboard_fallback_threshold`,
+  // as injecting long texts with keys is slow and error-prone. Disabled by
+  // default, as it overrides the mode explicitly chosen for the match.
+  fn keys_clipboard_fallback(&self) -> bool;
+
+  // Number of chars after which the `keys_clipboard_fallback` applies.
+  fn keys_clipboard_fallback_threshold(&self) -> usize;
+
   // Delay (in ms) that espanso should wait to trigger the paste shortcut
   // after copying the content in the clipboard. This is needed because
   // if we trigger a "paste" shortcut before the content is actually
@@ -86,10 +98,19 @@ pub trait Config: Send + Sync {
   // Defines the key that disables/enables espanso when double pressed
   fn toggle_key(&self) -> Option<ToggleKey>;
 
+  // Trigger that disables/enables espanso when typed. Unlike regular
+  // triggers, it's recognized even while espanso is disabled.
+  fn toggle_trigger(&self) -> Option<String>;
+
   // If true, instructs the daemon process to restart the worker (and refresh
   // the configuration) after a configuration file change is detected on disk.
   fn auto_restart(&self) -> bool;
 
+  // Number of milliseconds without further changes the daemon waits for
+  // before restarting the worker, so that a burst of file changes (for
+  // example, after a git checkout) results in a single restart.
+  fn watcher_quiet_period(&self) -> u64;
+
   // If true, espanso will attempt to preserve the previous clipboard content
   // after an expansion has taken place (when using the Clipboard backend).
   fn preserve_clipboard(&self) -> bool;
@@ -100,6 +121,11 @@ pub trait Config: Send + Sync {
   // the expansion content.
   fn restore_clipboard_delay(&self) -> usize;
 
+  // How many times espanso should wait for the original application to regain
+  // focus before pasting, if the focus changed during a clipboard injection.
+  // When the retries are exhausted, the injection is aborted.
+  fn paste_focus_retries(&self) -> usize;
+
   // Number of milliseconds between text injection events. Increase if the target
   // application is missing some characters.
   fn inject_delay(&self) -> Option<usize>;
@@ -108,6 +134,15 @@ pub trait Config: Send + Sync {
   // application is missing some key events.
   fn key_delay(&self) -> Option<usize>;
 
+  // If greater than 0, the replacement text (and the other key sequences) are
+  // injected in chunks of this many chars, waiting `inject_chunk_delay`
+  // milliseconds between them. Useful for applications (such as some
+  // Electron ones) dropping characters when typing fast.
+  fn inject_chunk_size(&self) -> usize;
+
+  // Number of milliseconds to wait between each chunk of keys
+  fn inject_chunk_delay(&self) -> usize;
+
   // Extra delay to apply when injecting modifiers under the EVDEV backend.
   // This is useful on Wayland if espanso is injecting seemingly random
   // cased letters, for example "Hi theRE1" instead of "Hi there!".
@@ -123,6 +158,96 @@ pub trait Config: Send + Sync {
   // are typed.
   fn backspace_limit(&self) -> usize;
 
+  // Maximum number of backspaces espanso can inject in a single expansion
+  // (including undo), as a safety net against runaway deletions.
+  // Key sequences exceeding the limit are discarded, and undo is refused
+  // when reverting the expansion would exceed it.
+  // Not to be confused with `backspace_limit`, which only affects matching.
+  fn max_backspaces_per_expansion(&self) -> usize;
+
+  // Number of milliseconds to wait after injecting the backspaces that delete
+  // the trigger, before injecting the replacement. Useful for remote-desktop
+  // setups dropping keys sent back-to-back. Zero disables the delay.
+  fn post_backspace_delay(&self) -> usize;
+
+  // Number of milliseconds a shell or script variable is allowed to run
+  // before being killed. Variables that time out expand to an empty string.
+  // Can be overridden on each variable with the `timeout` param, and 0
+  // (the default) disables the timeout entirely.
+  fn shell_timeout(&self) -> usize;
+
+  // Number of seconds a form is kept open before being automatically
+  // cancelled, aborting the expansion. 0 keeps the form open indefinitely.
+  fn form_timeout(&self) -> u64;
+
+  // Maximum number of times the output of a match marked with `propagate`
+  // can trigger another match. This is needed to prevent infinite loops
+  // when matches expand into each other.
+  fn max_propagation_depth(&self) -> usize;
+
+  // If true, log the global variables that are never referenced by
+  // any match once the configuration is loaded.
+  fn lint_unused_global_vars(&self) -> bool;
+
+  // If true, warn about triggers defined by more than one match
+  // in the same configuration, as they shadow each other.
+  fn lint_duplicate_triggers(&self) -> bool;
+
+  // If true, a diagnostic file describing the failure (active window,
+  // injection backend and event) is written to the config directory
+  // whenever a key injection fails. Nothing is sent over the network.
+  fn injection_failure_dumps(&self) -> bool;
+
+  // If true, the text typed by the key injector is included in the
+  // injection logs (enabled with -vv). Disabled by default, as the
+  // injected text might contain sensitive data.
+  fn trace_injected_text(&self) -> bool;
+
+  // Maximum number of matches that a single match group can define.
+  // Groups exceeding it are not loaded, to avoid running out of memory
+  // with corrupted or machine-generated files.
+  fn max_group_matches(&self) -> usize;
+
+  // Maximum number of matches that can be loaded across all groups.
+  fn max_total_matches(&self) -> usize;
+
+  // If true, a match group file defining a single match without a cause
+  // registers the file name as the trigger, so that `sig.yml` expands `:sig`
+  fn trigger_from_filename(&self) -> bool;
+
+  // If true, replacements longer than 16KB are not kept in memory, but loaded
+  // from their source file the first time the match is expanded.
+  // Match variables referencing these matches only see the first line.
+  fn lazy_replace(&self) -> bool;
+
+  // Prefixes that can be typed in place of the one a trigger starts with.
+  // For example, `":": [";"]` makes the trigger `:foo` also match `;foo`
+  fn trigger_prefix_aliases(&self) -> BTreeMap<String, Vec<String>>;
+
+  // Maximum number of typed chars that regex matches are evaluated against.
+  // Higher values allow longer regex matches, at the expense of CPU usage.
+  fn regex_max_lookback(&self) -> usize;
+
+  // If true, match files can import other match files from HTTPS URLs.
+  // Disabled by default, as remote files could change without notice.
+  fn allow_remote_imports(&self) -> bool;
+
+  // Number of seconds a downloaded remote import is used before being
+  // refreshed in the background.
+  fn remote_imports_ttl(&self) -> u64;
+
+  // Path of a YAML file containing secret values (such as API keys), which
+  // are made available to all matches as global variables. Relative paths
+  // are resolved against the espanso config directory. The file is never
+  // watched for changes and its values are never logged.
+  fn secrets_path(&self) -> Option<String>;
+
+  // Sound played after each expansion, as an accessibility cue. It can be
+  // either the path of a sound file (relative paths are resolved against
+  // the espanso config directory) or "beep" for the system sound.
+  // Disabled if None.
+  fn expansion_sound(&self) -> Option<String>;
+
   // If false, avoid applying the built-in patches to the current config.
   fn apply_patch(&self) -> bool;
 
@@ -136,6 +261,17 @@ pub trait Config: Send + Sync {
   // Hotkey used to trigger the Search UI
   fn search_shortcut(&self) -> Option<String>;
 
+  // Key sequence used to trigger the Search UI, such as "SHIFT+SHIFT within 300ms"
+  fn search_key_sequence(&self) -> Option<String>;
+
+  // If true, the Search UI distinguishes uppercase and lowercase letters
+  // when filtering the matches, so that "API" doesn't find "api"
+  fn search_case_sensitive(&self) -> bool;
+
+  // Maximum number of results shown in the Search UI, applied after
+  // filtering them with the query. Unlimited if None.
+  fn search_max_results(&self) -> Option<usize>;
+
   // When enabled, espanso automatically "reverts" an expansion if the user
   // presses the Backspace key afterwards.
   fn undo_backspace(&self) -> bool;
@@ -150,6 +286,10 @@ pub trait Config: Send + Sync {
   // If false, avoid showing the SecureInput notification on macOS
   fn secure_input_notification(&self) -> bool;
 
+  // If false, avoid showing the notification (with the number of loaded matches)
+  // after the configuration has been reloaded
+  fn reload_notification(&self) -> bool;
+
   // If true, filter out keyboard events without an explicit HID device source on Windows.
   // This is needed to filter out the software-generated events, including
   // those from espanso, but might need to be disabled when using some software-level keyboards.
@@ -173,25 +313,55 @@ pub trait Config: Send + Sync {
         paste_shortcut: {:?}
         inject_delay: {:?}
         key_delay: {:?}
+        inject_chunk_size: {:?}
+        inject_chunk_delay: {:?}
         apply_patch: {:?}
         word_separators: {:?}
         
         preserve_clipboard: {:?}
         clipboard_threshold: {:?}
+        keys_clipboard_fallback: {:?}
+        keys_clipboard_fallback_threshold: {}
         disable_x11_fast_inject: {}
         pre_paste_delay: {}
         paste_shortcut_event_delay: {}
         toggle_key: {:?}
+        toggle_trigger: {:?}
         auto_restart: {:?}
+        watcher_quiet_period: {}
         restore_clipboard_delay: {:?} 
+        paste_focus_retries: {}
         backspace_limit: {}
+        max_backspaces_per_expansion: {}
+        post_backspace_delay: {}
+        shell_timeout: {}
+        form_timeout: {}
+        max_propagation_depth: {}
+        lint_unused_global_vars: {:?}
+        lint_duplicate_triggers: {:?}
+        injection_failure_dumps: {:?}
+        trace_injected_text: {:?}
+        max_group_matches: {}
+        max_total_matches: {}
+        trigger_from_filename: {:?}
+        lazy_replace: {:?}
+        trigger_prefix_aliases: {:?}
+        regex_max_lookback: {}
+        allow_remote_imports: {:?}
+        remote_imports_ttl: {}
+        secrets_path: {:?}
+        expansion_sound: {:?}
         search_trigger: {:?}
         search_shortcut: {:?}
+        search_key_sequence: {:?}
+        search_case_sensitive: {:?}
+        search_max_results: {:?}
         keyboard_layout: {:?}
 
         show_icon: {:?}
         show_notifications: {:?}
         secure_input_notification: {:?}
+        reload_notification: {:?}
 
         win32_exclude_orphan_events: {:?}
         win32_keyboard_layout_cache_interval: {:?}
@@ -204,25 +374,55 @@ pub trait Config: Send + Sync {
       self.paste_shortcut(),
       self.inject_delay(),
       self.key_delay(),
+      self.inject_chunk_size(),
+      self.inject_chunk_delay(),
       self.apply_patch(),
       self.word_separators(),
 
       self.preserve_clipboard(),
       self.clipboard_threshold(),
+      self.keys_clipboard_fallback(),
+      self.keys_clipboard_fallback_threshold(),
       self.disable_x11_fast_inject(),
       self.pre_paste_delay(),
       self.paste_shortcut_event_delay(),
       self.toggle_key(),
+      self.toggle_trigger(),
       self.auto_restart(),
+      self.watcher_quiet_period(),
       self.restore_clipboard_delay(),
+      self.paste_focus_retries(),
       self.backspace_limit(),
+      self.max_backspaces_per_expansion(),
+      self.post_backspace_delay(),
+      self.shell_timeout(),
+      self.form_timeout(),
+      self.max_propagation_depth(),
+      self.lint_unused_global_vars(),
+      self.lint_duplicate_triggers(),
+      self.injection_failure_dumps(),
+      self.trace_injected_text(),
+      self.max_group_matches(),
+      self.max_total_matches(),
+      self.trigger_from_filename(),
+      self.lazy_replace(),
+      self.trigger_prefix_aliases(),
+      self.regex_max_lookback(),
+      self.allow_remote_imports(),
+      self.remote_imports_ttl(),
+      self.secrets_path(),
+      self.expansion_sound(),
       self.search_trigger(),
       self.search_shortcut(),
+      self.search_key_sequence(),
+      self.search_case_sensitive(),
+      self.search_max_results(),
       self.keyboard_layout(),
 
       self.show_icon(),
       self.show_notifications(),
       self.secure_input_notification(),
+      self.reload_notification(),
 
       self.win32_exclude_orphan_events(),
       self.win32_keyboard_layout_cache_interval(),
@@ -246,7 +446,7 @@ pub struct AppProperties<'a> {
   pub exec: Option<&'a str>,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Backend {
   Inject,
   Clipboard,
@@ -292,8 +492,52 @@ impl std::fmt::Display for RMLVOConfig {
   }
 }
 
-pub fn load_store(config_dir: &Path) -> Result<(impl ConfigStore, Vec<NonFatalErrorSet>)> {
-  store::DefaultConfigStore::load(config_dir)
+pub fn load_store(
+  config_dirs: &[PathBuf],
+  safe_mode: bool,
+) -> Result<(impl ConfigStore, Vec<NonFatalErrorSet>)> {
+  store::DefaultConfigStore::load(config_dirs, safe_mode)
+}
+
+// Loads every config file in the given directory, returning the errors
+// of the ones that can't be loaded
+pub fn check_dir(config_dir: &Path) -> Result<Vec<NonFatalErrorSet>> {
+  if !config_dir.is_dir() {
+    return Err(ConfigStoreError::InvalidConfigDir().into());
+  }
+
+  let mut errors = Vec::new();
+
+  let default_file = config_dir.join("default.yml");
+  if !default_file.is_file() {
+    errors.push(NonFatalErrorSet::single_error(
+      &default_file,
+      ConfigStoreError::MissingDefault().into(),
+    ));
+  }
+
+  let mut config_files = Vec::new();
+  for entry in std::fs::read_dir(config_dir).map_err(ConfigStoreError::IOError)? {
+    let config_file = entry?.path();
+    let extension = config_file
+      .extension()
+      .unwrap_or_default()
+      .to_string_lossy()
+      .to_lowercase();
+
+    if config_file.is_file() && (extension == "yml" || extension == "yaml") {
+      config_files.push(config_file);
+    }
+  }
+  config_files.sort();
+
+  for config_file in config_files {
+    if let Err(err) = resolve::ResolvedConfig::load(&config_file, None) {
+      errors.push(NonFatalErrorSet::single_error(&config_file, err));
+    }
+  }
+
+  Ok(errors)
 }
 
 #[derive(Error, Debug)]
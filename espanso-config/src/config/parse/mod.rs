@@ -21,37 +21,69 @@ use anyhow::Result;
 use std::{collections::BTreeMap, convert::TryInto, path::Path};
 use thiserror::Error;
 
+use super::Backend;
+
 mod yaml;
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub(crate) struct ParsedConfig {
   pub label: Option<String>,
 
-  pub backend: Option<String>,
+  pub backend: Option<Backend>,
   pub enable: Option<bool>,
   pub clipboard_threshold: Option<usize>,
+  pub keys_clipboard_fallback: Option<bool>,
+  pub keys_clipboard_fallback_threshold: Option<usize>,
   pub auto_restart: Option<bool>,
+  pub watcher_quiet_period: Option<u64>,
   pub preserve_clipboard: Option<bool>,
   pub toggle_key: Option<String>,
+  pub toggle_trigger: Option<String>,
   pub paste_shortcut: Option<String>,
   pub disable_x11_fast_inject: Option<bool>,
   pub word_separators: Option<Vec<String>>,
   pub backspace_limit: Option<usize>,
+  pub max_backspaces_per_expansion: Option<usize>,
+  pub post_backspace_delay: Option<usize>,
+  pub shell_timeout: Option<usize>,
+  pub form_timeout: Option<u64>,
+  pub max_propagation_depth: Option<usize>,
+  pub lint_unused_global_vars: Option<bool>,
+  pub lint_duplicate_triggers: Option<bool>,
+  pub injection_failure_dumps: Option<bool>,
+  pub trace_injected_text: Option<bool>,
+  pub max_group_matches: Option<usize>,
+  pub max_total_matches: Option<usize>,
+  pub trigger_from_filename: Option<bool>,
+  pub lazy_replace: Option<bool>,
+  pub trigger_prefix_aliases: Option<BTreeMap<String, Vec<String>>>,
+  pub regex_max_lookback: Option<usize>,
+  pub allow_remote_imports: Option<bool>,
+  pub remote_imports_ttl: Option<u64>,
+  pub secrets_path: Option<String>,
+  pub expansion_sound: Option<String>,
   pub apply_patch: Option<bool>,
   pub search_trigger: Option<String>,
   pub search_shortcut: Option<String>,
+  pub search_key_sequence: Option<String>,
+  pub search_case_sensitive: Option<bool>,
+  pub search_max_results: Option<usize>,
   pub undo_backspace: Option<bool>,
   pub show_notifications: Option<bool>,
   pub show_icon: Option<bool>,
   pub secure_input_notification: Option<bool>,
+  pub reload_notification: Option<bool>,
   pub win32_exclude_orphan_events: Option<bool>,
   pub win32_keyboard_layout_cache_interval: Option<i64>,
 
   pub pre_paste_delay: Option<usize>,
   pub restore_clipboard_delay: Option<usize>,
+  pub paste_focus_retries: Option<usize>,
   pub paste_shortcut_event_delay: Option<usize>,
   pub inject_delay: Option<usize>,
   pub key_delay: Option<usize>,
+  pub inject_chunk_size: Option<usize>,
+  pub inject_chunk_delay: Option<usize>,
   pub keyboard_layout: Option<BTreeMap<String, String>>,
   pub evdev_modifier_delay: Option<usize>,
 
@@ -62,6 +94,10 @@ pub(crate) struct ParsedConfig {
   pub extra_excludes: Option<Vec<String>>,
   pub use_standard_includes: Option<bool>,
 
+  // True if the config file defines its own `matches` section, which is
+  // loaded as an implicit match group
+  pub inline_matches: Option<bool>,
+
   // Filters
   pub filter_title: Option<String>,
   pub filter_class: Option<String>,
@@ -83,4 +119,13 @@ impl ParsedConfig {
 pub enum ParsedConfigError {
   #[error("can't load config `{0}`")]
   LoadFailed(#[from] anyhow::Error),
+
+  #[error("invalid backend `{0}`, expected one of: inject, clipboard, auto")]
+  InvalidBackend(String),
+
+  #[error("invalid keyboard_layout `{0}`, expected a layout name such as: us, de")]
+  InvalidKeyboardLayout(String),
+
+  #[error("invalid search_max_results `0`, expected a positive number")]
+  InvalidSearchMaxResults,
 }
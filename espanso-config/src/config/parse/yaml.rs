@@ -20,11 +20,11 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Mapping;
-use std::convert::TryFrom;
+use std::{collections::BTreeMap, convert::TryFrom};
 
-use crate::util::is_yaml_empty;
+use crate::{config::Backend, util::is_yaml_empty};
 
-use super::ParsedConfig;
+use super::{ParsedConfig, ParsedConfigError};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct YAMLConfig {
@@ -40,21 +40,36 @@ pub(crate) struct YAMLConfig {
   #[serde(default)]
   pub clipboard_threshold: Option<usize>,
 
+  #[serde(default)]
+  pub keys_clipboard_fallback: Option<bool>,
+
+  #[serde(default)]
+  pub keys_clipboard_fallback_threshold: Option<usize>,
+
   #[serde(default)]
   pub pre_paste_delay: Option<usize>,
 
   #[serde(default)]
   pub toggle_key: Option<String>,
 
+  #[serde(default)]
+  pub toggle_trigger: Option<String>,
+
   #[serde(default)]
   pub auto_restart: Option<bool>,
 
+  #[serde(default)]
+  pub watcher_quiet_period: Option<u64>,
+
   #[serde(default)]
   pub preserve_clipboard: Option<bool>,
 
   #[serde(default)]
   pub restore_clipboard_delay: Option<usize>,
 
+  #[serde(default)]
+  pub paste_focus_retries: Option<usize>,
+
   #[serde(default)]
   pub paste_shortcut_event_delay: Option<usize>,
 
@@ -70,6 +85,12 @@ pub(crate) struct YAMLConfig {
   #[serde(default)]
   pub key_delay: Option<usize>,
 
+  #[serde(default)]
+  pub inject_chunk_size: Option<usize>,
+
+  #[serde(default)]
+  pub inject_chunk_delay: Option<usize>,
+
   #[serde(default)]
   pub backspace_delay: Option<usize>,
 
@@ -82,11 +103,68 @@ pub(crate) struct YAMLConfig {
   #[serde(default)]
   pub backspace_limit: Option<usize>,
 
+  #[serde(default)]
+  pub max_backspaces_per_expansion: Option<usize>,
+
+  #[serde(default)]
+  pub post_backspace_delay: Option<usize>,
+
+  #[serde(default)]
+  pub shell_timeout: Option<usize>,
+
+  #[serde(default)]
+  pub form_timeout: Option<u64>,
+
+  #[serde(default)]
+  pub max_propagation_depth: Option<usize>,
+
+  #[serde(default)]
+  pub lint_unused_global_vars: Option<bool>,
+
+  #[serde(default)]
+  pub lint_duplicate_triggers: Option<bool>,
+
+  #[serde(default)]
+  pub injection_failure_dumps: Option<bool>,
+
+  #[serde(default)]
+  pub trace_injected_text: Option<bool>,
+
+  #[serde(default)]
+  pub max_group_matches: Option<usize>,
+
+  #[serde(default)]
+  pub max_total_matches: Option<usize>,
+
+  #[serde(default)]
+  pub trigger_from_filename: Option<bool>,
+
+  #[serde(default)]
+  pub lazy_replace: Option<bool>,
+
+  #[serde(default)]
+  pub trigger_prefix_aliases: Option<BTreeMap<String, Vec<String>>>,
+
+  #[serde(default)]
+  pub regex_max_lookback: Option<usize>,
+
+  #[serde(default)]
+  pub allow_remote_imports: Option<bool>,
+
+  #[serde(default)]
+  pub remote_imports_ttl: Option<u64>,
+
+  #[serde(default)]
+  pub secrets_path: Option<String>,
+
+  #[serde(default)]
+  pub expansion_sound: Option<String>,
+
   #[serde(default)]
   pub apply_patch: Option<bool>,
 
   #[serde(default)]
-  pub keyboard_layout: Option<Mapping>,
+  pub keyboard_layout: Option<YAMLKeyboardLayout>,
 
   #[serde(default)]
   pub search_trigger: Option<String>,
@@ -94,6 +172,15 @@ pub(crate) struct YAMLConfig {
   #[serde(default)]
   pub search_shortcut: Option<String>,
 
+  #[serde(default)]
+  pub search_key_sequence: Option<String>,
+
+  #[serde(default)]
+  pub search_case_sensitive: Option<bool>,
+
+  #[serde(default)]
+  pub search_max_results: Option<usize>,
+
   #[serde(default)]
   pub undo_backspace: Option<bool>,
 
@@ -106,6 +193,9 @@ pub(crate) struct YAMLConfig {
   #[serde(default)]
   pub secure_input_notification: Option<bool>,
 
+  #[serde(default)]
+  pub reload_notification: Option<bool>,
+
   #[serde(default)]
   pub win32_exclude_orphan_events: Option<bool>,
 
@@ -128,6 +218,11 @@ pub(crate) struct YAMLConfig {
   #[serde(default)]
   pub use_standard_includes: Option<bool>,
 
+  // Matches defined directly in the config file. They are parsed by the
+  // match loader, which reads the file as a regular match group
+  #[serde(default)]
+  pub matches: Option<Vec<serde_yaml::Value>>,
+
   // Filters
   #[serde(default)]
   pub filter_title: Option<String>,
@@ -161,42 +256,71 @@ impl TryFrom<YAMLConfig> for ParsedConfig {
   fn try_from(yaml_config: YAMLConfig) -> Result<Self, Self::Error> {
     Ok(Self {
       label: yaml_config.label,
-      backend: yaml_config.backend,
+      backend: yaml_config
+        .backend
+        .as_deref()
+        .map(parse_backend)
+        .transpose()?,
       enable: yaml_config.enable,
       clipboard_threshold: yaml_config.clipboard_threshold,
+      keys_clipboard_fallback: yaml_config.keys_clipboard_fallback,
+      keys_clipboard_fallback_threshold: yaml_config.keys_clipboard_fallback_threshold,
       auto_restart: yaml_config.auto_restart,
+      watcher_quiet_period: yaml_config.watcher_quiet_period,
       toggle_key: yaml_config.toggle_key,
+      toggle_trigger: yaml_config.toggle_trigger,
       preserve_clipboard: yaml_config.preserve_clipboard,
       paste_shortcut: yaml_config.paste_shortcut,
       disable_x11_fast_inject: yaml_config.disable_x11_fast_inject,
       inject_delay: yaml_config.inject_delay,
       key_delay: yaml_config.key_delay.or(yaml_config.backspace_delay),
+      inject_chunk_size: yaml_config.inject_chunk_size,
+      inject_chunk_delay: yaml_config.inject_chunk_delay,
       evdev_modifier_delay: yaml_config.evdev_modifier_delay,
       word_separators: yaml_config.word_separators,
       backspace_limit: yaml_config.backspace_limit,
+      max_backspaces_per_expansion: yaml_config.max_backspaces_per_expansion,
+      post_backspace_delay: yaml_config.post_backspace_delay,
+      shell_timeout: yaml_config.shell_timeout,
+      form_timeout: yaml_config.form_timeout,
+      max_propagation_depth: yaml_config.max_propagation_depth,
+      lint_unused_global_vars: yaml_config.lint_unused_global_vars,
+      lint_duplicate_triggers: yaml_config.lint_duplicate_triggers,
+      injection_failure_dumps: yaml_config.injection_failure_dumps,
+      trace_injected_text: yaml_config.trace_injected_text,
+      max_group_matches: yaml_config.max_group_matches,
+      max_total_matches: yaml_config.max_total_matches,
+      trigger_from_filename: yaml_config.trigger_from_filename,
+      lazy_replace: yaml_config.lazy_replace,
+      trigger_prefix_aliases: yaml_config.trigger_prefix_aliases,
+      regex_max_lookback: yaml_config.regex_max_lookback,
+      allow_remote_imports: yaml_config.allow_remote_imports,
+      remote_imports_ttl: yaml_config.remote_imports_ttl,
+      secrets_path: yaml_config.secrets_path,
+      expansion_sound: yaml_config.expansion_sound,
       apply_patch: yaml_config.apply_patch,
-      keyboard_layout: yaml_config.keyboard_layout.map(|mapping| {
-        mapping
-          .into_iter()
-          .filter_map(|(key, value)| {
-            if let (Some(key), Some(value)) = (key.as_str(), value.as_str()) {
-              Some((key.to_string(), value.to_string()))
-            } else {
-              None
-            }
-          })
-          .collect()
-      }),
+      keyboard_layout: yaml_config
+        .keyboard_layout
+        .map(parse_keyboard_layout)
+        .transpose()?,
       search_trigger: yaml_config.search_trigger,
       search_shortcut: yaml_config.search_shortcut,
+      search_key_sequence: yaml_config.search_key_sequence,
+      search_case_sensitive: yaml_config.search_case_sensitive,
+      search_max_results: yaml_config
+        .search_max_results
+        .map(parse_search_max_results)
+        .transpose()?,
       undo_backspace: yaml_config.undo_backspace,
 
       show_icon: yaml_config.show_icon,
       show_notifications: yaml_config.show_notifications,
       secure_input_notification: yaml_config.secure_input_notification,
+      reload_notification: yaml_config.reload_notification,
 
       pre_paste_delay: yaml_config.pre_paste_delay,
       restore_clipboard_delay: yaml_config.restore_clipboard_delay,
+      paste_focus_retries: yaml_config.paste_focus_retries,
       paste_shortcut_event_delay: yaml_config.paste_shortcut_event_delay,
 
       win32_exclude_orphan_events: yaml_config.win32_exclude_orphan_events,
@@ -208,6 +332,8 @@ impl TryFrom<YAMLConfig> for ParsedConfig {
       excludes: yaml_config.excludes,
       extra_excludes: yaml_config.extra_excludes,
 
+      inline_matches: yaml_config.matches.map(|matches| !matches.is_empty()),
+
       filter_class: yaml_config.filter_class,
       filter_exec: yaml_config.filter_exec,
       filter_os: yaml_config.filter_os,
@@ -216,10 +342,66 @@ impl TryFrom<YAMLConfig> for ParsedConfig {
   }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub(crate) enum YAMLKeyboardLayout {
+  // Shorthand to only specify the layout, such as "us" or "de"
+  Layout(String),
+  Mapping(Mapping),
+}
+
+fn parse_keyboard_layout(keyboard_layout: YAMLKeyboardLayout) -> Result<BTreeMap<String, String>> {
+  let keyboard_layout: BTreeMap<String, String> = match keyboard_layout {
+    YAMLKeyboardLayout::Layout(layout) => vec![("layout".to_string(), layout.trim().to_string())]
+      .into_iter()
+      .collect(),
+    YAMLKeyboardLayout::Mapping(mapping) => mapping
+      .into_iter()
+      .filter_map(|(key, value)| {
+        if let (Some(key), Some(value)) = (key.as_str(), value.as_str()) {
+          Some((key.to_string(), value.to_string()))
+        } else {
+          None
+        }
+      })
+      .collect(),
+  };
+
+  // Layout names (such as "us" or "de,it") are passed to xkbcommon as they are
+  if let Some(layout) = keyboard_layout.get("layout") {
+    let is_valid = !layout.is_empty()
+      && layout
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ','));
+    if !is_valid {
+      return Err(ParsedConfigError::InvalidKeyboardLayout(layout.to_string()).into());
+    }
+  }
+
+  Ok(keyboard_layout)
+}
+
+fn parse_search_max_results(max_results: usize) -> Result<usize> {
+  if max_results == 0 {
+    return Err(ParsedConfigError::InvalidSearchMaxResults.into());
+  }
+
+  Ok(max_results)
+}
+
+fn parse_backend(backend: &str) -> Result<Backend> {
+  match backend.trim().to_lowercase().as_str() {
+    "inject" => Ok(Backend::Inject),
+    "clipboard" => Ok(Backend::Clipboard),
+    "auto" => Ok(Backend::Auto),
+    _ => Err(ParsedConfigError::InvalidBackend(backend.to_string()).into()),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use std::{collections::BTreeMap, convert::TryInto};
+  use std::convert::TryInto;
 
   #[test]
   fn conversion_to_parsed_config_works_correctly() {
@@ -229,20 +411,47 @@ mod tests {
     backend: clipboard
     enable: false
     clipboard_threshold: 200
+    keys_clipboard_fallback: false
+    keys_clipboard_fallback_threshold: 500
     pre_paste_delay: 300
     toggle_key: CTRL
+    toggle_trigger: ":espanso off"
     auto_restart: false
+    watcher_quiet_period: 2000
     preserve_clipboard: false
     restore_clipboard_delay: 400
+    paste_focus_retries: 3
     paste_shortcut: CTRL+ALT+V
     paste_shortcut_event_delay: 10
     disable_x11_fast_inject: true
     inject_delay: 10
     key_delay: 20
+    inject_chunk_size: 8
+    inject_chunk_delay: 30
     backspace_delay: 30
     evdev_modifier_delay: 40
     word_separators: ["'", "."]
     backspace_limit: 10
+    max_backspaces_per_expansion: 200
+    post_backspace_delay: 15
+    shell_timeout: 5000
+    form_timeout: 120
+    max_propagation_depth: 2
+    lint_unused_global_vars: true
+    lint_duplicate_triggers: false
+    injection_failure_dumps: true
+    trace_injected_text: true
+    max_group_matches: 100
+    max_total_matches: 1000
+    trigger_from_filename: true
+    lazy_replace: true
+    trigger_prefix_aliases:
+      ":": [";"]
+    regex_max_lookback: 50
+    allow_remote_imports: true
+    remote_imports_ttl: 60
+    secrets_path: "secrets.yml"
+    expansion_sound: "beep"
     apply_patch: false
     keyboard_layout:
       rules: test_rule
@@ -252,10 +461,14 @@ mod tests {
       options: test_options
     search_trigger: "search"
     search_shortcut: "CTRL+SPACE"
+    search_key_sequence: "SHIFT+SHIFT"
+    search_case_sensitive: true
+    search_max_results: 20
     undo_backspace: false
     show_icon: false
     show_notifications: false
     secure_input_notification: false
+    reload_notification: false
     win32_exclude_orphan_events: false
     win32_keyboard_layout_cache_interval: 300
       
@@ -264,6 +477,10 @@ mod tests {
     extra_includes: ["test2"]
     excludes: ["test3"]
     extra_excludes: ["test4"]
+
+    matches:
+      - trigger: "hello"
+        replace: "world"
     
     filter_class: "test5"
     filter_exec: "test6"
@@ -289,26 +506,59 @@ mod tests {
       ParsedConfig {
         label: Some("test".to_string()),
 
-        backend: Some("clipboard".to_string()),
+        backend: Some(Backend::Clipboard),
         enable: Some(false),
         clipboard_threshold: Some(200),
+        keys_clipboard_fallback: Some(false),
+        keys_clipboard_fallback_threshold: Some(500),
         auto_restart: Some(false),
+        watcher_quiet_period: Some(2000),
         preserve_clipboard: Some(false),
         restore_clipboard_delay: Some(400),
+        paste_focus_retries: Some(3),
         paste_shortcut: Some("CTRL+ALT+V".to_string()),
         paste_shortcut_event_delay: Some(10),
         disable_x11_fast_inject: Some(true),
         inject_delay: Some(10),
         key_delay: Some(20),
+        inject_chunk_size: Some(8),
+        inject_chunk_delay: Some(30),
         backspace_limit: Some(10),
+        max_backspaces_per_expansion: Some(200),
+        post_backspace_delay: Some(15),
+        shell_timeout: Some(5000),
+        form_timeout: Some(120),
+        max_propagation_depth: Some(2),
+        lint_unused_global_vars: Some(true),
+        lint_duplicate_triggers: Some(false),
+        injection_failure_dumps: Some(true),
+        trace_injected_text: Some(true),
+        max_group_matches: Some(100),
+        max_total_matches: Some(1000),
+        trigger_from_filename: Some(true),
+        lazy_replace: Some(true),
+        trigger_prefix_aliases: Some(
+          vec![(":".to_string(), vec![";".to_string()])]
+            .into_iter()
+            .collect()
+        ),
+        regex_max_lookback: Some(50),
+        allow_remote_imports: Some(true),
+        remote_imports_ttl: Some(60),
+        secrets_path: Some("secrets.yml".to_string()),
+        expansion_sound: Some("beep".to_string()),
         apply_patch: Some(false),
         keyboard_layout: Some(keyboard_layout),
         search_trigger: Some("search".to_owned()),
         search_shortcut: Some("CTRL+SPACE".to_owned()),
+        search_key_sequence: Some("SHIFT+SHIFT".to_owned()),
+        search_case_sensitive: Some(true),
+        search_max_results: Some(20),
         undo_backspace: Some(false),
         show_icon: Some(false),
         show_notifications: Some(false),
         secure_input_notification: Some(false),
+        reload_notification: Some(false),
         win32_exclude_orphan_events: Some(false),
         win32_keyboard_layout_cache_interval: Some(300),
 
@@ -316,6 +566,7 @@ mod tests {
         evdev_modifier_delay: Some(40),
 
         toggle_key: Some("CTRL".to_string()),
+        toggle_trigger: Some(":espanso off".to_string()),
         word_separators: Some(vec!["'".to_owned(), ".".to_owned()]),
 
         use_standard_includes: Some(true),
@@ -324,6 +575,8 @@ mod tests {
         excludes: Some(vec!["test3".to_string()]),
         extra_excludes: Some(vec!["test4".to_string()]),
 
+        inline_matches: Some(true),
+
         filter_class: Some("test5".to_string()),
         filter_exec: Some("test6".to_string()),
         filter_os: Some("test7".to_string()),
@@ -331,4 +584,74 @@ mod tests {
       }
     )
   }
+
+  #[test]
+  fn backend_is_normalized() {
+    let parse = |yaml: &str| -> ParsedConfig {
+      YAMLConfig::parse_from_str(yaml)
+        .unwrap()
+        .try_into()
+        .unwrap()
+    };
+
+    assert_eq!(parse("backend: inject").backend, Some(Backend::Inject));
+    assert_eq!(
+      parse("backend: Clipboard").backend,
+      Some(Backend::Clipboard)
+    );
+    assert_eq!(parse("backend: AUTO").backend, Some(Backend::Auto));
+    assert_eq!(parse("enable: true").backend, None);
+  }
+
+  #[test]
+  fn invalid_backend_is_rejected() {
+    let config = YAMLConfig::parse_from_str("backend: clipbrd").unwrap();
+    let result: Result<ParsedConfig> = config.try_into();
+
+    assert!(matches!(
+      result.unwrap_err().downcast::<ParsedConfigError>().unwrap(),
+      ParsedConfigError::InvalidBackend(backend) if backend == "clipbrd"
+    ));
+  }
+
+  #[test]
+  fn keyboard_layout_shorthand_is_parsed() {
+    let config = YAMLConfig::parse_from_str("keyboard_layout: de").unwrap();
+    let parsed_config: ParsedConfig = config.try_into().unwrap();
+
+    let keyboard_layout: BTreeMap<String, String> = vec![("layout".to_string(), "de".to_string())]
+      .into_iter()
+      .collect();
+    assert_eq!(parsed_config.keyboard_layout, Some(keyboard_layout));
+  }
+
+  #[test]
+  fn invalid_keyboard_layout_is_rejected() {
+    let config = YAMLConfig::parse_from_str("keyboard_layout: \"de us\"").unwrap();
+    let result: Result<ParsedConfig> = config.try_into();
+    assert!(matches!(
+      result.unwrap_err().downcast::<ParsedConfigError>().unwrap(),
+      ParsedConfigError::InvalidKeyboardLayout(layout) if layout == "de us"
+    ));
+
+    let config = YAMLConfig::parse_from_str(
+      r#"
+    keyboard_layout:
+      layout: "us;rm -rf"
+    "#,
+    )
+    .unwrap();
+    let result: Result<ParsedConfig> = config.try_into();
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn zero_search_max_results_is_rejected() {
+    let config = YAMLConfig::parse_from_str("search_max_results: 0").unwrap();
+    let result: Result<ParsedConfig> = config.try_into();
+    assert!(matches!(
+      result.unwrap_err().downcast::<ParsedConfigError>().unwrap(),
+      ParsedConfigError::InvalidSearchMaxResults
+    ));
+  }
 }
@@ -19,23 +19,34 @@
 
 use super::{
   default::{
-    DEFAULT_CLIPBOARD_THRESHOLD, DEFAULT_PRE_PASTE_DELAY, DEFAULT_RESTORE_CLIPBOARD_DELAY,
-    DEFAULT_SHORTCUT_EVENT_DELAY,
+    DEFAULT_CLIPBOARD_THRESHOLD, DEFAULT_INJECT_CHUNK_DELAY,
+    DEFAULT_KEYS_CLIPBOARD_FALLBACK_THRESHOLD, DEFAULT_MAX_BACKSPACES_PER_EXPANSION,
+    DEFAULT_MAX_GROUP_MATCHES, DEFAULT_MAX_TOTAL_MATCHES, DEFAULT_PASTE_FOCUS_RETRIES,
+    DEFAULT_PRE_PASTE_DELAY, DEFAULT_REGEX_MAX_LOOKBACK, DEFAULT_REMOTE_IMPORTS_TTL,
+    DEFAULT_RESTORE_CLIPBOARD_DELAY, DEFAULT_SHELL_TIMEOUT, DEFAULT_SHORTCUT_EVENT_DELAY,
+    DEFAULT_WATCHER_QUIET_PERIOD,
   },
   parse::ParsedConfig,
   path::calculate_paths,
-  util::os_matches,
+  util::{os_matches, KNOWN_OS},
   AppProperties, Backend, Config, RMLVOConfig, ToggleKey,
 };
 use crate::{counter::next_id, merge};
 use anyhow::Result;
-use log::error;
+use log::{error, warn};
 use regex::Regex;
 use std::path::PathBuf;
-use std::{collections::HashSet, path::Path};
+use std::{
+  collections::{BTreeMap, HashMap, HashSet},
+  path::Path,
+};
 use thiserror::Error;
 
-const STANDARD_INCLUDES: &[&str] = &["../match/**/[!_]*.yml"];
+const STANDARD_INCLUDES: &[&str] = &[
+  "../match/**/[!_]*.yml",
+  "../match/**/[!_]*.json",
+  "../match/**/[!_]*.toml",
+];
 
 #[derive(Debug, Clone)]
 pub(crate) struct ResolvedConfig {
@@ -46,6 +57,7 @@ pub(crate) struct ResolvedConfig {
   // Generated properties
   id: i32,
   match_paths: Vec<String>,
+  inline_match_paths: Vec<String>,
 
   filter_title: Option<Regex>,
   filter_class: Option<Regex>,
@@ -59,6 +71,7 @@ impl Default for ResolvedConfig {
       source_path: None,
       id: 0,
       match_paths: Vec::new(),
+      inline_match_paths: Vec::new(),
       filter_title: None,
       filter_class: None,
       filter_exec: None,
@@ -139,23 +152,7 @@ impl Config for ResolvedConfig {
   }
 
   fn backend(&self) -> Backend {
-    // TODO: test
-    match self
-      .parsed
-      .backend
-      .as_deref()
-      .map(|b| b.to_lowercase())
-      .as_deref()
-    {
-      Some("clipboard") => Backend::Clipboard,
-      Some("inject") => Backend::Inject,
-      Some("auto") => Backend::Auto,
-      None => Backend::Auto,
-      err => {
-        error!("invalid backend specified {:?}, falling back to Auto", err);
-        Backend::Auto
-      }
-    }
+    self.parsed.backend.unwrap_or(Backend::Auto)
   }
 
   fn enable(&self) -> bool {
@@ -169,10 +166,28 @@ impl Config for ResolvedConfig {
       .unwrap_or(DEFAULT_CLIPBOARD_THRESHOLD)
   }
 
+  fn keys_clipboard_fallback(&self) -> bool {
+    self.parsed.keys_clipboard_fallback.unwrap_or(false)
+  }
+
+  fn keys_clipboard_fallback_threshold(&self) -> usize {
+    self
+      .parsed
+      .keys_clipboard_fallback_threshold
+      .unwrap_or(DEFAULT_KEYS_CLIPBOARD_FALLBACK_THRESHOLD)
+  }
+
   fn auto_restart(&self) -> bool {
     self.parsed.auto_restart.unwrap_or(true)
   }
 
+  fn watcher_quiet_period(&self) -> u64 {
+    self
+      .parsed
+      .watcher_quiet_period
+      .unwrap_or(DEFAULT_WATCHER_QUIET_PERIOD)
+  }
+
   fn pre_paste_delay(&self) -> usize {
     self
       .parsed
@@ -213,6 +228,14 @@ impl Config for ResolvedConfig {
     }
   }
 
+  fn toggle_trigger(&self) -> Option<String> {
+    self
+      .parsed
+      .toggle_trigger
+      .clone()
+      .filter(|trigger| !trigger.is_empty())
+  }
+
   fn preserve_clipboard(&self) -> bool {
     self.parsed.preserve_clipboard.unwrap_or(true)
   }
@@ -224,6 +247,13 @@ impl Config for ResolvedConfig {
       .unwrap_or(DEFAULT_RESTORE_CLIPBOARD_DELAY)
   }
 
+  fn paste_focus_retries(&self) -> usize {
+    self
+      .parsed
+      .paste_focus_retries
+      .unwrap_or(DEFAULT_PASTE_FOCUS_RETRIES)
+  }
+
   fn paste_shortcut_event_delay(&self) -> usize {
     self
       .parsed
@@ -247,6 +277,17 @@ impl Config for ResolvedConfig {
     self.parsed.key_delay
   }
 
+  fn inject_chunk_size(&self) -> usize {
+    self.parsed.inject_chunk_size.unwrap_or(0)
+  }
+
+  fn inject_chunk_delay(&self) -> usize {
+    self
+      .parsed
+      .inject_chunk_delay
+      .unwrap_or(DEFAULT_INJECT_CHUNK_DELAY)
+  }
+
   fn word_separators(&self) -> Vec<String> {
     self.parsed.word_separators.clone().unwrap_or_else(|| {
       vec![
@@ -266,6 +307,101 @@ impl Config for ResolvedConfig {
     self.parsed.backspace_limit.unwrap_or(5)
   }
 
+  fn max_backspaces_per_expansion(&self) -> usize {
+    self
+      .parsed
+      .max_backspaces_per_expansion
+      .unwrap_or(DEFAULT_MAX_BACKSPACES_PER_EXPANSION)
+  }
+
+  fn post_backspace_delay(&self) -> usize {
+    self.parsed.post_backspace_delay.unwrap_or(0)
+  }
+
+  fn shell_timeout(&self) -> usize {
+    self.parsed.shell_timeout.unwrap_or(DEFAULT_SHELL_TIMEOUT)
+  }
+
+  fn form_timeout(&self) -> u64 {
+    self.parsed.form_timeout.unwrap_or(0)
+  }
+
+  fn max_propagation_depth(&self) -> usize {
+    self.parsed.max_propagation_depth.unwrap_or(3)
+  }
+
+  fn lint_unused_global_vars(&self) -> bool {
+    self.parsed.lint_unused_global_vars.unwrap_or(false)
+  }
+
+  fn lint_duplicate_triggers(&self) -> bool {
+    self.parsed.lint_duplicate_triggers.unwrap_or(true)
+  }
+
+  fn injection_failure_dumps(&self) -> bool {
+    self.parsed.injection_failure_dumps.unwrap_or(false)
+  }
+
+  fn trace_injected_text(&self) -> bool {
+    self.parsed.trace_injected_text.unwrap_or(false)
+  }
+
+  fn max_group_matches(&self) -> usize {
+    self
+      .parsed
+      .max_group_matches
+      .unwrap_or(DEFAULT_MAX_GROUP_MATCHES)
+  }
+
+  fn max_total_matches(&self) -> usize {
+    self
+      .parsed
+      .max_total_matches
+      .unwrap_or(DEFAULT_MAX_TOTAL_MATCHES)
+  }
+
+  fn trigger_from_filename(&self) -> bool {
+    self.parsed.trigger_from_filename.unwrap_or(false)
+  }
+
+  fn lazy_replace(&self) -> bool {
+    self.parsed.lazy_replace.unwrap_or(false)
+  }
+
+  fn trigger_prefix_aliases(&self) -> BTreeMap<String, Vec<String>> {
+    self
+      .parsed
+      .trigger_prefix_aliases
+      .clone()
+      .unwrap_or_default()
+  }
+
+  fn regex_max_lookback(&self) -> usize {
+    self
+      .parsed
+      .regex_max_lookback
+      .unwrap_or(DEFAULT_REGEX_MAX_LOOKBACK)
+  }
+
+  fn allow_remote_imports(&self) -> bool {
+    self.parsed.allow_remote_imports.unwrap_or(false)
+  }
+
+  fn remote_imports_ttl(&self) -> u64 {
+    self
+      .parsed
+      .remote_imports_ttl
+      .unwrap_or(DEFAULT_REMOTE_IMPORTS_TTL)
+  }
+
+  fn secrets_path(&self) -> Option<String> {
+    self.parsed.secrets_path.clone()
+  }
+
+  fn expansion_sound(&self) -> Option<String> {
+    self.parsed.expansion_sound.clone()
+  }
+
   fn apply_patch(&self) -> bool {
     self.parsed.apply_patch.unwrap_or(true)
   }
@@ -300,6 +436,18 @@ impl Config for ResolvedConfig {
     }
   }
 
+  fn search_key_sequence(&self) -> Option<String> {
+    self.parsed.search_key_sequence.clone()
+  }
+
+  fn search_case_sensitive(&self) -> bool {
+    self.parsed.search_case_sensitive.unwrap_or(false)
+  }
+
+  fn search_max_results(&self) -> Option<usize> {
+    self.parsed.search_max_results
+  }
+
   fn undo_backspace(&self) -> bool {
     self.parsed.undo_backspace.unwrap_or(true)
   }
@@ -316,6 +464,10 @@ impl Config for ResolvedConfig {
     self.parsed.secure_input_notification.unwrap_or(true)
   }
 
+  fn reload_notification(&self) -> bool {
+    self.parsed.reload_notification.unwrap_or(true)
+  }
+
   fn win32_exclude_orphan_events(&self) -> bool {
     self.parsed.win32_exclude_orphan_events.unwrap_or(true)
   }
@@ -334,7 +486,40 @@ impl Config for ResolvedConfig {
 
 impl ResolvedConfig {
   pub fn load(path: &Path, parent: Option<&Self>) -> Result<Self> {
-    let mut config = ParsedConfig::load(path)?;
+    let config = ParsedConfig::load(path)?;
+    Self::from_parsed(config, path, parent)
+  }
+
+  // With multiple config roots, the includes are evaluated against the config directory
+  // of each root. A match file overrides the one with the same path (relative to its
+  // root) in the earlier roots, while the others are added
+  pub fn overlay_match_paths(&mut self, config_dirs: &[PathBuf]) {
+    self.match_paths = Self::generate_overlay_match_paths(&self.parsed, config_dirs);
+    Self::append_inline_match_paths(&mut self.match_paths, &self.inline_match_paths);
+  }
+
+  // Config using the default values for all the options, used in place
+  // of the one at the given path when it cannot be loaded
+  pub fn fallback(path: &Path) -> Result<Self> {
+    Self::from_parsed(ParsedConfig::default(), path, None)
+  }
+
+  fn from_parsed(mut config: ParsedConfig, path: &Path, parent: Option<&Self>) -> Result<Self> {
+    // Unknown values are still applied literally (never matching), but
+    // they are most likely typos, so we let the user know
+    if let Some(warning) = config.filter_os.as_deref().and_then(validate_filter_os) {
+      warn!("{} in config: {:?}", warning, path);
+    }
+
+    // Matches defined inline are loaded from the config file itself, and they are
+    // inherited by the child configs. This has to be evaluated before the merge
+    let mut inline_match_paths = parent
+      .map(|parent| parent.inline_match_paths.clone())
+      .unwrap_or_default();
+    if config.inline_matches.unwrap_or(false) {
+      let path = dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+      inline_match_paths.push(path.to_string_lossy().to_string());
+    }
 
     // Merge with parent config if present
     if let Some(parent) = parent {
@@ -346,9 +531,8 @@ impl ResolvedConfig {
       .parent()
       .ok_or_else(ResolveError::ParentResolveFailed)?;
 
-    let match_paths = Self::generate_match_paths(&config, base_dir)
-      .into_iter()
-      .collect();
+    let mut match_paths = Self::generate_match_paths(&config, base_dir);
+    Self::append_inline_match_paths(&mut match_paths, &inline_match_paths);
 
     let filter_title = if let Some(filter_title) = config.filter_title.as_deref() {
       Some(Regex::new(filter_title)?)
@@ -373,6 +557,7 @@ impl ResolvedConfig {
       source_path: Some(path.to_owned()),
       id: next_id(),
       match_paths,
+      inline_match_paths,
       filter_title,
       filter_class,
       filter_exec,
@@ -390,27 +575,57 @@ impl ResolvedConfig {
       backend,
       enable,
       clipboard_threshold,
+      keys_clipboard_fallback,
+      keys_clipboard_fallback_threshold,
       auto_restart,
+      watcher_quiet_period,
       pre_paste_delay,
       preserve_clipboard,
       restore_clipboard_delay,
+      paste_focus_retries,
       paste_shortcut,
       apply_patch,
       paste_shortcut_event_delay,
       disable_x11_fast_inject,
       toggle_key,
+      toggle_trigger,
       inject_delay,
       key_delay,
+      inject_chunk_size,
+      inject_chunk_delay,
       evdev_modifier_delay,
       word_separators,
       backspace_limit,
+      max_backspaces_per_expansion,
+      post_backspace_delay,
+      shell_timeout,
+      form_timeout,
+      max_propagation_depth,
+      lint_unused_global_vars,
+      lint_duplicate_triggers,
+      injection_failure_dumps,
+      trace_injected_text,
+      max_group_matches,
+      max_total_matches,
+      trigger_from_filename,
+      lazy_replace,
+      trigger_prefix_aliases,
+      regex_max_lookback,
+      allow_remote_imports,
+      remote_imports_ttl,
+      secrets_path,
+      expansion_sound,
       keyboard_layout,
       search_trigger,
       search_shortcut,
+      search_key_sequence,
+      search_case_sensitive,
+      search_max_results,
       undo_backspace,
       show_icon,
       show_notifications,
       secure_input_notification,
+      reload_notification,
       win32_exclude_orphan_events,
       win32_keyboard_layout_cache_interval,
       includes,
@@ -418,6 +633,7 @@ impl ResolvedConfig {
       extra_includes,
       extra_excludes,
       use_standard_includes,
+      inline_matches,
       filter_title,
       filter_class,
       filter_exec,
@@ -425,25 +641,31 @@ impl ResolvedConfig {
     );
   }
 
-  fn aggregate_includes(config: &ParsedConfig) -> HashSet<String> {
-    let mut includes = HashSet::new();
+  // The includes are ordered: standard includes first, then includes and finally extra_includes
+  fn aggregate_includes(config: &ParsedConfig) -> Vec<String> {
+    let mut includes: Vec<String> = Vec::new();
+    let mut add_include = |include: &str| {
+      if !includes.iter().any(|existing| existing == include) {
+        includes.push(include.to_string());
+      }
+    };
 
     if config.use_standard_includes.is_none() || config.use_standard_includes.unwrap() {
-      STANDARD_INCLUDES.iter().for_each(|include| {
-        includes.insert(include.to_string());
-      })
+      STANDARD_INCLUDES
+        .iter()
+        .for_each(|include| add_include(include))
     }
 
     if let Some(yaml_includes) = config.includes.as_ref() {
-      yaml_includes.iter().for_each(|include| {
-        includes.insert(include.to_string());
-      })
+      yaml_includes
+        .iter()
+        .for_each(|include| add_include(include))
     }
 
     if let Some(extra_includes) = config.extra_includes.as_ref() {
-      extra_includes.iter().for_each(|include| {
-        includes.insert(include.to_string());
-      })
+      extra_includes
+        .iter()
+        .for_each(|include| add_include(include))
     }
 
     includes
@@ -467,18 +689,79 @@ impl ResolvedConfig {
     excludes
   }
 
-  fn generate_match_paths(config: &ParsedConfig, base_dir: &Path) -> HashSet<String> {
+  // Paths are ordered following the includes, while excludes (and extra_excludes)
+  // always take precedence over any include
+  fn generate_match_paths(config: &ParsedConfig, base_dir: &Path) -> Vec<String> {
     let includes = Self::aggregate_includes(config);
     let excludes = Self::aggregate_excludes(config);
 
-    // Extract the paths
     let exclude_paths = calculate_paths(base_dir, excludes.iter());
-    let include_paths = calculate_paths(base_dir, includes.iter());
 
-    include_paths
-      .difference(&exclude_paths)
-      .cloned()
-      .collect::<HashSet<_>>()
+    let mut match_paths = Vec::new();
+    let mut visited_paths = HashSet::new();
+    for include in includes.iter() {
+      let mut include_paths: Vec<String> = calculate_paths(base_dir, std::iter::once(include))
+        .into_iter()
+        .collect();
+      include_paths.sort();
+
+      for path in include_paths {
+        if !exclude_paths.contains(&path) && visited_paths.insert(path.clone()) {
+          match_paths.push(path);
+        }
+      }
+    }
+
+    match_paths
+  }
+
+  fn append_inline_match_paths(match_paths: &mut Vec<String>, inline_match_paths: &[String]) {
+    for path in inline_match_paths {
+      if !match_paths.contains(path) {
+        match_paths.push(path.clone());
+      }
+    }
+  }
+
+  fn generate_overlay_match_paths(config: &ParsedConfig, config_dirs: &[PathBuf]) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut paths_by_key = HashMap::new();
+
+    for config_dir in config_dirs {
+      let root_dir = config_dir
+        .parent()
+        .and_then(|root_dir| dunce::canonicalize(root_dir).ok());
+
+      for path in Self::generate_match_paths(config, config_dir) {
+        // Paths outside of the root (for example, absolute includes) are kept as they are
+        let key = root_dir
+          .as_ref()
+          .and_then(|root_dir| Path::new(&path).strip_prefix(root_dir).ok())
+          .map(|relative| relative.to_string_lossy().to_string())
+          .unwrap_or_else(|| path.clone());
+
+        if paths_by_key.insert(key.clone(), path).is_none() {
+          keys.push(key);
+        }
+      }
+    }
+
+    keys
+      .into_iter()
+      .filter_map(|key| paths_by_key.remove(&key))
+      .collect()
+  }
+}
+
+fn validate_filter_os(filter_os: &str) -> Option<String> {
+  if KNOWN_OS.contains(&filter_os) {
+    None
+  } else {
+    Some(format!(
+      "unknown filter_os value {:?}, expected one of: {}",
+      filter_os,
+      KNOWN_OS.join(", ")
+    ))
   }
 }
 
@@ -500,10 +783,11 @@ mod tests {
       ResolvedConfig::aggregate_includes(&ParsedConfig {
         ..Default::default()
       }),
-      vec!["../match/**/[!_]*.yml".to_string(),]
-        .iter()
-        .cloned()
-        .collect::<HashSet<_>>()
+      vec![
+        "../match/**/[!_]*.yml".to_string(),
+        "../match/**/[!_]*.json".to_string(),
+        "../match/**/[!_]*.toml".to_string()
+      ]
     );
   }
 
@@ -514,7 +798,7 @@ mod tests {
         use_standard_includes: Some(false),
         ..Default::default()
       }),
-      HashSet::new()
+      Vec::<String>::new()
     );
   }
 
@@ -527,11 +811,10 @@ mod tests {
       }),
       vec![
         "../match/**/[!_]*.yml".to_string(),
+        "../match/**/[!_]*.json".to_string(),
+        "../match/**/[!_]*.toml".to_string(),
         "custom/*.yml".to_string()
       ]
-      .iter()
-      .cloned()
-      .collect::<HashSet<_>>()
     );
   }
 
@@ -544,11 +827,10 @@ mod tests {
       }),
       vec![
         "../match/**/[!_]*.yml".to_string(),
+        "../match/**/[!_]*.json".to_string(),
+        "../match/**/[!_]*.toml".to_string(),
         "custom/*.yml".to_string()
       ]
-      .iter()
-      .cloned()
-      .collect::<HashSet<_>>()
     );
   }
 
@@ -562,12 +844,11 @@ mod tests {
       }),
       vec![
         "../match/**/[!_]*.yml".to_string(),
+        "../match/**/[!_]*.json".to_string(),
+        "../match/**/[!_]*.toml".to_string(),
+        "sub/*.yml".to_string(),
         "custom/*.yml".to_string(),
-        "sub/*.yml".to_string()
       ]
-      .iter()
-      .cloned()
-      .collect::<HashSet<_>>()
     );
   }
 
@@ -636,6 +917,138 @@ mod tests {
     );
   }
 
+  #[test]
+  fn aggregate_includes_removes_duplicates() {
+    assert_eq!(
+      ResolvedConfig::aggregate_includes(&ParsedConfig {
+        includes: Some(vec!["custom/*.yml".to_string()]),
+        extra_includes: Some(vec![
+          "custom/*.yml".to_string(),
+          "../match/**/[!_]*.yml".to_string()
+        ]),
+        ..Default::default()
+      }),
+      vec![
+        "../match/**/[!_]*.yml".to_string(),
+        "../match/**/[!_]*.json".to_string(),
+        "../match/**/[!_]*.toml".to_string(),
+        "custom/*.yml".to_string()
+      ]
+    );
+  }
+
+  // Creates the following structure, returning the generated match paths:
+  // match/base.yml, match/_hidden.yml, custom/custom.yml, extra/extra.yml
+  fn generate_test_match_paths(config: ParsedConfig) -> Vec<String> {
+    let mut result = Vec::new();
+    use_test_directory(|base, match_dir, config_dir| {
+      std::fs::write(match_dir.join("base.yml"), "test").unwrap();
+      std::fs::write(match_dir.join("_hidden.yml"), "test").unwrap();
+
+      let custom_dir = base.join("custom");
+      create_dir_all(&custom_dir).unwrap();
+      std::fs::write(custom_dir.join("custom.yml"), "test").unwrap();
+
+      let extra_dir = base.join("extra");
+      create_dir_all(&extra_dir).unwrap();
+      std::fs::write(extra_dir.join("extra.yml"), "test").unwrap();
+
+      result = ResolvedConfig::generate_match_paths(&config, config_dir)
+        .into_iter()
+        .map(|path| {
+          Path::new(&path)
+            .strip_prefix(base)
+            .unwrap()
+            .to_string_lossy()
+            .replace('\\', "/")
+        })
+        .collect();
+    });
+    result
+  }
+
+  #[test]
+  fn generate_match_paths_standard_includes() {
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig::default()),
+      vec!["match/base.yml"]
+    );
+  }
+
+  #[test]
+  fn generate_match_paths_follows_includes_order() {
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig {
+        includes: Some(vec!["../match/_hidden.yml".to_string()]),
+        extra_includes: Some(vec!["../extra/*.yml".to_string()]),
+        ..Default::default()
+      }),
+      vec!["match/base.yml", "match/_hidden.yml", "extra/extra.yml"]
+    );
+
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig {
+        includes: Some(vec!["../extra/*.yml".to_string()]),
+        extra_includes: Some(vec!["../custom/*.yml".to_string()]),
+        ..Default::default()
+      }),
+      vec!["match/base.yml", "extra/extra.yml", "custom/custom.yml"]
+    );
+  }
+
+  #[test]
+  fn generate_match_paths_no_standard_includes() {
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig {
+        use_standard_includes: Some(false),
+        ..Default::default()
+      }),
+      Vec::<String>::new()
+    );
+
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig {
+        use_standard_includes: Some(false),
+        includes: Some(vec!["../custom/*.yml".to_string()]),
+        extra_includes: Some(vec!["../extra/*.yml".to_string()]),
+        ..Default::default()
+      }),
+      vec!["custom/custom.yml", "extra/extra.yml"]
+    );
+  }
+
+  #[test]
+  fn generate_match_paths_excludes_take_precedence() {
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig {
+        extra_includes: Some(vec!["../extra/*.yml".to_string()]),
+        excludes: Some(vec!["../extra/extra.yml".to_string()]),
+        ..Default::default()
+      }),
+      vec!["match/base.yml"]
+    );
+
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig {
+        includes: Some(vec!["../custom/*.yml".to_string()]),
+        extra_includes: Some(vec!["../extra/*.yml".to_string()]),
+        extra_excludes: Some(vec!["../match/*.yml".to_string()]),
+        ..Default::default()
+      }),
+      vec!["custom/custom.yml", "extra/extra.yml"]
+    );
+
+    assert_eq!(
+      generate_test_match_paths(ParsedConfig {
+        extra_includes: Some(vec!["../custom/*.yml".to_string()]),
+        excludes: Some(vec!["../custom/*.yml".to_string()]),
+        extra_excludes: Some(vec!["../match/base.yml".to_string()]),
+        ..Default::default()
+      }),
+      Vec::<String>::new()
+    );
+  }
+
   #[test]
   fn merge_parent_field_parent_fallback() {
     let parent = ParsedConfig {
@@ -941,6 +1354,29 @@ mod tests {
     ));
   }
 
+  #[test]
+  fn validate_filter_os_values() {
+    assert_eq!(validate_filter_os("windows"), None);
+    assert_eq!(validate_filter_os("macos"), None);
+    assert_eq!(validate_filter_os("linux"), None);
+
+    let warning = validate_filter_os("widows").unwrap();
+    assert!(warning.contains("\"widows\""));
+    assert!(warning.contains("windows, macos, linux"));
+  }
+
+  #[test]
+  fn unknown_filter_os_is_applied_literally() {
+    assert!(!test_filter_is_match(
+      "filter_os: widows",
+      &AppProperties {
+        title: Some("Google Mail"),
+        class: Some("Chrome"),
+        exec: Some("chrome.exe"),
+      },
+    ));
+  }
+
   #[test]
   fn is_match_multiple_filters() {
     assert!(test_filter_is_match(
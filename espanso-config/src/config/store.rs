@@ -23,7 +23,7 @@ use super::{resolve::ResolvedConfig, Config, ConfigStore, ConfigStoreError};
 use anyhow::{Context, Result};
 use log::{debug, error};
 use std::sync::Arc;
-use std::{collections::HashSet, path::Path};
+use std::{collections::HashSet, path::PathBuf};
 
 pub(crate) struct DefaultConfigStore {
   default: Arc<dyn Config>,
@@ -69,51 +69,85 @@ impl ConfigStore for DefaultConfigStore {
 }
 
 impl DefaultConfigStore {
-  pub fn load(config_dir: &Path) -> Result<(Self, Vec<NonFatalErrorSet>)> {
-    if !config_dir.is_dir() {
+  // Config files in the later directories override the ones with the same name
+  // in the earlier directories, while the match paths are merged across all of them
+  pub fn load(config_dirs: &[PathBuf], safe_mode: bool) -> Result<(Self, Vec<NonFatalErrorSet>)> {
+    if config_dirs.is_empty() || config_dirs.iter().any(|dir| !dir.is_dir()) {
       return Err(ConfigStoreError::InvalidConfigDir().into());
     }
 
     // First get the default.yml file
-    let default_file = config_dir.join("default.yml");
-    if !default_file.exists() || !default_file.is_file() {
+    let default_file = config_dirs
+      .iter()
+      .rev()
+      .map(|dir| dir.join("default.yml"))
+      .find(|file| file.is_file())
+      .unwrap_or_else(|| config_dirs[config_dirs.len() - 1].join("default.yml"));
+    if !safe_mode && (!default_file.exists() || !default_file.is_file()) {
       return Err(ConfigStoreError::MissingDefault().into());
     }
 
     let mut non_fatal_errors = Vec::new();
 
-    let default = ResolvedConfig::load(&default_file, None)
-      .context("failed to load default.yml configuration")?;
+    let mut default = match ResolvedConfig::load(&default_file, None)
+      .context("failed to load default.yml configuration")
+    {
+      Ok(default) => default,
+      // In safe mode, an invalid default.yml is replaced with the default options
+      Err(err) if safe_mode => {
+        error!(
+          "unable to load default.yml, using default options instead: {:?}",
+          err
+        );
+        non_fatal_errors.push(NonFatalErrorSet::single_error(&default_file, err));
+        ResolvedConfig::fallback(&default_file)?
+      }
+      Err(err) => return Err(err),
+    };
+    if config_dirs.len() > 1 {
+      default.overlay_match_paths(config_dirs);
+    }
     debug!("loaded default config at path: {:?}", default_file);
 
     // Then the others
+    let mut config_files: Vec<PathBuf> = Vec::new();
+    for config_dir in config_dirs {
+      for entry in std::fs::read_dir(config_dir).map_err(ConfigStoreError::IOError)? {
+        let entry = entry?;
+        let config_file = entry.path();
+        let extension = config_file
+          .extension()
+          .unwrap_or_default()
+          .to_string_lossy()
+          .to_lowercase();
+
+        if config_file.is_file()
+          && config_file.file_name() != default_file.file_name()
+          && (extension == "yml" || extension == "yaml")
+        {
+          config_files.retain(|file| file.file_name() != config_file.file_name());
+          config_files.push(config_file);
+        }
+      }
+    }
+
+    // Additional config files are loaded best-effort
     let mut customs: Vec<Arc<dyn Config>> = Vec::new();
-    for entry in std::fs::read_dir(config_dir).map_err(ConfigStoreError::IOError)? {
-      let entry = entry?;
-      let config_file = entry.path();
-      let extension = config_file
-        .extension()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_lowercase();
-
-      // Additional config files are loaded best-effort
-      if config_file.is_file()
-        && config_file != default_file
-        && (extension == "yml" || extension == "yaml")
-      {
-        match ResolvedConfig::load(&config_file, Some(&default)) {
-          Ok(config) => {
-            customs.push(Arc::new(config));
-            debug!("loaded config at path: {:?}", config_file);
-          }
-          Err(err) => {
-            error!(
-              "unable to load config at path: {:?}, with error: {}",
-              config_file, err
-            );
-            non_fatal_errors.push(NonFatalErrorSet::single_error(&config_file, err));
+    for config_file in config_files {
+      match ResolvedConfig::load(&config_file, Some(&default)) {
+        Ok(mut config) => {
+          if config_dirs.len() > 1 {
+            config.overlay_match_paths(config_dirs);
           }
+          customs.push(Arc::new(config));
+          debug!("loaded config at path: {:?}", config_file);
+        }
+        Err(err) => {
+          error!(
+            "unable to load config at path: {:?}, with error: {}",
+            config_file, err
+          );
+          non_fatal_errors.push(NonFatalErrorSet::single_error(&config_file, err));
         }
       }
     }
@@ -38,6 +38,9 @@ macro_rules! merge {
   };
 }
 
+// Values accepted by the filter_os option
+pub const KNOWN_OS: &[&str] = &["windows", "macos", "linux"];
+
 pub fn os_matches(os: &str) -> bool {
   match os {
     "macos" => cfg!(target_os = "macos"),
@@ -20,7 +20,11 @@
 use anyhow::Result;
 use log::warn;
 use regex::Regex;
-use std::{collections::HashMap, path::Path, sync::Arc};
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::Path,
+  sync::Arc,
+};
 
 use self::config::LegacyConfig;
 use crate::matches::{
@@ -45,6 +49,9 @@ use std::convert::TryInto;
 mod config;
 mod model;
 
+// Matches are hashed by their definition, the compiled field filters included.
+// The regex caches are never part of the hash, so they are safe to use as keys
+#[allow(clippy::mutable_key_type)]
 pub fn load(
   base_dir: &Path,
   package_dir: &Path,
@@ -121,6 +128,7 @@ fn split_config(config: LegacyConfig) -> (LegacyInteropConfig, LegacyMatchGroup)
 
 /// Due to the way the legacy configs are loaded (matches are copied multiple times in the various configs)
 /// we need to deduplicate the ids of those matches (and global vars).
+#[allow(clippy::mutable_key_type)]
 fn deduplicate_ids(
   match_group: &mut LegacyMatchGroup,
   match_map: &mut HashMap<Match, StructId>,
@@ -130,6 +138,7 @@ fn deduplicate_ids(
   deduplicate_matches(&mut match_group.matches, match_map, var_map);
 }
 
+#[allow(clippy::mutable_key_type)]
 fn deduplicate_matches(
   matches: &mut [Match],
   match_map: &mut HashMap<Match, StructId>,
@@ -224,6 +233,10 @@ impl Config for LegacyInteropConfig {
     self.config.auto_restart
   }
 
+  fn watcher_quiet_period(&self) -> u64 {
+    crate::config::default::DEFAULT_WATCHER_QUIET_PERIOD
+  }
+
   fn match_paths(&self) -> &[String] {
     &self.match_paths
   }
@@ -271,6 +284,14 @@ impl Config for LegacyInteropConfig {
     crate::config::default::DEFAULT_CLIPBOARD_THRESHOLD
   }
 
+  fn keys_clipboard_fallback(&self) -> bool {
+    false
+  }
+
+  fn keys_clipboard_fallback_threshold(&self) -> usize {
+    crate::config::default::DEFAULT_KEYS_CLIPBOARD_FALLBACK_THRESHOLD
+  }
+
   fn pre_paste_delay(&self) -> usize {
     crate::config::default::DEFAULT_PRE_PASTE_DELAY
   }
@@ -295,6 +316,10 @@ impl Config for LegacyInteropConfig {
     }
   }
 
+  fn toggle_trigger(&self) -> Option<String> {
+    None
+  }
+
   fn preserve_clipboard(&self) -> bool {
     self.config.preserve_clipboard
   }
@@ -303,6 +328,10 @@ impl Config for LegacyInteropConfig {
     self.config.restore_clipboard_delay.try_into().unwrap()
   }
 
+  fn paste_focus_retries(&self) -> usize {
+    1
+  }
+
   fn paste_shortcut_event_delay(&self) -> usize {
     crate::config::default::DEFAULT_SHORTCUT_EVENT_DELAY
   }
@@ -338,6 +367,14 @@ impl Config for LegacyInteropConfig {
     }
   }
 
+  fn inject_chunk_size(&self) -> usize {
+    0
+  }
+
+  fn inject_chunk_delay(&self) -> usize {
+    crate::config::default::DEFAULT_INJECT_CHUNK_DELAY
+  }
+
   fn word_separators(&self) -> Vec<String> {
     self
       .config
@@ -351,6 +388,82 @@ impl Config for LegacyInteropConfig {
     self.config.backspace_limit.try_into().unwrap()
   }
 
+  fn max_backspaces_per_expansion(&self) -> usize {
+    crate::config::default::DEFAULT_MAX_BACKSPACES_PER_EXPANSION
+  }
+
+  fn post_backspace_delay(&self) -> usize {
+    0
+  }
+
+  fn shell_timeout(&self) -> usize {
+    crate::config::default::DEFAULT_SHELL_TIMEOUT
+  }
+
+  fn form_timeout(&self) -> u64 {
+    0
+  }
+
+  fn max_propagation_depth(&self) -> usize {
+    3
+  }
+
+  fn lint_unused_global_vars(&self) -> bool {
+    false
+  }
+
+  fn lint_duplicate_triggers(&self) -> bool {
+    false
+  }
+
+  fn injection_failure_dumps(&self) -> bool {
+    false
+  }
+
+  fn trace_injected_text(&self) -> bool {
+    false
+  }
+
+  fn max_group_matches(&self) -> usize {
+    crate::config::default::DEFAULT_MAX_GROUP_MATCHES
+  }
+
+  fn max_total_matches(&self) -> usize {
+    crate::config::default::DEFAULT_MAX_TOTAL_MATCHES
+  }
+
+  fn trigger_from_filename(&self) -> bool {
+    false
+  }
+
+  fn lazy_replace(&self) -> bool {
+    false
+  }
+
+  fn trigger_prefix_aliases(&self) -> BTreeMap<String, Vec<String>> {
+    BTreeMap::new()
+  }
+
+  fn regex_max_lookback(&self) -> usize {
+    crate::config::default::DEFAULT_REGEX_MAX_LOOKBACK
+  }
+
+  fn allow_remote_imports(&self) -> bool {
+    false
+  }
+
+  fn remote_imports_ttl(&self) -> u64 {
+    crate::config::default::DEFAULT_REMOTE_IMPORTS_TTL
+  }
+
+  fn secrets_path(&self) -> Option<String> {
+    None
+  }
+
+  fn expansion_sound(&self) -> Option<String> {
+    None
+  }
+
   fn apply_patch(&self) -> bool {
     true
   }
@@ -367,6 +480,18 @@ impl Config for LegacyInteropConfig {
     self.config.search_shortcut.clone()
   }
 
+  fn search_key_sequence(&self) -> Option<String> {
+    None
+  }
+
+  fn search_case_sensitive(&self) -> bool {
+    false
+  }
+
+  fn search_max_results(&self) -> Option<usize> {
+    None
+  }
+
   fn undo_backspace(&self) -> bool {
     self.config.undo_backspace
   }
@@ -383,6 +508,10 @@ impl Config for LegacyInteropConfig {
     self.config.secure_input_notification
   }
 
+  fn reload_notification(&self) -> bool {
+    true
+  }
+
   fn enable(&self) -> bool {
     self.config.enable_active
   }
@@ -439,6 +568,18 @@ impl MatchStore for LegacyMatchStore {
   fn loaded_paths(&self) -> Vec<String> {
     self.groups.keys().cloned().collect()
   }
+
+  fn source_path(&self, match_id: i32) -> Option<&str> {
+    self
+      .groups
+      .iter()
+      .find(|(_, group)| group.matches.iter().any(|m| m.id == match_id))
+      .map(|(path, _)| path.as_str())
+  }
+
+  fn group_label(&self, _: i32) -> Option<&str> {
+    None
+  }
 }
 
 #[cfg(test)]
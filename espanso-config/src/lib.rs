@@ -19,8 +19,9 @@
 
 use anyhow::Result;
 use config::ConfigStore;
+use log::warn;
 use matches::store::MatchStore;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[macro_use]
@@ -31,8 +32,29 @@ mod counter;
 pub mod error;
 mod legacy;
 pub mod matches;
+pub mod secrets;
 mod util;
 
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+  // If true, files that cannot be loaded (including the default.yml config)
+  // are reported as non-fatal errors instead of aborting the loading
+  pub safe_mode: bool,
+
+  // Config roots loaded before the main one, such as a shared read-only config.
+  // Files in the later roots override the ones with the same relative path
+  // in the earlier roots, while the other files are merged
+  pub base_paths: Vec<PathBuf>,
+
+  // Per-user directory (such as one inside the runtime dir) in which remote
+  // imports are cached. Remote imports are skipped if not set
+  pub remote_imports_cache_dir: Option<PathBuf>,
+
+  // Checks the (normalized) hotkeys against the keys supported by the
+  // detection backend. Matches with an invalid hotkey are skipped
+  pub hotkey_validator: Option<matches::store::HotKeyValidator>,
+}
+
 #[allow(clippy::type_complexity)]
 pub fn load(
   base_path: &Path,
@@ -41,16 +63,85 @@ pub fn load(
   Box<dyn MatchStore>,
   Vec<error::NonFatalErrorSet>,
 )> {
-  let config_dir = base_path.join("config");
-  if !config_dir.exists() || !config_dir.is_dir() {
-    return Err(ConfigError::MissingConfigDir().into());
+  load_with_options(base_path, &LoadOptions::default())
+}
+
+#[allow(clippy::type_complexity)]
+pub fn load_with_options(
+  base_path: &Path,
+  options: &LoadOptions,
+) -> Result<(
+  Box<dyn ConfigStore>,
+  Box<dyn MatchStore>,
+  Vec<error::NonFatalErrorSet>,
+)> {
+  let mut config_dirs = Vec::new();
+  for root in options
+    .base_paths
+    .iter()
+    .map(PathBuf::as_path)
+    .chain(std::iter::once(base_path))
+  {
+    let config_dir = root.join("config");
+    if !config_dir.exists() || !config_dir.is_dir() {
+      return Err(ConfigError::MissingConfigDir().into());
+    }
+    config_dirs.push(config_dir);
   }
 
-  let (config_store, non_fatal_config_errors) = config::load_store(&config_dir)?;
+  let (config_store, non_fatal_config_errors) =
+    config::load_store(&config_dirs, options.safe_mode)?;
   let root_paths = config_store.get_all_match_paths();
 
-  let (match_store, non_fatal_match_errors) =
-    matches::store::load(&root_paths.into_iter().collect::<Vec<String>>());
+  // The match paths pointing inside the config directories are config
+  // files defining their matches inline
+  let canonical_config_dirs: Vec<PathBuf> = config_dirs
+    .iter()
+    .map(|dir| dunce::canonicalize(dir).unwrap_or_else(|_| dir.clone()))
+    .collect();
+  let inline_group_paths = root_paths
+    .iter()
+    .filter(|path| {
+      canonical_config_dirs
+        .iter()
+        .any(|dir| Path::new(path).starts_with(dir))
+    })
+    .cloned()
+    .collect();
+
+  let default_config = config_store.default();
+  let match_store_options = matches::store::MatchStoreOptions {
+    max_group_matches: default_config.max_group_matches(),
+    max_total_matches: default_config.max_total_matches(),
+    trigger_from_filename: default_config.trigger_from_filename(),
+    lazy_replace: default_config.lazy_replace(),
+    remote_imports: match &options.remote_imports_cache_dir {
+      Some(cache_dir) if default_config.allow_remote_imports() => {
+        Some(matches::store::remote::RemoteImportOptions {
+          cache_dir: cache_dir.clone(),
+          ttl: std::time::Duration::from_secs(default_config.remote_imports_ttl()),
+        })
+      }
+      _ => None,
+    },
+    inline_group_paths,
+    hotkey_validator: options.hotkey_validator,
+    ..Default::default()
+  };
+  let (match_store, non_fatal_match_errors) = matches::store::load(
+    &root_paths.into_iter().collect::<Vec<String>>(),
+    &match_store_options,
+  );
+
+  if default_config.lint_unused_global_vars() {
+    let match_set = match_store.query(&match_store.loaded_paths());
+    for name in matches::store::lint::find_unused_global_vars(&match_set) {
+      warn!(
+        "global variable '{}' is never referenced by any match",
+        name
+      );
+    }
+  }
 
   let mut non_fatal_errors = Vec::new();
   non_fatal_errors.extend(non_fatal_config_errors.into_iter());
@@ -63,6 +154,24 @@ pub fn load(
   ))
 }
 
+// Loads every config file and match group in the config tree, collecting
+// all the errors instead of stopping at the first one
+pub fn check(base_path: &Path) -> Result<Vec<error::NonFatalErrorSet>> {
+  let config_dir = base_path.join("config");
+  if !config_dir.exists() || !config_dir.is_dir() {
+    return Err(ConfigError::MissingConfigDir().into());
+  }
+
+  let mut errors = config::check_dir(&config_dir)?;
+
+  let match_dir = base_path.join("match");
+  if match_dir.is_dir() {
+    errors.extend(matches::group::check_dir(&match_dir));
+  }
+
+  Ok(errors)
+}
+
 pub fn load_legacy(
   config_dir: &Path,
   package_dir: &Path,
@@ -181,6 +290,110 @@ mod tests {
     });
   }
 
+  #[test]
+  fn load_includes_json_and_toml_match_files() {
+    use_test_directory(|base, match_dir, config_dir| {
+      std::fs::write(
+        match_dir.join("base.json"),
+        r#"{"matches": [{"trigger": "hello", "replace": "world"}]}"#,
+      )
+      .unwrap();
+      std::fs::write(
+        match_dir.join("other.toml"),
+        "[[matches]]\ntrigger = \"hi\"\nreplace = \"there\"\n",
+      )
+      .unwrap();
+      std::fs::write(
+        match_dir.join("_sub.json"),
+        r#"{"matches": [{"trigger": "ignored", "replace": "world"}]}"#,
+      )
+      .unwrap();
+      std::fs::write(config_dir.join("default.yml"), "").unwrap();
+
+      let (config_store, match_store, errors) = load(base).unwrap();
+
+      assert_eq!(errors.len(), 0);
+      assert_eq!(
+        match_store
+          .query(config_store.default().match_paths())
+          .matches
+          .len(),
+        2
+      );
+    });
+  }
+
+  #[test]
+  fn load_inline_matches_from_config() {
+    use_test_directory(|base, match_dir, config_dir| {
+      std::fs::write(
+        match_dir.join("base.yml"),
+        r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(
+        config_dir.join("default.yml"),
+        r#"
+      backend: Clipboard
+      label: "Default config"
+      word_separators: ["."]
+
+      matches:
+        - trigger: "inline"
+          replace: "match"
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(
+        config_dir.join("custom.yml"),
+        r#"
+      filter_title: "Chrome"
+      use_standard_includes: false
+      "#,
+      )
+      .unwrap();
+
+      let (config_store, match_store, errors) = load(base).unwrap();
+      assert_eq!(errors.len(), 0);
+
+      let replacements = |paths: &[String]| -> Vec<String> {
+        match_store
+          .query(paths)
+          .matches
+          .iter()
+          .map(|m| m.description().to_string())
+          .collect()
+      };
+
+      let mut default_replacements = replacements(config_store.default().match_paths());
+      default_replacements.sort();
+      assert_eq!(default_replacements, vec!["match", "world"]);
+
+      // Inline matches are inherited by the custom configs
+      let custom_config = config_store.active(&AppProperties {
+        title: Some("Chrome"),
+        class: None,
+        exec: None,
+      });
+      assert_eq!(replacements(custom_config.match_paths()), vec!["match"]);
+
+      // The label and word separators configure espanso, not the inline group
+      let match_set = match_store.query(custom_config.match_paths());
+      let inline_match = match_set.matches[0];
+      assert_eq!(match_store.group_label(inline_match.id), None);
+      assert_eq!(
+        inline_match.cause.as_trigger().unwrap().word_separators,
+        None
+      );
+    });
+  }
+
   #[test]
   fn load_non_fatal_errors() {
     use_test_directory(|base, match_dir, config_dir| {
@@ -309,6 +522,246 @@ mod tests {
     });
   }
 
+  #[test]
+  fn load_multiple_roots_merges_and_overrides_matches() {
+    use_test_directory(|shared, shared_match_dir, shared_config_dir| {
+      use_test_directory(|personal, personal_match_dir, _| {
+        std::fs::write(shared_config_dir.join("default.yml"), "").unwrap();
+        std::fs::write(
+          shared_match_dir.join("base.yml"),
+          r#"
+        matches:
+          - trigger: "hello"
+            replace: "shared"
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+          shared_match_dir.join("team.yml"),
+          r#"
+        matches:
+          - trigger: "team"
+            replace: "shared team"
+        "#,
+        )
+        .unwrap();
+
+        // Overrides the shared file with the same path
+        std::fs::write(
+          personal_match_dir.join("base.yml"),
+          r#"
+        matches:
+          - trigger: "hello"
+            replace: "personal"
+        "#,
+        )
+        .unwrap();
+        // Added to the shared ones
+        std::fs::write(
+          personal_match_dir.join("mine.yml"),
+          r#"
+        matches:
+          - trigger: "mine"
+            replace: "personal mine"
+        "#,
+        )
+        .unwrap();
+
+        let (config_store, match_store, errors) = load_with_options(
+          personal,
+          &LoadOptions {
+            base_paths: vec![shared.to_path_buf()],
+            ..Default::default()
+          },
+        )
+        .unwrap();
+        assert_eq!(errors.len(), 0);
+
+        let match_set = match_store.query(config_store.default().match_paths());
+        let mut replacements: Vec<&str> = match_set
+          .matches
+          .iter()
+          .map(|m| m.effect.as_text().unwrap().replace.as_str())
+          .collect();
+        replacements.sort_unstable();
+        assert_eq!(
+          replacements,
+          vec!["personal", "personal mine", "shared team"]
+        );
+      });
+    });
+  }
+
+  #[test]
+  fn load_multiple_roots_overrides_configs_by_name() {
+    use_test_directory(|shared, _, shared_config_dir| {
+      use_test_directory(|personal, _, personal_config_dir| {
+        std::fs::write(shared_config_dir.join("default.yml"), "backend: Clipboard").unwrap();
+        std::fs::write(
+          shared_config_dir.join("chrome.yml"),
+          r#"
+        filter_title: "Chrome"
+        label: "shared chrome"
+        "#,
+        )
+        .unwrap();
+        std::fs::write(
+          personal_config_dir.join("chrome.yml"),
+          r#"
+        filter_title: "Chrome"
+        label: "personal chrome"
+        "#,
+        )
+        .unwrap();
+
+        let (config_store, _, errors) = load_with_options(
+          personal,
+          &LoadOptions {
+            base_paths: vec![shared.to_path_buf()],
+            ..Default::default()
+          },
+        )
+        .unwrap();
+        assert_eq!(errors.len(), 0);
+
+        // The personal root has no default.yml, so the shared one is used
+        assert_eq!(
+          config_store.default().backend(),
+          crate::config::Backend::Clipboard
+        );
+        assert_eq!(config_store.configs().len(), 2);
+        assert_eq!(
+          config_store
+            .active(&AppProperties {
+              title: Some("Chrome"),
+              class: None,
+              exec: None,
+            })
+            .label(),
+          "personal chrome"
+        );
+      });
+    });
+  }
+
+  #[test]
+  fn load_safe_mode_skips_broken_files() {
+    use_test_directory(|base, match_dir, config_dir| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      matches:
+        - trigger: hello
+          replace: world
+      "#,
+      )
+      .unwrap();
+
+      let broken_file = match_dir.join("broken.yml");
+      std::fs::write(
+        &broken_file,
+        r#"
+      matches:
+        - trigger: "broken
+      "#,
+      )
+      .unwrap();
+
+      let config_file = config_dir.join("default.yml");
+      std::fs::write(
+        &config_file,
+        r#"
+      invalid
+
+      "
+      "#,
+      )
+      .unwrap();
+
+      let (config_store, match_store, errors) = load_with_options(
+        base,
+        &LoadOptions {
+          safe_mode: true,
+          ..Default::default()
+        },
+      )
+      .unwrap();
+
+      let mut failed_files: Vec<&Path> = errors.iter().map(|set| set.file.as_path()).collect();
+      failed_files.sort();
+      assert_eq!(
+        failed_files,
+        vec![config_file.as_path(), broken_file.as_path()]
+      );
+
+      let matches = match_store
+        .query(config_store.default().match_paths())
+        .matches;
+      assert_eq!(matches.len(), 1);
+      assert_eq!(
+        matches[0].cause.as_trigger().unwrap().triggers,
+        vec!["hello".to_string()]
+      );
+    });
+  }
+
+  #[test]
+  fn check_reports_all_broken_files() {
+    use_test_directory(|base, match_dir, config_dir| {
+      std::fs::write(
+        match_dir.join("base.yml"),
+        r#"
+      matches:
+        - trigger: hello
+          replace: world
+      "#,
+      )
+      .unwrap();
+
+      let broken_import = match_dir.join("_broken.yml");
+      std::fs::write(
+        &broken_import,
+        r#"
+      matches:
+        - trigger: "broken
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(config_dir.join("default.yml"), "").unwrap();
+
+      let broken_config = config_dir.join("custom.yml");
+      std::fs::write(&broken_config, "filter_title: \"(\"").unwrap();
+
+      let errors = check(base).unwrap();
+
+      let failed_files: Vec<&Path> = errors.iter().map(|set| set.file.as_path()).collect();
+      assert_eq!(
+        failed_files,
+        vec![broken_config.as_path(), broken_import.as_path()]
+      );
+    });
+  }
+
+  #[test]
+  fn check_valid_config_has_no_errors() {
+    use_test_directory(|base, match_dir, config_dir| {
+      std::fs::write(
+        match_dir.join("base.yml"),
+        r#"
+      matches:
+        - trigger: hello
+          replace: world
+      "#,
+      )
+      .unwrap();
+      std::fs::write(config_dir.join("default.yml"), "").unwrap();
+
+      assert!(check(base).unwrap().is_empty());
+    });
+  }
+
   #[test]
   fn load_without_valid_config_dir() {
     use_test_directory(|_, match_dir, _| {
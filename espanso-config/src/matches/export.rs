@@ -0,0 +1,29 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use anyhow::Result;
+
+use super::{group::loader::yaml::export::export_match_group, store::MatchSet};
+
+// Serializes the given matches and global variables into a single
+// YAML match group, that can be loaded back as a regular match file.
+// Imports are already resolved in the match set, so the result is self-contained.
+pub fn export_yaml(match_set: &MatchSet) -> Result<String> {
+  export_match_group(&match_set.matches, &match_set.global_vars)
+}
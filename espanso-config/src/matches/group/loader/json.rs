@@ -0,0 +1,164 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::error::NonFatalErrorSet;
+
+use super::{
+  yaml::{convert_match_group, parse::YAMLMatchGroup},
+  Importer,
+};
+use crate::matches::group::MatchGroup;
+
+// JSON match groups share the same structure of the YAML ones
+pub(crate) struct JSONImporter {}
+
+impl JSONImporter {
+  pub fn new() -> Self {
+    Self {}
+  }
+}
+
+impl Importer for JSONImporter {
+  fn is_supported(&self, extension: &str) -> bool {
+    extension == "json"
+  }
+
+  fn load_group(
+    &self,
+    path: &Path,
+    max_matches: usize,
+  ) -> Result<(MatchGroup, Option<NonFatalErrorSet>)> {
+    let content = std::fs::read_to_string(path)?;
+    let group: YAMLMatchGroup = serde_json::from_str(&content)
+      .with_context(|| format!("unable to parse match group file: {:?}", path))
+      .context("failed to parse JSON match group")?;
+
+    convert_match_group(path, group, max_matches)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    matches::{
+      group::loader::yaml::{parse::YAMLMatch, try_convert_into_match},
+      Match, MatchCause, MatchEffect, TextEffect, TriggerCause,
+    },
+    util::tests::use_test_directory,
+  };
+
+  fn create_match(json: &str) -> Result<Match> {
+    let yaml_match: YAMLMatch = serde_json::from_str(json)?;
+    let (mut m, warnings) = try_convert_into_match(yaml_match, false)?;
+    assert!(warnings.is_empty());
+
+    // Reset the ID to correctly compare them
+    m.id = 0;
+    Ok(m)
+  }
+
+  #[test]
+  fn basic_match_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        {
+          "trigger": "Hello",
+          "replace": "world"
+        }
+        "#
+      )
+      .unwrap(),
+      Match {
+        cause: MatchCause::Trigger(TriggerCause {
+          triggers: vec!["Hello".to_string()],
+          ..Default::default()
+        }),
+        effect: MatchEffect::Text(TextEffect {
+          replace: "world".to_string(),
+          ..Default::default()
+        }),
+        ..Default::default()
+      }
+    )
+  }
+
+  #[test]
+  fn importer_is_supported() {
+    let importer = JSONImporter::new();
+    assert!(importer.is_supported("json"));
+    assert!(!importer.is_supported("yml"));
+  }
+
+  #[test]
+  fn importer_works_correctly() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.json");
+      std::fs::write(
+        &base_file,
+        r#"
+        {
+          "global_vars": [
+            { "name": "var1", "type": "test" }
+          ],
+          "matches": [
+            {
+              "trigger": "hello",
+              "replace": "world {{var2}}",
+              "vars": [
+                { "name": "var2", "type": "echo", "params": { "echo": "!" } }
+              ]
+            },
+            { "trigger": "foo", "replace": "bar" }
+          ]
+        }
+        "#,
+      )
+      .unwrap();
+
+      let importer = JSONImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert!(non_fatal_error_set.is_none());
+
+      assert_eq!(group.global_vars.len(), 1);
+      assert_eq!(group.global_vars[0].name, "var1");
+      assert_eq!(group.matches.len(), 2);
+      assert_eq!(group.matches[0].effect.as_text().unwrap().vars.len(), 1);
+      assert_eq!(
+        group.matches[1].cause.as_trigger().unwrap().triggers,
+        vec!["foo".to_string()]
+      );
+    });
+  }
+
+  #[test]
+  fn importer_invalid_json() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.json");
+      std::fs::write(&base_file, "{ \"matches\": [ }").unwrap();
+
+      let importer = JSONImporter::new();
+      assert!(importer.load_group(&base_file, usize::MAX).is_err());
+    });
+  }
+}
@@ -23,22 +23,56 @@ use thiserror::Error;
 
 use crate::error::NonFatalErrorSet;
 
-use self::yaml::YAMLImporter;
+use self::{json::JSONImporter, toml::TOMLImporter, yaml::YAMLImporter};
 
 use super::MatchGroup;
 
+pub(crate) mod json;
+pub(crate) mod toml;
 pub(crate) mod yaml;
 
 trait Importer {
   fn is_supported(&self, extension: &str) -> bool;
-  fn load_group(&self, path: &Path) -> Result<(MatchGroup, Option<NonFatalErrorSet>)>;
+  fn load_group(
+    &self,
+    path: &Path,
+    max_matches: usize,
+  ) -> Result<(MatchGroup, Option<NonFatalErrorSet>)>;
 }
 
 lazy_static! {
-  static ref IMPORTERS: Vec<Box<dyn Importer + Sync + Send>> = vec![Box::new(YAMLImporter::new()),];
+  static ref IMPORTERS: Vec<Box<dyn Importer + Sync + Send>> = vec![
+    Box::new(YAMLImporter::new()),
+    Box::new(JSONImporter::new()),
+    Box::new(TOMLImporter::new()),
+  ];
+}
+
+// Limits enforced while loading a group, before and right after parsing, so that
+// corrupted or machine-generated files don't exhaust the memory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LoadLimits {
+  pub max_matches: usize,
+  pub max_file_size: u64,
+}
+
+impl Default for LoadLimits {
+  fn default() -> Self {
+    Self {
+      max_matches: usize::MAX,
+      max_file_size: u64::MAX,
+    }
+  }
 }
 
 pub(crate) fn load_match_group(path: &Path) -> Result<(MatchGroup, Option<NonFatalErrorSet>)> {
+  load_match_group_with_limits(path, &LoadLimits::default())
+}
+
+pub(crate) fn load_match_group_with_limits(
+  path: &Path,
+  limits: &LoadLimits,
+) -> Result<(MatchGroup, Option<NonFatalErrorSet>)> {
   if let Some(extension) = path.extension() {
     let extension = extension.to_string_lossy().to_lowercase();
 
@@ -46,8 +80,10 @@ pub(crate) fn load_match_group(path: &Path) -> Result<(MatchGroup, Option<NonFat
       .iter()
       .find(|importer| importer.is_supported(&extension));
 
+    check_file_size(path, limits)?;
+
     match importer {
-      Some(importer) => match importer.load_group(path) {
+      Some(importer) => match importer.load_group(path, limits.max_matches) {
         Ok((group, non_fatal_error_set)) => Ok((group, non_fatal_error_set)),
         Err(err) => Err(LoadError::ParsingError(err).into()),
       },
@@ -58,6 +94,43 @@ pub(crate) fn load_match_group(path: &Path) -> Result<(MatchGroup, Option<NonFat
   }
 }
 
+// Loads the matches defined inline in a config file, ignoring the other keys
+pub(crate) fn load_inline_match_group_with_limits(
+  path: &Path,
+  limits: &LoadLimits,
+) -> Result<(MatchGroup, Option<NonFatalErrorSet>)> {
+  check_file_size(path, limits)?;
+
+  yaml::load_inline_group(path, limits.max_matches)
+    .map_err(|err| LoadError::ParsingError(err).into())
+}
+
+fn check_file_size(path: &Path, limits: &LoadLimits) -> Result<()> {
+  let size = std::fs::metadata(path)?.len();
+  if size > limits.max_file_size {
+    return Err(
+      LoadError::FileTooLarge {
+        size,
+        limit: limits.max_file_size,
+      }
+      .into(),
+    );
+  }
+  Ok(())
+}
+
+// Whether the file has the extension of a supported match group format
+pub fn is_match_group_file(path: &Path) -> bool {
+  if let Some(extension) = path.extension() {
+    let extension = extension.to_string_lossy().to_lowercase();
+    IMPORTERS
+      .iter()
+      .any(|importer| importer.is_supported(&extension))
+  } else {
+    false
+  }
+}
+
 #[derive(Error, Debug)]
 pub enum LoadError {
   #[error("missing extension in match group file")]
@@ -68,6 +141,9 @@ pub enum LoadError {
 
   #[error(transparent)]
   ParsingError(anyhow::Error),
+
+  #[error("match group file is {size} bytes, exceeding the limit of {limit} bytes")]
+  FileTooLarge { size: u64, limit: u64 },
 }
 
 #[cfg(test)]
@@ -176,4 +252,100 @@ mod tests {
       assert_eq!(load_match_group(&file).unwrap().0.matches.len(), 1);
     });
   }
+
+  #[test]
+  fn load_group_rejects_files_exceeding_size_limit() {
+    use_test_directory(|_, match_dir, _| {
+      let file = match_dir.join("base.yml");
+      std::fs::write(
+        &file,
+        r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let limits = LoadLimits {
+        max_file_size: 10,
+        ..Default::default()
+      };
+      assert!(matches!(
+        load_match_group_with_limits(&file, &limits)
+          .unwrap_err()
+          .downcast::<LoadError>()
+          .unwrap(),
+        LoadError::FileTooLarge { limit: 10, .. }
+      ));
+    });
+  }
+
+  #[test]
+  fn load_group_rejects_too_many_matches_before_converting_them() {
+    use_test_directory(|_, match_dir, _| {
+      let yaml_file = match_dir.join("base.yml");
+      std::fs::write(
+        &yaml_file,
+        r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+        - trigger: "foo"
+          replace: "bar"
+      "#,
+      )
+      .unwrap();
+      let json_file = match_dir.join("base.json");
+      std::fs::write(
+        &json_file,
+        r#"{"matches": [{"trigger": "hello", "replace": "world"}, {"trigger": "foo", "replace": "bar"}]}"#,
+      )
+      .unwrap();
+      let toml_file = match_dir.join("base.toml");
+      std::fs::write(
+        &toml_file,
+        r#"
+[[matches]]
+trigger = "hello"
+replace = "world"
+
+[[matches]]
+trigger = "foo"
+replace = "bar"
+"#,
+      )
+      .unwrap();
+
+      let limits = LoadLimits {
+        max_matches: 1,
+        ..Default::default()
+      };
+      for file in [&yaml_file, &json_file, &toml_file] {
+        let err = load_match_group_with_limits(file, &limits).unwrap_err();
+        match err.downcast_ref::<LoadError>() {
+          Some(LoadError::ParsingError(err)) => assert!(matches!(
+            err.downcast_ref::<yaml::parse::YAMLParseError>(),
+            Some(yaml::parse::YAMLParseError::TooManyMatches { count: 2, limit: 1 })
+          )),
+          other => panic!("unexpected error: {:?}", other),
+        }
+      }
+
+      let limits = LoadLimits {
+        max_matches: 2,
+        ..Default::default()
+      };
+      for file in [&yaml_file, &json_file, &toml_file] {
+        assert_eq!(
+          load_match_group_with_limits(file, &limits)
+            .unwrap()
+            .0
+            .matches
+            .len(),
+          2
+        );
+      }
+    });
+  }
 }
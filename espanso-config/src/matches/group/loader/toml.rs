@@ -0,0 +1,264 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use toml::Value;
+
+use crate::error::NonFatalErrorSet;
+
+use super::{
+  yaml::{convert_match_group, parse::YAMLMatchGroup},
+  Importer,
+};
+use crate::matches::group::MatchGroup;
+
+// TOML match groups share the same structure of the YAML ones
+pub(crate) struct TOMLImporter {}
+
+impl TOMLImporter {
+  pub fn new() -> Self {
+    Self {}
+  }
+}
+
+impl Importer for TOMLImporter {
+  fn is_supported(&self, extension: &str) -> bool {
+    extension == "toml"
+  }
+
+  fn load_group(
+    &self,
+    path: &Path,
+    max_matches: usize,
+  ) -> Result<(MatchGroup, Option<NonFatalErrorSet>)> {
+    let content = std::fs::read_to_string(path)?;
+    let group = parse_group(&content)
+      .with_context(|| format!("unable to parse match group file: {:?}", path))
+      .context("failed to parse TOML match group")?;
+
+    convert_match_group(path, group, max_matches)
+  }
+}
+
+fn parse_group(content: &str) -> Result<YAMLMatchGroup> {
+  let mut value: Value = toml::from_str(content)?;
+  normalize_triggers(&mut value);
+  Ok(value.try_into()?)
+}
+
+// Unlike YAML, TOML doesn't treat unquoted scalars as strings, so a trigger
+// such as `trigger = 1` would fail to deserialize. Here we convert them
+// to their string representation.
+fn normalize_triggers(value: &mut Value) {
+  if let Some(matches) = value.get_mut("matches").and_then(Value::as_array_mut) {
+    for m in matches.iter_mut().filter_map(Value::as_table_mut) {
+      if let Some(trigger) = m.get_mut("trigger") {
+        stringify_scalar(trigger);
+      }
+      if let Some(triggers) = m.get_mut("triggers").and_then(Value::as_array_mut) {
+        triggers.iter_mut().for_each(stringify_scalar);
+      }
+    }
+  }
+}
+
+fn stringify_scalar(value: &mut Value) {
+  let as_string = match value {
+    Value::Integer(integer) => integer.to_string(),
+    Value::Float(float) => float.to_string(),
+    Value::Boolean(boolean) => boolean.to_string(),
+    _ => return,
+  };
+  *value = Value::String(as_string);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    matches::{
+      group::loader::yaml::{
+        parse::{YAMLMatch, YAMLMatchGroup},
+        try_convert_into_match,
+      },
+      Match, MatchCause, MatchEffect, TextEffect, TriggerCause,
+    },
+    util::tests::use_test_directory,
+  };
+
+  fn convert(m: YAMLMatch) -> Match {
+    let (mut m, warnings) = try_convert_into_match(m, false).unwrap();
+    assert!(warnings.is_empty());
+
+    // Reset the IDs to correctly compare them
+    m.id = 0;
+    if let MatchEffect::Text(effect) = &mut m.effect {
+      effect.vars.iter_mut().for_each(|var| var.id = 0);
+    }
+    m
+  }
+
+  fn create_matches(toml: &str) -> Vec<Match> {
+    parse_group(toml)
+      .unwrap()
+      .matches
+      .unwrap()
+      .into_iter()
+      .map(convert)
+      .collect()
+  }
+
+  #[test]
+  fn basic_match_maps_correctly() {
+    assert_eq!(
+      create_matches(
+        r#"
+        [[matches]]
+        trigger = "Hello"
+        replace = "world"
+        "#
+      ),
+      vec![Match {
+        cause: MatchCause::Trigger(TriggerCause {
+          triggers: vec!["Hello".to_string()],
+          ..Default::default()
+        }),
+        effect: MatchEffect::Text(TextEffect {
+          replace: "world".to_string(),
+          ..Default::default()
+        }),
+        ..Default::default()
+      }]
+    )
+  }
+
+  #[test]
+  fn same_match_as_yaml_equivalent() {
+    let yaml_group = YAMLMatchGroup::parse_from_str(
+      r#"
+      matches:
+        - triggers: [":a", ":b"]
+          replace: "hello {{name}}"
+          word: true
+          vars:
+            - name: name
+              type: echo
+              params:
+                echo: "John"
+      "#,
+    )
+    .unwrap();
+    let yaml_matches: Vec<Match> = yaml_group
+      .matches
+      .unwrap()
+      .into_iter()
+      .map(convert)
+      .collect();
+
+    let toml_matches = create_matches(
+      r#"
+      [[matches]]
+      triggers = [":a", ":b"]
+      replace = "hello {{name}}"
+      word = true
+
+      [[matches.vars]]
+      name = "name"
+      type = "echo"
+      params = { echo = "John" }
+      "#,
+    );
+
+    assert_eq!(toml_matches, yaml_matches);
+  }
+
+  #[test]
+  fn non_string_triggers_are_converted() {
+    let matches = create_matches(
+      r#"
+      [[matches]]
+      trigger = 1
+      replace = "one"
+
+      [[matches]]
+      triggers = [2, 3]
+      replace = "other"
+      "#,
+    );
+
+    assert_eq!(
+      matches[0].cause.as_trigger().unwrap().triggers,
+      vec!["1".to_string()]
+    );
+    assert_eq!(
+      matches[1].cause.as_trigger().unwrap().triggers,
+      vec!["2".to_string(), "3".to_string()]
+    );
+  }
+
+  #[test]
+  fn importer_is_supported() {
+    let importer = TOMLImporter::new();
+    assert!(importer.is_supported("toml"));
+    assert!(!importer.is_supported("yml"));
+  }
+
+  #[test]
+  fn importer_works_correctly() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.toml");
+      std::fs::write(
+        &base_file,
+        r#"
+        [[global_vars]]
+        name = "var1"
+        type = "test"
+
+        [[matches]]
+        trigger = "hello"
+        replace = "world"
+
+        [[matches]]
+        trigger = "foo"
+        replace = "bar"
+        "#,
+      )
+      .unwrap();
+
+      let importer = TOMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert!(non_fatal_error_set.is_none());
+
+      assert_eq!(group.global_vars.len(), 1);
+      assert_eq!(group.matches.len(), 2);
+    });
+  }
+
+  #[test]
+  fn importer_invalid_toml() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.toml");
+      std::fs::write(&base_file, "[[matches]\ntrigger = ").unwrap();
+
+      let importer = TOMLImporter::new();
+      assert!(importer.load_group(&base_file, usize::MAX).is_err());
+    });
+  }
+}
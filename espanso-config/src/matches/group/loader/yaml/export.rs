@@ -0,0 +1,421 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use anyhow::Result;
+use serde_yaml::Mapping;
+
+use super::parse::{YAMLMatch, YAMLTextVariant, YAMLVariable};
+use crate::matches::{
+  MarkdownFlavor, Match, MatchCause, MatchEffect, MatchRegex, Number, Params, TextFormat,
+  TextInjectMode, TextVariant, UpperCasingStyle, Value, Variable,
+};
+
+// The reverse of the conversion performed by the loader. Options are written
+// only when they differ from their default, and the text is exported as
+// already processed (for example, trimmed or with the trigger prefix applied),
+// so that loading the result produces the same matches.
+pub(crate) fn export_match_group(matches: &[&Match], global_vars: &[&Variable]) -> Result<String> {
+  let mut group = Mapping::new();
+
+  if !global_vars.is_empty() {
+    let global_vars = global_vars
+      .iter()
+      .map(|var| to_yaml_value(&convert_into_yaml_variable(var)))
+      .collect::<Result<Vec<_>>>()?;
+    group.insert(
+      "global_vars".into(),
+      serde_yaml::Value::Sequence(global_vars),
+    );
+  }
+
+  let matches = matches
+    .iter()
+    .map(|m| to_yaml_value(&convert_into_yaml_match(m)?))
+    .collect::<Result<Vec<_>>>()?;
+  group.insert("matches".into(), serde_yaml::Value::Sequence(matches));
+
+  Ok(serde_yaml::to_string(&group)?)
+}
+
+pub(crate) fn convert_into_yaml_match(m: &Match) -> Result<YAMLMatch> {
+  let mut yaml_match = YAMLMatch {
+    label: m.label.clone(),
+    description: m.search_description.clone(),
+    searchable: if m.searchable { None } else { Some(false) },
+    search_terms: if m.search_terms.is_empty() {
+      None
+    } else {
+      Some(m.search_terms.clone())
+    },
+    exclusive: if m.exclusive { Some(true) } else { None },
+    filter_field: m
+      .filter_field
+      .as_ref()
+      .map(|filter| filter.as_str().to_string()),
+    ..Default::default()
+  };
+
+  match &m.cause {
+    MatchCause::None => {}
+    MatchCause::Trigger(cause) => {
+      if cause.triggers.len() == 1 {
+        yaml_match.trigger = cause.triggers.first().cloned();
+      } else {
+        yaml_match.triggers = Some(cause.triggers.clone());
+      }
+      yaml_match.left_word = if cause.left_word { Some(true) } else { None };
+      yaml_match.right_word = if cause.right_word { Some(true) } else { None };
+      yaml_match.line_start = if cause.line_start { Some(true) } else { None };
+      yaml_match.propagate_case = if cause.propagate_case {
+        Some(true)
+      } else {
+        None
+      };
+      yaml_match.uppercase_style = convert_uppercase_style(&cause.uppercase_style);
+      yaml_match.word_separators = cause.word_separators.clone();
+    }
+    MatchCause::Regex(cause) => {
+      yaml_match.regex = Some(cause.regex.clone());
+      yaml_match.max_lookback = cause.max_lookback;
+      yaml_match.propagate_case = if cause.propagate_case {
+        Some(true)
+      } else {
+        None
+      };
+      yaml_match.uppercase_style = convert_uppercase_style(&cause.uppercase_style);
+    }
+    MatchCause::HotKey(cause) => {
+      yaml_match.hotkey = Some(cause.hotkey.clone());
+    }
+    MatchCause::KeySequence(cause) => {
+      yaml_match.key_sequence = Some(cause.to_spec());
+    }
+  }
+
+  match &m.effect {
+    MatchEffect::None => {}
+    // Forms are exported in their resolved shape, that is a text
+    // replacement with an explicit "form" variable
+    MatchEffect::Text(effect) => {
+      if !effect.variants.is_empty() {
+        yaml_match.variants = Some(
+          effect
+            .variants
+            .iter()
+            .map(|variant| convert_into_yaml_variant(variant, &effect.format))
+            .collect(),
+        );
+      }
+
+      if !effect.replace_random.is_empty() {
+        yaml_match.replace_random = Some(effect.replace_random.clone());
+      } else {
+        let replace = effect.full_replace()?.into_owned();
+        match effect.format {
+          TextFormat::Plain => yaml_match.replace = Some(replace),
+          TextFormat::Markdown => {
+            yaml_match.markdown = Some(replace);
+            if effect.markdown_flavor == MarkdownFlavor::Gfm {
+              yaml_match.markdown_flavor = Some("gfm".to_string());
+            }
+          }
+          TextFormat::Html => yaml_match.html = Some(replace),
+        }
+      }
+
+      if !effect.vars.is_empty() {
+        yaml_match.vars = Some(effect.vars.iter().map(convert_into_yaml_variable).collect());
+      }
+
+      yaml_match.force_mode = effect.force_mode.as_ref().map(|mode| {
+        match mode {
+          TextInjectMode::Keys => "keys",
+          TextInjectMode::Clipboard => "clipboard",
+        }
+        .to_string()
+      });
+      yaml_match.propagate = if effect.propagate { Some(true) } else { None };
+      yaml_match.keep_trigger = if effect.keep_trigger {
+        Some(true)
+      } else {
+        None
+      };
+      yaml_match.newline_as_key = if effect.newline_as_key {
+        Some(true)
+      } else {
+        None
+      };
+      yaml_match.notify = effect.notify.clone();
+      if !effect.transforms.is_empty() {
+        yaml_match.transforms = Some(
+          effect
+            .transforms
+            .iter()
+            .map(|transform| transform.name().to_string())
+            .collect(),
+        );
+      }
+      yaml_match.confirm = if effect.confirm { Some(true) } else { None };
+    }
+    MatchEffect::Image(effect) => {
+      yaml_match.image_path = Some(effect.path.clone());
+      yaml_match.preserve_image_format = if effect.preserve_format {
+        Some(true)
+      } else {
+        None
+      };
+      yaml_match.notify = effect.notify.clone();
+    }
+  }
+
+  Ok(yaml_match)
+}
+
+pub(crate) fn convert_into_yaml_variable(var: &Variable) -> YAMLVariable {
+  YAMLVariable {
+    name: var.name.clone(),
+    var_type: var.var_type.clone(),
+    params: convert_into_mapping(&var.params),
+    inject_vars: if var.inject_vars { None } else { Some(false) },
+    depends_on: var.depends_on.clone(),
+    persist: var.persist,
+  }
+}
+
+// Variants are written with the same key as the replacement of their match
+fn convert_into_yaml_variant(variant: &TextVariant, format: &TextFormat) -> YAMLTextVariant {
+  let filter =
+    |filter: &Option<MatchRegex>| filter.as_ref().map(|regex| regex.as_str().to_string());
+  let replace = Some(variant.replace.clone());
+  let (replace, markdown, html) = match format {
+    TextFormat::Plain => (replace, None, None),
+    TextFormat::Markdown => (None, replace, None),
+    TextFormat::Html => (None, None, replace),
+  };
+
+  YAMLTextVariant {
+    filter_title: filter(&variant.filter_title),
+    filter_class: filter(&variant.filter_class),
+    filter_exec: filter(&variant.filter_exec),
+    replace,
+    markdown,
+    html,
+  }
+}
+
+fn convert_uppercase_style(style: &UpperCasingStyle) -> Option<String> {
+  match style {
+    UpperCasingStyle::Uppercase => None,
+    UpperCasingStyle::Capitalize => Some("capitalize".to_string()),
+    UpperCasingStyle::CapitalizeWords => Some("capitalize_words".to_string()),
+  }
+}
+
+fn convert_into_mapping(params: &Params) -> Mapping {
+  params
+    .iter()
+    .map(|(key, value)| (key.as_str().into(), convert_into_yaml_value(value)))
+    .collect()
+}
+
+fn convert_into_yaml_value(value: &Value) -> serde_yaml::Value {
+  match value {
+    Value::Null => serde_yaml::Value::Null,
+    Value::Bool(value) => serde_yaml::Value::Bool(*value),
+    Value::Number(Number::Integer(value)) => serde_yaml::Value::Number((*value).into()),
+    Value::Number(Number::Float(value)) => serde_yaml::Value::Number(value.into_inner().into()),
+    Value::String(value) => serde_yaml::Value::String(value.clone()),
+    Value::Array(values) => {
+      serde_yaml::Value::Sequence(values.iter().map(convert_into_yaml_value).collect())
+    }
+    Value::Object(params) => serde_yaml::Value::Mapping(convert_into_mapping(params)),
+  }
+}
+
+// Unset options would otherwise be serialized as null values
+fn to_yaml_value<T: serde::Serialize>(value: &T) -> Result<serde_yaml::Value> {
+  let mut value = serde_yaml::to_value(value)?;
+  if let serde_yaml::Value::Mapping(mapping) = &mut value {
+    remove_null_fields(mapping);
+    for key in &["vars", "variants"] {
+      if let Some(serde_yaml::Value::Sequence(entries)) = mapping.get_mut(&(*key).into()) {
+        for entry in entries.iter_mut() {
+          if let serde_yaml::Value::Mapping(entry) = entry {
+            remove_null_fields(entry);
+          }
+        }
+      }
+    }
+  }
+  Ok(value)
+}
+
+fn remove_null_fields(mapping: &mut Mapping) {
+  let null_keys: Vec<serde_yaml::Value> = mapping
+    .iter()
+    .filter(|(_, value)| value.is_null())
+    .map(|(key, _)| key.clone())
+    .collect();
+  for key in null_keys {
+    mapping.remove(&key);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{matches::group::MatchGroup, util::tests::use_test_directory};
+  use std::path::Path;
+
+  fn load_group(path: &Path) -> MatchGroup {
+    let (mut group, non_fatal_error_set) = MatchGroup::load(path).unwrap();
+    assert!(non_fatal_error_set.is_none());
+
+    // Ids are generated at load time, so they can't be compared
+    for m in group.matches.iter_mut() {
+      m.id = 0;
+      if let MatchEffect::Text(effect) = &mut m.effect {
+        effect.vars.iter_mut().for_each(|var| var.id = 0);
+      }
+    }
+    group.global_vars.iter_mut().for_each(|var| var.id = 0);
+    group
+  }
+
+  #[test]
+  fn export_round_trip() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      trigger_prefix: ";"
+
+      global_vars:
+        - name: greeting
+          type: echo
+          params:
+            echo: "Hi"
+
+      matches:
+        - trigger: "hello"
+          replace: "{{greeting}} world  \n"
+          trim: true
+          word: true
+          label: "Greeting"
+          search_terms: ["salutation"]
+        - triggers: ["dog", "cat"]
+          auto_suffixes: ["s"]
+          replace: "animal"
+          propagate_case: true
+          uppercase_style: "capitalize"
+          force_mode: "clipboard"
+        - regex: "hi(?P<person>.*)\\."
+          max_lookback: 10
+          replace: "Hi {{person}}"
+        - hotkey: "CTRL+ALT+E"
+          replace: "hotkey"
+          searchable: false
+        - key_sequence: "SHIFT+SHIFT within 200ms"
+          replace: "key sequence"
+        - trigger: "random"
+          replace_random: ["one", "two"]
+        - trigger: "variants"
+          replace: "base"
+          variants:
+            - filter_class: "Slack"
+              replace: "slack"
+        - trigger: "md"
+          markdown: "**bold**"
+          markdown_flavor: "gfm"
+          variants:
+            - filter_exec: "thunderbird"
+              markdown: "*italic*"
+          keep_trigger: true
+        - trigger: "html"
+          html: "<b>bold</b>"
+          newline_as_key: true
+        - trigger: "image"
+          image_path: "$CONFIG/image.png"
+        - trigger: "transparent"
+          image_path: "$CONFIG/transparent.png"
+          preserve_image_format: true
+        - trigger: "form"
+          form: "Hey [[name]]"
+          form_fields:
+            name:
+              type: list
+              values: ["John", "Jane"]
+        - trigger: "vars"
+          replace: "{{a}} {{b}}"
+          vars:
+            - name: a
+              type: shell
+              params:
+                cmd: "echo a"
+                trim: false
+                timeout: 100
+                ratio: 1.5
+            - name: b
+              type: echo
+              inject_vars: false
+              depends_on: ["a"]
+              persist: true
+              params:
+                echo: "{{a}}"
+      "#,
+      )
+      .unwrap();
+
+      let group = load_group(&base_file);
+
+      let exported = export_match_group(
+        &group.matches.iter().collect::<Vec<_>>(),
+        &group.global_vars.iter().collect::<Vec<_>>(),
+      )
+      .unwrap();
+      let exported_file = match_dir.join("exported.yml");
+      std::fs::write(&exported_file, exported).unwrap();
+
+      let reloaded = load_group(&exported_file);
+      assert_eq!(reloaded.matches, group.matches);
+      assert_eq!(reloaded.global_vars, group.global_vars);
+    })
+  }
+
+  #[test]
+  fn unset_options_are_not_exported() {
+    let m = Match {
+      cause: MatchCause::Trigger(crate::matches::TriggerCause {
+        triggers: vec!["hello".to_string()],
+        ..Default::default()
+      }),
+      effect: MatchEffect::Text(crate::matches::TextEffect {
+        replace: "world".to_string(),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      export_match_group(&[&m], &[]).unwrap(),
+      "---\nmatches:\n  - trigger: hello\n    replace: world\n"
+    );
+  }
+}
@@ -21,28 +21,34 @@ use crate::{
   counter::next_id,
   error::{ErrorRecord, NonFatalErrorSet},
   matches::{
-    group::{path::resolve_imports, MatchGroup},
-    ImageEffect, Match, Params, RegexCause, TextFormat, TextInjectMode, UpperCasingStyle, Value,
-    Variable,
+    group::{
+      path::{is_remote_import, resolve_imports, Import},
+      MatchGroup,
+    },
+    ImageEffect, MarkdownFlavor, Match, MatchRegex, Params, RegexCause, TextFormat, TextInjectMode,
+    TextTransform, TextVariant, UpperCasingStyle, Value, Variable,
   },
 };
 use anyhow::{anyhow, bail, Context, Result};
 use parse::YAMLMatchGroup;
 use regex::{Captures, Regex};
+use std::path::PathBuf;
 
 use self::{
-  parse::{YAMLMatch, YAMLVariable},
-  util::convert_params,
+  parse::{YAMLImport, YAMLMatch, YAMLVariable},
+  util::{convert_params, parse_hotkey, parse_key_sequence},
 };
 use crate::matches::{MatchCause, MatchEffect, TextEffect, TriggerCause};
 
 use super::Importer;
 
+pub(crate) mod export;
 pub(crate) mod parse;
-mod util;
+pub(crate) mod util;
 
 lazy_static! {
-  static ref VAR_REGEX: Regex = Regex::new("\\{\\{\\s*(\\w+)(\\.\\w+)?\\s*\\}\\}").unwrap();
+  pub(crate) static ref VAR_REGEX: Regex =
+    Regex::new("\\{\\{\\s*(\\w+)(\\.\\w+)?\\s*\\}\\}").unwrap();
   static ref FORM_CONTROL_REGEX: Regex =
     Regex::new("\\[\\[\\s*(\\w+)(\\.\\w+)?\\s*\\]\\]").unwrap();
 }
@@ -66,29 +72,117 @@ impl Importer for YAMLImporter {
   fn load_group(
     &self,
     path: &std::path::Path,
+    max_matches: usize,
   ) -> anyhow::Result<(crate::matches::group::MatchGroup, Option<NonFatalErrorSet>)> {
     let yaml_group =
       YAMLMatchGroup::parse_from_file(path).context("failed to parse YAML match group")?;
 
-    let mut non_fatal_errors = Vec::new();
+    convert_match_group(path, yaml_group, max_matches)
+  }
+}
 
-    let mut global_vars = Vec::new();
-    for yaml_global_var in yaml_group.global_vars.as_ref().cloned().unwrap_or_default() {
-      match try_convert_into_variable(yaml_global_var, false) {
-        Ok((var, warnings)) => {
-          global_vars.push(var);
-          non_fatal_errors.extend(warnings.into_iter().map(ErrorRecord::warn));
+// Matches defined inline in a config file, which is always in the YAML format
+pub(crate) fn load_inline_group(
+  path: &std::path::Path,
+  max_matches: usize,
+) -> anyhow::Result<(crate::matches::group::MatchGroup, Option<NonFatalErrorSet>)> {
+  let yaml_group = YAMLMatchGroup::parse_inline_from_file(path)
+    .context("failed to parse the matches defined in the config file")?;
+
+  convert_match_group(path, yaml_group, max_matches)
+}
+
+// Other formats sharing the same structure (such as JSON) reuse this conversion
+pub(crate) fn convert_match_group(
+  path: &std::path::Path,
+  yaml_group: YAMLMatchGroup,
+  max_matches: usize,
+) -> anyhow::Result<(crate::matches::group::MatchGroup, Option<NonFatalErrorSet>)> {
+  parse::check_match_limit(&yaml_group, max_matches)?;
+
+  let mut include_stack = vec![canonical_path(path)];
+  convert_match_group_with_includes(path, yaml_group, &mut include_stack)
+}
+
+fn apply_trigger_prefix(m: &mut Match, prefix: &str) {
+  if let MatchCause::Trigger(trigger_cause) = &mut m.cause {
+    for trigger in trigger_cause.triggers.iter_mut() {
+      trigger.insert_str(0, prefix);
+    }
+  }
+}
+
+// Separators specified on the match take precedence over the group ones
+fn apply_group_word_separators(m: &mut Match, word_separators: &[String]) {
+  if let MatchCause::Trigger(trigger_cause) = &mut m.cause {
+    if trigger_cause.word_separators.is_none() {
+      trigger_cause.word_separators = Some(word_separators.to_vec());
+    }
+  }
+}
+
+fn convert_match_group_with_includes(
+  path: &std::path::Path,
+  yaml_group: YAMLMatchGroup,
+  include_stack: &mut Vec<PathBuf>,
+) -> anyhow::Result<(crate::matches::group::MatchGroup, Option<NonFatalErrorSet>)> {
+  let mut non_fatal_errors = Vec::new();
+  let mut included_imports = Vec::new();
+
+  let mut global_vars = Vec::new();
+  for yaml_global_var in yaml_group.global_vars.as_ref().cloned().unwrap_or_default() {
+    match try_convert_into_variable(yaml_global_var, false) {
+      Ok((var, warnings)) => {
+        global_vars.push(var);
+        non_fatal_errors.extend(warnings.into_iter().map(ErrorRecord::warn));
+      }
+      Err(err) => {
+        non_fatal_errors.push(ErrorRecord::error(err));
+      }
+    }
+  }
+
+  let mut matches = Vec::new();
+  for yaml_match in yaml_group.matches.as_ref().cloned().unwrap_or_default() {
+    // Included groups are merged inline, at the position of the include entry
+    if let Some(include) = yaml_match.include.as_deref() {
+      match load_include(path, include, include_stack) {
+        Ok((group, errors)) => {
+          global_vars.extend(group.global_vars);
+          matches.extend(group.matches);
+          included_imports.extend(group.imports);
+          non_fatal_errors.extend(errors);
         }
         Err(err) => {
           non_fatal_errors.push(ErrorRecord::error(err));
         }
       }
+      continue;
     }
 
-    let mut matches = Vec::new();
-    for yaml_match in yaml_group.matches.as_ref().cloned().unwrap_or_default() {
+    let yaml_matches = if yaml_match.table.is_some() {
+      match expand_table(yaml_match) {
+        Ok(yaml_matches) => yaml_matches,
+        Err(err) => {
+          non_fatal_errors.push(ErrorRecord::error(err));
+          continue;
+        }
+      }
+    } else {
+      vec![yaml_match]
+    };
+
+    for yaml_match in yaml_matches {
       match try_convert_into_match(yaml_match, false) {
-        Ok((m, warnings)) => {
+        Ok((mut m, warnings)) => {
+          // The prefix only applies to the matches defined in this group,
+          // included and imported files can define their own
+          if let Some(prefix) = yaml_group.trigger_prefix.as_deref() {
+            apply_trigger_prefix(&mut m, prefix);
+          }
+          if let Some(word_separators) = yaml_group.word_separators.as_deref() {
+            apply_group_word_separators(&mut m, word_separators);
+          }
           matches.push(m);
           non_fatal_errors.extend(warnings.into_iter().map(ErrorRecord::warn));
         }
@@ -97,28 +191,149 @@ impl Importer for YAMLImporter {
         }
       }
     }
+  }
+
+  // Resolve imports
+  let imports: Vec<Import> = yaml_group
+    .imports
+    .unwrap_or_default()
+    .into_iter()
+    .map(|import| match import {
+      YAMLImport::Path(path) => Import { path, os: None },
+      YAMLImport::Conditional { path, os } => Import { path, os },
+    })
+    .collect();
+  let (mut resolved_imports, import_errors) =
+    resolve_imports(path, &imports).context("failed to resolve match group imports")?;
+  non_fatal_errors.extend(import_errors);
+  resolved_imports.extend(included_imports);
+
+  let non_fatal_error_set = if !non_fatal_errors.is_empty() {
+    Some(NonFatalErrorSet::new(path, non_fatal_errors))
+  } else {
+    None
+  };
 
-    // Resolve imports
-    let (resolved_imports, import_errors) =
-      resolve_imports(path, &yaml_group.imports.unwrap_or_default())
-        .context("failed to resolve YAML match group imports")?;
-    non_fatal_errors.extend(import_errors);
+  Ok((
+    MatchGroup {
+      imports: resolved_imports,
+      global_vars,
+      matches,
+      label: yaml_group.label,
+      word_separators: yaml_group.word_separators,
+    },
+    non_fatal_error_set,
+  ))
+}
 
-    let non_fatal_error_set = if !non_fatal_errors.is_empty() {
-      Some(NonFatalErrorSet::new(path, non_fatal_errors))
-    } else {
-      None
-    };
+// A table expands into one match per entry, all sharing the other options
+// of the block. As the entries define both the trigger and the replacement,
+// the table can't be combined with other causes or effects.
+fn expand_table(mut yaml_match: YAMLMatch) -> Result<Vec<YAMLMatch>> {
+  let table = yaml_match.table.take().unwrap_or_default();
+
+  if yaml_match.trigger.is_some()
+    || yaml_match.triggers.is_some()
+    || yaml_match.regex.is_some()
+    || yaml_match.hotkey.is_some()
+    || yaml_match.key_sequence.is_some()
+  {
+    bail!(
+      "'table' can't be used together with 'trigger', 'triggers', 'regex', 'hotkey' or 'key_sequence'"
+    );
+  }
 
-    Ok((
-      MatchGroup {
-        imports: resolved_imports,
-        global_vars,
-        matches,
-      },
-      non_fatal_error_set,
-    ))
+  if yaml_match.replace.is_some()
+    || yaml_match.replace_random.is_some()
+    || yaml_match.variants.is_some()
+    || yaml_match.markdown.is_some()
+    || yaml_match.html.is_some()
+    || yaml_match.image_path.is_some()
+    || yaml_match.form.is_some()
+  {
+    bail!("'table' can't be used together with other replacement fields, such as 'replace'");
+  }
+
+  table
+    .into_iter()
+    .map(|(trigger, replace)| match (trigger, replace) {
+      (serde_yaml::Value::String(trigger), serde_yaml::Value::String(replace)) => {
+        let mut entry = yaml_match.clone();
+        entry.trigger = Some(trigger);
+        entry.replace = Some(replace);
+        Ok(entry)
+      }
+      (trigger, _) => Err(anyhow!(
+        "invalid 'table' entry {:?}, both the trigger and the replacement must be strings",
+        trigger
+      )),
+    })
+    .collect()
+}
+
+// Include paths are resolved with the same rules as imports (relative to the
+// including file), and the errors of the included group are reported as part
+// of the including one.
+fn load_include(
+  path: &std::path::Path,
+  include: &str,
+  include_stack: &mut Vec<PathBuf>,
+) -> Result<(MatchGroup, Vec<ErrorRecord>)> {
+  if is_remote_import(include) {
+    bail!(
+      "remote paths can't be included, use an import instead: {}",
+      include
+    );
+  }
+
+  let (resolved, mut errors) = resolve_imports(path, &[Import::from(include)])?;
+  let include_path = match resolved.into_iter().next() {
+    Some(include_path) => PathBuf::from(include_path),
+    None => {
+      let error = errors
+        .pop()
+        .map(|record| record.error)
+        .unwrap_or_else(|| anyhow!("unable to resolve include: {}", include));
+      return Err(error.context(format!("unable to include: {}", include)));
+    }
+  };
+
+  if include_stack.contains(&include_path) {
+    bail!("circular include detected: {:?}", include_path);
   }
+
+  let yaml_group = YAMLMatchGroup::parse_from_file(&include_path)
+    .with_context(|| format!("failed to parse included match group: {:?}", include_path))?;
+
+  include_stack.push(include_path.clone());
+  let result = convert_match_group_with_includes(&include_path, yaml_group, include_stack);
+  include_stack.pop();
+
+  let (group, non_fatal_error_set) = result?;
+  let errors = non_fatal_error_set
+    .map(|error_set| error_set.errors)
+    .unwrap_or_default()
+    .into_iter()
+    .map(|record| ErrorRecord {
+      level: record.level,
+      error: record
+        .error
+        .context(format!("in included file: {:?}", include_path)),
+    })
+    .collect();
+
+  Ok((group, errors))
+}
+
+fn canonical_path(path: &std::path::Path) -> PathBuf {
+  dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Parse a single match, written with the same syntax used in the match files
+pub fn parse_match_definition(definition: &str) -> Result<(Match, Vec<Warning>)> {
+  let yaml_match: YAMLMatch =
+    serde_yaml::from_str(definition).context("unable to parse match definition")?;
+  try_convert_into_match(yaml_match, false)
 }
 
 pub fn try_convert_into_match(
@@ -133,12 +348,59 @@ pub fn try_convert_into_match(
     ));
   }
 
+  if yaml_match.markdown_flavor.is_some() && yaml_match.markdown.is_none() {
+    warnings.push(anyhow!(
+      "specifying the 'markdown_flavor' option without 'markdown' has no effect"
+    ));
+  }
+
+  if yaml_match.preserve_image_format.is_some() && yaml_match.image_path.is_none() {
+    warnings.push(anyhow!(
+      "specifying the 'preserve_image_format' option without 'image_path' has no effect"
+    ));
+  }
+
+  if yaml_match.max_lookback.is_some() && yaml_match.regex.is_none() {
+    warnings.push(anyhow!(
+      "specifying the 'max_lookback' option without a regex has no effect"
+    ));
+  }
+
+  if yaml_match.word_separators.is_some()
+    && yaml_match.trigger.is_none()
+    && yaml_match.triggers.is_none()
+  {
+    warnings.push(anyhow!(
+      "specifying the 'word_separators' option without a trigger has no effect"
+    ));
+  }
+
+  if yaml_match.line_start.is_some()
+    && yaml_match.trigger.is_none()
+    && yaml_match.triggers.is_none()
+  {
+    warnings.push(anyhow!(
+      "specifying the 'line_start' option without a trigger has no effect"
+    ));
+  }
+
   let triggers = if let Some(trigger) = yaml_match.trigger {
     Some(vec![trigger])
   } else {
     yaml_match.triggers
   };
 
+  let triggers = match (triggers, yaml_match.auto_suffixes) {
+    (Some(triggers), Some(suffixes)) => Some(generate_suffix_variants(triggers, &suffixes)),
+    (None, Some(_)) => {
+      warnings.push(anyhow!(
+        "specifying the 'auto_suffixes' option without a trigger has no effect"
+      ));
+      None
+    }
+    (triggers, None) => triggers,
+  };
+
   let uppercase_style = match yaml_match
     .uppercase_style
     .map(|s| s.to_lowercase())
@@ -168,14 +430,29 @@ pub fn try_convert_into_match(
         .right_word
         .or(yaml_match.word)
         .unwrap_or(TriggerCause::default().right_word),
+      line_start: yaml_match
+        .line_start
+        .unwrap_or(TriggerCause::default().line_start),
       propagate_case: yaml_match
         .propagate_case
         .unwrap_or(TriggerCause::default().propagate_case),
       uppercase_style,
+      word_separators: yaml_match.word_separators,
     })
   } else if let Some(regex) = yaml_match.regex {
     // TODO: add test case
-    MatchCause::Regex(RegexCause { regex })
+    MatchCause::Regex(RegexCause {
+      regex,
+      max_lookback: yaml_match.max_lookback,
+      propagate_case: yaml_match
+        .propagate_case
+        .unwrap_or(RegexCause::default().propagate_case),
+      uppercase_style,
+    })
+  } else if let Some(hotkey) = yaml_match.hotkey {
+    MatchCause::HotKey(parse_hotkey(&hotkey)?)
+  } else if let Some(key_sequence) = yaml_match.key_sequence {
+    MatchCause::KeySequence(parse_key_sequence(&key_sequence)?)
   } else {
     MatchCause::None
   };
@@ -193,100 +470,317 @@ pub fn try_convert_into_match(
     None
   };
 
-  let effect =
-    if yaml_match.replace.is_some() || yaml_match.markdown.is_some() || yaml_match.html.is_some() {
-      // TODO: test markdown and html cases
-      let (replace, format) = if let Some(plain) = yaml_match.replace {
-        (plain, TextFormat::Plain)
-      } else if let Some(markdown) = yaml_match.markdown {
-        (markdown, TextFormat::Markdown)
-      } else if let Some(html) = yaml_match.html {
-        (html, TextFormat::Html)
-      } else {
-        unreachable!();
-      };
+  // When enabled, the expansion output is fed back to the matchers
+  let propagate = yaml_match.propagate.unwrap_or(false);
+
+  let markdown_flavor = match yaml_match
+    .markdown_flavor
+    .map(|s| s.to_lowercase())
+    .as_deref()
+  {
+    Some("standard") | None => MarkdownFlavor::Standard,
+    Some("gfm") => MarkdownFlavor::Gfm,
+    Some(flavor) => {
+      warnings.push(anyhow!(
+        "unrecognized markdown_flavor: {:?}, falling back to the default",
+        flavor
+      ));
+      MarkdownFlavor::Standard
+    }
+  };
 
-      let mut vars: Vec<Variable> = Vec::new();
-      for yaml_var in yaml_match.vars.unwrap_or_default() {
-        let (var, var_warnings) =
-          try_convert_into_variable(yaml_var.clone(), use_compatibility_mode)
-            .with_context(|| format!("failed to load variable: {:?}", yaml_var))?;
-        warnings.extend(var_warnings);
-        vars.push(var);
+  // When enabled, the replacement is appended after the typed trigger
+  let keep_trigger = yaml_match.keep_trigger.unwrap_or(false);
+
+  // When enabled, newlines are typed with the Enter key
+  let newline_as_key = yaml_match.newline_as_key.unwrap_or(false);
+
+  // Message shown in a notification after the expansion
+  let notify = yaml_match.notify;
+
+  // Applied to the rendered replacement, images are left untouched
+  let transforms: Vec<TextTransform> = yaml_match
+    .transforms
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|name| {
+      let transform = TextTransform::from_name(name);
+      if transform.is_none() {
+        warnings.push(anyhow!(
+          "unrecognized transform: {:?}, expected one of trim, upper, lower or title",
+          name
+        ));
       }
+      transform
+    })
+    .collect();
+  if !transforms.is_empty() && yaml_match.image_path.is_some() {
+    warnings.push(anyhow!(
+      "specifying the 'transforms' option with 'image_path' has no effect"
+    ));
+  }
 
-      MatchEffect::Text(TextEffect {
-        replace,
-        vars,
-        format,
-        force_mode,
-      })
-    } else if let Some(form_layout) = yaml_match.form {
-      // Replace all the form fields with actual variables
-
-      // In v2.1.0-alpha the form control syntax was replaced with [[control]]
-      // instead of {{control}}, so we check if compatibility mode is being used.
-      // TODO: remove once compatibility mode is removed
-
-      let (resolved_replace, resolved_layout) = if use_compatibility_mode {
-        (
-          VAR_REGEX
-            .replace_all(&form_layout, |caps: &Captures| {
-              let var_name = caps.get(1).unwrap().as_str();
-              format!("{{{{form1.{}}}}}", var_name)
-            })
-            .to_string(),
-          VAR_REGEX
-            .replace_all(&form_layout, |caps: &Captures| {
-              let var_name = caps.get(1).unwrap().as_str();
-              format!("[[{}]]", var_name)
-            })
-            .to_string(),
-        )
-      } else {
-        (
-          FORM_CONTROL_REGEX
-            .replace_all(&form_layout, |caps: &Captures| {
-              let var_name = caps.get(1).unwrap().as_str();
-              format!("{{{{form1.{}}}}}", var_name)
-            })
-            .to_string(),
-          form_layout,
-        )
-      };
+  // When enabled, the expansion has to be confirmed by the user
+  let confirm = yaml_match.confirm.unwrap_or(false);
+  if confirm && yaml_match.image_path.is_some() {
+    warnings.push(anyhow!(
+      "specifying the 'confirm' option with 'image_path' has no effect"
+    ));
+  }
+
+  // Block scalars often carry trailing spaces and a final newline,
+  // which might not be desirable in the expansion
+  let should_trim = yaml_match.trim.unwrap_or(false);
+  // Block scalars whose content starts after an empty line keep it as a leading newline
+  let should_strip_leading_newline = yaml_match.strip_leading_newline.unwrap_or(false);
+  // Text pasted from word processors often contains curly quotes and dashes
+  let should_normalize_quotes = yaml_match.normalize_quotes.unwrap_or(false);
+  let trim = |replace: String| {
+    let replace = if should_strip_leading_newline {
+      strip_leading_newline(replace)
+    } else {
+      replace
+    };
+    let replace = if should_normalize_quotes {
+      normalize_quotes(&replace)
+    } else {
+      replace
+    };
 
-      // Convert escaped brakets in forms
-      let resolved_replace = resolved_replace.replace("\\{", "{ ").replace("\\}", " }");
+    if should_trim {
+      trim_trailing_whitespace(&replace)
+    } else {
+      replace
+    }
+  };
 
-      // Convert the form data to valid variables
-      let mut params = Params::new();
-      params.insert("layout".to_string(), Value::String(resolved_layout));
+  let replace_random: Vec<String> = yaml_match
+    .replace_random
+    .unwrap_or_default()
+    .into_iter()
+    .map(trim)
+    .collect();
+
+  // Variants are written with the same key as the replacement of their match,
+  // as they are rendered and injected with its format
+  let format_key = if yaml_match.replace.is_some() || !replace_random.is_empty() {
+    "replace"
+  } else if yaml_match.markdown.is_some() {
+    "markdown"
+  } else if yaml_match.html.is_some() {
+    "html"
+  } else {
+    "replace"
+  };
 
-      if let Some(fields) = yaml_match.form_fields {
-        params.insert("fields".to_string(), Value::Object(convert_params(fields)?));
+  let compile_filter = |filter: &Option<String>| -> Result<Option<MatchRegex>> {
+    match filter {
+      Some(filter) => {
+        Ok(Some(MatchRegex::new(filter).with_context(|| {
+          format!("invalid variant filter: {}", filter)
+        })?))
       }
+      None => Ok(None),
+    }
+  };
 
-      let vars = vec![Variable {
-        id: next_id(),
-        name: "form1".to_owned(),
-        var_type: "form".to_owned(),
-        params,
-        ..Default::default()
-      }];
+  let mut variants = Vec::new();
+  for yaml_variant in yaml_match.variants.unwrap_or_default() {
+    let filters = [
+      &yaml_variant.filter_title,
+      &yaml_variant.filter_class,
+      &yaml_variant.filter_exec,
+    ];
+    if filters.iter().all(|filter| filter.is_none()) {
+      warnings.push(anyhow!(
+        "variant without 'filter_title', 'filter_class' or 'filter_exec' will be ignored"
+      ));
+      continue;
+    }
 
-      MatchEffect::Text(TextEffect {
-        replace: resolved_replace,
-        vars,
-        format: TextFormat::Plain,
-        force_mode,
-      })
-    } else if let Some(image_path) = yaml_match.image_path {
-      // TODO: test image case
-      MatchEffect::Image(ImageEffect { path: image_path })
+    let (key, replace) = match (
+      yaml_variant.replace,
+      yaml_variant.markdown,
+      yaml_variant.html,
+    ) {
+      (Some(replace), None, None) => ("replace", replace),
+      (None, Some(markdown), None) => ("markdown", markdown),
+      (None, None, Some(html)) => ("html", html),
+      _ => bail!("variant must specify exactly one of 'replace', 'markdown' or 'html'"),
+    };
+    if key != format_key {
+      bail!(
+        "variant specifies '{}', but its match uses '{}'",
+        key,
+        format_key
+      );
+    }
+
+    variants.push(TextVariant {
+      filter_title: compile_filter(&yaml_variant.filter_title)?,
+      filter_class: compile_filter(&yaml_variant.filter_class)?,
+      filter_exec: compile_filter(&yaml_variant.filter_exec)?,
+      replace: trim(replace),
+    });
+  }
+
+  if !variants.is_empty()
+    && yaml_match.replace.is_none()
+    && replace_random.is_empty()
+    && yaml_match.markdown.is_none()
+    && yaml_match.html.is_none()
+  {
+    warnings.push(anyhow!(
+      "specifying 'variants' without a 'replace' has no effect"
+    ));
+  }
+
+  if !replace_random.is_empty()
+    && (yaml_match.replace.is_some() || yaml_match.markdown.is_some() || yaml_match.html.is_some())
+  {
+    bail!("'replace_random' can't be combined with 'replace', 'markdown' or 'html'");
+  }
+
+  let effect = if yaml_match.replace.is_some()
+    || !replace_random.is_empty()
+    || yaml_match.markdown.is_some()
+    || yaml_match.html.is_some()
+  {
+    // TODO: test markdown and html cases
+    let (replace, format) = if let Some(plain) = yaml_match.replace {
+      (trim(plain), TextFormat::Plain)
+    } else if let Some(first_choice) = replace_random.first() {
+      // The first choice is used when a single replacement is needed (for example,
+      // in the search bar). The choices have already been trimmed
+      (first_choice.clone(), TextFormat::Plain)
+    } else if let Some(markdown) = yaml_match.markdown {
+      (trim(markdown), TextFormat::Markdown)
+    } else if let Some(html) = yaml_match.html {
+      (trim(html), TextFormat::Html)
+    } else {
+      unreachable!();
+    };
+
+    let mut vars: Vec<Variable> = Vec::new();
+    for yaml_var in yaml_match.vars.unwrap_or_default() {
+      let (var, var_warnings) = try_convert_into_variable(yaml_var.clone(), use_compatibility_mode)
+        .with_context(|| format!("failed to load variable: {:?}", yaml_var))?;
+      warnings.extend(var_warnings);
+      vars.push(var);
+    }
+
+    MatchEffect::Text(TextEffect {
+      replace,
+      lazy_replace: None,
+      vars,
+      format,
+      force_mode,
+      propagate,
+      markdown_flavor,
+      keep_trigger,
+      newline_as_key,
+      replace_random,
+      variants,
+      notify,
+      transforms,
+      confirm,
+    })
+  } else if let Some(form_layout) = yaml_match.form {
+    // Replace all the form fields with actual variables
+
+    // In v2.1.0-alpha the form control syntax was replaced with [[control]]
+    // instead of {{control}}, so we check if compatibility mode is being used.
+    // TODO: remove once compatibility mode is removed
+
+    let (resolved_replace, resolved_layout) = if use_compatibility_mode {
+      (
+        VAR_REGEX
+          .replace_all(&form_layout, |caps: &Captures| {
+            let var_name = caps.get(1).unwrap().as_str();
+            format!("{{{{form1.{}}}}}", var_name)
+          })
+          .to_string(),
+        VAR_REGEX
+          .replace_all(&form_layout, |caps: &Captures| {
+            let var_name = caps.get(1).unwrap().as_str();
+            format!("[[{}]]", var_name)
+          })
+          .to_string(),
+      )
     } else {
-      MatchEffect::None
+      (
+        FORM_CONTROL_REGEX
+          .replace_all(&form_layout, |caps: &Captures| {
+            let var_name = caps.get(1).unwrap().as_str();
+            format!("{{{{form1.{}}}}}", var_name)
+          })
+          .to_string(),
+        form_layout,
+      )
     };
 
+    // Convert escaped brakets in forms
+    let resolved_replace = resolved_replace.replace("\\{", "{ ").replace("\\}", " }");
+
+    // Convert the form data to valid variables
+    let mut params = Params::new();
+    params.insert("layout".to_string(), Value::String(resolved_layout));
+
+    if let Some(fields) = yaml_match.form_fields {
+      // Params are sorted by name, so keep track of the declaration order
+      let order = fields
+        .iter()
+        .filter_map(|(name, _)| name.as_str())
+        .map(|name| Value::String(name.to_string()))
+        .collect();
+      params.insert("order".to_string(), Value::Array(order));
+      params.insert("fields".to_string(), Value::Object(convert_params(fields)?));
+    }
+
+    let vars = vec![Variable {
+      id: next_id(),
+      name: "form1".to_owned(),
+      var_type: "form".to_owned(),
+      params,
+      ..Default::default()
+    }];
+
+    MatchEffect::Text(TextEffect {
+      replace: resolved_replace,
+      lazy_replace: None,
+      vars,
+      format: TextFormat::Plain,
+      force_mode,
+      propagate,
+      markdown_flavor: MarkdownFlavor::Standard,
+      keep_trigger,
+      newline_as_key,
+      replace_random: Vec::new(),
+      variants: Vec::new(),
+      notify,
+      transforms,
+      confirm,
+    })
+  } else if let Some(image_path) = yaml_match.image_path {
+    // TODO: test image case
+    MatchEffect::Image(ImageEffect {
+      path: image_path,
+      preserve_format: yaml_match
+        .preserve_image_format
+        .unwrap_or(ImageEffect::default().preserve_format),
+      notify,
+    })
+  } else {
+    MatchEffect::None
+  };
+
+  let filter_field = match &yaml_match.filter_field {
+    Some(filter) => {
+      Some(MatchRegex::new(filter).with_context(|| format!("invalid field filter: {}", filter))?)
+    }
+    None => None,
+  };
+
   if let MatchEffect::None = effect {
     bail!(
       "match triggered by {:?} does not produce any effect. Did you forget the 'replace' field?",
@@ -299,12 +793,72 @@ pub fn try_convert_into_match(
       cause,
       effect,
       label: yaml_match.label,
+      search_description: yaml_match.description,
+      searchable: yaml_match.searchable.unwrap_or(true),
+      search_terms: yaml_match.search_terms.unwrap_or_default(),
+      exclusive: yaml_match.exclusive.unwrap_or(false),
+      filter_field,
       id: next_id(),
     },
     warnings,
   ))
 }
 
+// Each trigger is followed by its suffixed variants, so that
+// ":dog" with the "s" suffix also matches ":dogs"
+fn generate_suffix_variants(triggers: Vec<String>, suffixes: &[String]) -> Vec<String> {
+  let mut variants: Vec<String> = Vec::new();
+  for trigger in triggers {
+    let suffixed: Vec<String> = suffixes
+      .iter()
+      .filter(|suffix| !suffix.is_empty())
+      .map(|suffix| format!("{}{}", trigger, suffix))
+      .collect();
+
+    for variant in std::iter::once(trigger).chain(suffixed) {
+      if !variants.contains(&variant) {
+        variants.push(variant);
+      }
+    }
+  }
+  variants
+}
+
+// Remove the trailing whitespace of each line, as well as a single trailing newline
+fn trim_trailing_whitespace(text: &str) -> String {
+  text
+    .lines()
+    .map(str::trim_end)
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+// Remove a single leading newline, if present
+fn strip_leading_newline(text: String) -> String {
+  if let Some(stripped) = text.strip_prefix("\r\n") {
+    stripped.to_string()
+  } else if let Some(stripped) = text.strip_prefix('\n') {
+    stripped.to_string()
+  } else {
+    text
+  }
+}
+
+// Replace the typographic quotes and dashes with their ASCII counterparts
+fn normalize_quotes(text: &str) -> String {
+  let mut normalized = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => normalized.push('\''),
+      '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => normalized.push('"'),
+      '\u{2013}' => normalized.push('-'),
+      '\u{2014}' => normalized.push_str("--"),
+      _ => normalized.push(c),
+    }
+  }
+  normalized
+}
+
 pub fn try_convert_into_variable(
   yaml_var: YAMLVariable,
   use_compatibility_mode: bool,
@@ -317,6 +871,7 @@ pub fn try_convert_into_variable(
       id: next_id(),
       inject_vars: !use_compatibility_mode && yaml_var.inject_vars.unwrap_or(true),
       depends_on: yaml_var.depends_on,
+      persist: yaml_var.persist,
     },
     Vec::new(),
   ))
@@ -326,9 +881,10 @@ pub fn try_convert_into_variable(
 mod tests {
   use super::*;
   use crate::{
-    matches::{Match, Params, Value},
+    matches::{HotKeyCause, KeySequenceCause, Match, Params, Value},
     util::tests::use_test_directory,
   };
+  use parse::YAMLParseError;
   use std::fs::create_dir_all;
 
   fn create_match_with_warnings(
@@ -482,6 +1038,50 @@ mod tests {
     )
   }
 
+  #[test]
+  fn line_start_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: ":todo"
+        replace: "TODO: "
+        line_start: true
+        "#
+      )
+      .unwrap(),
+      Match {
+        cause: MatchCause::Trigger(TriggerCause {
+          triggers: vec![":todo".to_string()],
+          line_start: true,
+          ..Default::default()
+        }),
+        effect: MatchEffect::Text(TextEffect {
+          replace: "TODO: ".to_string(),
+          ..Default::default()
+        }),
+        ..Default::default()
+      }
+    )
+  }
+
+  #[test]
+  fn parse_match_definition_accepts_json_and_yaml() {
+    let (m, warnings) =
+      parse_match_definition(r#"{"trigger": ":hi", "replace": "hello"}"#).unwrap();
+    assert!(warnings.is_empty());
+    assert_eq!(
+      m.cause.as_trigger().unwrap().triggers,
+      vec![":hi".to_string()]
+    );
+    assert_eq!(m.effect.as_text().unwrap().replace, "hello");
+
+    let (m, _) = parse_match_definition("trigger: \":bye\"\nreplace: \"goodbye\"").unwrap();
+    assert_eq!(m.effect.as_text().unwrap().replace, "goodbye");
+
+    assert!(parse_match_definition("trigger: [").is_err());
+    assert!(parse_match_definition("trigger: {}").is_err());
+  }
+
   #[test]
   fn propagate_case_maps_correctly() {
     assert_eq!(
@@ -596,39 +1196,910 @@ mod tests {
   }
 
   #[test]
-  fn form_maps_correctly() {
-    let mut params = Params::new();
-    params.insert(
-      "layout".to_string(),
-      Value::String("Hi [[name]]!".to_string()),
+  fn trim_maps_correctly() {
+    let yaml = "
+      trigger: \"Hello\"
+      replace: |
+        first line   
+        second line\t
+      trim: true
+    ";
+    assert_eq!(
+      create_match(yaml)
+        .unwrap()
+        .effect
+        .into_text()
+        .unwrap()
+        .replace,
+      "first line\nsecond line"
     );
+  }
 
+  #[test]
+  fn trim_disabled_preserves_whitespace() {
+    let yaml = "
+      trigger: \"Hello\"
+      replace: |
+        first line   
+        second line\t
+    ";
     assert_eq!(
-      create_match(
-        r#"
-        trigger: "Hello"
-        form: "Hi [[name]]!"
-        "#
-      )
-      .unwrap(),
-      Match {
-        cause: MatchCause::Trigger(TriggerCause {
-          triggers: vec!["Hello".to_string()],
-          ..Default::default()
-        }),
-        effect: MatchEffect::Text(TextEffect {
-          replace: "Hi {{form1.name}}!".to_string(),
-          vars: vec![Variable {
-            id: 0,
-            name: "form1".to_string(),
-            var_type: "form".to_string(),
-            params,
-            ..Default::default()
-          }],
-          ..Default::default()
-        }),
-        ..Default::default()
-      }
+      create_match(yaml)
+        .unwrap()
+        .effect
+        .into_text()
+        .unwrap()
+        .replace,
+      "first line   \nsecond line\t\n"
+    );
+  }
+
+  fn create_replace(yaml: &str) -> String {
+    create_match(yaml)
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .replace
+  }
+
+  #[test]
+  fn strip_leading_newline_literal_block() {
+    let yaml = "
+      trigger: \"Hello\"
+      strip_leading_newline: false
+      replace: |
+
+        first line
+        second line
+    ";
+    assert_eq!(create_replace(yaml), "\nfirst line\nsecond line\n");
+    assert_eq!(
+      create_replace(&yaml.replace(
+        "strip_leading_newline: false",
+        "strip_leading_newline: true"
+      )),
+      "first line\nsecond line\n"
+    );
+  }
+
+  #[test]
+  fn strip_leading_newline_strip_block() {
+    let yaml = "
+      trigger: \"Hello\"
+      strip_leading_newline: false
+      replace: |-
+
+        first line
+        second line
+    ";
+    assert_eq!(create_replace(yaml), "\nfirst line\nsecond line");
+    assert_eq!(
+      create_replace(&yaml.replace(
+        "strip_leading_newline: false",
+        "strip_leading_newline: true"
+      )),
+      "first line\nsecond line"
+    );
+  }
+
+  #[test]
+  fn strip_leading_newline_keep_block() {
+    let yaml = "
+      trigger: \"Hello\"
+      strip_leading_newline: false
+      replace: |+
+
+        first line
+
+    ";
+    assert_eq!(create_replace(yaml), "\nfirst line\n\n");
+    assert_eq!(
+      create_replace(&yaml.replace(
+        "strip_leading_newline: false",
+        "strip_leading_newline: true"
+      )),
+      "first line\n\n"
+    );
+  }
+
+  #[test]
+  fn strip_leading_newline_removes_a_single_newline() {
+    assert_eq!(strip_leading_newline("\n\ntext".to_string()), "\ntext");
+    assert_eq!(strip_leading_newline("\r\ntext".to_string()), "text");
+    assert_eq!(strip_leading_newline("text\n".to_string()), "text\n");
+  }
+
+  #[test]
+  fn normalize_quotes_is_disabled_by_default() {
+    let yaml = "
+      trigger: \"Hello\"
+      replace: \"\\u201Cdon\\u2019t\\u201D \\u2013 ok\"
+    ";
+    assert_eq!(
+      create_replace(yaml),
+      "\u{201C}don\u{2019}t\u{201D} \u{2013} ok"
+    );
+  }
+
+  #[test]
+  fn normalize_quotes_converts_to_ascii() {
+    let yaml = "
+      trigger: \"Hello\"
+      normalize_quotes: true
+      replace: \"\\u201Cdon\\u2019t\\u201D \\u2013 ok \\u2014 \\u2018fine\\u2019\"
+    ";
+    assert_eq!(create_replace(yaml), "\"don't\" - ok -- 'fine'");
+  }
+
+  #[test]
+  fn preserve_image_format_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        image_path: "$CONFIG/image.png"
+        preserve_image_format: true
+        "#
+      )
+      .unwrap()
+      .effect,
+      MatchEffect::Image(ImageEffect {
+        path: "$CONFIG/image.png".to_string(),
+        preserve_format: true,
+        notify: None,
+      })
+    );
+
+    assert!(
+      !create_match(
+        r#"
+        trigger: "Hello"
+        image_path: "$CONFIG/image.png"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_image()
+      .unwrap()
+      .preserve_format
+    );
+  }
+
+  #[test]
+  fn key_sequence_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        key_sequence: "shift+shift within 200ms"
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .cause,
+      MatchCause::KeySequence(KeySequenceCause {
+        keys: vec!["SHIFT".to_string(), "SHIFT".to_string()],
+        window_ms: 200,
+      })
+    );
+
+    assert!(create_match(
+      r#"
+        key_sequence: "shift"
+        replace: "world"
+        "#
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn hotkey_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        hotkey: "ctrl+alt+e"
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .cause,
+      MatchCause::HotKey(HotKeyCause {
+        hotkey: "CTRL+ALT+E".to_string()
+      })
+    );
+
+    assert!(create_match(
+      r#"
+        hotkey: "e"
+        replace: "world"
+        "#
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn replace_random_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace_random: ["world", "there  "]
+        trim: true
+        "#
+      )
+      .unwrap(),
+      Match {
+        cause: MatchCause::Trigger(TriggerCause {
+          triggers: vec!["Hello".to_string()],
+          ..Default::default()
+        }),
+        effect: MatchEffect::Text(TextEffect {
+          replace: "world".to_string(),
+          replace_random: vec!["world".to_string(), "there".to_string()],
+          ..Default::default()
+        }),
+        ..Default::default()
+      }
+    )
+  }
+
+  #[test]
+  fn replace_random_choices_are_trimmed_once() {
+    let m = create_match(
+      r#"
+        trigger: "Hello"
+        replace_random: ["\n\nworld", "there"]
+        strip_leading_newline: true
+        "#,
+    )
+    .unwrap();
+
+    let effect = m.effect.as_text().unwrap();
+    assert_eq!(effect.replace_random[0], "\nworld");
+    assert_eq!(effect.replace, effect.replace_random[0]);
+  }
+
+  #[test]
+  fn replace_random_with_replace_is_rejected() {
+    assert!(create_match(
+      r#"
+      trigger: "Hello"
+      replace: "world"
+      replace_random: ["world", "there"]
+      "#
+    )
+    .is_err());
+
+    assert!(create_match(
+      r#"
+      trigger: "Hello"
+      markdown: "**world**"
+      replace_random: ["world", "there"]
+      "#
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn variants_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        variants:
+          - filter_class: "Slack"
+            replace: "there"
+          - filter_exec: "thunderbird"
+            filter_title: "Write"
+            replace: "everyone"
+        "#
+      )
+      .unwrap(),
+      Match {
+        cause: MatchCause::Trigger(TriggerCause {
+          triggers: vec!["Hello".to_string()],
+          ..Default::default()
+        }),
+        effect: MatchEffect::Text(TextEffect {
+          replace: "world".to_string(),
+          variants: vec![
+            TextVariant {
+              filter_title: None,
+              filter_class: Some(MatchRegex::new("Slack").unwrap()),
+              filter_exec: None,
+              replace: "there".to_string(),
+            },
+            TextVariant {
+              filter_title: Some(MatchRegex::new("Write").unwrap()),
+              filter_class: None,
+              filter_exec: Some(MatchRegex::new("thunderbird").unwrap()),
+              replace: "everyone".to_string(),
+            },
+          ],
+          ..Default::default()
+        }),
+        ..Default::default()
+      }
+    )
+  }
+
+  #[test]
+  fn variants_without_filters_are_skipped() {
+    let (m, warnings) = create_match_with_warnings(
+      r#"
+        trigger: "Hello"
+        replace: "world"
+        variants:
+          - replace: "there"
+        "#,
+      false,
+    )
+    .unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    assert!(m.effect.as_text().unwrap().variants.is_empty());
+  }
+
+  #[test]
+  fn variants_share_the_format_of_the_match() {
+    let m = create_match(
+      r#"
+        trigger: "Hello"
+        markdown: "**world**"
+        variants:
+          - filter_class: "Slack"
+            markdown: "*there*"
+        "#,
+    )
+    .unwrap();
+
+    let effect = m.effect.as_text().unwrap();
+    assert_eq!(effect.format, TextFormat::Markdown);
+    assert_eq!(effect.variants[0].replace, "*there*");
+  }
+
+  #[test]
+  fn variants_with_a_different_format_fail() {
+    assert!(create_match(
+      r#"
+        trigger: "Hello"
+        markdown: "**world**"
+        variants:
+          - filter_class: "Slack"
+            replace: "there"
+        "#
+    )
+    .is_err());
+
+    assert!(create_match(
+      r#"
+        trigger: "Hello"
+        replace: "world"
+        variants:
+          - filter_class: "Slack"
+            replace: "there"
+            html: "<b>there</b>"
+        "#
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn variants_with_invalid_filter_fails() {
+    assert!(create_match(
+      r#"
+        trigger: "Hello"
+        replace: "world"
+        variants:
+          - filter_title: "(unclosed"
+            replace: "there"
+        "#
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn propagate_maps_correctly() {
+    assert!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: ":world"
+        propagate: true
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .propagate
+    );
+
+    assert!(
+      !create_match(
+        r#"
+        trigger: "Hello"
+        replace: ":world"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .propagate
+    );
+  }
+
+  #[test]
+  fn auto_suffixes_generate_trigger_variants() {
+    assert_eq!(
+      create_match(
+        r#"
+        triggers: [":dog", ":box"]
+        replace: "animal"
+        auto_suffixes: ["s", "es"]
+        "#
+      )
+      .unwrap()
+      .cause
+      .into_trigger()
+      .unwrap()
+      .triggers,
+      vec![
+        ":dog".to_string(),
+        ":dogs".to_string(),
+        ":doges".to_string(),
+        ":box".to_string(),
+        ":boxs".to_string(),
+        ":boxes".to_string(),
+      ]
+    );
+
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: ":dog"
+        replace: "animal"
+        auto_suffixes: ["s", "", "s"]
+        "#
+      )
+      .unwrap()
+      .cause
+      .into_trigger()
+      .unwrap()
+      .triggers,
+      vec![":dog".to_string(), ":dogs".to_string()]
+    );
+  }
+
+  #[test]
+  fn description_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        description: "Greeting"
+        "#
+      )
+      .unwrap()
+      .search_description,
+      Some("Greeting".to_string())
+    );
+  }
+
+  #[test]
+  fn searchable_maps_correctly() {
+    assert!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .searchable
+    );
+
+    assert!(
+      !create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        searchable: false
+        "#
+      )
+      .unwrap()
+      .searchable
+    );
+  }
+
+  #[test]
+  fn search_terms_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: ":inv"
+        replace: "Invoice #001"
+        search_terms: ["billing", "receipt"]
+        "#
+      )
+      .unwrap()
+      .search_terms,
+      vec!["billing".to_string(), "receipt".to_string()]
+    );
+
+    assert!(create_match(
+      r#"
+      trigger: ":inv"
+      replace: "Invoice #001"
+      "#
+    )
+    .unwrap()
+    .search_terms
+    .is_empty());
+  }
+
+  #[test]
+  fn exclusive_maps_correctly() {
+    assert!(
+      !create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .exclusive
+    );
+
+    assert!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        exclusive: true
+        "#
+      )
+      .unwrap()
+      .exclusive
+    );
+  }
+
+  #[test]
+  fn filter_field_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .filter_field,
+      None
+    );
+
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        filter_field: "(?i)address"
+        "#
+      )
+      .unwrap()
+      .filter_field
+      .map(|filter| filter.as_str().to_string())
+      .as_deref(),
+      Some("(?i)address")
+    );
+
+    assert!(create_match(
+      r#"
+        trigger: "Hello"
+        replace: "world"
+        filter_field: "(address"
+        "#
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn markdown_flavor_maps_correctly() {
+    let text_effect = create_match(
+      r#"
+      trigger: "Hello"
+      markdown: "```\ncode\n```"
+      markdown_flavor: gfm
+      "#,
+    )
+    .unwrap()
+    .effect
+    .into_text()
+    .unwrap();
+    assert_eq!(text_effect.format, TextFormat::Markdown);
+    assert_eq!(text_effect.markdown_flavor, MarkdownFlavor::Gfm);
+
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        markdown: "**world**"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .markdown_flavor,
+      MarkdownFlavor::Standard
+    );
+  }
+
+  #[test]
+  fn notify_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: ":sig"
+        replace: "Best regards"
+        notify: "Inserted signature"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .notify
+      .as_deref(),
+      Some("Inserted signature")
+    );
+
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: ":logo"
+        image_path: "$CONFIG/logo.png"
+        notify: "Inserted logo"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_image()
+      .unwrap()
+      .notify
+      .as_deref(),
+      Some("Inserted logo")
+    );
+
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .notify,
+      None
+    );
+  }
+
+  #[test]
+  fn newline_as_key_maps_correctly() {
+    assert!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "first\nsecond"
+        newline_as_key: true
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .newline_as_key
+    );
+
+    assert!(
+      !create_match(
+        r#"
+        trigger: "Hello"
+        replace: "first\nsecond"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .newline_as_key
+    );
+  }
+
+  #[test]
+  fn keep_trigger_maps_correctly() {
+    assert!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: " world"
+        keep_trigger: true
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .keep_trigger
+    );
+
+    assert!(
+      !create_match(
+        r#"
+        trigger: "Hello"
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .keep_trigger
+    );
+  }
+
+  #[test]
+  fn transforms_map_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        replace: "  world  "
+        transforms: ["trim", "upper"]
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .transforms,
+      vec![TextTransform::Trim, TextTransform::Upper]
+    );
+  }
+
+  #[test]
+  fn unknown_transforms_are_skipped_with_a_warning() {
+    let (m, warnings) = create_match_with_warnings(
+      r#"
+        trigger: "Hello"
+        replace: "  world  "
+        transforms: ["trim", "uper"]
+        "#,
+      false,
+    )
+    .unwrap();
+    assert_eq!(
+      m.effect.into_text().unwrap().transforms,
+      vec![TextTransform::Trim]
+    );
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].to_string().contains("\"uper\""));
+  }
+
+  #[test]
+  fn confirm_maps_correctly() {
+    assert!(
+      create_match(
+        r#"
+        trigger: ":pwd"
+        replace: "secret"
+        confirm: true
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .confirm
+    );
+
+    assert!(
+      !create_match(
+        r#"
+        trigger: ":pwd"
+        replace: "secret"
+        "#
+      )
+      .unwrap()
+      .effect
+      .into_text()
+      .unwrap()
+      .confirm
+    );
+  }
+
+  #[test]
+  fn regex_max_lookback_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        regex: "hello\\d+"
+        max_lookback: 10
+        replace: "world"
+        "#
+      )
+      .unwrap()
+      .cause,
+      MatchCause::Regex(RegexCause {
+        regex: "hello\\d+".to_string(),
+        max_lookback: Some(10),
+        ..Default::default()
+      })
+    );
+  }
+
+  #[test]
+  fn regex_propagate_case_maps_correctly() {
+    assert_eq!(
+      create_match(
+        r#"
+        regex: "hi (?P<name>\\w+)"
+        replace: "hello {{name}}"
+        propagate_case: true
+        uppercase_style: "capitalize_words"
+        "#
+      )
+      .unwrap()
+      .cause,
+      MatchCause::Regex(RegexCause {
+        regex: "hi (?P<name>\\w+)".to_string(),
+        max_lookback: None,
+        propagate_case: true,
+        uppercase_style: UpperCasingStyle::CapitalizeWords,
+      })
+    );
+  }
+
+  #[test]
+  fn form_maps_correctly() {
+    let mut params = Params::new();
+    params.insert(
+      "layout".to_string(),
+      Value::String("Hi [[name]]!".to_string()),
+    );
+
+    assert_eq!(
+      create_match(
+        r#"
+        trigger: "Hello"
+        form: "Hi [[name]]!"
+        "#
+      )
+      .unwrap(),
+      Match {
+        cause: MatchCause::Trigger(TriggerCause {
+          triggers: vec!["Hello".to_string()],
+          ..Default::default()
+        }),
+        effect: MatchEffect::Text(TextEffect {
+          replace: "Hi {{form1.name}}!".to_string(),
+          vars: vec![Variable {
+            id: 0,
+            name: "form1".to_string(),
+            var_type: "form".to_string(),
+            params,
+            ..Default::default()
+          }],
+          ..Default::default()
+        }),
+        ..Default::default()
+      }
     )
   }
 
@@ -708,6 +2179,34 @@ mod tests {
     )
   }
 
+  #[test]
+  fn form_fields_keep_declaration_order() {
+    let form_match = create_match(
+      r#"
+      trigger: "Hello"
+      form: "[[surname]] [[name]] [[age]]"
+      form_fields:
+        surname:
+          default: "Doe"
+        age:
+          type: text
+        name:
+          default: "John"
+      "#,
+    )
+    .unwrap();
+
+    let params = &form_match.effect.as_text().unwrap().vars[0].params;
+    assert_eq!(
+      params.get("order").unwrap(),
+      &Value::Array(vec![
+        Value::String("surname".to_string()),
+        Value::String("age".to_string()),
+        Value::String("name".to_string()),
+      ])
+    );
+  }
+
   #[test]
   fn vars_maps_correctly() {
     let mut params = Params::new();
@@ -747,7 +2246,7 @@ mod tests {
   }
 
   #[test]
-  fn vars_inject_vars_and_depends_on() {
+  fn vars_inject_vars_depends_on_and_persist() {
     let vars = vec![
       Variable {
         name: "var1".to_string(),
@@ -761,6 +2260,12 @@ mod tests {
         inject_vars: false,
         ..Default::default()
       },
+      Variable {
+        name: "var3".to_string(),
+        var_type: "test".to_string(),
+        persist: true,
+        ..Default::default()
+      },
     ];
     assert_eq!(
       create_match(
@@ -774,6 +2279,9 @@ mod tests {
           - name: var2
             type: "test"
             inject_vars: false
+          - name: var3
+            type: test
+            persist: true
         "#
       )
       .unwrap(),
@@ -863,7 +2371,7 @@ mod tests {
       std::fs::write(&sub_file, "").unwrap();
 
       let importer = YAMLImporter::new();
-      let (mut group, non_fatal_error_set) = importer.load_group(&base_file).unwrap();
+      let (mut group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
       // The invalid import path should be reported as error
       assert_eq!(non_fatal_error_set.unwrap().errors.len(), 1);
 
@@ -894,11 +2402,52 @@ mod tests {
             }),
             ..Default::default()
           }],
+          label: None,
+          word_separators: None,
         }
       )
     });
   }
 
+  #[test]
+  fn importer_conditional_imports() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      let other_os = if cfg!(target_os = "windows") {
+        "linux"
+      } else {
+        "windows"
+      };
+      std::fs::write(
+        &base_file,
+        format!(
+          r#"
+      imports:
+        - "plain.yml"
+        - path: "conditional.yml"
+          os: "{}"
+      "#,
+          other_os
+        ),
+      )
+      .unwrap();
+
+      let plain_file = match_dir.join("plain.yml");
+      std::fs::write(&plain_file, "").unwrap();
+      let conditional_file = match_dir.join("conditional.yml");
+      std::fs::write(&conditional_file, "").unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+
+      assert!(non_fatal_error_set.is_none());
+      assert_eq!(
+        group.imports,
+        vec![plain_file.to_string_lossy().to_string()]
+      );
+    });
+  }
+
   #[test]
   fn importer_invalid_syntax() {
     use_test_directory(|_, match_dir, _| {
@@ -914,7 +2463,360 @@ mod tests {
       .unwrap();
 
       let importer = YAMLImporter::new();
-      assert!(importer.load_group(&base_file).is_err());
+      assert!(importer.load_group(&base_file, usize::MAX).is_err());
+    })
+  }
+
+  #[test]
+  fn importer_undefined_alias() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      imports:
+        - "_another.yml"
+
+      matches:
+        - trigger: "hello"
+          replace: *greeting
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let err = importer.load_group(&base_file, usize::MAX).unwrap_err();
+      let message = format!("{:?}", err);
+      assert!(message.contains(&base_file.to_string_lossy().to_string()));
+
+      match err.downcast::<YAMLParseError>().unwrap() {
+        YAMLParseError::UndefinedAlias { alias, line } => {
+          assert_eq!(alias, "greeting");
+          assert_eq!(line, 7);
+        }
+        other => panic!("unexpected error: {:?}", other),
+      }
+    })
+  }
+
+  #[test]
+  fn importer_tab_indentation() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        "matches:\n  - trigger: hello\n  \treplace: world\n",
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let err = importer.load_group(&base_file, usize::MAX).unwrap_err();
+      let message = format!("{:?}", err);
+      assert!(message.contains(&base_file.to_string_lossy().to_string()));
+      assert!(message.contains("line 3 is indented with tabs"));
+    })
+  }
+
+  #[test]
+  fn importer_include_merges_matches_inline() {
+    use_test_directory(|_, match_dir, _| {
+      let sub_dir = match_dir.join("sub");
+      create_dir_all(&sub_dir).unwrap();
+
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      matches:
+        - trigger: "first"
+          replace: "1"
+        - !include "sub/_included.yml"
+        - trigger: "last"
+          replace: "3"
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(
+        sub_dir.join("_included.yml"),
+        r#"
+      global_vars:
+        - name: "var1"
+          type: "test"
+
+      matches:
+        - trigger: "included"
+          replace: "2"
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert!(non_fatal_error_set.is_none());
+
+      let triggers: Vec<String> = group
+        .matches
+        .iter()
+        .map(|m| m.cause.as_trigger().unwrap().triggers[0].clone())
+        .collect();
+      assert_eq!(triggers, vec!["first", "included", "last"]);
+      assert_eq!(group.global_vars.len(), 1);
+      assert_eq!(group.global_vars[0].name, "var1");
+    })
+  }
+
+  #[test]
+  fn importer_circular_include_is_reported() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+        - !include "_other.yml"
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(
+        match_dir.join("_other.yml"),
+        r#"
+      matches:
+        - !include "base.yml"
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert_eq!(group.matches.len(), 1);
+
+      let errors = non_fatal_error_set.unwrap().errors;
+      assert_eq!(errors.len(), 1);
+      assert!(format!("{:?}", errors[0].error).contains("circular include"));
+    })
+  }
+
+  #[test]
+  fn importer_trigger_prefix_is_applied() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      trigger_prefix: ";"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+        - triggers: ["one", "two"]
+          replace: "multiple"
+        - regex: "hi(?P<name>.*)"
+          replace: "regex"
+        - !include "_included.yml"
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(
+        match_dir.join("_included.yml"),
+        r#"
+      matches:
+        - trigger: "included"
+          replace: "unprefixed"
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert!(non_fatal_error_set.is_none());
+
+      assert_eq!(
+        group.matches[0].cause.as_trigger().unwrap().triggers,
+        vec![";hello"]
+      );
+      assert_eq!(
+        group.matches[1].cause.as_trigger().unwrap().triggers,
+        vec![";one", ";two"]
+      );
+      assert_eq!(
+        group.matches[2].cause.as_regex().unwrap().regex,
+        "hi(?P<name>.*)"
+      );
+      assert_eq!(
+        group.matches[3].cause.as_trigger().unwrap().triggers,
+        vec!["included"]
+      );
+    })
+  }
+
+  #[test]
+  fn importer_group_word_separators_are_applied() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      word_separators: [" ", "-"]
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+          word: true
+        - trigger: "hi"
+          replace: "there"
+          word: true
+          word_separators: [" "]
+        - regex: "hey"
+          replace: "regex"
+        - !include "_included.yml"
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(
+        match_dir.join("_included.yml"),
+        r#"
+      matches:
+        - trigger: "included"
+          replace: "global separators"
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert!(non_fatal_error_set.is_none());
+
+      assert_eq!(
+        group.word_separators,
+        Some(vec![" ".to_string(), "-".to_string()])
+      );
+      assert_eq!(
+        group.matches[0].cause.as_trigger().unwrap().word_separators,
+        Some(vec![" ".to_string(), "-".to_string()])
+      );
+      assert_eq!(
+        group.matches[1].cause.as_trigger().unwrap().word_separators,
+        Some(vec![" ".to_string()])
+      );
+      assert!(group.matches[2].cause.as_regex().is_some());
+      assert_eq!(
+        group.matches[3].cause.as_trigger().unwrap().word_separators,
+        None
+      );
+    })
+  }
+
+  #[test]
+  fn importer_table_expands_into_matches() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      matches:
+        - table:
+            ":afaik": "as far as I know"
+            ":brb": "be right back"
+            ":imo": "in my opinion"
+          propagate_case: true
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert!(non_fatal_error_set.is_none());
+
+      assert_eq!(group.matches.len(), 3);
+      let expanded: Vec<(&str, &str, bool)> = group
+        .matches
+        .iter()
+        .map(|m| {
+          let cause = m.cause.as_trigger().unwrap();
+          (
+            cause.triggers[0].as_str(),
+            m.effect.as_text().unwrap().replace.as_str(),
+            cause.propagate_case,
+          )
+        })
+        .collect();
+      assert_eq!(
+        expanded,
+        vec![
+          (":afaik", "as far as I know", true),
+          (":brb", "be right back", true),
+          (":imo", "in my opinion", true),
+        ]
+      );
+    })
+  }
+
+  #[test]
+  fn importer_table_with_replace_is_reported() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      matches:
+        - table:
+            ":brb": "be right back"
+          replace: "conflicting"
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, non_fatal_error_set) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert_eq!(group.matches.len(), 1);
+
+      let errors = non_fatal_error_set.unwrap().errors;
+      assert_eq!(errors.len(), 1);
+      assert!(format!("{:?}", errors[0].error).contains("'table'"));
+    })
+  }
+
+  #[test]
+  fn importer_group_label_is_attached() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      label: "Emails"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let unlabeled_file = match_dir.join("unlabeled.yml");
+      std::fs::write(
+        &unlabeled_file,
+        r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let importer = YAMLImporter::new();
+      let (group, _) = importer.load_group(&base_file, usize::MAX).unwrap();
+      assert_eq!(group.label.as_deref(), Some("Emails"));
+
+      let (group, _) = importer.load_group(&unlabeled_file, usize::MAX).unwrap();
+      assert_eq!(group.label, None);
     })
   }
 }
@@ -17,65 +17,276 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::path::Path;
+use std::{borrow::Cow, path::Path};
 
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_yaml::Mapping;
+use thiserror::Error;
+use yaml_rust::scanner::{Scanner, TokenType};
 
 use crate::util::is_yaml_empty;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct YAMLMatchGroup {
   #[serde(default)]
-  pub imports: Option<Vec<String>>,
+  pub imports: Option<Vec<YAMLImport>>,
 
   #[serde(default)]
   pub global_vars: Option<Vec<YAMLVariable>>,
 
   #[serde(default)]
   pub matches: Option<Vec<YAMLMatch>>,
+
+  // Prepended to all the triggers defined in this group
+  #[serde(default)]
+  pub trigger_prefix: Option<String>,
+
+  // Name of the group, used in place of the file name
+  #[serde(default)]
+  pub label: Option<String>,
+
+  // Word separators of the trigger matches defined in this group
+  #[serde(default)]
+  pub word_separators: Option<Vec<String>>,
+}
+
+// Matches defined inline in a config file. Only the `matches` and `global_vars`
+// keys are read, as the other ones (such as `label`) configure espanso
+#[derive(Debug, Deserialize)]
+struct YAMLInlineMatchGroup {
+  #[serde(default)]
+  global_vars: Option<Vec<YAMLVariable>>,
+
+  #[serde(default)]
+  matches: Option<Vec<YAMLMatch>>,
 }
 
 impl YAMLMatchGroup {
   pub fn parse_from_str(yaml: &str) -> Result<Self> {
-    // Because an empty string is not valid YAML but we want to support it anyway
-    if is_yaml_empty(yaml) {
-      return Ok(serde_yaml::from_str(
-        "arbitrary_field_that_will_not_block_the_parser: true",
-      )?);
-    }
-
-    Ok(serde_yaml::from_str(yaml)?)
+    parse_group_str(yaml)
   }
 
   // TODO: test
   pub fn parse_from_file(path: &Path) -> Result<Self> {
     let content = std::fs::read_to_string(path)?;
     Self::parse_from_str(&content)
+      .with_context(|| format!("unable to parse match group file: {:?}", path))
+  }
+
+  pub fn parse_inline_from_str(yaml: &str) -> Result<Self> {
+    let inline_group: YAMLInlineMatchGroup = parse_group_str(yaml)?;
+    Ok(Self {
+      imports: None,
+      global_vars: inline_group.global_vars,
+      matches: inline_group.matches,
+      trigger_prefix: None,
+      label: None,
+      word_separators: None,
+    })
+  }
+
+  pub fn parse_inline_from_file(path: &Path) -> Result<Self> {
+    let content = std::fs::read_to_string(path)?;
+    Self::parse_inline_from_str(&content)
+      .with_context(|| format!("unable to parse the matches of config file: {:?}", path))
   }
 }
 
+fn parse_group_str<T: DeserializeOwned>(yaml: &str) -> Result<T> {
+  // Because an empty string is not valid YAML but we want to support it anyway
+  if is_yaml_empty(yaml) {
+    return Ok(serde_yaml::from_str(
+      "arbitrary_field_that_will_not_block_the_parser: true",
+    )?);
+  }
+
+  let yaml = expand_include_tags(yaml);
+
+  serde_yaml::from_str(&yaml).map_err(|err| {
+    if let Some((alias, line)) = find_undefined_alias(&yaml, &err) {
+      YAMLParseError::UndefinedAlias { alias, line }.into()
+    } else if let Some(line) = find_tab_indentation(&yaml, &err) {
+      YAMLParseError::TabIndentation { line }.into()
+    } else {
+      err.into()
+    }
+  })
+}
+
+// Rejects the groups defining more than `max_matches` matches. serde_yaml 0.8
+// builds the event list of the whole document before deserializing it, so the
+// parsing can't be aborted early: the count is checked right after it, before
+// the matches are converted.
+pub(crate) fn check_match_limit(group: &YAMLMatchGroup, max_matches: usize) -> Result<()> {
+  let count = group.matches.as_ref().map_or(0, Vec::len);
+  if count > max_matches {
+    return Err(
+      YAMLParseError::TooManyMatches {
+        count,
+        limit: max_matches,
+      }
+      .into(),
+    );
+  }
+
+  Ok(())
+}
+
+const INCLUDE_TAG: &str = "!include";
+const INCLUDE_KEY: &str = "include:";
+
+// serde_yaml ignores custom tags on plain values, so before parsing we rewrite
+// every `!include path.yml` entry into an `include: path.yml` one.
+// The replacement has the same length, so error locations are preserved.
+fn expand_include_tags(yaml: &str) -> Cow<'_, str> {
+  if !yaml.contains(INCLUDE_TAG) {
+    return Cow::Borrowed(yaml);
+  }
+
+  // The scanner takes care of skipping comments and quoted strings
+  let tag_positions: Vec<usize> = Scanner::new(yaml.chars())
+    .filter_map(|token| match token.1 {
+      TokenType::Tag(handle, suffix) if handle == "!" && suffix == "include" => {
+        Some(token.0.index())
+      }
+      _ => None,
+    })
+    .collect();
+  if tag_positions.is_empty() {
+    return Cow::Borrowed(yaml);
+  }
+
+  // Markers are expressed in chars, so we need to convert them to byte offsets
+  let mut expanded = yaml.to_string();
+  for (char_index, (byte_index, _)) in yaml.char_indices().enumerate() {
+    if tag_positions.contains(&char_index) && yaml[byte_index..].starts_with(INCLUDE_TAG) {
+      expanded.replace_range(byte_index..byte_index + INCLUDE_TAG.len(), INCLUDE_KEY);
+    }
+  }
+
+  Cow::Owned(expanded)
+}
+
+// Anchors are scoped to the file that defines them, so an alias referencing
+// an anchor declared in another (imported) group makes the YAML parser fail
+// with a rather cryptic scanner error. Here we extract the alias name to
+// provide a more meaningful message.
+fn find_undefined_alias(yaml: &str, err: &serde_yaml::Error) -> Option<(String, usize)> {
+  if !err.to_string().contains("unknown anchor") {
+    return None;
+  }
+
+  let location = err.location()?;
+  let alias: String = yaml
+    .chars()
+    .skip(location.index())
+    .skip_while(|c| *c == '*')
+    .take_while(|c| !c.is_whitespace() && !matches!(c, ',' | '[' | ']' | '{' | '}'))
+    .collect();
+
+  if alias.is_empty() {
+    None
+  } else {
+    Some((alias, location.line()))
+  }
+}
+
+// YAML forbids tabs for indentation, but the resulting scanner errors
+// are hard to decipher for new users. Here we check if the line where
+// the error occurred (or the following one, as the scanner often reports
+// the end of the previous token) is indented with tabs.
+fn find_tab_indentation(yaml: &str, err: &serde_yaml::Error) -> Option<usize> {
+  let location = err.location()?;
+  let error_line = location.line().checked_sub(1)?;
+
+  yaml
+    .lines()
+    .enumerate()
+    .skip(error_line)
+    .take(2)
+    .find(|(_, line)| {
+      line
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .any(|c| c == '\t')
+    })
+    .map(|(index, _)| index + 1)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum YAMLImport {
+  Path(String),
+  Conditional {
+    path: String,
+
+    #[serde(default)]
+    os: Option<String>,
+  },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct YAMLMatch {
   #[serde(default)]
   pub label: Option<String>,
 
+  #[serde(default)]
+  pub description: Option<String>,
+
+  #[serde(default)]
+  pub searchable: Option<bool>,
+
+  #[serde(default)]
+  pub search_terms: Option<Vec<String>>,
+
+  #[serde(default)]
+  pub exclusive: Option<bool>,
+
+  #[serde(default)]
+  pub filter_field: Option<String>,
+
   #[serde(default)]
   pub trigger: Option<String>,
 
   #[serde(default)]
   pub triggers: Option<Vec<String>>,
 
+  // Compact form of several matches sharing the same options,
+  // each entry maps a trigger to its replacement
+  #[serde(default)]
+  pub table: Option<Mapping>,
+
+  #[serde(default)]
+  pub auto_suffixes: Option<Vec<String>>,
+
   #[serde(default)]
   pub regex: Option<String>,
 
+  #[serde(default)]
+  pub max_lookback: Option<usize>,
+
+  #[serde(default)]
+  pub hotkey: Option<String>,
+
+  #[serde(default)]
+  pub key_sequence: Option<String>,
+
   #[serde(default)]
   pub replace: Option<String>,
 
+  #[serde(default)]
+  pub replace_random: Option<Vec<String>>,
+
+  #[serde(default)]
+  pub variants: Option<Vec<YAMLTextVariant>>,
+
   #[serde(default)]
   pub image_path: Option<String>,
 
+  #[serde(default)]
+  pub preserve_image_format: Option<bool>,
+
   #[serde(default)]
   pub form: Option<String>,
 
@@ -94,6 +305,12 @@ pub struct YAMLMatch {
   #[serde(default)]
   pub right_word: Option<bool>,
 
+  #[serde(default)]
+  pub line_start: Option<bool>,
+
+  #[serde(default)]
+  pub word_separators: Option<Vec<String>>,
+
   #[serde(default)]
   pub propagate_case: Option<bool>,
 
@@ -109,11 +326,67 @@ pub struct YAMLMatch {
   #[serde(default)]
   pub markdown: Option<String>,
 
+  #[serde(default)]
+  pub markdown_flavor: Option<String>,
+
   #[serde(default)]
   pub paragraph: Option<bool>,
 
   #[serde(default)]
   pub html: Option<String>,
+
+  #[serde(default)]
+  pub trim: Option<bool>,
+
+  #[serde(default)]
+  pub strip_leading_newline: Option<bool>,
+
+  #[serde(default)]
+  pub normalize_quotes: Option<bool>,
+
+  #[serde(default)]
+  pub propagate: Option<bool>,
+
+  #[serde(default)]
+  pub keep_trigger: Option<bool>,
+
+  #[serde(default)]
+  pub newline_as_key: Option<bool>,
+
+  #[serde(default)]
+  pub notify: Option<String>,
+
+  #[serde(default)]
+  pub transforms: Option<Vec<String>>,
+
+  #[serde(default)]
+  pub confirm: Option<bool>,
+
+  // Set by the `!include path.yml` entries, whose matches are merged inline
+  #[serde(default)]
+  pub include: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct YAMLTextVariant {
+  #[serde(default)]
+  pub filter_title: Option<String>,
+
+  #[serde(default)]
+  pub filter_class: Option<String>,
+
+  #[serde(default)]
+  pub filter_exec: Option<String>,
+
+  // Only the key matching the format of the match can be used
+  #[serde(default)]
+  pub replace: Option<String>,
+
+  #[serde(default)]
+  pub markdown: Option<String>,
+
+  #[serde(default)]
+  pub html: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -129,10 +402,98 @@ pub struct YAMLVariable {
   #[serde(default)]
   pub inject_vars: Option<bool>,
 
-  #[serde(default)]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub depends_on: Vec<String>,
+
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub persist: bool,
 }
 
 fn default_params() -> Mapping {
   Mapping::new()
 }
+
+#[derive(Error, Debug)]
+pub enum YAMLParseError {
+  #[error("undefined alias '*{alias}' at line {line}, anchors can only be referenced within the file that defines them (not across imports)")]
+  UndefinedAlias { alias: String, line: usize },
+
+  #[error("line {line} is indented with tabs, but YAML only allows spaces for indentation")]
+  TabIndentation { line: usize },
+
+  #[error("the group defines {count} matches, exceeding the limit of {limit} (max_group_matches)")]
+  TooManyMatches { count: usize, limit: usize },
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn expand_include_tags_rewrites_tags() {
+    let yaml = "matches:\n  - !include other.yml\n  - trigger: \"a\"\n";
+    assert_eq!(
+      expand_include_tags(yaml),
+      "matches:\n  - include: other.yml\n  - trigger: \"a\"\n"
+    );
+  }
+
+  #[test]
+  fn expand_include_tags_ignores_strings_and_comments() {
+    let yaml = "matches:\n  # !include other.yml\n  - trigger: \"!include\"\n";
+    assert_eq!(expand_include_tags(yaml), yaml);
+  }
+
+  #[test]
+  fn inline_groups_only_read_matches_and_global_vars() {
+    let group = YAMLMatchGroup::parse_inline_from_str(
+      r#"
+      label: "Work"
+      word_separators: [" "]
+      backend: Clipboard
+      global_vars:
+        - name: "a"
+          type: "echo"
+          params:
+            echo: "b"
+      matches:
+        - trigger: "a"
+          replace: "b"
+      "#,
+    )
+    .unwrap();
+
+    assert_eq!(group.global_vars.unwrap().len(), 1);
+    assert_eq!(group.matches.unwrap().len(), 1);
+    assert!(group.label.is_none());
+    assert!(group.word_separators.is_none());
+  }
+
+  #[test]
+  fn include_entries_are_parsed() {
+    let group = YAMLMatchGroup::parse_from_str(
+      r#"
+      matches:
+        - trigger: "a"
+          replace: "b"
+        - !include "sub/other.yml"
+      "#,
+    )
+    .unwrap();
+
+    let matches = group.matches.unwrap();
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0].include, None);
+    assert_eq!(matches[1].include.as_deref(), Some("sub/other.yml"));
+  }
+
+  #[test]
+  fn tab_indentation_is_reported() {
+    let yaml = "matches:\n  - trigger: a\n  \treplace: b\n";
+    let err = YAMLMatchGroup::parse_from_str(yaml).unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<YAMLParseError>(),
+      Some(YAMLParseError::TabIndentation { line: 3 })
+    ));
+  }
+}
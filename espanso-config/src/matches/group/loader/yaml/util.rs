@@ -23,7 +23,9 @@ use anyhow::Result;
 use serde_yaml::Mapping;
 use thiserror::Error;
 
-use crate::matches::{Number, Params, Value};
+use crate::matches::{HotKeyCause, KeySequenceCause, Number, Params, Value};
+
+pub const DEFAULT_KEY_SEQUENCE_WINDOW_MS: u64 = 300;
 
 pub(crate) fn convert_params(m: Mapping) -> Result<Params> {
   let mut params = Params::new();
@@ -73,6 +75,127 @@ fn convert_value(value: serde_yaml::Value) -> Result<Value> {
   })
 }
 
+// Parse a shortcut such as "CTRL+ALT+E", normalizing the modifier aliases.
+// Only the structure is checked here, the key itself is validated against the
+// keys supported by the detection backend (see `MatchStoreOptions::hotkey_validator`)
+pub(crate) fn parse_hotkey(spec: &str) -> Result<HotKeyCause> {
+  let mut modifiers: Vec<&str> = Vec::new();
+  let mut key = None;
+
+  for token in spec.split('+').map(|token| token.trim().to_uppercase()) {
+    let modifier = match token.as_str() {
+      "CTRL" | "CONTROL" => Some("CTRL"),
+      "ALT" | "OPTION" => Some("ALT"),
+      "SHIFT" => Some("SHIFT"),
+      "META" | "CMD" | "SUPER" => Some("META"),
+      _ => None,
+    };
+
+    match modifier {
+      Some(modifier) => {
+        if !modifiers.contains(&modifier) {
+          modifiers.push(modifier);
+        }
+      }
+      None if token.is_empty() || key.is_some() => {
+        return Err(HotKeyError::InvalidHotKey(spec.to_string()).into())
+      }
+      None => key = Some(token),
+    }
+  }
+
+  match key {
+    Some(key) if !modifiers.is_empty() => Ok(HotKeyCause {
+      hotkey: format!("{}+{}", modifiers.join("+"), key),
+    }),
+    _ => Err(HotKeyError::InvalidHotKey(spec.to_string()).into()),
+  }
+}
+
+// Parse a key sequence such as "SHIFT+SHIFT within 300ms", where the keys
+// have to be pressed (and released) one after the other within the window
+pub fn parse_key_sequence(spec: &str) -> Result<KeySequenceCause> {
+  let invalid = || KeySequenceError::InvalidKeySequence(spec.to_string());
+
+  let normalized = spec.trim().to_uppercase();
+  let (keys_spec, window_ms) = match normalized.split_once(" WITHIN ") {
+    Some((keys_spec, window_spec)) => {
+      let window_ms = window_spec
+        .trim()
+        .strip_suffix("MS")
+        .and_then(|millis| millis.trim().parse::<u64>().ok())
+        .filter(|millis| *millis > 0)
+        .ok_or_else(invalid)?;
+      (keys_spec, window_ms)
+    }
+    None => (normalized.as_str(), DEFAULT_KEY_SEQUENCE_WINDOW_MS),
+  };
+
+  let keys = keys_spec
+    .split('+')
+    .map(|token| normalize_sequence_key(token.trim()).ok_or_else(invalid))
+    .collect::<std::result::Result<Vec<String>, KeySequenceError>>()?;
+
+  if keys.len() < 2 {
+    return Err(invalid().into());
+  }
+
+  Ok(KeySequenceCause { keys, window_ms })
+}
+
+fn normalize_sequence_key(token: &str) -> Option<String> {
+  let (side, key) = if let Some(key) = token.strip_prefix("LEFT_") {
+    ("LEFT_", key)
+  } else if let Some(key) = token.strip_prefix("RIGHT_") {
+    ("RIGHT_", key)
+  } else {
+    ("", token)
+  };
+
+  let modifier = match key {
+    "CTRL" | "CONTROL" => Some("CTRL"),
+    "ALT" | "OPTION" => Some("ALT"),
+    "SHIFT" => Some("SHIFT"),
+    "META" | "CMD" | "SUPER" => Some("META"),
+    _ => None,
+  };
+  if let Some(modifier) = modifier {
+    return Some(format!("{}{}", side, modifier));
+  }
+
+  // Only modifiers have a left and right variant
+  if !side.is_empty() {
+    return None;
+  }
+
+  let is_function_key = key
+    .strip_prefix('F')
+    .and_then(|n| n.parse::<u8>().ok())
+    .map(|n| (1..=20).contains(&n))
+    .unwrap_or(false);
+
+  match key {
+    "ESC" | "ESCAPE" => Some("ESCAPE".to_string()),
+    "CAPSLOCK" | "ENTER" | "TAB" | "SPACE" => Some(key.to_string()),
+    _ if is_function_key => Some(key.to_string()),
+    _ => None,
+  }
+}
+
+#[derive(Error, Debug)]
+pub enum KeySequenceError {
+  #[error(
+    "invalid key sequence `{0}`, expected two or more keys, such as SHIFT+SHIFT within 300ms"
+  )]
+  InvalidKeySequence(String),
+}
+
+#[derive(Error, Debug)]
+pub enum HotKeyError {
+  #[error("invalid hotkey `{0}`, expected one or more modifiers and a key, such as CTRL+ALT+E")]
+  InvalidHotKey(String),
+}
+
 #[derive(Error, Debug)]
 pub enum ConversionError {
   #[error("invalid key format")]
@@ -173,4 +296,52 @@ mod tests {
 
     assert!(convert_params(mapping).is_err());
   }
+
+  #[test]
+  fn parse_hotkey_works_correctly() {
+    assert_eq!(parse_hotkey("CTRL+ALT+E").unwrap().hotkey, "CTRL+ALT+E");
+    assert_eq!(
+      parse_hotkey("ctrl + shift + v").unwrap().hotkey,
+      "CTRL+SHIFT+V"
+    );
+    assert_eq!(parse_hotkey("Cmd+Space").unwrap().hotkey, "META+SPACE");
+    assert_eq!(parse_hotkey("ALT+F1").unwrap().hotkey, "ALT+F1");
+  }
+
+  #[test]
+  fn parse_key_sequence_works_correctly() {
+    let sequence = parse_key_sequence("shift+shift").unwrap();
+    assert_eq!(sequence.keys, vec!["SHIFT", "SHIFT"]);
+    assert_eq!(sequence.window_ms, DEFAULT_KEY_SEQUENCE_WINDOW_MS);
+
+    let sequence = parse_key_sequence("Left_Ctrl + Cmd + esc within 500ms").unwrap();
+    assert_eq!(sequence.keys, vec!["LEFT_CTRL", "META", "ESCAPE"]);
+    assert_eq!(sequence.window_ms, 500);
+
+    let sequence = parse_key_sequence("F13+F13 WITHIN 250 ms").unwrap();
+    assert_eq!(sequence.keys, vec!["F13", "F13"]);
+    assert_eq!(sequence.window_ms, 250);
+  }
+
+  #[test]
+  fn parse_key_sequence_invalid() {
+    assert!(parse_key_sequence("SHIFT").is_err());
+    assert!(parse_key_sequence("").is_err());
+    assert!(parse_key_sequence("SHIFT+A").is_err());
+    assert!(parse_key_sequence("SHIFT++SHIFT").is_err());
+    assert!(parse_key_sequence("LEFT_SPACE+SPACE").is_err());
+    assert!(parse_key_sequence("F21+F21").is_err());
+    assert!(parse_key_sequence("SHIFT+SHIFT within 300").is_err());
+    assert!(parse_key_sequence("SHIFT+SHIFT within 0ms").is_err());
+    assert!(parse_key_sequence("SHIFT+SHIFT within fast").is_err());
+  }
+
+  #[test]
+  fn parse_hotkey_invalid() {
+    assert!(parse_hotkey("E").is_err());
+    assert!(parse_hotkey("CTRL+ALT").is_err());
+    assert!(parse_hotkey("CTRL+A+B").is_err());
+    assert!(parse_hotkey("CTRL++").is_err());
+    assert!(parse_hotkey("").is_err());
+  }
 }
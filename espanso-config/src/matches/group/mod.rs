@@ -19,19 +19,28 @@
 
 use anyhow::Result;
 use std::path::Path;
+use walkdir::WalkDir;
 
 use crate::error::NonFatalErrorSet;
 
 use super::{Match, Variable};
 
 pub(crate) mod loader;
-mod path;
+pub(crate) mod path;
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct MatchGroup {
   pub imports: Vec<String>,
   pub global_vars: Vec<Variable>,
   pub matches: Vec<Match>,
+
+  // Shown in place of the file name when grouping matches,
+  // for example in the search bar
+  pub label: Option<String>,
+
+  // Word separators of the trigger matches defined in this group,
+  // unless they specify their own
+  pub word_separators: Option<Vec<String>>,
 }
 
 impl Default for MatchGroup {
@@ -40,6 +49,8 @@ impl Default for MatchGroup {
       imports: Vec::new(),
       global_vars: Vec::new(),
       matches: Vec::new(),
+      label: None,
+      word_separators: None,
     }
   }
 }
@@ -49,4 +60,47 @@ impl MatchGroup {
   pub fn load(group_path: &Path) -> Result<(Self, Option<NonFatalErrorSet>)> {
     loader::load_match_group(group_path)
   }
+
+  pub fn load_with_limits(
+    group_path: &Path,
+    limits: &loader::LoadLimits,
+  ) -> Result<(Self, Option<NonFatalErrorSet>)> {
+    loader::load_match_group_with_limits(group_path, limits)
+  }
+
+  // Only the matches and global variables of the config file are loaded
+  pub fn load_inline_with_limits(
+    config_path: &Path,
+    limits: &loader::LoadLimits,
+  ) -> Result<(Self, Option<NonFatalErrorSet>)> {
+    loader::load_inline_match_group_with_limits(config_path, limits)
+  }
+}
+
+// Loads every match group found under the given directory, including the
+// ones that are only imported, returning the errors found along the way
+pub(crate) fn check_dir(match_dir: &Path) -> Vec<NonFatalErrorSet> {
+  let mut errors = Vec::new();
+
+  for entry in WalkDir::new(match_dir)
+    .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+    .into_iter()
+    .filter_map(|entry| entry.ok())
+  {
+    let path = entry.path();
+
+    // Package manifests live alongside the matches, but are not match groups
+    if !path.is_file() || !loader::is_match_group_file(path) || entry.file_name() == "_manifest.yml"
+    {
+      continue;
+    }
+
+    match MatchGroup::load(path) {
+      Ok((_, Some(non_fatal_error_set))) => errors.push(non_fatal_error_set),
+      Ok((_, None)) => {}
+      Err(err) => errors.push(NonFatalErrorSet::single_error(path, err)),
+    }
+  }
+
+  errors
 }
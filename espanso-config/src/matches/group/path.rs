@@ -21,11 +21,32 @@ use anyhow::{anyhow, Context, Result};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::error::ErrorRecord;
+use crate::{config::util::os_matches, error::ErrorRecord};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+  pub path: String,
+
+  // If specified, the import is only loaded on the given OS
+  pub os: Option<String>,
+}
+
+impl From<&str> for Import {
+  fn from(path: &str) -> Self {
+    Self {
+      path: path.to_string(),
+      os: None,
+    }
+  }
+}
+
+pub fn is_remote_import(path: &str) -> bool {
+  path.starts_with("http://") || path.starts_with("https://")
+}
 
 pub fn resolve_imports(
   group_path: &Path,
-  imports: &[String],
+  imports: &[Import],
 ) -> Result<(Vec<String>, Vec<ErrorRecord>)> {
   let mut paths = Vec::new();
 
@@ -49,7 +70,19 @@ pub fn resolve_imports(
   let mut non_fatal_errors = Vec::new();
 
   for import in imports.iter() {
-    let import_path = PathBuf::from(import);
+    if let Some(os) = import.os.as_deref() {
+      if !os_matches(os) {
+        continue;
+      }
+    }
+
+    // Remote imports are resolved when loading the match store
+    if is_remote_import(&import.path) {
+      paths.push(PathBuf::from(&import.path));
+      continue;
+    }
+
+    let import_path = PathBuf::from(&import.path);
 
     // Absolute or relative import
     let full_path = if import_path.is_relative() {
@@ -114,11 +147,12 @@ pub mod tests {
       let absolute_file = sub_dir.join("absolute.yml");
       std::fs::write(&absolute_file, "test").unwrap();
 
-      let imports = vec![
-        "another.yml".to_string(),
-        "sub/sub.yml".to_string(),
-        absolute_file.to_string_lossy().to_string(),
-        "sub/invalid.yml".to_string(), // Should be skipped
+      let absolute_path = absolute_file.to_string_lossy().to_string();
+      let imports: Vec<Import> = vec![
+        "another.yml".into(),
+        "sub/sub.yml".into(),
+        absolute_path.as_str().into(),
+        "sub/invalid.yml".into(), // Should be skipped
       ];
 
       let (resolved_imports, errors) = resolve_imports(&base_file, &imports).unwrap();
@@ -137,6 +171,21 @@ pub mod tests {
     });
   }
 
+  #[test]
+  fn resolve_imports_keeps_remote_imports() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(&base_file, "test").unwrap();
+
+      let imports: Vec<Import> = vec!["https://example.com/team.yml".into()];
+
+      let (resolved_imports, errors) = resolve_imports(&base_file, &imports).unwrap();
+
+      assert_eq!(resolved_imports, vec!["https://example.com/team.yml"]);
+      assert!(errors.is_empty());
+    });
+  }
+
   #[test]
   fn resolve_imports_parent_relative_path() {
     use_test_directory(|_, match_dir, _| {
@@ -149,7 +198,7 @@ pub mod tests {
       let sub_file = sub_dir.join("sub.yml");
       std::fs::write(&sub_file, "test").unwrap();
 
-      let imports = vec!["../base.yml".to_string()];
+      let imports: Vec<Import> = vec!["../base.yml".into()];
 
       let (resolved_imports, errors) = resolve_imports(&sub_file, &imports).unwrap();
 
@@ -161,4 +210,67 @@ pub mod tests {
       assert_eq!(errors.len(), 0);
     });
   }
+
+  fn current_os() -> &'static str {
+    if cfg!(target_os = "windows") {
+      "windows"
+    } else if cfg!(target_os = "macos") {
+      "macos"
+    } else {
+      "linux"
+    }
+  }
+
+  fn other_os() -> &'static str {
+    if cfg!(target_os = "windows") {
+      "linux"
+    } else {
+      "windows"
+    }
+  }
+
+  #[test]
+  fn resolve_imports_matching_os() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(&base_file, "test").unwrap();
+
+      let os_file = match_dir.join("os.yml");
+      std::fs::write(&os_file, "test").unwrap();
+
+      let imports = vec![Import {
+        path: "os.yml".to_string(),
+        os: Some(current_os().to_string()),
+      }];
+
+      let (resolved_imports, errors) = resolve_imports(&base_file, &imports).unwrap();
+
+      assert_eq!(
+        resolved_imports,
+        vec![os_file.to_string_lossy().to_string()]
+      );
+      assert_eq!(errors.len(), 0);
+    });
+  }
+
+  #[test]
+  fn resolve_imports_non_matching_os() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(&base_file, "test").unwrap();
+
+      let os_file = match_dir.join("os.yml");
+      std::fs::write(&os_file, "test").unwrap();
+
+      let imports = vec![Import {
+        path: "os.yml".to_string(),
+        os: Some(other_os().to_string()),
+      }];
+
+      let (resolved_imports, errors) = resolve_imports(&base_file, &imports).unwrap();
+
+      assert!(resolved_imports.is_empty());
+      assert_eq!(errors.len(), 0);
+    });
+  }
 }
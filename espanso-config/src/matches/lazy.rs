@@ -0,0 +1,251 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use super::{
+  group::{loader, MatchGroup},
+  MatchEffect,
+};
+
+// When the `lazy_replace` option is enabled, replacements longer than this (in bytes)
+// are not kept in memory, but loaded from their source file the first time the match
+// is expanded. Their content is not available to the other matches referencing them
+// with a "match" variable
+pub(crate) const LAZY_REPLACE_THRESHOLD: usize = 16 * 1024;
+
+// Length (in chars) of the preview kept in place of a deferred replacement
+const PREVIEW_LENGTH: usize = 80;
+
+// Locates a replacement in its source file. Caching the
+// loaded replacement, if needed, is up to the caller
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LazyReplace {
+  path: PathBuf,
+
+  // Position of the match in the group, as returned by the loader
+  index: usize,
+
+  // Hash of the replacement, used to detect whether the
+  // source file changed after being loaded
+  hash: String,
+
+  // Size of the replacement in bytes
+  size: usize,
+}
+
+impl LazyReplace {
+  pub(crate) fn new(path: &Path, index: usize, replace: &str) -> Self {
+    Self {
+      path: path.to_owned(),
+      index,
+      hash: hash_replace(replace),
+      size: replace.len(),
+    }
+  }
+
+  pub fn size(&self) -> usize {
+    self.size
+  }
+
+  pub fn load(&self) -> Result<String> {
+    let (group, _) = loader::load_match_group(&self.path)?;
+    let replace = group
+      .matches
+      .into_iter()
+      .nth(self.index)
+      .and_then(|m| match m.effect {
+        MatchEffect::Text(effect) => Some(effect.replace),
+        _ => None,
+      })
+      .ok_or_else(|| LazyReplaceError::MissingMatch(self.path.clone()))?;
+
+    if hash_replace(&replace) != self.hash {
+      return Err(LazyReplaceError::SourceChanged(self.path.clone()).into());
+    }
+
+    Ok(replace)
+  }
+}
+
+fn hash_replace(replace: &str) -> String {
+  hex::encode(Sha256::digest(replace.as_bytes()))
+}
+
+// Replaces the long replacements of the group with a short preview, so that
+// they are loaded only when needed. Returns the number of deferred bytes
+pub(crate) fn defer_long_replacements(group: &mut MatchGroup, path: &Path) -> usize {
+  let mut deferred = 0;
+
+  for (index, m) in group.matches.iter_mut().enumerate() {
+    if let MatchEffect::Text(effect) = &mut m.effect {
+      // Random replacements are all needed anyway
+      if effect.replace.len() > LAZY_REPLACE_THRESHOLD
+        && effect.replace_random.is_empty()
+        && effect.lazy_replace.is_none()
+      {
+        deferred += effect.replace.len();
+        effect.lazy_replace = Some(LazyReplace::new(path, index, &effect.replace));
+        effect.replace = preview(&effect.replace);
+      }
+    }
+  }
+
+  deferred
+}
+
+// Returns the size in bytes of the replacements kept in memory
+// and of the ones that are loaded lazily
+pub(crate) fn measure_replacements<'a>(
+  groups: impl Iterator<Item = &'a MatchGroup>,
+) -> (usize, usize) {
+  let mut in_memory = 0;
+  let mut deferred = 0;
+
+  for m in groups.flat_map(|group| group.matches.iter()) {
+    if let MatchEffect::Text(effect) = &m.effect {
+      in_memory += effect.replace.len();
+      in_memory += effect.replace_random.iter().map(String::len).sum::<usize>();
+      if let Some(lazy_replace) = &effect.lazy_replace {
+        deferred += lazy_replace.size();
+      }
+    }
+  }
+
+  (in_memory, deferred)
+}
+
+fn preview(replace: &str) -> String {
+  let first_line = replace.lines().next().unwrap_or_default();
+  let mut preview: String = first_line.chars().take(PREVIEW_LENGTH).collect();
+  if preview.len() < replace.len() {
+    preview.push('…');
+  }
+  preview
+}
+
+#[derive(Error, Debug)]
+pub enum LazyReplaceError {
+  #[error("the match is no longer defined in {0:?}")]
+  MissingMatch(PathBuf),
+
+  #[error("{0:?} changed after being loaded")]
+  SourceChanged(PathBuf),
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::tests::use_test_directory;
+
+  fn load_group(path: &Path) -> MatchGroup {
+    let (mut group, _) = loader::load_match_group(path).unwrap();
+    defer_long_replacements(&mut group, path);
+    group
+  }
+
+  #[test]
+  fn long_replacements_are_loaded_lazily() {
+    use_test_directory(|_, match_dir, _| {
+      let body = "Lorem ipsum dolor sit amet\n".repeat(1000);
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        format!(
+          "matches:\n  - trigger: \"short\"\n    replace: \"text\"\n  - trigger: \"long\"\n    replace: {:?}\n    trim: true\n",
+          body
+        ),
+      )
+      .unwrap();
+
+      let group = load_group(&base_file);
+      let short = group.matches[0].effect.as_text().unwrap();
+      assert_eq!(short.replace, "text");
+      assert!(short.lazy_replace.is_none());
+
+      let long = group.matches[1].effect.as_text().unwrap();
+      assert_eq!(long.replace, "Lorem ipsum dolor sit amet…");
+      assert_eq!(
+        long.lazy_replace.as_ref().unwrap().load().unwrap(),
+        body.trim_end()
+      );
+    })
+  }
+
+  #[test]
+  fn changed_source_is_reported() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      let write_body = |body: &str| {
+        std::fs::write(
+          &base_file,
+          format!("matches:\n  - trigger: \"long\"\n    replace: {:?}\n", body),
+        )
+        .unwrap();
+      };
+
+      write_body(&"a".repeat(LAZY_REPLACE_THRESHOLD + 1));
+      let group = load_group(&base_file);
+      let lazy_replace = group.matches[0]
+        .effect
+        .as_text()
+        .unwrap()
+        .lazy_replace
+        .clone();
+
+      // Same length, but different content
+      write_body(&"b".repeat(LAZY_REPLACE_THRESHOLD + 1));
+      assert!(lazy_replace.unwrap().load().is_err());
+    })
+  }
+
+  #[test]
+  fn measure_replacements_reports_deferred_bytes() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        format!(
+          "matches:\n  - trigger: \"short\"\n    replace: \"text\"\n  - trigger: \"long\"\n    replace: {:?}\n",
+          "a".repeat(LAZY_REPLACE_THRESHOLD + 1)
+        ),
+      )
+      .unwrap();
+
+      let (group, _) = loader::load_match_group(&base_file).unwrap();
+      assert_eq!(
+        measure_replacements(std::iter::once(&group)),
+        (LAZY_REPLACE_THRESHOLD + 5, 0)
+      );
+
+      let group = load_group(&base_file);
+      assert_eq!(
+        measure_replacements(std::iter::once(&group)),
+        (
+          4 + PREVIEW_LENGTH + '…'.len_utf8(),
+          LAZY_REPLACE_THRESHOLD + 1
+        )
+      );
+    })
+  }
+}
@@ -19,13 +19,25 @@
 
 use enum_as_inner::EnumAsInner;
 use ordered_float::OrderedFloat;
-use std::collections::BTreeMap;
+use regex::Regex;
+use std::{
+  borrow::Cow,
+  collections::BTreeMap,
+  hash::{Hash, Hasher},
+  ops::Deref,
+};
 
-use crate::counter::StructId;
+use crate::{config::AppProperties, counter::StructId};
 
+pub mod export;
 pub(crate) mod group;
+pub mod lazy;
 pub mod store;
 
+pub use group::loader::is_match_group_file;
+pub use group::loader::yaml::parse_match_definition;
+pub use group::loader::yaml::util::{parse_key_sequence, KeySequenceError};
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Match {
   pub id: StructId,
@@ -35,6 +47,23 @@ pub struct Match {
 
   // Metadata
   pub label: Option<String>,
+
+  // Secondary text shown in the search bar, in place of the cause description
+  pub search_description: Option<String>,
+
+  // If false, the match is hidden from the search bar, but can still be triggered
+  pub searchable: bool,
+
+  // Additional keywords the match can be found with in the search bar
+  pub search_terms: Vec<String>,
+
+  // If true, when this match fires the other candidates detected on the same
+  // buffer are dropped, instead of being offered in the selection dialog
+  pub exclusive: bool,
+
+  // Regex matched against the role of the focused text field (for example,
+  // a browser address bar). On platforms that can't detect it, the match never fires
+  pub filter_field: Option<MatchRegex>,
 }
 
 impl Default for Match {
@@ -43,6 +72,11 @@ impl Default for Match {
       cause: MatchCause::None,
       effect: MatchEffect::None,
       label: None,
+      search_description: None,
+      searchable: true,
+      search_terms: Vec::new(),
+      exclusive: false,
+      filter_field: None,
       id: 0,
     }
   }
@@ -66,6 +100,21 @@ impl Match {
   pub fn cause_description(&self) -> Option<&str> {
     self.cause.description()
   }
+
+  // Matches without a field filter are allowed in any field
+  pub fn is_field_allowed(&self, field_role: &str) -> bool {
+    match &self.filter_field {
+      Some(filter) => filter.is_match(field_role),
+      None => true,
+    }
+  }
+
+  pub fn search_tag(&self) -> Option<&str> {
+    self
+      .search_description
+      .as_deref()
+      .or_else(|| self.cause_description())
+  }
 }
 
 // Causes
@@ -75,29 +124,31 @@ pub enum MatchCause {
   None,
   Trigger(TriggerCause),
   Regex(RegexCause),
-  // TODO: shortcut
+  HotKey(HotKeyCause),
+  KeySequence(KeySequenceCause),
 }
 
 impl MatchCause {
   // TODO: test
   pub fn description(&self) -> Option<&str> {
-    if let MatchCause::Trigger(trigger_cause) = &self {
-      trigger_cause.triggers.first().map(|s| s.as_str())
-    } else {
-      None
+    match &self {
+      MatchCause::Trigger(trigger_cause) => trigger_cause.triggers.first().map(|s| s.as_str()),
+      MatchCause::HotKey(hotkey_cause) => Some(&hotkey_cause.hotkey),
+      _ => None,
     }
-    // TODO: insert rendering for hotkey/shortcut
     // TODO: insert rendering for regex? I'm worried it might be too long
   }
 
   // TODO: test
   pub fn long_description(&self) -> String {
-    if let MatchCause::Trigger(trigger_cause) = &self {
-      format!("triggers: {:?}", trigger_cause.triggers)
-    } else {
-      "No description available".to_owned()
+    match &self {
+      MatchCause::Trigger(trigger_cause) => format!("triggers: {:?}", trigger_cause.triggers),
+      MatchCause::HotKey(hotkey_cause) => format!("hotkey: {:?}", hotkey_cause.hotkey),
+      MatchCause::KeySequence(key_sequence_cause) => {
+        format!("key sequence: {:?}", key_sequence_cause.to_spec())
+      }
+      _ => "No description available".to_owned(),
     }
-    // TODO: insert rendering for hotkey/shortcut
     // TODO: insert rendering for regex? I'm worried it might be too long
   }
 }
@@ -109,8 +160,15 @@ pub struct TriggerCause {
   pub left_word: bool,
   pub right_word: bool,
 
+  // Only match when the trigger is typed at the start of a line
+  pub line_start: bool,
+
   pub propagate_case: bool,
   pub uppercase_style: UpperCasingStyle,
+
+  // Characters delimiting words for this match, in place of the
+  // global `word_separators` option
+  pub word_separators: Option<Vec<String>>,
 }
 
 impl Default for TriggerCause {
@@ -119,8 +177,10 @@ impl Default for TriggerCause {
       triggers: Vec::new(),
       left_word: false,
       right_word: false,
+      line_start: false,
       propagate_case: false,
       uppercase_style: UpperCasingStyle::Uppercase,
+      word_separators: None,
     }
   }
 }
@@ -135,16 +195,48 @@ pub enum UpperCasingStyle {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RegexCause {
   pub regex: String,
+
+  // Restricts the global `regex_max_lookback` option further for this match
+  pub max_lookback: Option<usize>,
+
+  // If enabled, the uppercase_style is applied to the named captures
+  pub propagate_case: bool,
+  pub uppercase_style: UpperCasingStyle,
 }
 
 impl Default for RegexCause {
   fn default() -> Self {
     Self {
       regex: String::new(),
+      max_lookback: None,
+      propagate_case: false,
+      uppercase_style: UpperCasingStyle::Uppercase,
     }
   }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HotKeyCause {
+  // Normalized shortcut, such as "CTRL+ALT+E"
+  pub hotkey: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequenceCause {
+  // Normalized keys, such as ["SHIFT", "SHIFT"]. Modifiers might
+  // be restricted to one side, as in "LEFT_CTRL"
+  pub keys: Vec<String>,
+
+  // Maximum time between the first and the last key of the sequence
+  pub window_ms: u64,
+}
+
+impl KeySequenceCause {
+  pub fn to_spec(&self) -> String {
+    format!("{} within {}ms", self.keys.join("+"), self.window_ms)
+  }
+}
+
 // Effects
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumAsInner)]
@@ -156,10 +248,82 @@ pub enum MatchEffect {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TextEffect {
+  // If the replacement is loaded lazily, this is only a short preview of it
   pub replace: String,
+  pub lazy_replace: Option<lazy::LazyReplace>,
   pub vars: Vec<Variable>,
   pub format: TextFormat,
   pub force_mode: Option<TextInjectMode>,
+  pub propagate: bool,
+
+  // Only relevant for the Markdown format
+  pub markdown_flavor: MarkdownFlavor,
+
+  // When enabled, the typed trigger is not deleted before injecting the replacement
+  pub keep_trigger: bool,
+
+  // When enabled, newlines are injected as Enter key presses instead of
+  // being part of the injected text
+  pub newline_as_key: bool,
+
+  // If not empty, one of these replacements is randomly chosen
+  // (in place of `replace`) every time the match is expanded
+  pub replace_random: Vec<String>,
+
+  // Replacements specific to some applications, the first one matching
+  // the active application is used in place of `replace`
+  pub variants: Vec<TextVariant>,
+
+  // Message shown in a notification once the match has been injected
+  pub notify: Option<String>,
+
+  // Built-in transforms applied, in order, to the rendered replacement
+  pub transforms: Vec<TextTransform>,
+
+  // When enabled, the user has to confirm the expansion before the
+  // replacement is rendered and injected, useful for sensitive snippets
+  pub confirm: bool,
+}
+
+impl TextEffect {
+  pub fn variant_for(&self, app: &AppProperties) -> Option<&TextVariant> {
+    self.variants.iter().find(|variant| variant.is_match(app))
+  }
+
+  // The complete replacement, loading it from the source file if needed
+  pub fn full_replace(&self) -> anyhow::Result<Cow<'_, str>> {
+    match &self.lazy_replace {
+      Some(lazy_replace) => Ok(Cow::Owned(lazy_replace.load()?)),
+      None => Ok(Cow::Borrowed(&self.replace)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextVariant {
+  pub filter_title: Option<MatchRegex>,
+  pub filter_class: Option<MatchRegex>,
+  pub filter_exec: Option<MatchRegex>,
+
+  // Shares the format of the match it belongs to
+  pub replace: String,
+}
+
+impl TextVariant {
+  // All the specified filters must match
+  pub fn is_match(&self, app: &AppProperties) -> bool {
+    let filters = [
+      (&self.filter_title, app.title),
+      (&self.filter_class, app.class),
+      (&self.filter_exec, app.exec),
+    ];
+
+    filters.iter().all(|(filter, value)| match (filter, value) {
+      (Some(filter), Some(value)) => filter.is_match(value),
+      (Some(_), None) => false,
+      (None, _) => true,
+    })
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -169,6 +333,44 @@ pub enum TextFormat {
   Html,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextTransform {
+  Trim,
+  Upper,
+  Lower,
+  Title,
+}
+
+impl TextTransform {
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "trim" => Some(Self::Trim),
+      "upper" => Some(Self::Upper),
+      "lower" => Some(Self::Lower),
+      "title" => Some(Self::Title),
+      _ => None,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Trim => "trim",
+      Self::Upper => "upper",
+      Self::Lower => "lower",
+      Self::Title => "title",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MarkdownFlavor {
+  Standard,
+
+  // Renders fenced code blocks and pipe tables in a way that preserves
+  // their formatting in the target application
+  Gfm,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TextInjectMode {
   Keys,
@@ -179,9 +381,19 @@ impl Default for TextEffect {
   fn default() -> Self {
     Self {
       replace: String::new(),
+      lazy_replace: None,
       vars: Vec::new(),
       format: TextFormat::Plain,
       force_mode: None,
+      propagate: false,
+      markdown_flavor: MarkdownFlavor::Standard,
+      keep_trigger: false,
+      newline_as_key: false,
+      replace_random: Vec::new(),
+      variants: Vec::new(),
+      notify: None,
+      transforms: Vec::new(),
+      confirm: false,
     }
   }
 }
@@ -189,12 +401,21 @@ impl Default for TextEffect {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ImageEffect {
   pub path: String,
+
+  // If true, the image is copied to the clipboard in its original
+  // format (when supported), instead of being converted
+  pub preserve_format: bool,
+
+  // Message shown in a notification once the match has been injected
+  pub notify: Option<String>,
 }
 
 impl Default for ImageEffect {
   fn default() -> Self {
     Self {
       path: String::new(),
+      preserve_format: false,
+      notify: None,
     }
   }
 }
@@ -207,6 +428,9 @@ pub struct Variable {
   pub params: Params,
   pub inject_vars: bool,
   pub depends_on: Vec<String>,
+  // If true, the variable is evaluated only once and its value
+  // is reused for the whole session
+  pub persist: bool,
 }
 
 impl Default for Variable {
@@ -218,10 +442,44 @@ impl Default for Variable {
       params: Params::new(),
       inject_vars: true,
       depends_on: Vec::new(),
+      persist: false,
     }
   }
 }
 
+// A regex compiled once at load time. Compared and hashed by its source, so
+// that it can be part of the match definitions
+#[derive(Debug, Clone)]
+pub struct MatchRegex(Regex);
+
+impl MatchRegex {
+  pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+    Ok(Self(Regex::new(pattern)?))
+  }
+}
+
+impl Deref for MatchRegex {
+  type Target = Regex;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl PartialEq for MatchRegex {
+  fn eq(&self, other: &Self) -> bool {
+    self.0.as_str() == other.0.as_str()
+  }
+}
+
+impl Eq for MatchRegex {}
+
+impl Hash for MatchRegex {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.0.as_str().hash(state);
+  }
+}
+
 pub type Params = BTreeMap<String, Value>;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumAsInner)]
@@ -239,3 +497,105 @@ pub enum Number {
   Integer(i64),
   Float(OrderedFloat<f64>),
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn search_tag_prefers_description() {
+    let m = Match {
+      cause: MatchCause::Trigger(TriggerCause {
+        triggers: vec![":sig".to_string()],
+        ..Default::default()
+      }),
+      search_description: Some("Email signature".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(m.search_tag(), Some("Email signature"));
+    assert_eq!(m.cause_description(), Some(":sig"));
+  }
+
+  #[test]
+  fn search_tag_falls_back_to_cause_description() {
+    let m = Match {
+      cause: MatchCause::Trigger(TriggerCause {
+        triggers: vec![":sig".to_string()],
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    assert_eq!(m.search_tag(), Some(":sig"));
+  }
+
+  fn variants_effect() -> TextEffect {
+    TextEffect {
+      replace: "Hello".to_string(),
+      variants: vec![
+        TextVariant {
+          filter_class: Some(MatchRegex::new("Slack").unwrap()),
+          filter_title: None,
+          filter_exec: None,
+          replace: "Hey :wave:".to_string(),
+        },
+        TextVariant {
+          filter_exec: Some(MatchRegex::new("thunderbird").unwrap()),
+          filter_title: Some(MatchRegex::new("Write").unwrap()),
+          filter_class: None,
+          replace: "Dear".to_string(),
+        },
+      ],
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn is_field_allowed_checks_filter() {
+    let m = Match {
+      filter_field: Some(MatchRegex::new("(?i)address").unwrap()),
+      ..Default::default()
+    };
+    assert!(m.is_field_allowed("AXTextField:Address and search bar"));
+    assert!(!m.is_field_allowed("AXTextArea"));
+    assert!(Match::default().is_field_allowed("AXTextArea"));
+  }
+
+  #[test]
+  fn variant_for_selects_first_matching_variant() {
+    let effect = variants_effect();
+
+    let slack = AppProperties {
+      title: Some("general"),
+      class: Some("Slack"),
+      exec: Some("/usr/bin/slack"),
+    };
+    assert_eq!(effect.variant_for(&slack).unwrap().replace, "Hey :wave:");
+
+    let email = AppProperties {
+      title: Some("Write: Re: meeting"),
+      class: None,
+      exec: Some("/usr/bin/thunderbird"),
+    };
+    assert_eq!(effect.variant_for(&email).unwrap().replace, "Dear");
+  }
+
+  #[test]
+  fn variant_for_falls_back_when_no_variant_matches() {
+    let effect = variants_effect();
+
+    // All the filters of a variant must match
+    let app = AppProperties {
+      title: Some("Inbox"),
+      class: None,
+      exec: Some("/usr/bin/thunderbird"),
+    };
+    assert!(effect.variant_for(&app).is_none());
+
+    let app = AppProperties {
+      title: None,
+      class: None,
+      exec: None,
+    };
+    assert!(effect.variant_for(&app).is_none());
+  }
+}
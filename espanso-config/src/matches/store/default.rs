@@ -17,33 +17,88 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use super::{MatchSet, MatchStore};
+use super::{
+  remote::{resolve_remote_import, HttpFetcher, RemoteFetcher, RemoteImportError},
+  HotKeyValidator, MatchSet, MatchStore, MatchStoreOptions,
+};
 use crate::{
   counter::StructId,
-  error::NonFatalErrorSet,
-  matches::{group::MatchGroup, Match, Variable},
+  error::{ErrorRecord, NonFatalErrorSet},
+  matches::{
+    group::{loader::LoadLimits, path::is_remote_import, MatchGroup},
+    lazy::{defer_long_replacements, measure_replacements},
+    Match, MatchCause, TriggerCause, Variable,
+  },
 };
 use anyhow::Context;
+use log::{debug, info};
 use std::{
   collections::{HashMap, HashSet},
-  path::PathBuf,
+  path::{Path, PathBuf},
+  sync::Arc,
 };
+use thiserror::Error;
 
 pub(crate) struct DefaultMatchStore {
   pub groups: HashMap<String, MatchGroup>,
+
+  // Path of the group in which each match is defined
+  group_paths: HashMap<i32, String>,
 }
 
 impl DefaultMatchStore {
+  #[cfg(test)]
   pub fn load(paths: &[String]) -> (Self, Vec<NonFatalErrorSet>) {
+    Self::load_with_options(paths, &MatchStoreOptions::default())
+  }
+
+  pub fn load_with_options(
+    paths: &[String],
+    options: &MatchStoreOptions,
+  ) -> (Self, Vec<NonFatalErrorSet>) {
+    Self::load_with_fetcher(paths, options, Arc::new(HttpFetcher {}))
+  }
+
+  fn load_with_fetcher(
+    paths: &[String],
+    options: &MatchStoreOptions,
+    fetcher: Arc<dyn RemoteFetcher>,
+  ) -> (Self, Vec<NonFatalErrorSet>) {
     let mut groups = HashMap::new();
     let mut non_fatal_error_sets = Vec::new();
 
     // Because match groups can imports other match groups,
     // we have to load them recursively starting from the
     // top-level ones.
-    load_match_groups_recursively(&mut groups, paths, &mut non_fatal_error_sets);
+    load_match_groups_recursively(
+      &mut groups,
+      paths,
+      &mut Vec::new(),
+      options,
+      &fetcher,
+      &mut non_fatal_error_sets,
+    );
+
+    if options.lazy_replace {
+      let (in_memory, deferred) = measure_replacements(groups.values());
+      info!(
+        "replacements take {} bytes of memory, {} more bytes are loaded when needed",
+        in_memory, deferred
+      );
+    }
 
-    (Self { groups }, non_fatal_error_sets)
+    let group_paths = groups
+      .iter()
+      .flat_map(|(path, group)| group.matches.iter().map(move |m| (m.id, path.clone())))
+      .collect();
+
+    (
+      Self {
+        groups,
+        group_paths,
+      },
+      non_fatal_error_sets,
+    )
   }
 }
 
@@ -74,20 +129,111 @@ impl MatchStore for DefaultMatchStore {
   fn loaded_paths(&self) -> Vec<String> {
     self.groups.keys().cloned().collect()
   }
+
+  fn source_path(&self, match_id: i32) -> Option<&str> {
+    self.group_paths.get(&match_id).map(String::as_str)
+  }
+
+  fn group_label(&self, match_id: i32) -> Option<&str> {
+    let path = self.group_paths.get(&match_id)?;
+    self.groups.get(path)?.label.as_deref()
+  }
 }
 
+// The ancestors are the (canonical) paths of the groups currently being
+// loaded, used to detect circular imports
 fn load_match_groups_recursively(
   groups: &mut HashMap<String, MatchGroup>,
   paths: &[String],
+  ancestors: &mut Vec<PathBuf>,
+  options: &MatchStoreOptions,
+  fetcher: &Arc<dyn RemoteFetcher>,
   non_fatal_error_sets: &mut Vec<NonFatalErrorSet>,
 ) {
   for path in paths.iter() {
+    let canonical_path = dunce::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    if ancestors.contains(&canonical_path) {
+      // The warning is reported for the group containing the import
+      let importer = ancestors.last().cloned().unwrap_or_default();
+      non_fatal_error_sets.push(NonFatalErrorSet::new(
+        &importer,
+        vec![ErrorRecord::warn(
+          MatchStoreError::CircularImport {
+            path: path.clone(),
+            importer: importer.to_string_lossy().to_string(),
+          }
+          .into(),
+        )],
+      ));
+      continue;
+    }
+
     if !groups.contains_key(path) {
-      let group_path = PathBuf::from(path);
-      match MatchGroup::load(&group_path)
+      let group_path = if is_remote_import(path) {
+        // Remote groups are loaded from a local copy, but stored with their URL
+        let result = match &options.remote_imports {
+          Some(remote_options) => {
+            resolve_remote_import(path, remote_options, options.max_group_file_size, fetcher)
+          }
+          None => Err(RemoteImportError::Disabled(path.clone()).into()),
+        };
+
+        match result {
+          Ok(cached_path) => cached_path,
+          Err(err) => {
+            non_fatal_error_sets.push(NonFatalErrorSet::new(
+              &PathBuf::from(path),
+              vec![ErrorRecord::warn(err)],
+            ));
+            continue;
+          }
+        }
+      } else {
+        PathBuf::from(path)
+      };
+
+      let limits = LoadLimits {
+        max_matches: options.max_group_matches,
+        max_file_size: options.max_group_file_size,
+      };
+      let result = if options.inline_group_paths.contains(path) {
+        MatchGroup::load_inline_with_limits(&group_path, &limits)
+      } else {
+        MatchGroup::load_with_limits(&group_path, &limits)
+      };
+      match result
+        .and_then(|result| check_match_limits(groups, path, result, options))
         .with_context(|| format!("unable to load match group {:?}", group_path))
       {
-        Ok((group, non_fatal_error_set)) => {
+        Ok((mut group, mut non_fatal_error_set)) => {
+          if let Some(validator) = options.hotkey_validator {
+            let errors = remove_invalid_hotkeys(&mut group, validator);
+            if !errors.is_empty() {
+              match &mut non_fatal_error_set {
+                Some(error_set) => error_set.errors.extend(errors),
+                None => non_fatal_error_set = Some(NonFatalErrorSet::new(&group_path, errors)),
+              }
+            }
+          }
+
+          if options.trigger_from_filename && !is_remote_import(path) {
+            apply_trigger_from_filename(&mut group, &group_path);
+          }
+
+          if options.lazy_replace {
+            let deferred = defer_long_replacements(&mut group, &group_path);
+            if deferred > 0 {
+              debug!(
+                "deferred loading of {} bytes of replacements defined in {:?}",
+                deferred, group_path
+              );
+            }
+          }
+
+          if let Some(label) = &group.label {
+            debug!("loaded match group {:?} labeled as {:?}", path, label);
+          }
+
           let imports = group.imports.clone();
           groups.insert(path.clone(), group);
 
@@ -95,7 +241,16 @@ fn load_match_groups_recursively(
             non_fatal_error_sets.push(non_fatal_error_set);
           }
 
-          load_match_groups_recursively(groups, &imports, non_fatal_error_sets);
+          ancestors.push(canonical_path);
+          load_match_groups_recursively(
+            groups,
+            &imports,
+            ancestors,
+            options,
+            fetcher,
+            non_fatal_error_sets,
+          );
+          ancestors.pop();
         }
         Err(err) => {
           non_fatal_error_sets.push(NonFatalErrorSet::single_error(&group_path, err));
@@ -105,6 +260,73 @@ fn load_match_groups_recursively(
   }
 }
 
+// Removes the matches whose hotkey is not accepted by the validator,
+// returning the reason for each of them
+fn remove_invalid_hotkeys(group: &mut MatchGroup, validator: HotKeyValidator) -> Vec<ErrorRecord> {
+  let mut errors = Vec::new();
+  group.matches.retain(|m| {
+    if let MatchCause::HotKey(cause) = &m.cause {
+      if let Err(err) = validator(&cause.hotkey) {
+        errors.push(ErrorRecord::error(
+          err.context(format!("match with hotkey {:?} was skipped", cause.hotkey)),
+        ));
+        return false;
+      }
+    }
+    true
+  });
+  errors
+}
+
+// A group defining a single match without a cause gets the trigger
+// derived from its file name, such as `:sig` for `sig.yml`
+fn apply_trigger_from_filename(group: &mut MatchGroup, path: &Path) {
+  if group.matches.len() != 1 || !matches!(group.matches[0].cause, MatchCause::None) {
+    return;
+  }
+
+  if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+    let trigger = format!(":{}", stem);
+    debug!("assigning trigger {:?} to the match in {:?}", trigger, path);
+    group.matches[0].cause = MatchCause::Trigger(TriggerCause {
+      triggers: vec![trigger],
+      ..Default::default()
+    });
+  }
+}
+
+fn check_match_limits(
+  groups: &HashMap<String, MatchGroup>,
+  path: &str,
+  result: (MatchGroup, Option<NonFatalErrorSet>),
+  options: &MatchStoreOptions,
+) -> anyhow::Result<(MatchGroup, Option<NonFatalErrorSet>)> {
+  let count = result.0.matches.len();
+  if count > options.max_group_matches {
+    return Err(
+      MatchStoreError::TooManyGroupMatches {
+        path: path.to_string(),
+        count,
+        limit: options.max_group_matches,
+      }
+      .into(),
+    );
+  }
+
+  let total: usize = groups.values().map(|group| group.matches.len()).sum();
+  if total + count > options.max_total_matches {
+    return Err(
+      MatchStoreError::TooManyTotalMatches {
+        path: path.to_string(),
+        limit: options.max_total_matches,
+      }
+      .into(),
+    );
+  }
+
+  Ok(result)
+}
+
 fn query_matches_for_paths<'a>(
   groups: &'a HashMap<String, MatchGroup>,
   visited_paths: &mut HashSet<String>,
@@ -147,14 +369,35 @@ fn query_matches_for_paths<'a>(
   }
 }
 
+#[derive(Error, Debug)]
+pub enum MatchStoreError {
+  #[error("match group {path:?} defines {count} matches, exceeding the limit of {limit} (max_group_matches)")]
+  TooManyGroupMatches {
+    path: String,
+    count: usize,
+    limit: usize,
+  },
+
+  #[error("loading match group {path:?} would exceed the limit of {limit} total matches (max_total_matches)")]
+  TooManyTotalMatches { path: String, limit: usize },
+
+  #[error("circular import of match group {path:?} from {importer:?}, skipping it")]
+  CircularImport { path: String, importer: String },
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::{
-    matches::{MatchCause, MatchEffect, TextEffect, TriggerCause},
+    error::ErrorLevel,
+    matches::{
+      lazy::LAZY_REPLACE_THRESHOLD, store::remote::RemoteImportOptions, MatchCause, MatchEffect,
+      TextEffect, TriggerCause,
+    },
     util::tests::use_test_directory,
   };
   use std::fs::create_dir_all;
+  use std::time::Duration;
 
   fn create_match(trigger: &str, replace: &str) -> Match {
     Match {
@@ -346,7 +589,91 @@ mod tests {
         DefaultMatchStore::load(&[base_file.to_string_lossy().to_string()]);
 
       assert_eq!(match_store.groups.len(), 3);
-      assert_eq!(non_fatal_error_sets.len(), 0);
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      assert_eq!(non_fatal_error_sets[0].errors[0].level, ErrorLevel::Warning);
+    });
+  }
+
+  #[test]
+  fn match_store_load_circular_imports() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      imports:
+        - "_another.yml"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let another_file = match_dir.join("_another.yml");
+      std::fs::write(
+        &another_file,
+        r#"
+      imports:
+        - "base.yml"
+
+      matches:
+        - trigger: "foo"
+          replace: "bar"
+      "#,
+      )
+      .unwrap();
+
+      // A non-canonical path, which differs from the one resolved by the import
+      let paths = vec![match_dir
+        .join(".")
+        .join("base.yml")
+        .to_string_lossy()
+        .to_string()];
+
+      let (match_store, non_fatal_error_sets) = DefaultMatchStore::load(&paths);
+
+      // The import of base.yml from _another.yml closes the cycle
+      let another_file = dunce::canonicalize(&another_file).unwrap();
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      assert_eq!(non_fatal_error_sets[0].file, another_file);
+      assert_eq!(non_fatal_error_sets[0].errors.len(), 1);
+      assert_eq!(non_fatal_error_sets[0].errors[0].level, ErrorLevel::Warning);
+      match non_fatal_error_sets[0].errors[0]
+        .error
+        .downcast_ref::<MatchStoreError>()
+      {
+        Some(MatchStoreError::CircularImport { path, importer }) => {
+          assert_eq!(
+            dunce::canonicalize(path).unwrap(),
+            dunce::canonicalize(&base_file).unwrap()
+          );
+          assert_eq!(importer, &another_file.to_string_lossy().to_string());
+        }
+        other => panic!("unexpected error: {:?}", other),
+      }
+      assert!(non_fatal_error_sets[0].errors[0]
+        .error
+        .to_string()
+        .starts_with("circular import of match group"));
+
+      // Each file is loaded exactly once
+      assert_eq!(match_store.groups.len(), 2);
+
+      let match_set = match_store.query(&paths);
+      assert_eq!(
+        match_set
+          .matches
+          .into_iter()
+          .cloned()
+          .map(|mut m| {
+            m.id = 0;
+            m
+          })
+          .collect::<Vec<Match>>(),
+        create_matches(&[("foo", "bar"), ("hello", "world")])
+      );
     });
   }
 
@@ -504,7 +831,8 @@ mod tests {
 
       let (match_store, non_fatal_error_sets) =
         DefaultMatchStore::load(&[base_file.to_string_lossy().to_string()]);
-      assert_eq!(non_fatal_error_sets.len(), 0);
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      assert_eq!(non_fatal_error_sets[0].errors[0].level, ErrorLevel::Warning);
 
       let match_set = match_store.query(&[base_file.to_string_lossy().to_string()]);
 
@@ -734,5 +1062,423 @@ mod tests {
     });
   }
 
+  #[test]
+  fn match_store_source_path() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      imports:
+        - "_another.yml"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let another_file = match_dir.join("_another.yml");
+      std::fs::write(
+        &another_file,
+        r#"
+      matches:
+        - trigger: "foo"
+          replace: "bar"
+      "#,
+      )
+      .unwrap();
+
+      let base_path = base_file.to_string_lossy().to_string();
+      let another_path = another_file.to_string_lossy().to_string();
+      let (match_store, _) = DefaultMatchStore::load(&[base_file.to_string_lossy().to_string()]);
+
+      let match_set = match_store.query(&[base_file.to_string_lossy().to_string()]);
+      let sources: Vec<Option<&str>> = match_set
+        .matches
+        .iter()
+        .map(|m| match_store.source_path(m.id))
+        .collect();
+
+      assert_eq!(
+        sources,
+        vec![Some(another_path.as_str()), Some(base_path.as_str())]
+      );
+      assert_eq!(match_store.source_path(-1), None);
+    });
+  }
+
+  #[test]
+  fn match_store_group_label() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      label: "Greetings"
+
+      imports:
+        - "_another.yml"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      std::fs::write(
+        match_dir.join("_another.yml"),
+        r#"
+      matches:
+        - trigger: "foo"
+          replace: "bar"
+      "#,
+      )
+      .unwrap();
+
+      let paths = vec![base_file.to_string_lossy().to_string()];
+      let (match_store, _) = DefaultMatchStore::load(&paths);
+
+      let match_set = match_store.query(&paths);
+      let labels: Vec<Option<&str>> = match_set
+        .matches
+        .iter()
+        .map(|m| match_store.group_label(m.id))
+        .collect();
+
+      assert_eq!(labels, vec![None, Some("Greetings")]);
+    });
+  }
+
+  #[test]
+  fn match_store_rejects_groups_exceeding_limits() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      imports:
+        - "_another.yml"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+        - trigger: "foo"
+          replace: "bar"
+      "#,
+      )
+      .unwrap();
+
+      let another_file = match_dir.join("_another.yml");
+      std::fs::write(
+        &another_file,
+        r#"
+      matches:
+        - trigger: "another"
+          replace: "match"
+      "#,
+      )
+      .unwrap();
+
+      let paths = vec![base_file.to_string_lossy().to_string()];
+
+      let (match_store, non_fatal_error_sets) = DefaultMatchStore::load_with_options(
+        &paths,
+        &MatchStoreOptions {
+          max_group_matches: 1,
+          ..Default::default()
+        },
+      );
+      assert!(match_store.groups.is_empty());
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      let error = format!("{:?}", non_fatal_error_sets[0].errors[0].error);
+      assert!(error.contains("base.yml"));
+      assert!(error.contains("exceeding the limit of 1"));
+
+      let (match_store, non_fatal_error_sets) = DefaultMatchStore::load_with_options(
+        &paths,
+        &MatchStoreOptions {
+          max_total_matches: 2,
+          ..Default::default()
+        },
+      );
+      assert_eq!(match_store.groups.len(), 1);
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      assert!(matches!(
+        non_fatal_error_sets[0].errors[0]
+          .error
+          .downcast_ref::<MatchStoreError>(),
+        Some(MatchStoreError::TooManyTotalMatches { limit: 2, .. })
+      ));
+    });
+  }
+
+  #[test]
+  fn match_store_derives_trigger_from_filename() {
+    use_test_directory(|_, match_dir, _| {
+      let sig_file = match_dir.join("sig.yml");
+      std::fs::write(
+        &sig_file,
+        r#"
+      matches:
+        - replace: "Best regards"
+      "#,
+      )
+      .unwrap();
+
+      let explicit_file = match_dir.join("explicit.yml");
+      std::fs::write(
+        &explicit_file,
+        r#"
+      matches:
+        - trigger: ":hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let paths = vec![
+        sig_file.to_string_lossy().to_string(),
+        explicit_file.to_string_lossy().to_string(),
+      ];
+
+      let triggers_of = |match_store: &DefaultMatchStore, path: &Path| -> Option<Vec<String>> {
+        let group = match_store
+          .groups
+          .get(&path.to_string_lossy().to_string())
+          .unwrap();
+        group.matches[0]
+          .cause
+          .as_trigger()
+          .map(|cause| cause.triggers.clone())
+      };
+
+      let (match_store, non_fatal_error_sets) = DefaultMatchStore::load_with_options(
+        &paths,
+        &MatchStoreOptions {
+          trigger_from_filename: true,
+          ..Default::default()
+        },
+      );
+      assert!(non_fatal_error_sets.is_empty());
+      assert_eq!(
+        triggers_of(&match_store, &sig_file),
+        Some(vec![":sig".to_string()])
+      );
+      assert_eq!(
+        triggers_of(&match_store, &explicit_file),
+        Some(vec![":hello".to_string()])
+      );
+
+      // The option is opt-in
+      let (match_store, _) = DefaultMatchStore::load(&paths);
+      assert_eq!(triggers_of(&match_store, &sig_file), None);
+    });
+  }
+
+  #[test]
+  fn match_store_defers_long_replacements_when_enabled() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        format!(
+          "matches:\n  - trigger: \"long\"\n    replace: {:?}\n",
+          "a".repeat(LAZY_REPLACE_THRESHOLD + 1)
+        ),
+      )
+      .unwrap();
+      let paths = vec![base_file.to_string_lossy().to_string()];
+
+      let is_lazy = |match_store: &DefaultMatchStore| {
+        match_store.query(&paths).matches[0]
+          .effect
+          .as_text()
+          .unwrap()
+          .lazy_replace
+          .is_some()
+      };
+
+      let (match_store, _) = DefaultMatchStore::load_with_options(
+        &paths,
+        &MatchStoreOptions {
+          lazy_replace: true,
+          ..Default::default()
+        },
+      );
+      assert!(is_lazy(&match_store));
+
+      // The option is opt-in
+      let (match_store, _) = DefaultMatchStore::load(&paths);
+      assert!(!is_lazy(&match_store));
+    });
+  }
+
+  struct MockFetcher {
+    content: Option<&'static str>,
+  }
+
+  impl RemoteFetcher for MockFetcher {
+    fn fetch(&self, _: &str, _: u64) -> anyhow::Result<String> {
+      self
+        .content
+        .map(|content| content.to_string())
+        .ok_or_else(|| anyhow::anyhow!("connection refused"))
+    }
+  }
+
+  fn validate_hotkey(hotkey: &str) -> anyhow::Result<()> {
+    if hotkey.ends_with("+FOO") {
+      anyhow::bail!("`FOO` is not a valid key")
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn match_store_skips_invalid_hotkeys() {
+    use_test_directory(|_, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      matches:
+        - hotkey: "CTRL+FOO"
+          replace: "invalid"
+        - hotkey: "CTRL+E"
+          replace: "valid"
+      "#,
+      )
+      .unwrap();
+
+      let paths = vec![base_file.to_string_lossy().to_string()];
+      let (match_store, non_fatal_error_sets) = DefaultMatchStore::load_with_options(
+        &paths,
+        &MatchStoreOptions {
+          hotkey_validator: Some(validate_hotkey),
+          ..Default::default()
+        },
+      );
+
+      let hotkeys: Vec<&str> = match_store
+        .query(&paths)
+        .matches
+        .into_iter()
+        .map(|m| m.cause.as_hot_key().unwrap().hotkey.as_str())
+        .collect();
+      assert_eq!(hotkeys, vec!["CTRL+E"]);
+
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      assert_eq!(non_fatal_error_sets[0].file, base_file);
+      let error = format!("{:?}", non_fatal_error_sets[0].errors[0].error);
+      assert!(error.contains("CTRL+FOO"));
+      assert!(error.contains("`FOO` is not a valid key"));
+    });
+  }
+
+  #[test]
+  fn match_store_loads_remote_imports() {
+    use_test_directory(|base, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      imports:
+        - "https://example.com/team.yml"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let paths = vec![base_file.to_string_lossy().to_string()];
+      let enabled_options = MatchStoreOptions {
+        remote_imports: Some(RemoteImportOptions {
+          cache_dir: base.join("cache"),
+          ttl: Duration::from_secs(60),
+        }),
+        ..Default::default()
+      };
+      let fetcher: Arc<dyn RemoteFetcher> = Arc::new(MockFetcher {
+        content: Some(
+          r#"
+          matches:
+            - trigger: "team"
+              replace: "snippet"
+          "#,
+        ),
+      });
+
+      let (match_store, non_fatal_error_sets) =
+        DefaultMatchStore::load_with_fetcher(&paths, &enabled_options, fetcher.clone());
+      assert!(non_fatal_error_sets.is_empty());
+      let triggers: Vec<&String> = match_store
+        .query(&paths)
+        .matches
+        .into_iter()
+        .flat_map(|m| &m.cause.as_trigger().unwrap().triggers)
+        .collect();
+      assert_eq!(triggers, vec!["team", "hello"]);
+
+      // Remote imports are skipped with a warning when disabled
+      let (match_store, non_fatal_error_sets) =
+        DefaultMatchStore::load_with_fetcher(&paths, &MatchStoreOptions::default(), fetcher);
+      assert_eq!(match_store.query(&paths).matches.len(), 1);
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      assert_eq!(non_fatal_error_sets[0].errors[0].level, ErrorLevel::Warning);
+      assert!(matches!(
+        non_fatal_error_sets[0].errors[0]
+          .error
+          .downcast_ref::<RemoteImportError>(),
+        Some(RemoteImportError::Disabled(_))
+      ));
+    });
+  }
+
+  #[test]
+  fn match_store_skips_unreachable_remote_imports() {
+    use_test_directory(|base, match_dir, _| {
+      let base_file = match_dir.join("base.yml");
+      std::fs::write(
+        &base_file,
+        r#"
+      imports:
+        - "https://example.com/team.yml"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+      )
+      .unwrap();
+
+      let paths = vec![base_file.to_string_lossy().to_string()];
+      let options = MatchStoreOptions {
+        remote_imports: Some(RemoteImportOptions {
+          cache_dir: base.join("cache"),
+          ttl: Duration::from_secs(60),
+        }),
+        ..Default::default()
+      };
+
+      let (match_store, non_fatal_error_sets) = DefaultMatchStore::load_with_fetcher(
+        &paths,
+        &options,
+        Arc::new(MockFetcher { content: None }),
+      );
+      assert_eq!(match_store.query(&paths).matches.len(), 1);
+      assert_eq!(non_fatal_error_sets.len(), 1);
+      assert!(matches!(
+        non_fatal_error_sets[0].errors[0]
+          .error
+          .downcast_ref::<RemoteImportError>(),
+        Some(RemoteImportError::FetchFailed { .. })
+      ));
+    });
+  }
+
   // TODO: add fatal and non-fatal error cases
 }
@@ -0,0 +1,214 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::collections::HashSet;
+
+use log::error;
+
+use crate::matches::{group::loader::yaml::VAR_REGEX, MatchEffect, Params, Value, Variable};
+
+use super::MatchSet;
+
+// Returns the names of the global variables that are never referenced,
+// neither by the matches nor by other variables
+pub fn find_unused_global_vars<'a>(match_set: &MatchSet<'a>) -> Vec<&'a str> {
+  let mut referenced = HashSet::new();
+
+  for m in match_set.matches.iter() {
+    if let MatchEffect::Text(effect) = &m.effect {
+      match effect.full_replace() {
+        Ok(replace) => collect_references(&replace, &mut referenced),
+        Err(err) => error!("unable to load replacement for linting: {}", err),
+      }
+      for choice in effect.replace_random.iter() {
+        collect_references(choice, &mut referenced);
+      }
+      for variant in effect.variants.iter() {
+        collect_references(&variant.replace, &mut referenced);
+      }
+      for var in effect.vars.iter() {
+        collect_variable_references(var, &mut referenced);
+      }
+    }
+  }
+
+  for var in match_set.global_vars.iter() {
+    collect_variable_references(var, &mut referenced);
+  }
+
+  match_set
+    .global_vars
+    .iter()
+    .map(|var| var.name.as_str())
+    .filter(|name| !referenced.contains(*name))
+    .collect()
+}
+
+fn collect_variable_references(var: &Variable, referenced: &mut HashSet<String>) {
+  referenced.extend(var.depends_on.iter().cloned());
+  collect_params_references(&var.params, referenced);
+}
+
+fn collect_params_references(params: &Params, referenced: &mut HashSet<String>) {
+  for value in params.values() {
+    collect_value_references(value, referenced);
+  }
+}
+
+fn collect_value_references(value: &Value, referenced: &mut HashSet<String>) {
+  match value {
+    Value::String(string) => collect_references(string, referenced),
+    Value::Array(values) => {
+      for value in values {
+        collect_value_references(value, referenced);
+      }
+    }
+    Value::Object(params) => collect_params_references(params, referenced),
+    _ => {}
+  }
+}
+
+fn collect_references(body: &str, referenced: &mut HashSet<String>) {
+  for caps in VAR_REGEX.captures_iter(body) {
+    if let Some(name) = caps.get(1) {
+      referenced.insert(name.as_str().to_string());
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::matches::{Match, MatchRegex, TextEffect, TextVariant};
+
+  fn var(name: &str) -> Variable {
+    Variable {
+      name: name.to_string(),
+      var_type: "echo".to_string(),
+      ..Default::default()
+    }
+  }
+
+  fn text_match(replace: &str, vars: Vec<Variable>) -> Match {
+    Match {
+      effect: MatchEffect::Text(TextEffect {
+        replace: replace.to_string(),
+        vars,
+        ..Default::default()
+      }),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn find_unused_global_vars_works_correctly() {
+    let used = var("used");
+    let unused = var("unused");
+    let m = text_match("hello {{used}}", Vec::new());
+
+    let match_set = MatchSet {
+      matches: vec![&m],
+      global_vars: vec![&used, &unused],
+    };
+
+    assert_eq!(find_unused_global_vars(&match_set), vec!["unused"]);
+  }
+
+  #[test]
+  fn find_unused_global_vars_considers_replace_random() {
+    let used = var("used");
+    let m = Match {
+      effect: MatchEffect::Text(TextEffect {
+        replace: "hello".to_string(),
+        replace_random: vec!["hello".to_string(), "hi {{used}}".to_string()],
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+
+    let match_set = MatchSet {
+      matches: vec![&m],
+      global_vars: vec![&used],
+    };
+
+    assert!(find_unused_global_vars(&match_set).is_empty());
+  }
+
+  #[test]
+  fn find_unused_global_vars_considers_variants() {
+    let used = var("used");
+    let m = Match {
+      effect: MatchEffect::Text(TextEffect {
+        replace: "hello".to_string(),
+        variants: vec![TextVariant {
+          filter_title: None,
+          filter_class: Some(MatchRegex::new("Slack").unwrap()),
+          filter_exec: None,
+          replace: "hey {{used}}".to_string(),
+        }],
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+
+    let match_set = MatchSet {
+      matches: vec![&m],
+      global_vars: vec![&used],
+    };
+
+    assert!(find_unused_global_vars(&match_set).is_empty());
+  }
+
+  #[test]
+  fn find_unused_global_vars_considers_variable_params() {
+    let used_by_param = var("param");
+    let used_by_dependency = var("dependency");
+    let used_by_global = var("global");
+
+    let mut params = Params::new();
+    params.insert(
+      "cmd".to_string(),
+      Value::String("echo {{param}}".to_string()),
+    );
+    let mut local = var("local");
+    local.params = params;
+    local.depends_on = vec!["dependency".to_string()];
+
+    let mut global_params = Params::new();
+    global_params.insert(
+      "echo".to_string(),
+      Value::Array(vec![Value::String("{{global}}".to_string())]),
+    );
+    let mut referencing_global = var("referencing");
+    referencing_global.params = global_params;
+
+    let m = text_match("{{local}} {{referencing}}", vec![local]);
+
+    let match_set = MatchSet {
+      matches: vec![&m],
+      global_vars: vec![
+        &used_by_param,
+        &used_by_dependency,
+        &used_by_global,
+        &referencing_global,
+      ],
+    };
+
+    assert!(find_unused_global_vars(&match_set).is_empty());
+  }
+}
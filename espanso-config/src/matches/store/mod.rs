@@ -17,15 +17,28 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::error::NonFatalErrorSet;
+use crate::{
+  config::default::{
+    DEFAULT_MAX_GROUP_FILE_SIZE, DEFAULT_MAX_GROUP_MATCHES, DEFAULT_MAX_TOTAL_MATCHES,
+  },
+  error::NonFatalErrorSet,
+};
 
 use super::{Match, Variable};
 
 mod default;
+pub mod lint;
+pub mod remote;
 
 pub trait MatchStore: Send {
   fn query(&self, paths: &[String]) -> MatchSet;
   fn loaded_paths(&self) -> Vec<String>;
+
+  // Returns the path of the match group file in which the given match is defined
+  fn source_path(&self, match_id: i32) -> Option<&str>;
+
+  // Returns the label of the match group in which the given match is defined, if any
+  fn group_label(&self, match_id: i32) -> Option<&str>;
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -34,8 +47,59 @@ pub struct MatchSet<'a> {
   pub global_vars: Vec<&'a Variable>,
 }
 
-pub fn load(paths: &[String]) -> (impl MatchStore, Vec<NonFatalErrorSet>) {
+pub type HotKeyValidator = fn(&str) -> anyhow::Result<()>;
+
+#[derive(Debug, Clone)]
+pub struct MatchStoreOptions {
+  // Maximum number of matches a single group can define
+  pub max_group_matches: usize,
+
+  // Maximum number of matches that can be loaded across all groups
+  pub max_total_matches: usize,
+
+  // Group files larger than this (in bytes) are rejected before being read
+  pub max_group_file_size: u64,
+
+  // Derive the trigger of a causeless match from the name of its file,
+  // for groups defining a single match
+  pub trigger_from_filename: bool,
+
+  // Load long replacements from their source file when needed,
+  // instead of keeping them in memory
+  pub lazy_replace: bool,
+
+  // If None, imports from HTTP(S) URLs are skipped
+  pub remote_imports: Option<remote::RemoteImportOptions>,
+
+  // Config files defining matches inline. Only their `matches` and
+  // `global_vars` keys are loaded, as the other ones configure espanso
+  pub inline_group_paths: Vec<String>,
+
+  // Matches whose hotkey is rejected by the validator are skipped, and the
+  // error is reported for the file defining them
+  pub hotkey_validator: Option<HotKeyValidator>,
+}
+
+impl Default for MatchStoreOptions {
+  fn default() -> Self {
+    Self {
+      max_group_matches: DEFAULT_MAX_GROUP_MATCHES,
+      max_total_matches: DEFAULT_MAX_TOTAL_MATCHES,
+      max_group_file_size: DEFAULT_MAX_GROUP_FILE_SIZE,
+      trigger_from_filename: false,
+      lazy_replace: false,
+      remote_imports: None,
+      inline_group_paths: Vec::new(),
+      hotkey_validator: None,
+    }
+  }
+}
+
+pub fn load(
+  paths: &[String],
+  options: &MatchStoreOptions,
+) -> (impl MatchStore, Vec<NonFatalErrorSet>) {
   // TODO: here we can replace the DefaultMatchStore with a caching wrapper
   // that returns the same response for the given "paths" query
-  default::DefaultMatchStore::load(paths)
+  default::DefaultMatchStore::load_with_options(paths, options)
 }
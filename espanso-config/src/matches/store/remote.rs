@@ -0,0 +1,473 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::{
+  io::Read,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+  time::{Duration, SystemTime},
+};
+use thiserror::Error;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteImportOptions {
+  // Per-user directory in which the downloaded match files are stored.
+  // It's created with owner-only permissions, and rejected if owned
+  // by another user
+  pub cache_dir: PathBuf,
+
+  // How long a downloaded file is reused before fetching it again
+  pub ttl: Duration,
+}
+
+pub(crate) trait RemoteFetcher: Send + Sync {
+  // Bodies larger than `max_size` bytes are rejected without being read entirely
+  fn fetch(&self, url: &str, max_size: u64) -> Result<String>;
+}
+
+pub(crate) struct HttpFetcher {}
+
+impl RemoteFetcher for HttpFetcher {
+  fn fetch(&self, url: &str, max_size: u64) -> Result<String> {
+    let client = reqwest::blocking::Client::builder()
+      .timeout(FETCH_TIMEOUT)
+      .https_only(true)
+      .build()?;
+    let response = client.get(url).send()?.error_for_status()?;
+
+    // Reading one byte past the limit is enough to tell if the body exceeds it
+    let mut body = Vec::new();
+    response
+      .take(max_size.saturating_add(1))
+      .read_to_end(&mut body)?;
+    if body.len() as u64 > max_size {
+      return Err(
+        RemoteImportError::TooLarge {
+          url: url.to_string(),
+          limit: max_size,
+        }
+        .into(),
+      );
+    }
+
+    Ok(String::from_utf8(body)?)
+  }
+}
+
+// Returns the path of a local copy of the given remote match file. The file
+// is only downloaded while loading the config if no copy exists yet, while
+// an expired copy is used as-is and refreshed in the background, so that
+// the next load picks up the updated version.
+pub(crate) fn resolve_remote_import(
+  url: &str,
+  options: &RemoteImportOptions,
+  max_file_size: u64,
+  fetcher: &Arc<dyn RemoteFetcher>,
+) -> Result<PathBuf> {
+  if !url.starts_with("https://") {
+    return Err(RemoteImportError::InsecureUrl(url.to_string()).into());
+  }
+
+  ensure_private_dir(&options.cache_dir)?;
+
+  let cached_path = cache_path(url, &options.cache_dir);
+  if !cached_path.is_file() {
+    download(url, &cached_path, max_file_size, fetcher.as_ref())?;
+    return Ok(cached_path);
+  }
+
+  if !is_fresh(&cached_path, options.ttl) {
+    let url = url.to_string();
+    let refresh_path = cached_path.clone();
+    let fetcher = Arc::clone(fetcher);
+    std::thread::Builder::new()
+      .name("remote-import-refresh".to_string())
+      .spawn(
+        move || match download(&url, &refresh_path, max_file_size, fetcher.as_ref()) {
+          Ok(_) => info!("refreshed remote import {}", url),
+          Err(err) => warn!(
+            "unable to refresh remote import {}, keeping the cached copy: {:?}",
+            url, err
+          ),
+        },
+      )?;
+  }
+
+  Ok(cached_path)
+}
+
+// The content is written to a temporary file in the same directory and then
+// renamed, so that a load running concurrently with a background refresh
+// never reads a partially written file
+fn download(url: &str, path: &Path, max_file_size: u64, fetcher: &dyn RemoteFetcher) -> Result<()> {
+  let content =
+    fetcher
+      .fetch(url, max_file_size)
+      .map_err(|err| RemoteImportError::FetchFailed {
+        url: url.to_string(),
+        reason: format!("{:?}", err),
+      })?;
+  if content.len() as u64 > max_file_size {
+    return Err(
+      RemoteImportError::TooLarge {
+        url: url.to_string(),
+        limit: max_file_size,
+      }
+      .into(),
+    );
+  }
+
+  let temp_path = temp_path(path);
+  let result = std::fs::write(&temp_path, content)
+    .and_then(|_| std::fs::rename(&temp_path, path))
+    .with_context(|| format!("unable to cache remote import: {:?}", path));
+  if result.is_err() {
+    std::fs::remove_file(&temp_path).ok();
+  }
+  result
+}
+
+// Unique for each download, as the same file might be refreshed concurrently
+fn temp_path(path: &Path) -> PathBuf {
+  static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+  let file_name = path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_default();
+  path.with_file_name(format!(
+    ".{}.{}.{}.tmp",
+    file_name,
+    std::process::id(),
+    COUNTER.fetch_add(1, Ordering::SeqCst)
+  ))
+}
+
+#[cfg(unix)]
+fn ensure_private_dir(dir: &Path) -> Result<()> {
+  use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+  if !dir.exists() {
+    std::fs::DirBuilder::new()
+      .recursive(true)
+      .mode(0o700)
+      .create(dir)
+      .with_context(|| format!("unable to create remote imports cache directory: {:?}", dir))?;
+  }
+
+  // Symlinks are not followed, as they could point to a directory
+  // controlled by another user
+  let metadata = std::fs::symlink_metadata(dir)?;
+  if !metadata.is_dir() || metadata.uid() != unsafe { libc::getuid() } {
+    return Err(RemoteImportError::UnsafeCacheDir(dir.to_path_buf()).into());
+  }
+
+  if metadata.mode() & 0o077 != 0 {
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+  }
+
+  Ok(())
+}
+
+// On Windows, the runtime directory already lives in the user profile
+#[cfg(not(unix))]
+fn ensure_private_dir(dir: &Path) -> Result<()> {
+  std::fs::create_dir_all(dir)
+    .with_context(|| format!("unable to create remote imports cache directory: {:?}", dir))
+}
+
+// The cached file keeps the extension of the remote one, so that the
+// right importer is used to parse it
+fn cache_path(url: &str, cache_dir: &Path) -> PathBuf {
+  let hash = hex::encode(Sha256::digest(url.as_bytes()));
+
+  let file_name = url
+    .split(&['?', '#'][..])
+    .next()
+    .and_then(|path| path.rsplit('/').next())
+    .unwrap_or_default();
+  let extension = match Path::new(file_name)
+    .extension()
+    .and_then(|extension| extension.to_str())
+  {
+    Some(extension) if ["yml", "yaml", "json", "toml"].contains(&extension) => extension,
+    _ => "yml",
+  };
+
+  cache_dir.join(format!("{}.{}", hash, extension))
+}
+
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+  let modified = match std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+    Ok(modified) => modified,
+    Err(_) => return false,
+  };
+
+  match SystemTime::now().duration_since(modified) {
+    Ok(age) => age < ttl,
+    // The file was modified in the future, so its age can't be trusted
+    Err(_) => false,
+  }
+}
+
+#[derive(Error, Debug)]
+pub enum RemoteImportError {
+  #[error("unable to fetch remote import `{url}`: {reason}")]
+  FetchFailed { url: String, reason: String },
+
+  #[error("remote import `{0}` was skipped, set `allow_remote_imports: true` to enable it")]
+  Disabled(String),
+
+  #[error("remote import `{0}` was skipped, only https:// URLs are supported")]
+  InsecureUrl(String),
+
+  #[error("remote imports cache directory {0:?} is not a directory owned by the current user")]
+  UnsafeCacheDir(PathBuf),
+
+  #[error("remote import `{url}` exceeds the maximum size of {limit} bytes (max_group_file_size)")]
+  TooLarge { url: String, limit: u64 },
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use anyhow::anyhow;
+  use tempdir::TempDir;
+
+  struct MockFetcher {
+    result: Option<&'static str>,
+    calls: AtomicUsize,
+  }
+
+  impl MockFetcher {
+    fn new(result: Option<&'static str>) -> Arc<Self> {
+      Arc::new(Self {
+        result,
+        calls: AtomicUsize::new(0),
+      })
+    }
+
+    fn calls(&self) -> usize {
+      self.calls.load(Ordering::SeqCst)
+    }
+  }
+
+  impl RemoteFetcher for MockFetcher {
+    fn fetch(&self, _: &str, _: u64) -> Result<String> {
+      self.calls.fetch_add(1, Ordering::SeqCst);
+      self
+        .result
+        .map(|content| content.to_string())
+        .ok_or_else(|| anyhow!("connection refused"))
+    }
+  }
+
+  fn options(cache_dir: &Path, ttl: Duration) -> RemoteImportOptions {
+    RemoteImportOptions {
+      cache_dir: cache_dir.to_path_buf(),
+      ttl,
+    }
+  }
+
+  fn resolve(
+    url: &str,
+    options: &RemoteImportOptions,
+    fetcher: &Arc<MockFetcher>,
+  ) -> Result<PathBuf> {
+    resolve_with_limit(url, options, u64::MAX, fetcher)
+  }
+
+  fn resolve_with_limit(
+    url: &str,
+    options: &RemoteImportOptions,
+    max_file_size: u64,
+    fetcher: &Arc<MockFetcher>,
+  ) -> Result<PathBuf> {
+    let fetcher: Arc<dyn RemoteFetcher> = fetcher.clone();
+    resolve_remote_import(url, options, max_file_size, &fetcher)
+  }
+
+  const URL: &str = "https://example.com/team.yml";
+
+  #[test]
+  fn cache_path_keeps_extension() {
+    let dir = Path::new("cache");
+    assert!(cache_path("https://example.com/a.json?v=2", dir)
+      .to_string_lossy()
+      .ends_with(".json"));
+    assert!(cache_path("https://example.com/snippets", dir)
+      .to_string_lossy()
+      .ends_with(".yml"));
+    assert_ne!(
+      cache_path("https://example.com/a.yml", dir),
+      cache_path("https://example.com/b.yml", dir)
+    );
+  }
+
+  #[test]
+  fn cache_path_is_named_by_sha256() {
+    assert_eq!(
+      cache_path(URL, Path::new("cache")),
+      Path::new("cache").join(format!(
+        "{}.yml",
+        hex::encode(Sha256::digest(URL.as_bytes()))
+      ))
+    );
+  }
+
+  #[test]
+  fn remote_import_is_downloaded_and_cached() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let options = options(&cache_dir.path().join("remote"), Duration::from_secs(60));
+    let fetcher = MockFetcher::new(Some("matches: []"));
+
+    let path = resolve(URL, &options, &fetcher).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "matches: []");
+
+    // The second time, the cached copy is used
+    assert_eq!(resolve(URL, &options, &fetcher).unwrap(), path);
+    assert_eq!(fetcher.calls(), 1);
+  }
+
+  #[test]
+  fn download_leaves_no_temporary_files() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let options = options(cache_dir.path(), Duration::from_secs(60));
+
+    let path = resolve(URL, &options, &MockFetcher::new(Some("matches: []"))).unwrap();
+
+    let files: Vec<PathBuf> = std::fs::read_dir(cache_dir.path())
+      .unwrap()
+      .map(|entry| entry.unwrap().path())
+      .collect();
+    assert_eq!(files, vec![path]);
+  }
+
+  #[test]
+  fn oversized_remote_import_is_not_cached() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let options = options(cache_dir.path(), Duration::from_secs(60));
+    let fetcher = MockFetcher::new(Some("matches: []"));
+
+    let err = resolve_with_limit(URL, &options, 5, &fetcher).unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<RemoteImportError>(),
+      Some(RemoteImportError::TooLarge { limit: 5, .. })
+    ));
+    assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 0);
+  }
+
+  #[test]
+  fn plain_http_imports_are_rejected() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let options = options(cache_dir.path(), Duration::from_secs(60));
+    let fetcher = MockFetcher::new(Some("matches: []"));
+
+    let err = resolve("http://example.com/team.yml", &options, &fetcher).unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<RemoteImportError>(),
+      Some(RemoteImportError::InsecureUrl(_))
+    ));
+    assert_eq!(fetcher.calls(), 0);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn cache_dir_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let remote_dir = cache_dir.path().join("remote");
+    std::fs::create_dir(&remote_dir).unwrap();
+    std::fs::set_permissions(&remote_dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+
+    let options = options(&remote_dir, Duration::from_secs(60));
+    resolve(URL, &options, &MockFetcher::new(Some("matches: []"))).unwrap();
+
+    let mode = std::fs::metadata(&remote_dir).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn symlinked_cache_dir_is_rejected() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let target_dir = cache_dir.path().join("target");
+    std::fs::create_dir(&target_dir).unwrap();
+    let remote_dir = cache_dir.path().join("remote");
+    std::os::unix::fs::symlink(&target_dir, &remote_dir).unwrap();
+
+    let options = options(&remote_dir, Duration::from_secs(60));
+    let err = resolve(URL, &options, &MockFetcher::new(Some("matches: []"))).unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<RemoteImportError>(),
+      Some(RemoteImportError::UnsafeCacheDir(_))
+    ));
+  }
+
+  #[test]
+  fn expired_remote_import_is_refreshed_in_background() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let options = options(cache_dir.path(), Duration::from_secs(0));
+    let fetcher = MockFetcher::new(Some("matches: []"));
+
+    let path = resolve(URL, &options, &fetcher).unwrap();
+
+    // The expired copy is returned right away
+    assert_eq!(resolve(URL, &options, &fetcher).unwrap(), path);
+
+    let started = std::time::Instant::now();
+    while fetcher.calls() < 2 && started.elapsed() < Duration::from_secs(5) {
+      std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(fetcher.calls(), 2);
+  }
+
+  #[test]
+  fn future_modification_time_is_not_fresh() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let path = cache_dir.path().join("file.yml");
+    let file = std::fs::File::create(&path).unwrap();
+    file
+      .set_modified(SystemTime::now() + Duration::from_secs(3600))
+      .unwrap();
+
+    assert!(!is_fresh(&path, Duration::from_secs(u64::MAX / 2)));
+  }
+
+  #[test]
+  fn fetch_failure_without_cached_copy_is_an_error() {
+    let cache_dir = TempDir::new("espanso-remote").unwrap();
+    let options = options(cache_dir.path(), Duration::from_secs(0));
+
+    let err = resolve(URL, &options, &MockFetcher::new(None)).unwrap_err();
+    assert!(matches!(
+      err.downcast_ref::<RemoteImportError>(),
+      Some(RemoteImportError::FetchFailed { .. })
+    ));
+  }
+}
@@ -0,0 +1,142 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use anyhow::Result;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::{
+  counter::next_id,
+  matches::{Params, Value, Variable},
+};
+
+// Load the secrets file, a flat YAML mapping of names to scalar values,
+// as a list of "echo" variables that can be used as global vars.
+// None of the errors include the secret values, so that they can be
+// safely logged.
+pub fn load_secrets(path: &Path) -> Result<Vec<Variable>> {
+  let content = std::fs::read_to_string(path).map_err(|_| SecretsError::Read())?;
+  if content.trim().is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let mapping: serde_yaml::Mapping =
+    serde_yaml::from_str(&content).map_err(|err| SecretsError::InvalidFormat {
+      line: err.location().map(|location| location.line()),
+    })?;
+
+  let mut variables = Vec::new();
+  for (key, value) in mapping {
+    let name = match key {
+      serde_yaml::Value::String(name) => name,
+      _ => return Err(SecretsError::InvalidName().into()),
+    };
+
+    let value = match value {
+      serde_yaml::Value::String(value) => value,
+      serde_yaml::Value::Number(value) => value.to_string(),
+      serde_yaml::Value::Bool(value) => value.to_string(),
+      _ => return Err(SecretsError::NonScalarValue { name }.into()),
+    };
+
+    let mut params = Params::new();
+    params.insert("echo".to_string(), Value::String(value));
+
+    variables.push(Variable {
+      id: next_id(),
+      name,
+      var_type: "echo".to_string(),
+      params,
+      inject_vars: false,
+      depends_on: Vec::new(),
+      persist: false,
+    });
+  }
+
+  Ok(variables)
+}
+
+#[derive(Error, Debug)]
+pub enum SecretsError {
+  #[error("unable to read the secrets file")]
+  Read(),
+
+  #[error("secrets file must be a mapping of names to values (line: {line:?})")]
+  InvalidFormat { line: Option<usize> },
+
+  #[error("secrets file contains a name that is not a string")]
+  InvalidName(),
+
+  #[error("secret `{name}` must be a string, number or boolean")]
+  NonScalarValue { name: String },
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempdir::TempDir;
+
+  fn load(content: &str) -> Result<Vec<Variable>> {
+    let dir = TempDir::new("tempsecrets").unwrap();
+    let path = dir.path().join("secrets.yml");
+    std::fs::write(&path, content).unwrap();
+    load_secrets(&path)
+  }
+
+  #[test]
+  fn secrets_are_loaded_as_echo_variables() {
+    let vars = load("api_key: abc123\nport: 8080\n").unwrap();
+    assert_eq!(vars.len(), 2);
+
+    assert_eq!(vars[0].name, "api_key");
+    assert_eq!(vars[0].var_type, "echo");
+    assert_eq!(
+      vars[0].params.get("echo"),
+      Some(&Value::String("abc123".to_string()))
+    );
+    assert!(!vars[0].inject_vars);
+
+    assert_eq!(vars[1].name, "port");
+    assert_eq!(
+      vars[1].params.get("echo"),
+      Some(&Value::String("8080".to_string()))
+    );
+  }
+
+  #[test]
+  fn empty_secrets_file() {
+    assert!(load("").unwrap().is_empty());
+  }
+
+  #[test]
+  fn errors_do_not_contain_secret_values() {
+    let err = load("token:\n  nested: supersecret\n").unwrap_err();
+    assert!(err.to_string().contains("token"));
+    assert!(!err.to_string().contains("supersecret"));
+
+    let err = load("supersecret").unwrap_err();
+    assert!(!err.to_string().contains("supersecret"));
+  }
+
+  #[test]
+  fn missing_secrets_file() {
+    let dir = TempDir::new("tempsecrets").unwrap();
+    assert!(load_secrets(&dir.path().join("missing.yml")).is_err());
+  }
+}
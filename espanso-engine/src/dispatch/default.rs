@@ -17,8 +17,12 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use super::{ContextMenuHandler, Event, IconHandler, ImageInjector, SecureInputManager};
+use super::{
+  BackspaceLimitProvider, ContextMenuHandler, Event, IconHandler, ImageInjector,
+  NotificationHandler, SoundPlayer,
+};
 use super::{Dispatcher, Executor, HtmlInjector, KeyInjector, ModeProvider, TextInjector};
+use super::{InjectionFailureHandler, PostBackspaceDelayProvider, SecureInputManager};
 
 pub struct DefaultDispatcher<'a> {
   executors: Vec<Box<dyn Executor + 'a>>,
@@ -31,11 +35,16 @@ impl<'a> DefaultDispatcher<'a> {
     clipboard_injector: &'a dyn TextInjector,
     mode_provider: &'a dyn ModeProvider,
     key_injector: &'a dyn KeyInjector,
+    backspace_limit_provider: &'a dyn BackspaceLimitProvider,
+    post_backspace_delay_provider: &'a dyn PostBackspaceDelayProvider,
+    injection_failure_handler: &'a dyn InjectionFailureHandler,
     html_injector: &'a dyn HtmlInjector,
     image_injector: &'a dyn ImageInjector,
     context_menu_handler: &'a dyn ContextMenuHandler,
     icon_handler: &'a dyn IconHandler,
     secure_input_manager: &'a dyn SecureInputManager,
+    notification_handler: &'a dyn NotificationHandler,
+    sound_player: &'a dyn SoundPlayer,
   ) -> Self {
     Self {
       executors: vec![
@@ -43,9 +52,13 @@ impl<'a> DefaultDispatcher<'a> {
           event_injector,
           clipboard_injector,
           mode_provider,
+          key_injector,
         )),
         Box::new(super::executor::key_inject::KeyInjectExecutor::new(
           key_injector,
+          backspace_limit_provider,
+          post_backspace_delay_provider,
+          injection_failure_handler,
         )),
         Box::new(super::executor::html_inject::HtmlInjectExecutor::new(
           html_injector,
@@ -62,6 +75,10 @@ impl<'a> DefaultDispatcher<'a> {
         Box::new(super::executor::secure_input::SecureInputExecutor::new(
           secure_input_manager,
         )),
+        Box::new(super::executor::notification::NotificationExecutor::new(
+          notification_handler,
+        )),
+        Box::new(super::executor::sound::SoundExecutor::new(sound_player)),
       ],
     }
   }
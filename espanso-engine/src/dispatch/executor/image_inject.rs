@@ -26,7 +26,7 @@ use crate::{
 };
 
 pub trait ImageInjector {
-  fn inject_image(&self, path: &str) -> Result<()>;
+  fn inject_image(&self, path: &str, preserve_format: bool) -> Result<()>;
 }
 
 pub struct ImageInjectExecutor<'a> {
@@ -42,7 +42,10 @@ impl<'a> ImageInjectExecutor<'a> {
 impl<'a> Executor for ImageInjectExecutor<'a> {
   fn execute(&self, event: &Event) -> bool {
     if let EventType::ImageInject(inject_event) = &event.etype {
-      if let Err(error) = self.injector.inject_image(&inject_event.image_path) {
+      if let Err(error) = self
+        .injector
+        .inject_image(&inject_event.image_path, inject_event.preserve_format)
+      {
         error!("image injector reported an error: {:?}", error);
       }
 
@@ -19,30 +19,80 @@
 
 use crate::{
   dispatch::Executor,
-  event::{input::Key, Event, EventType},
+  event::{effect::KeyStroke, input::Key, Event, EventType},
 };
 use anyhow::Result;
 use log::error;
+use std::time::Duration;
 
 pub trait KeyInjector {
-  fn inject_sequence(&self, keys: &[Key]) -> Result<()>;
+  fn inject_sequence(&self, keys: &[KeyStroke]) -> Result<()>;
+}
+
+pub trait BackspaceLimitProvider {
+  fn max_backspaces_per_expansion(&self) -> usize;
+}
+
+pub trait PostBackspaceDelayProvider {
+  // In milliseconds, zero disables the delay
+  fn post_backspace_delay(&self) -> usize;
+}
+
+// Notified whenever the injector fails, with the event that couldn't be injected
+pub trait InjectionFailureHandler {
+  fn on_injection_failure(&self, event: &Event, error: &anyhow::Error);
 }
 
 pub struct KeyInjectExecutor<'a> {
   injector: &'a dyn KeyInjector,
+  backspace_limit_provider: &'a dyn BackspaceLimitProvider,
+  post_backspace_delay_provider: &'a dyn PostBackspaceDelayProvider,
+  failure_handler: &'a dyn InjectionFailureHandler,
 }
 
 impl<'a> KeyInjectExecutor<'a> {
-  pub fn new(injector: &'a dyn KeyInjector) -> Self {
-    Self { injector }
+  pub fn new(
+    injector: &'a dyn KeyInjector,
+    backspace_limit_provider: &'a dyn BackspaceLimitProvider,
+    post_backspace_delay_provider: &'a dyn PostBackspaceDelayProvider,
+    failure_handler: &'a dyn InjectionFailureHandler,
+  ) -> Self {
+    Self {
+      injector,
+      backspace_limit_provider,
+      post_backspace_delay_provider,
+      failure_handler,
+    }
   }
 }
 
 impl<'a> Executor for KeyInjectExecutor<'a> {
   fn execute(&self, event: &Event) -> bool {
     if let EventType::KeySequenceInject(inject_event) = &event.etype {
+      // Safety net in case the number of chars to delete was miscalculated
+      let backspace_count = count_backspaces(&inject_event.keys);
+      let max_backspaces = self.backspace_limit_provider.max_backspaces_per_expansion();
+      if backspace_count > max_backspaces {
+        error!(
+          "refusing to inject {} backspaces, as it exceeds the max_backspaces_per_expansion limit of {}",
+          backspace_count, max_backspaces
+        );
+        return true;
+      }
+
       if let Err(error) = self.injector.inject_sequence(&inject_event.keys) {
         error!("key injector reported an error: {}", error);
+        self.failure_handler.on_injection_failure(event, &error);
+        return true;
+      }
+
+      // Give the target application time to process the backspaces
+      // before the replacement is injected
+      if backspace_count > 0 {
+        let delay = self.post_backspace_delay_provider.post_backspace_delay();
+        if delay > 0 {
+          std::thread::sleep(Duration::from_millis(delay as u64));
+        }
       }
       return true;
     }
@@ -50,3 +100,210 @@ impl<'a> Executor for KeyInjectExecutor<'a> {
     false
   }
 }
+
+fn count_backspaces(keys: &[KeyStroke]) -> usize {
+  keys
+    .iter()
+    .filter(|stroke| matches!(stroke, KeyStroke::Key(Key::Backspace)))
+    .count()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::effect::KeySequenceInjectRequest;
+  use std::cell::{Cell, RefCell};
+  use std::time::Instant;
+
+  struct MockInjector {
+    injected: RefCell<Vec<KeyStroke>>,
+  }
+
+  impl KeyInjector for MockInjector {
+    fn inject_sequence(&self, keys: &[KeyStroke]) -> Result<()> {
+      self.injected.borrow_mut().extend_from_slice(keys);
+      Ok(())
+    }
+  }
+
+  struct FailingInjector;
+
+  impl KeyInjector for FailingInjector {
+    fn inject_sequence(&self, _: &[KeyStroke]) -> Result<()> {
+      Err(anyhow::anyhow!("simulated failure"))
+    }
+  }
+
+  struct MockFailureHandler {
+    failures: RefCell<Vec<(u32, String)>>,
+  }
+
+  impl InjectionFailureHandler for MockFailureHandler {
+    fn on_injection_failure(&self, event: &Event, error: &anyhow::Error) {
+      self
+        .failures
+        .borrow_mut()
+        .push((event.source_id, error.to_string()));
+    }
+  }
+
+  fn failure_handler() -> MockFailureHandler {
+    MockFailureHandler {
+      failures: RefCell::new(Vec::new()),
+    }
+  }
+
+  struct MockLimitProvider(usize);
+
+  impl BackspaceLimitProvider for MockLimitProvider {
+    fn max_backspaces_per_expansion(&self) -> usize {
+      self.0
+    }
+  }
+
+  struct MockDelayProvider {
+    delay: usize,
+    reads: Cell<usize>,
+  }
+
+  impl MockDelayProvider {
+    fn new(delay: usize) -> Self {
+      Self {
+        delay,
+        reads: Cell::new(0),
+      }
+    }
+  }
+
+  impl PostBackspaceDelayProvider for MockDelayProvider {
+    fn post_backspace_delay(&self) -> usize {
+      self.reads.set(self.reads.get() + 1);
+      self.delay
+    }
+  }
+
+  fn backspaces(count: usize) -> Event {
+    Event::caused_by(
+      0,
+      EventType::KeySequenceInject(KeySequenceInjectRequest {
+        keys: (0..count).map(|_| Key::Backspace.into()).collect(),
+      }),
+    )
+  }
+
+  #[test]
+  fn backspaces_within_limit_are_injected() {
+    let injector = MockInjector {
+      injected: RefCell::new(Vec::new()),
+    };
+    let limit = MockLimitProvider(5);
+    let handler = failure_handler();
+    let delay = MockDelayProvider::new(0);
+    let executor = KeyInjectExecutor::new(&injector, &limit, &delay, &handler);
+
+    assert!(executor.execute(&backspaces(5)));
+    assert_eq!(injector.injected.borrow().len(), 5);
+  }
+
+  #[test]
+  fn backspaces_exceeding_limit_are_discarded() {
+    let injector = MockInjector {
+      injected: RefCell::new(Vec::new()),
+    };
+    let limit = MockLimitProvider(5);
+    let handler = failure_handler();
+    let delay = MockDelayProvider::new(0);
+    let executor = KeyInjectExecutor::new(&injector, &limit, &delay, &handler);
+
+    // The event is still consumed, so that no other executor handles it
+    assert!(executor.execute(&backspaces(1000)));
+    assert!(injector.injected.borrow().is_empty());
+  }
+
+  #[test]
+  fn other_keys_do_not_count_towards_limit() {
+    let injector = MockInjector {
+      injected: RefCell::new(Vec::new()),
+    };
+    let limit = MockLimitProvider(0);
+    let handler = failure_handler();
+    let delay = MockDelayProvider::new(0);
+    let executor = KeyInjectExecutor::new(&injector, &limit, &delay, &handler);
+
+    let event = Event::caused_by(
+      0,
+      EventType::KeySequenceInject(KeySequenceInjectRequest {
+        keys: vec![Key::ArrowLeft.into(), Key::ArrowLeft.into()],
+      }),
+    );
+    assert!(executor.execute(&event));
+    assert_eq!(injector.injected.borrow().len(), 2);
+  }
+
+  #[test]
+  fn injection_errors_are_reported_to_failure_handler() {
+    let limit = MockLimitProvider(5);
+    let handler = failure_handler();
+    let delay = MockDelayProvider::new(0);
+    let executor = KeyInjectExecutor::new(&FailingInjector, &limit, &delay, &handler);
+
+    assert!(executor.execute(&backspaces(2)));
+
+    assert_eq!(
+      *handler.failures.borrow(),
+      vec![(0, "simulated failure".to_string())]
+    );
+  }
+
+  #[test]
+  fn successful_injections_are_not_reported() {
+    let injector = MockInjector {
+      injected: RefCell::new(Vec::new()),
+    };
+    let limit = MockLimitProvider(5);
+    let handler = failure_handler();
+    let delay = MockDelayProvider::new(0);
+    let executor = KeyInjectExecutor::new(&injector, &limit, &delay, &handler);
+
+    assert!(executor.execute(&backspaces(2)));
+    assert!(handler.failures.borrow().is_empty());
+  }
+
+  #[test]
+  fn post_backspace_delay_is_applied_after_backspaces() {
+    let injector = MockInjector {
+      injected: RefCell::new(Vec::new()),
+    };
+    let limit = MockLimitProvider(5);
+    let delay = MockDelayProvider::new(20);
+    let handler = failure_handler();
+    let executor = KeyInjectExecutor::new(&injector, &limit, &delay, &handler);
+
+    let start = Instant::now();
+    assert!(executor.execute(&backspaces(2)));
+
+    assert_eq!(delay.reads.get(), 1);
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert_eq!(injector.injected.borrow().len(), 2);
+  }
+
+  #[test]
+  fn post_backspace_delay_is_not_applied_without_backspaces() {
+    let injector = MockInjector {
+      injected: RefCell::new(Vec::new()),
+    };
+    let limit = MockLimitProvider(5);
+    let delay = MockDelayProvider::new(20);
+    let handler = failure_handler();
+    let executor = KeyInjectExecutor::new(&injector, &limit, &delay, &handler);
+
+    let event = Event::caused_by(
+      0,
+      EventType::KeySequenceInject(KeySequenceInjectRequest {
+        keys: vec![Key::ArrowLeft.into()],
+      }),
+    );
+    assert!(executor.execute(&event));
+    assert_eq!(delay.reads.get(), 0);
+  }
+}
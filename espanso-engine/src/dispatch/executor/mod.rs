@@ -22,5 +22,7 @@ pub mod html_inject;
 pub mod icon_update;
 pub mod image_inject;
 pub mod key_inject;
+pub mod notification;
 pub mod secure_input;
+pub mod sound;
 pub mod text_inject;
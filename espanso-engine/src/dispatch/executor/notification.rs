@@ -0,0 +1,119 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use anyhow::Result;
+use log::error;
+
+use crate::{
+  dispatch::Executor,
+  event::{Event, EventType},
+};
+
+pub trait NotificationHandler {
+  fn show_notification(&self, message: &str) -> Result<()>;
+}
+
+pub struct NotificationExecutor<'a> {
+  handler: &'a dyn NotificationHandler,
+}
+
+impl<'a> NotificationExecutor<'a> {
+  pub fn new(handler: &'a dyn NotificationHandler) -> Self {
+    Self { handler }
+  }
+}
+
+impl<'a> Executor for NotificationExecutor<'a> {
+  fn execute(&self, event: &Event) -> bool {
+    if let EventType::ShowNotification(m_event) = &event.etype {
+      if let Err(error) = self.handler.show_notification(&m_event.message) {
+        error!("notification handler reported an error: {:?}", error);
+      }
+
+      return true;
+    }
+
+    false
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::ui::ShowNotificationEvent;
+  use std::cell::RefCell;
+
+  struct MockHandler {
+    messages: RefCell<Vec<String>>,
+    fail: bool,
+  }
+
+  impl NotificationHandler for MockHandler {
+    fn show_notification(&self, message: &str) -> Result<()> {
+      self.messages.borrow_mut().push(message.to_string());
+      if self.fail {
+        Err(anyhow::anyhow!("simulated failure"))
+      } else {
+        Ok(())
+      }
+    }
+  }
+
+  fn handler(fail: bool) -> MockHandler {
+    MockHandler {
+      messages: RefCell::new(Vec::new()),
+      fail,
+    }
+  }
+
+  fn notification(message: &str) -> Event {
+    Event::caused_by(
+      1,
+      EventType::ShowNotification(ShowNotificationEvent {
+        message: message.to_string(),
+      }),
+    )
+  }
+
+  #[test]
+  fn notification_is_shown() {
+    let handler = handler(false);
+    let executor = NotificationExecutor::new(&handler);
+
+    assert!(executor.execute(&notification("Copied!")));
+    assert_eq!(*handler.messages.borrow(), vec!["Copied!".to_string()]);
+  }
+
+  #[test]
+  fn handler_errors_are_not_propagated() {
+    let handler = handler(true);
+    let executor = NotificationExecutor::new(&handler);
+
+    assert!(executor.execute(&notification("Copied!")));
+    assert_eq!(handler.messages.borrow().len(), 1);
+  }
+
+  #[test]
+  fn other_events_are_ignored() {
+    let handler = handler(false);
+    let executor = NotificationExecutor::new(&handler);
+
+    assert!(!executor.execute(&Event::caused_by(1, EventType::NOOP)));
+    assert!(handler.messages.borrow().is_empty());
+  }
+}
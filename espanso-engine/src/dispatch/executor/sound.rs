@@ -0,0 +1,98 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use anyhow::Result;
+use log::error;
+
+use crate::{
+  dispatch::Executor,
+  event::{Event, EventType},
+};
+
+pub trait SoundPlayer {
+  fn play(&self, sound: &str) -> Result<()>;
+}
+
+pub struct SoundExecutor<'a> {
+  player: &'a dyn SoundPlayer,
+}
+
+impl<'a> SoundExecutor<'a> {
+  pub fn new(player: &'a dyn SoundPlayer) -> Self {
+    Self { player }
+  }
+}
+
+impl<'a> Executor for SoundExecutor<'a> {
+  fn execute(&self, event: &Event) -> bool {
+    if let EventType::PlaySound(request) = &event.etype {
+      if let Err(error) = self.player.play(&request.sound) {
+        error!("sound player reported an error: {:?}", error);
+      }
+
+      return true;
+    }
+
+    false
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+
+  use super::*;
+  use crate::event::effect::PlaySoundRequest;
+
+  #[derive(Default)]
+  struct MockPlayer {
+    played: RefCell<Vec<String>>,
+  }
+
+  impl SoundPlayer for MockPlayer {
+    fn play(&self, sound: &str) -> Result<()> {
+      self.played.borrow_mut().push(sound.to_string());
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn play_sound_invokes_player() {
+    let player = MockPlayer::default();
+    let executor = SoundExecutor::new(&player);
+    let event = Event::caused_by(
+      0,
+      EventType::PlaySound(PlaySoundRequest {
+        sound: "beep".to_string(),
+      }),
+    );
+
+    assert!(executor.execute(&event));
+    assert_eq!(*player.played.borrow(), vec!["beep"]);
+  }
+
+  #[test]
+  fn other_events_are_ignored() {
+    let player = MockPlayer::default();
+    let executor = SoundExecutor::new(&player);
+
+    assert!(!executor.execute(&Event::caused_by(0, EventType::MatchInjected)));
+    assert!(player.played.borrow().is_empty());
+  }
+}
@@ -18,11 +18,15 @@
  */
 
 use crate::{
-  dispatch::Executor,
-  event::{effect::TextInjectMode, Event, EventType},
+  dispatch::{Executor, KeyInjector},
+  event::{
+    effect::{KeyStroke, TextInjectMode},
+    input::Key,
+    Event, EventType,
+  },
 };
 use anyhow::Result;
-use log::{error, trace};
+use log::{debug, error, trace};
 
 pub trait TextInjector {
   fn name(&self) -> &'static str;
@@ -31,6 +35,11 @@ pub trait TextInjector {
 
 pub trait ModeProvider {
   fn active_mode(&self) -> Mode;
+
+  // Length after which a text forced to be injected with keys is injected
+  // with the clipboard instead, as injecting long texts with keys is slow.
+  // If None, forced keys are always respected.
+  fn forced_keys_fallback_threshold(&self) -> Option<usize>;
 }
 
 pub enum Mode {
@@ -47,6 +56,7 @@ pub struct TextInjectExecutor<'a> {
   event_injector: &'a dyn TextInjector,
   clipboard_injector: &'a dyn TextInjector,
   mode_provider: &'a dyn ModeProvider,
+  key_injector: &'a dyn KeyInjector,
 }
 
 impl<'a> TextInjectExecutor<'a> {
@@ -54,52 +64,126 @@ impl<'a> TextInjectExecutor<'a> {
     event_injector: &'a dyn TextInjector,
     clipboard_injector: &'a dyn TextInjector,
     mode_provider: &'a dyn ModeProvider,
+    key_injector: &'a dyn KeyInjector,
   ) -> Self {
     Self {
       event_injector,
       clipboard_injector,
       mode_provider,
+      key_injector,
     }
   }
+
+  fn inject_with_enter_keys(&self, injector: &dyn TextInjector, text: &str) -> Result<()> {
+    for step in split_newlines(text) {
+      match step {
+        InjectionStep::Text(line) => injector.inject_text(line)?,
+        InjectionStep::Enter => self
+          .key_injector
+          .inject_sequence(&[KeyStroke::Key(Key::Enter)])?,
+      }
+    }
+
+    Ok(())
+  }
 }
 
-impl<'a> Executor for TextInjectExecutor<'a> {
-  fn execute(&self, event: &Event) -> bool {
-    if let EventType::TextInject(inject_event) = &event.etype {
-      let active_mode = self.mode_provider.active_mode();
+#[derive(Debug, PartialEq)]
+enum InjectionStep<'t> {
+  Text(&'t str),
+  Enter,
+}
 
-      let injector = if let Some(force_mode) = &inject_event.force_mode {
-        if let TextInjectMode::Keys = force_mode {
-          self.event_injector
-        } else {
-          self.clipboard_injector
-        }
-      } else if let Mode::Clipboard = active_mode {
-        self.clipboard_injector
-      } else if let Mode::Event = active_mode {
-        self.event_injector
-      } else if let Mode::Auto {
-        clipboard_threshold,
-      } = active_mode
-      {
-        if inject_event.text.chars().count() > clipboard_threshold {
-          self.clipboard_injector
-        } else if cfg!(target_os = "linux") {
-          if inject_event.text.chars().all(|c| c.is_ascii()) {
-            self.event_injector
-          } else {
-            self.clipboard_injector
-          }
-        } else {
-          self.event_injector
+// Some applications (such as chats) handle Enter presses differently from
+// newline characters, so they are injected separately from the text
+fn split_newlines(text: &str) -> Vec<InjectionStep<'_>> {
+  let mut steps = Vec::new();
+  for (index, line) in text.split('\n').enumerate() {
+    if index > 0 {
+      steps.push(InjectionStep::Enter);
+    }
+
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    if !line.is_empty() {
+      steps.push(InjectionStep::Text(line));
+    }
+  }
+  steps
+}
+
+#[derive(Debug, PartialEq)]
+enum InjectorKind {
+  Event,
+  Clipboard,
+}
+
+fn select_injector(
+  text: &str,
+  force_mode: Option<&TextInjectMode>,
+  active_mode: Mode,
+  forced_keys_fallback_threshold: Option<usize>,
+) -> InjectorKind {
+  if let Some(force_mode) = force_mode {
+    if let TextInjectMode::Keys = force_mode {
+      match forced_keys_fallback_threshold {
+        Some(threshold) if text.chars().count() > threshold => {
+          debug!(
+            "text exceeds the forced keys threshold of {} chars, falling back to the clipboard",
+            threshold
+          );
+          InjectorKind::Clipboard
         }
+        _ => InjectorKind::Event,
+      }
+    } else {
+      InjectorKind::Clipboard
+    }
+  } else if let Mode::Clipboard = active_mode {
+    InjectorKind::Clipboard
+  } else if let Mode::Event = active_mode {
+    InjectorKind::Event
+  } else if let Mode::Auto {
+    clipboard_threshold,
+  } = active_mode
+  {
+    if text.chars().count() > clipboard_threshold {
+      InjectorKind::Clipboard
+    } else if cfg!(target_os = "linux") {
+      if text.is_ascii() {
+        InjectorKind::Event
       } else {
-        self.event_injector
+        InjectorKind::Clipboard
+      }
+    } else {
+      InjectorKind::Event
+    }
+  } else {
+    InjectorKind::Event
+  }
+}
+
+impl<'a> Executor for TextInjectExecutor<'a> {
+  fn execute(&self, event: &Event) -> bool {
+    if let EventType::TextInject(inject_event) = &event.etype {
+      let injector = match select_injector(
+        &inject_event.text,
+        inject_event.force_mode.as_ref(),
+        self.mode_provider.active_mode(),
+        self.mode_provider.forced_keys_fallback_threshold(),
+      ) {
+        InjectorKind::Event => self.event_injector,
+        InjectorKind::Clipboard => self.clipboard_injector,
       };
 
       trace!("using injector: {}", injector.name());
 
-      if let Err(error) = injector.inject_text(&inject_event.text) {
+      let result = if inject_event.newline_as_key {
+        self.inject_with_enter_keys(injector, &inject_event.text)
+      } else {
+        injector.inject_text(&inject_event.text)
+      };
+
+      if let Err(error) = result {
         error!(
           "text injector ({}) reported an error: {:?}",
           injector.name(),
@@ -114,4 +198,147 @@ impl<'a> Executor for TextInjectExecutor<'a> {
   }
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::effect::TextInjectRequest;
+  use std::cell::RefCell;
+
+  struct MockInjector<'a> {
+    log: &'a RefCell<Vec<String>>,
+  }
+
+  impl<'a> TextInjector for MockInjector<'a> {
+    fn name(&self) -> &'static str {
+      "mock"
+    }
+
+    fn inject_text(&self, text: &str) -> Result<()> {
+      self.log.borrow_mut().push(format!("text:{}", text));
+      Ok(())
+    }
+  }
+
+  impl<'a> KeyInjector for MockInjector<'a> {
+    fn inject_sequence(&self, keys: &[KeyStroke]) -> Result<()> {
+      self.log.borrow_mut().push(format!("keys:{:?}", keys));
+      Ok(())
+    }
+  }
+
+  struct MockModeProvider;
+
+  impl ModeProvider for MockModeProvider {
+    fn active_mode(&self) -> Mode {
+      Mode::Event
+    }
+
+    fn forced_keys_fallback_threshold(&self) -> Option<usize> {
+      None
+    }
+  }
+
+  fn inject(text: &str, newline_as_key: bool) -> Vec<String> {
+    let log = RefCell::new(Vec::new());
+    let injector = MockInjector { log: &log };
+    let executor = TextInjectExecutor::new(&injector, &injector, &MockModeProvider, &injector);
+
+    assert!(executor.execute(&Event::caused_by(
+      0,
+      EventType::TextInject(TextInjectRequest {
+        text: text.to_string(),
+        force_mode: None,
+        newline_as_key,
+      }),
+    )));
+
+    log.into_inner()
+  }
+
+  #[test]
+  fn newlines_are_injected_as_enter_presses() {
+    assert_eq!(
+      inject("first\nsecond\r\n\nthird", true),
+      vec![
+        "text:first",
+        "keys:[Key(Enter)]",
+        "text:second",
+        "keys:[Key(Enter)]",
+        "keys:[Key(Enter)]",
+        "text:third",
+      ]
+    );
+  }
+
+  #[test]
+  fn newlines_are_kept_in_text_by_default() {
+    assert_eq!(inject("first\nsecond", false), vec!["text:first\nsecond"]);
+  }
+
+  #[test]
+  fn forced_keys_are_respected_below_threshold() {
+    assert_eq!(
+      select_injector(
+        "hello",
+        Some(&TextInjectMode::Keys),
+        Mode::Clipboard,
+        Some(10)
+      ),
+      InjectorKind::Event
+    );
+  }
+
+  #[test]
+  fn forced_keys_fall_back_to_clipboard_above_threshold() {
+    assert_eq!(
+      select_injector(
+        "a very long text",
+        Some(&TextInjectMode::Keys),
+        Mode::Event,
+        Some(10)
+      ),
+      InjectorKind::Clipboard
+    );
+  }
+
+  #[test]
+  fn forced_keys_fallback_can_be_disabled() {
+    assert_eq!(
+      select_injector(
+        "a very long text",
+        Some(&TextInjectMode::Keys),
+        Mode::Event,
+        None
+      ),
+      InjectorKind::Event
+    );
+  }
+
+  #[test]
+  fn forced_clipboard_ignores_threshold() {
+    assert_eq!(
+      select_injector(
+        "hi",
+        Some(&TextInjectMode::Clipboard),
+        Mode::Event,
+        Some(10)
+      ),
+      InjectorKind::Clipboard
+    );
+  }
+
+  #[test]
+  fn auto_mode_uses_clipboard_threshold() {
+    assert_eq!(
+      select_injector(
+        "a very long text",
+        None,
+        Mode::Auto {
+          clipboard_threshold: 5
+        },
+        None
+      ),
+      InjectorKind::Clipboard
+    );
+  }
+}
@@ -35,8 +35,12 @@ pub use executor::context_menu::ContextMenuHandler;
 pub use executor::html_inject::HtmlInjector;
 pub use executor::icon_update::IconHandler;
 pub use executor::image_inject::ImageInjector;
-pub use executor::key_inject::KeyInjector;
+pub use executor::key_inject::{
+  BackspaceLimitProvider, InjectionFailureHandler, KeyInjector, PostBackspaceDelayProvider,
+};
+pub use executor::notification::NotificationHandler;
 pub use executor::secure_input::SecureInputManager;
+pub use executor::sound::SoundPlayer;
 pub use executor::text_inject::{Mode, ModeProvider, TextInjector};
 
 #[allow(clippy::too_many_arguments)]
@@ -45,21 +49,31 @@ pub fn default<'a>(
   clipboard_injector: &'a dyn TextInjector,
   mode_provider: &'a dyn ModeProvider,
   key_injector: &'a dyn KeyInjector,
+  backspace_limit_provider: &'a dyn BackspaceLimitProvider,
+  post_backspace_delay_provider: &'a dyn PostBackspaceDelayProvider,
+  injection_failure_handler: &'a dyn InjectionFailureHandler,
   html_injector: &'a dyn HtmlInjector,
   image_injector: &'a dyn ImageInjector,
   context_menu_handler: &'a dyn ContextMenuHandler,
   icon_handler: &'a dyn IconHandler,
   secure_input_manager: &'a dyn SecureInputManager,
+  notification_handler: &'a dyn NotificationHandler,
+  sound_player: &'a dyn SoundPlayer,
 ) -> impl Dispatcher + 'a {
   default::DefaultDispatcher::new(
     event_injector,
     clipboard_injector,
     mode_provider,
     key_injector,
+    backspace_limit_provider,
+    post_backspace_delay_provider,
+    injection_failure_handler,
     html_injector,
     image_injector,
     context_menu_handler,
     icon_handler,
     secure_input_manager,
+    notification_handler,
+    sound_player,
   )
 }
@@ -34,11 +34,21 @@ pub struct CursorHintCompensationEvent {
 pub struct TextInjectRequest {
   pub text: String,
   pub force_mode: Option<TextInjectMode>,
+  // If true, newlines are injected as Enter key presses
+  pub newline_as_key: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct MarkdownInjectRequest {
   pub markdown: String,
+  pub flavor: MarkdownFlavor,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum MarkdownFlavor {
+  Standard,
+  // Preserves fenced code blocks and pipe tables when converting to HTML
+  Gfm,
 }
 
 #[derive(Debug, Clone)]
@@ -54,10 +64,34 @@ pub enum TextInjectMode {
 
 #[derive(Debug, Clone)]
 pub struct KeySequenceInjectRequest {
-  pub keys: Vec<Key>,
+  pub keys: Vec<KeyStroke>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyStroke {
+  Key(Key),
+  // A key pressed while holding the given modifiers, such as CTRL+SHIFT+V.
+  // Modifiers are pressed in order before the key and released in reverse order.
+  Combination { modifiers: Vec<Key>, key: Key },
+  // A character without a corresponding key code, which is injected
+  // using the platform's unicode input method.
+  Char(char),
+}
+
+impl From<Key> for KeyStroke {
+  fn from(key: Key) -> Self {
+    KeyStroke::Key(key)
+  }
 }
 
 #[derive(Debug, Clone)]
 pub struct ImageInjectRequest {
   pub image_path: String,
+  pub preserve_format: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaySoundRequest {
+  // Either the path of a sound file or "beep" for the system sound
+  pub sound: String,
 }
@@ -65,11 +65,14 @@ pub enum TextFormat {
 pub struct ImageRequestedEvent {
   pub match_id: i32,
   pub image_path: String,
+  pub preserve_format: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ImageResolvedEvent {
+  pub match_id: i32,
   pub image_path: String,
+  pub preserve_format: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
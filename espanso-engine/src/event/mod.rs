@@ -95,12 +95,14 @@ pub enum EventType {
   MarkdownInject(effect::MarkdownInjectRequest),
   HtmlInject(effect::HtmlInjectRequest),
   ImageInject(effect::ImageInjectRequest),
+  PlaySound(effect::PlaySoundRequest),
 
   // UI
   ShowContextMenu(ui::ShowContextMenuEvent),
   IconStatusChange(ui::IconStatusChangeEvent),
   DisplaySecureInputTroubleshoot,
   ShowSearchBar,
+  ShowNotification(ui::ShowNotificationEvent),
 
   // Other
   LaunchSecureInputAutoFix,
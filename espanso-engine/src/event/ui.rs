@@ -52,3 +52,8 @@ pub enum IconStatus {
   Disabled,
   SecureInputDisabled,
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowNotificationEvent {
+  pub message: String,
+}
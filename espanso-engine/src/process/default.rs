@@ -21,7 +21,7 @@ use log::trace;
 
 use super::{
   middleware::{
-    action::{ActionMiddleware, EventSequenceProvider},
+    action::{ActionMiddleware, EventSequenceProvider, ExpansionSoundProvider},
     cause::CauseCompensateMiddleware,
     cursor_hint::CursorHintMiddleware,
     delay_modifiers::{DelayForModifierReleaseMiddleware, ModifierStatusProvider},
@@ -30,21 +30,22 @@ use super::{
     match_select::MatchSelectMiddleware,
     matcher::MatcherMiddleware,
     multiplex::MultiplexMiddleware,
-    render::RenderMiddleware,
+    render::{RenderMiddleware, TransformProvider},
   },
-  DisableOptions, EnabledStatusProvider, MatchFilter, MatchInfoProvider, MatchProvider,
-  MatchResolver, MatchSelector, Matcher, MatcherMiddlewareConfigProvider, Middleware,
-  ModifierStateProvider, Multiplexer, NotificationManager, PathProvider, Processor, Renderer,
-  UndoEnabledProvider,
+  DisableOptions, EnabledStatusProvider, KeySequence, MatchFilter, MatchInfoProvider,
+  MatchProvider, MatchResolver, MatchSelector, MatchUsageRecorder, Matcher,
+  MatcherMiddlewareConfigProvider, Middleware, ModifierStateProvider, Multiplexer,
+  NotificationManager, PathProvider, Processor, Renderer, SecureFieldDetector, UndoEnabledProvider,
 };
 use crate::{
   event::{Event, EventType},
   process::middleware::{
     context_menu::ContextMenuMiddleware, disable::DisableMiddleware, exit::ExitMiddleware,
     hotkey::HotKeyMiddleware, icon_status::IconStatusMiddleware,
-    image_resolve::ImageResolverMiddleware, match_exec::MatchExecRequestMiddleware,
-    notification::NotificationMiddleware, search::SearchMiddleware, suppress::SuppressMiddleware,
-    undo::UndoMiddleware,
+    image_resolve::ImageResolverMiddleware, key_sequence::KeySequenceMiddleware,
+    match_exec::MatchExecRequestMiddleware, notification::NotificationMiddleware,
+    propagate::PropagateMiddleware, search::SearchMiddleware, secure_field::SecureFieldMiddleware,
+    suppress::SuppressMiddleware, undo::UndoMiddleware, usage::UsageMiddleware,
   },
 };
 use std::collections::VecDeque;
@@ -67,6 +68,7 @@ impl<'a> DefaultProcessor<'a> {
     event_sequence_provider: &'a dyn EventSequenceProvider,
     path_provider: &'a dyn PathProvider,
     disable_options: DisableOptions,
+    key_sequences: Vec<KeySequence>,
     matcher_options_provider: &'a dyn MatcherMiddlewareConfigProvider,
     match_provider: &'a dyn MatchProvider,
     undo_enabled_provider: &'a dyn UndoEnabledProvider,
@@ -74,6 +76,10 @@ impl<'a> DefaultProcessor<'a> {
     modifier_state_provider: &'a dyn ModifierStateProvider,
     match_resolver: &'a dyn MatchResolver,
     notification_manager: &'a dyn NotificationManager,
+    usage_recorders: &'a [&'a dyn MatchUsageRecorder],
+    expansion_sound_provider: &'a dyn ExpansionSoundProvider,
+    transform_provider: &'a dyn TransformProvider,
+    secure_field_detector: &'a dyn SecureFieldDetector,
   ) -> DefaultProcessor<'a> {
     Self {
       event_queue: VecDeque::new(),
@@ -81,6 +87,7 @@ impl<'a> DefaultProcessor<'a> {
         Box::new(EventsDiscardMiddleware::new()),
         Box::new(DisableMiddleware::new(disable_options)),
         Box::new(IconStatusMiddleware::new()),
+        Box::new(KeySequenceMiddleware::new(key_sequences)),
         Box::new(MatcherMiddleware::new(
           matchers,
           matcher_options_provider,
@@ -90,21 +97,29 @@ impl<'a> DefaultProcessor<'a> {
         Box::new(SuppressMiddleware::new(enabled_status_provider)),
         Box::new(ContextMenuMiddleware::new()),
         Box::new(HotKeyMiddleware::new()),
+        Box::new(SecureFieldMiddleware::new(secure_field_detector)),
         Box::new(MatchSelectMiddleware::new(
           match_filter,
           match_selector,
           event_sequence_provider,
         )),
-        Box::new(CauseCompensateMiddleware::new()),
+        Box::new(CauseCompensateMiddleware::new(match_info_provider)),
         Box::new(MultiplexMiddleware::new(multiplexer)),
-        Box::new(RenderMiddleware::new(renderer)),
+        Box::new(RenderMiddleware::new(renderer, transform_provider)),
+        Box::new(UsageMiddleware::new(usage_recorders)),
         Box::new(ImageResolverMiddleware::new(path_provider)),
         Box::new(CursorHintMiddleware::new()),
         Box::new(ExitMiddleware::new()),
         Box::new(UndoMiddleware::new(undo_enabled_provider)),
+        Box::new(PropagateMiddleware::new(
+          match_info_provider,
+          matcher_options_provider,
+          event_sequence_provider,
+        )),
         Box::new(ActionMiddleware::new(
           match_info_provider,
           event_sequence_provider,
+          expansion_sound_provider,
         )),
         Box::new(SearchMiddleware::new(match_provider)),
         Box::new(MarkdownMiddleware::new()),
@@ -20,35 +20,49 @@
 use super::super::Middleware;
 use crate::event::{
   effect::{
-    HtmlInjectRequest, ImageInjectRequest, KeySequenceInjectRequest, MarkdownInjectRequest,
-    TextInjectMode, TextInjectRequest,
+    HtmlInjectRequest, ImageInjectRequest, KeySequenceInjectRequest, MarkdownFlavor,
+    MarkdownInjectRequest, PlaySoundRequest, TextInjectMode, TextInjectRequest,
   },
   input::Key,
   internal::{DiscardPreviousEvent, TextFormat},
+  ui::ShowNotificationEvent,
   Event, EventType,
 };
 
 pub trait MatchInfoProvider {
   fn get_force_mode(&self, match_id: i32) -> Option<TextInjectMode>;
+  fn should_propagate(&self, match_id: i32) -> bool;
+  fn should_keep_trigger(&self, match_id: i32) -> bool;
+  fn should_inject_newline_as_key(&self, match_id: i32) -> bool;
+  fn get_markdown_flavor(&self, match_id: i32) -> MarkdownFlavor;
+  fn get_notification(&self, match_id: i32) -> Option<String>;
 }
 
 pub trait EventSequenceProvider {
   fn get_next_id(&self) -> u32;
 }
 
+pub trait ExpansionSoundProvider {
+  // Sound played after each expansion, None if disabled
+  fn get_expansion_sound(&self) -> Option<String>;
+}
+
 pub struct ActionMiddleware<'a> {
   match_info_provider: &'a dyn MatchInfoProvider,
   event_sequence_provider: &'a dyn EventSequenceProvider,
+  expansion_sound_provider: &'a dyn ExpansionSoundProvider,
 }
 
 impl<'a> ActionMiddleware<'a> {
   pub fn new(
     match_info_provider: &'a dyn MatchInfoProvider,
     event_sequence_provider: &'a dyn EventSequenceProvider,
+    expansion_sound_provider: &'a dyn ExpansionSoundProvider,
   ) -> Self {
     Self {
       match_info_provider,
       event_sequence_provider,
+      expansion_sound_provider,
     }
   }
 }
@@ -69,6 +83,26 @@ impl<'a> Middleware for ActionMiddleware<'a> {
           }),
         ));
 
+        // Dispatched events are handled after the injection, so the
+        // notification is shown once the match has been expanded
+        let match_id = match &event.etype {
+          EventType::Rendered(m_event) => m_event.match_id,
+          EventType::ImageResolved(m_event) => m_event.match_id,
+          _ => unreachable!(),
+        };
+        if let Some(message) = self.match_info_provider.get_notification(match_id) {
+          dispatch(Event::caused_by(
+            event.source_id,
+            EventType::ShowNotification(ShowNotificationEvent { message }),
+          ));
+        }
+        if let Some(sound) = self.expansion_sound_provider.get_expansion_sound() {
+          dispatch(Event::caused_by(
+            event.source_id,
+            EventType::PlaySound(PlaySoundRequest { sound }),
+          ));
+        }
+
         match &event.etype {
           EventType::Rendered(m_event) => Event::caused_by(
             event.source_id,
@@ -76,12 +110,18 @@ impl<'a> Middleware for ActionMiddleware<'a> {
               TextFormat::Plain => EventType::TextInject(TextInjectRequest {
                 text: m_event.body.clone(),
                 force_mode: self.match_info_provider.get_force_mode(m_event.match_id),
+                newline_as_key: self
+                  .match_info_provider
+                  .should_inject_newline_as_key(m_event.match_id),
               }),
               TextFormat::Html => EventType::HtmlInject(HtmlInjectRequest {
                 html: m_event.body.clone(),
               }),
               TextFormat::Markdown => EventType::MarkdownInject(MarkdownInjectRequest {
                 markdown: m_event.body.clone(),
+                flavor: self
+                  .match_info_provider
+                  .get_markdown_flavor(m_event.match_id),
               }),
             },
           ),
@@ -89,6 +129,7 @@ impl<'a> Middleware for ActionMiddleware<'a> {
             event.source_id,
             EventType::ImageInject(ImageInjectRequest {
               image_path: m_event.image_path.clone(),
+              preserve_format: m_event.preserve_format,
             }),
           ),
           _ => unreachable!(),
@@ -106,7 +147,7 @@ impl<'a> Middleware for ActionMiddleware<'a> {
           event.source_id,
           EventType::KeySequenceInject(KeySequenceInjectRequest {
             keys: (0..m_event.cursor_hint_back_count)
-              .map(|_| Key::ArrowLeft)
+              .map(|_| Key::ArrowLeft.into())
               .collect(),
           }),
         )
@@ -122,7 +163,9 @@ impl<'a> Middleware for ActionMiddleware<'a> {
         Event::caused_by(
           event.source_id,
           EventType::KeySequenceInject(KeySequenceInjectRequest {
-            keys: (0..backspace_count).map(|_| Key::Backspace).collect(),
+            keys: (0..backspace_count)
+              .map(|_| Key::Backspace.into())
+              .collect(),
           }),
         )
       }
@@ -136,13 +179,16 @@ impl<'a> Middleware for ActionMiddleware<'a> {
           EventType::TextInject(TextInjectRequest {
             text: m_event.trigger.clone(),
             force_mode: self.match_info_provider.get_force_mode(m_event.match_id),
+            newline_as_key: false,
           }),
         ));
 
         Event::caused_by(
           event.source_id,
           EventType::KeySequenceInject(KeySequenceInjectRequest {
-            keys: (0..backspace_count).map(|_| Key::Backspace).collect(),
+            keys: (0..backspace_count)
+              .map(|_| Key::Backspace.into())
+              .collect(),
           }),
         )
       }
@@ -151,4 +197,139 @@ impl<'a> Middleware for ActionMiddleware<'a> {
   }
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::internal::{ImageResolvedEvent, RenderedEvent};
+
+  struct MockMatchInfoProvider;
+
+  impl MatchInfoProvider for MockMatchInfoProvider {
+    fn get_force_mode(&self, _: i32) -> Option<TextInjectMode> {
+      None
+    }
+
+    fn should_propagate(&self, _: i32) -> bool {
+      false
+    }
+
+    fn should_keep_trigger(&self, _: i32) -> bool {
+      false
+    }
+
+    fn should_inject_newline_as_key(&self, _: i32) -> bool {
+      false
+    }
+
+    fn get_markdown_flavor(&self, _: i32) -> MarkdownFlavor {
+      MarkdownFlavor::Standard
+    }
+
+    fn get_notification(&self, match_id: i32) -> Option<String> {
+      if match_id == 1 {
+        Some("Inserted signature".to_string())
+      } else {
+        None
+      }
+    }
+  }
+
+  struct MockSequenceProvider;
+
+  impl EventSequenceProvider for MockSequenceProvider {
+    fn get_next_id(&self) -> u32 {
+      0
+    }
+  }
+
+  struct MockSoundProvider(Option<&'static str>);
+
+  impl ExpansionSoundProvider for MockSoundProvider {
+    fn get_expansion_sound(&self) -> Option<String> {
+      self.0.map(String::from)
+    }
+  }
+
+  fn run(event_type: EventType) -> (Event, Vec<Event>) {
+    run_with_sound(event_type, None)
+  }
+
+  fn run_with_sound(event_type: EventType, sound: Option<&'static str>) -> (Event, Vec<Event>) {
+    let sound_provider = MockSoundProvider(sound);
+    let middleware = ActionMiddleware::new(
+      &MockMatchInfoProvider,
+      &MockSequenceProvider,
+      &sound_provider,
+    );
+    let mut dispatched = Vec::new();
+    let result = middleware.next(Event::caused_by(0, event_type), &mut |event| {
+      dispatched.push(event)
+    });
+    (result, dispatched)
+  }
+
+  fn notifications(events: &[Event]) -> Vec<String> {
+    events
+      .iter()
+      .filter_map(|event| match &event.etype {
+        EventType::ShowNotification(n_event) => Some(n_event.message.clone()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn rendered(match_id: i32) -> EventType {
+    EventType::Rendered(RenderedEvent {
+      match_id,
+      body: "Best regards".to_string(),
+      format: TextFormat::Plain,
+    })
+  }
+
+  #[test]
+  fn notification_is_dispatched_after_rendering() {
+    let (result, dispatched) = run(rendered(1));
+    assert!(matches!(result.etype, EventType::TextInject(_)));
+    assert_eq!(notifications(&dispatched), vec!["Inserted signature"]);
+  }
+
+  #[test]
+  fn notification_is_dispatched_for_images() {
+    let (result, dispatched) = run(EventType::ImageResolved(ImageResolvedEvent {
+      match_id: 1,
+      image_path: "image.png".to_string(),
+      preserve_format: false,
+    }));
+    assert!(matches!(result.etype, EventType::ImageInject(_)));
+    assert_eq!(notifications(&dispatched), vec!["Inserted signature"]);
+  }
+
+  #[test]
+  fn no_notification_without_notify_option() {
+    let (_, dispatched) = run(rendered(2));
+    assert!(notifications(&dispatched).is_empty());
+  }
+
+  fn sounds(events: &[Event]) -> Vec<String> {
+    events
+      .iter()
+      .filter_map(|event| match &event.etype {
+        EventType::PlaySound(request) => Some(request.sound.clone()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn sound_is_played_after_rendering() {
+    let (result, dispatched) = run_with_sound(rendered(2), Some("beep"));
+    assert!(matches!(result.etype, EventType::TextInject(_)));
+    assert_eq!(sounds(&dispatched), vec!["beep"]);
+  }
+
+  #[test]
+  fn no_sound_when_disabled() {
+    let (_, dispatched) = run(rendered(1));
+    assert!(sounds(&dispatched).is_empty());
+  }
+}
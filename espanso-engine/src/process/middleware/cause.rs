@@ -21,16 +21,21 @@ use super::super::Middleware;
 use crate::event::{
   effect::TriggerCompensationEvent, internal::CauseCompensatedMatchEvent, Event, EventType,
 };
+use crate::process::MatchInfoProvider;
 
-pub struct CauseCompensateMiddleware {}
+pub struct CauseCompensateMiddleware<'a> {
+  match_info_provider: &'a dyn MatchInfoProvider,
+}
 
-impl CauseCompensateMiddleware {
-  pub fn new() -> Self {
-    Self {}
+impl<'a> CauseCompensateMiddleware<'a> {
+  pub fn new(match_info_provider: &'a dyn MatchInfoProvider) -> Self {
+    Self {
+      match_info_provider,
+    }
   }
 }
 
-impl Middleware for CauseCompensateMiddleware {
+impl<'a> Middleware for CauseCompensateMiddleware<'a> {
   fn name(&self) -> &'static str {
     "cause_compensate"
   }
@@ -44,6 +49,14 @@ impl Middleware for CauseCompensateMiddleware {
         }),
       );
 
+      // Matches marked with `keep_trigger` leave the typed trigger in place
+      if self
+        .match_info_provider
+        .should_keep_trigger(m_event.chosen.id)
+      {
+        return compensated_event;
+      }
+
       if let Some(trigger) = &m_event.chosen.trigger {
         dispatch(compensated_event);
 
@@ -64,4 +77,107 @@ impl Middleware for CauseCompensateMiddleware {
   }
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::{
+    effect::{MarkdownFlavor, TextInjectMode},
+    input::Key,
+    internal::{DetectedMatch, MatchSelectedEvent},
+  };
+  use crate::process::{
+    middleware::action::ActionMiddleware, EventSequenceProvider, ExpansionSoundProvider,
+  };
+
+  struct MockMatchInfoProvider;
+
+  impl MatchInfoProvider for MockMatchInfoProvider {
+    fn get_force_mode(&self, _: i32) -> Option<TextInjectMode> {
+      None
+    }
+
+    fn should_propagate(&self, _: i32) -> bool {
+      false
+    }
+
+    fn should_keep_trigger(&self, match_id: i32) -> bool {
+      match_id == 1
+    }
+
+    fn get_markdown_flavor(&self, _: i32) -> MarkdownFlavor {
+      MarkdownFlavor::Standard
+    }
+
+    fn should_inject_newline_as_key(&self, _: i32) -> bool {
+      false
+    }
+
+    fn get_notification(&self, _: i32) -> Option<String> {
+      None
+    }
+  }
+
+  struct MockSequenceProvider;
+
+  impl EventSequenceProvider for MockSequenceProvider {
+    fn get_next_id(&self) -> u32 {
+      0
+    }
+  }
+
+  struct MockSoundProvider;
+
+  impl ExpansionSoundProvider for MockSoundProvider {
+    fn get_expansion_sound(&self) -> Option<String> {
+      None
+    }
+  }
+
+  // Runs the selection of the given match through the cause and action
+  // middlewares, returning the number of injected backspaces
+  fn backspace_count(match_id: i32) -> usize {
+    let cause = CauseCompensateMiddleware::new(&MockMatchInfoProvider);
+    let action = ActionMiddleware::new(
+      &MockMatchInfoProvider,
+      &MockSequenceProvider,
+      &MockSoundProvider,
+    );
+
+    let mut events = Vec::new();
+    let event = cause.next(
+      Event::caused_by(
+        0,
+        EventType::MatchSelected(MatchSelectedEvent {
+          chosen: DetectedMatch {
+            id: match_id,
+            trigger: Some(":hello ".to_string()),
+            ..Default::default()
+          },
+        }),
+      ),
+      &mut |e| events.push(e),
+    );
+    events.push(event);
+
+    events
+      .into_iter()
+      .map(|event| action.next(event, &mut |_| {}))
+      .filter_map(|event| match event.etype {
+        EventType::KeySequenceInject(request) => Some(request.keys),
+        _ => None,
+      })
+      .flatten()
+      .filter(|key| *key == Key::Backspace.into())
+      .count()
+  }
+
+  #[test]
+  fn trigger_is_deleted_by_default() {
+    assert_eq!(backspace_count(2), 7);
+  }
+
+  #[test]
+  fn keep_trigger_emits_no_backspaces() {
+    assert_eq!(backspace_count(1), 0);
+  }
+}
@@ -26,6 +26,7 @@ use log::info;
 
 use super::super::Middleware;
 use crate::event::{
+  effect::TriggerCompensationEvent,
   input::{Key, KeyboardEvent, Status, Variant},
   Event, EventType,
 };
@@ -34,12 +35,16 @@ pub struct DisableOptions {
   pub toggle_key: Option<Key>,
   pub toggle_key_variant: Option<Variant>,
   pub toggle_key_maximum_window: Duration,
+  // Typing this trigger toggles the enabled state, even while disabled
+  pub toggle_trigger: Option<String>,
   // TODO: toggle shortcut?
 }
 
 pub struct DisableMiddleware {
   enabled: RefCell<bool>,
   last_toggle_press: RefCell<Option<Instant>>,
+  // Last typed chars, used to detect the toggle trigger
+  typed_buffer: RefCell<String>,
   options: DisableOptions,
 }
 
@@ -48,9 +53,44 @@ impl DisableMiddleware {
     Self {
       enabled: RefCell::new(true),
       last_toggle_press: RefCell::new(None),
+      typed_buffer: RefCell::new(String::new()),
       options,
     }
   }
+
+  // The regular matchers can't be used, as they don't receive
+  // any event while espanso is disabled
+  fn is_toggle_trigger_completed(&self, event: &KeyboardEvent) -> bool {
+    let trigger = match &self.options.toggle_trigger {
+      Some(trigger) => trigger,
+      None => return false,
+    };
+
+    let mut buffer = self.typed_buffer.borrow_mut();
+    if event.key == Key::Backspace {
+      buffer.pop();
+      return false;
+    }
+
+    if let Some(value) = &event.value {
+      buffer.push_str(value);
+
+      let excess = buffer
+        .chars()
+        .count()
+        .saturating_sub(trigger.chars().count());
+      if excess > 0 {
+        *buffer = buffer.chars().skip(excess).collect();
+      }
+
+      if buffer.as_str() == trigger {
+        buffer.clear();
+        return true;
+      }
+    }
+
+    false
+  }
 }
 
 impl Middleware for DisableMiddleware {
@@ -64,6 +104,22 @@ impl Middleware for DisableMiddleware {
 
     match &event.etype {
       EventType::Keyboard(m_event) => {
+        if m_event.status == Status::Pressed && self.is_toggle_trigger_completed(m_event) {
+          *enabled = !*enabled;
+          has_status_changed = true;
+
+          // Remove the typed trigger, as it's not meant to end up in the text
+          if let Some(trigger) = &self.options.toggle_trigger {
+            dispatch(Event::caused_by(
+              event.source_id,
+              EventType::TriggerCompensation(TriggerCompensationEvent {
+                trigger: trigger.clone(),
+                left_separator: None,
+              }),
+            ));
+          }
+        }
+
         if m_event.status == Status::Released {
           let mut last_toggle_press = self.last_toggle_press.borrow_mut();
           if is_toggle_key(m_event, &self.options) {
@@ -141,4 +197,95 @@ fn is_toggle_key(event: &KeyboardEvent, options: &DisableOptions) -> bool {
   }
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_middleware(toggle_trigger: Option<&str>) -> DisableMiddleware {
+    DisableMiddleware::new(DisableOptions {
+      toggle_key: None,
+      toggle_key_variant: None,
+      toggle_key_maximum_window: Duration::from_millis(1000),
+      toggle_trigger: toggle_trigger.map(String::from),
+    })
+  }
+
+  fn key_press(value: &str) -> Event {
+    Event::caused_by(
+      0,
+      EventType::Keyboard(KeyboardEvent {
+        key: Key::Other(0),
+        value: Some(value.to_string()),
+        status: Status::Pressed,
+        variant: None,
+      }),
+    )
+  }
+
+  // Returns the resulting event and the dispatched ones
+  fn type_text(middleware: &DisableMiddleware, text: &str) -> (Event, Vec<Event>) {
+    let mut dispatched = Vec::new();
+    let mut result = None;
+    for c in text.chars() {
+      result = Some(middleware.next(key_press(&c.to_string()), &mut |e| dispatched.push(e)));
+    }
+    (result.unwrap(), dispatched)
+  }
+
+  #[test]
+  fn toggle_trigger_suppresses_matching_until_typed_again() {
+    let middleware = create_middleware(Some(":off"));
+
+    let (_, dispatched) = type_text(&middleware, "hi:off");
+    assert!(dispatched
+      .iter()
+      .any(|e| matches!(e.etype, EventType::Disabled)));
+    assert!(dispatched.iter().any(|e| matches!(
+      &e.etype,
+      EventType::TriggerCompensation(compensation) if compensation.trigger == ":off"
+    )));
+
+    // Keyboard events no longer reach the matchers
+    let (event, _) = type_text(&middleware, "a");
+    assert!(matches!(event.etype, EventType::NOOP));
+
+    let (_, dispatched) = type_text(&middleware, ":off");
+    assert!(dispatched
+      .iter()
+      .any(|e| matches!(e.etype, EventType::Enabled)));
+
+    let (event, _) = type_text(&middleware, "a");
+    assert!(matches!(event.etype, EventType::Keyboard(_)));
+  }
+
+  #[test]
+  fn enable_and_disable_requests_toggle_the_flag() {
+    let middleware = create_middleware(None);
+
+    let mut dispatched = Vec::new();
+    middleware.next(Event::caused_by(0, EventType::DisableRequest), &mut |e| {
+      dispatched.push(e)
+    });
+    assert!(matches!(dispatched[0].etype, EventType::Disabled));
+
+    let (event, _) = type_text(&middleware, "a");
+    assert!(matches!(event.etype, EventType::NOOP));
+
+    middleware.next(Event::caused_by(0, EventType::EnableRequest), &mut |e| {
+      dispatched.push(e)
+    });
+    assert!(matches!(dispatched[1].etype, EventType::Enabled));
+
+    let (event, _) = type_text(&middleware, "a");
+    assert!(matches!(event.etype, EventType::Keyboard(_)));
+  }
+
+  #[test]
+  fn toggle_trigger_is_ignored_when_not_configured() {
+    let middleware = create_middleware(None);
+
+    let (event, dispatched) = type_text(&middleware, ":off");
+    assert!(dispatched.is_empty());
+    assert!(matches!(event.etype, EventType::Keyboard(_)));
+  }
+}
@@ -70,7 +70,11 @@ impl<'a> Middleware for ImageResolverMiddleware<'a> {
 
       return Event::caused_by(
         event.source_id,
-        EventType::ImageResolved(ImageResolvedEvent { image_path: path }),
+        EventType::ImageResolved(ImageResolvedEvent {
+          match_id: m_event.match_id,
+          image_path: path,
+          preserve_format: m_event.preserve_format,
+        }),
       );
     }
 
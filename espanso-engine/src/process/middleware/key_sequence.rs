@@ -0,0 +1,294 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{
+  cell::RefCell,
+  time::{Duration, Instant},
+};
+
+use super::super::Middleware;
+use crate::event::{
+  input::{Key, KeyboardEvent, Status, Variant},
+  internal::{DetectedMatch, MatchesDetectedEvent},
+  Event, EventType,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySequence {
+  pub id: i32,
+  // If the variant is not specified, both sides are accepted
+  pub keys: Vec<(Key, Option<Variant>)>,
+  // Maximum time between the first and the last key of the sequence
+  pub window: Duration,
+}
+
+#[derive(Debug, Default)]
+struct SequenceProgress {
+  matched: usize,
+  started_at: Option<Instant>,
+}
+
+pub struct KeySequenceMiddleware {
+  sequences: Vec<KeySequence>,
+  progress: RefCell<Vec<SequenceProgress>>,
+}
+
+impl KeySequenceMiddleware {
+  pub fn new(sequences: Vec<KeySequence>) -> Self {
+    let progress = sequences
+      .iter()
+      .map(|_| SequenceProgress::default())
+      .collect();
+    Self {
+      sequences,
+      progress: RefCell::new(progress),
+    }
+  }
+}
+
+impl Middleware for KeySequenceMiddleware {
+  fn name(&self) -> &'static str {
+    "key_sequence"
+  }
+
+  fn next(&self, event: Event, dispatch: &mut dyn FnMut(Event)) -> Event {
+    if let EventType::Keyboard(keyboard_event) = &event.etype {
+      let now = Instant::now();
+      let mut progress = self.progress.borrow_mut();
+      for (sequence, progress) in self.sequences.iter().zip(progress.iter_mut()) {
+        if update_progress(sequence, progress, keyboard_event, now) {
+          dispatch(Event::caused_by(
+            event.source_id,
+            EventType::MatchesDetected(MatchesDetectedEvent {
+              matches: vec![DetectedMatch {
+                id: sequence.id,
+                ..Default::default()
+              }],
+              is_search: false,
+            }),
+          ));
+        }
+      }
+    }
+
+    event
+  }
+}
+
+// Keys are considered when released, so that a sequence of modifiers
+// doesn't interfere with regular shortcuts (a press of any other key in
+// between resets the progress). Returns true when the sequence is completed.
+fn update_progress(
+  sequence: &KeySequence,
+  progress: &mut SequenceProgress,
+  event: &KeyboardEvent,
+  now: Instant,
+) -> bool {
+  let is_expected = |index: usize| {
+    sequence
+      .keys
+      .get(index)
+      .map(|expected| is_same_key(expected, event))
+      .unwrap_or(false)
+  };
+
+  match event.status {
+    Status::Pressed => {
+      if progress.matched > 0 && !is_expected(progress.matched) {
+        *progress = SequenceProgress::default();
+      }
+      false
+    }
+    Status::Released => {
+      let is_within_window = progress
+        .started_at
+        .map(|started_at| now.duration_since(started_at) <= sequence.window)
+        .unwrap_or(false);
+
+      if progress.matched > 0 && is_within_window && is_expected(progress.matched) {
+        progress.matched += 1;
+      } else if is_expected(0) {
+        // Restart the sequence from this key
+        progress.matched = 1;
+        progress.started_at = Some(now);
+      } else {
+        *progress = SequenceProgress::default();
+      }
+
+      if progress.matched == sequence.keys.len() {
+        *progress = SequenceProgress::default();
+        true
+      } else {
+        false
+      }
+    }
+  }
+}
+
+fn is_same_key(expected: &(Key, Option<Variant>), event: &KeyboardEvent) -> bool {
+  let (key, variant) = expected;
+  if key != &event.key {
+    return false;
+  }
+
+  match (variant, &event.variant) {
+    (Some(variant), Some(event_variant)) => variant == event_variant,
+    _ => true,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn double_shift() -> KeySequence {
+    KeySequence {
+      id: 1,
+      keys: vec![(Key::Shift, None), (Key::Shift, None)],
+      window: Duration::from_millis(300),
+    }
+  }
+
+  fn key_event(key: Key, status: Status) -> KeyboardEvent {
+    KeyboardEvent {
+      key,
+      value: None,
+      status,
+      variant: None,
+    }
+  }
+
+  // Feeds a full key press (press and release) at the given offset
+  fn tap(
+    sequence: &KeySequence,
+    progress: &mut SequenceProgress,
+    key: Key,
+    start: Instant,
+    offset_ms: u64,
+  ) -> bool {
+    let now = start + Duration::from_millis(offset_ms);
+    update_progress(
+      sequence,
+      progress,
+      &key_event(key.clone(), Status::Pressed),
+      now,
+    );
+    update_progress(sequence, progress, &key_event(key, Status::Released), now)
+  }
+
+  #[test]
+  fn sequence_within_window_is_detected() {
+    let sequence = double_shift();
+    let mut progress = SequenceProgress::default();
+    let start = Instant::now();
+
+    assert!(!tap(&sequence, &mut progress, Key::Shift, start, 0));
+    assert!(tap(&sequence, &mut progress, Key::Shift, start, 250));
+
+    // The progress is reset after a detection
+    assert!(!tap(&sequence, &mut progress, Key::Shift, start, 400));
+  }
+
+  #[test]
+  fn sequence_outside_window_is_not_detected() {
+    let sequence = double_shift();
+    let mut progress = SequenceProgress::default();
+    let start = Instant::now();
+
+    assert!(!tap(&sequence, &mut progress, Key::Shift, start, 0));
+    assert!(!tap(&sequence, &mut progress, Key::Shift, start, 350));
+
+    // The late key starts a new sequence
+    assert!(tap(&sequence, &mut progress, Key::Shift, start, 500));
+  }
+
+  #[test]
+  fn other_key_in_between_resets_the_sequence() {
+    let sequence = double_shift();
+    let mut progress = SequenceProgress::default();
+    let start = Instant::now();
+
+    assert!(!tap(&sequence, &mut progress, Key::Shift, start, 0));
+    assert!(!tap(&sequence, &mut progress, Key::Other(65), start, 50));
+    assert!(!tap(&sequence, &mut progress, Key::Shift, start, 100));
+    assert!(tap(&sequence, &mut progress, Key::Shift, start, 150));
+  }
+
+  #[test]
+  fn variant_is_respected() {
+    let sequence = KeySequence {
+      id: 1,
+      keys: vec![
+        (Key::Control, Some(Variant::Left)),
+        (Key::Control, Some(Variant::Left)),
+      ],
+      window: Duration::from_millis(300),
+    };
+    let mut progress = SequenceProgress::default();
+    let start = Instant::now();
+    let left = KeyboardEvent {
+      variant: Some(Variant::Left),
+      ..key_event(Key::Control, Status::Released)
+    };
+    let right = KeyboardEvent {
+      variant: Some(Variant::Right),
+      ..key_event(Key::Control, Status::Released)
+    };
+
+    assert!(!update_progress(&sequence, &mut progress, &left, start));
+    assert!(!update_progress(
+      &sequence,
+      &mut progress,
+      &right,
+      start + Duration::from_millis(100)
+    ));
+    assert!(!update_progress(
+      &sequence,
+      &mut progress,
+      &left,
+      start + Duration::from_millis(150)
+    ));
+    assert!(update_progress(
+      &sequence,
+      &mut progress,
+      &left,
+      start + Duration::from_millis(200)
+    ));
+  }
+
+  #[test]
+  fn middleware_dispatches_detected_match() {
+    let middleware = KeySequenceMiddleware::new(vec![double_shift()]);
+    let mut dispatched = Vec::new();
+
+    for _ in 0..2 {
+      for status in [Status::Pressed, Status::Released] {
+        let event = Event::caused_by(0, EventType::Keyboard(key_event(Key::Shift, status)));
+        let result = middleware.next(event, &mut |e| dispatched.push(e));
+        assert!(matches!(result.etype, EventType::Keyboard(_)));
+      }
+    }
+
+    assert_eq!(dispatched.len(), 1);
+    assert!(matches!(
+      &dispatched[0].etype,
+      EventType::MatchesDetected(detected) if detected.matches[0].id == 1
+    ));
+  }
+}
@@ -18,9 +18,13 @@
  */
 
 use log::error;
+use pulldown_cmark::{html, Options, Parser};
 
 use super::super::Middleware;
-use crate::event::{effect::HtmlInjectRequest, Event, EventType};
+use crate::event::{
+  effect::{HtmlInjectRequest, MarkdownFlavor},
+  Event, EventType,
+};
 
 // Convert markdown injection requests to HTML on the fly
 pub struct MarkdownMiddleware {}
@@ -43,16 +47,14 @@ impl Middleware for MarkdownMiddleware {
       // the library panics. Ideally, the library would return a Result::Err in that case, but
       // for now it doesn't, so we employ that workaround.
       // See also: https://github.com/federico-terzi/espanso/issues/759
-      let html = std::panic::catch_unwind(|| markdown::to_html(&m_event.markdown));
+      let html = std::panic::catch_unwind(|| match m_event.flavor {
+        MarkdownFlavor::Standard => standard_to_html(&m_event.markdown),
+        MarkdownFlavor::Gfm => gfm_to_html(&m_event.markdown),
+      });
       if let Ok(html) = html {
-        let html = html.trim();
-        let html = remove_paragraph_tag_if_single_occurrence(html);
-
         return Event::caused_by(
           event.source_id,
-          EventType::HtmlInject(HtmlInjectRequest {
-            html: html.to_owned(),
-          }),
+          EventType::HtmlInject(HtmlInjectRequest { html }),
         );
       } else {
         error!("unable to convert markdown to HTML, is it malformed?");
@@ -65,6 +67,20 @@ impl Middleware for MarkdownMiddleware {
   }
 }
 
+fn standard_to_html(markdown: &str) -> String {
+  let html = markdown::to_html(markdown);
+  remove_paragraph_tag_if_single_occurrence(html.trim()).to_owned()
+}
+
+// The markdown library renders fenced code blocks and tables poorly, so the
+// GFM flavor relies on a CommonMark parser with tables enabled
+fn gfm_to_html(markdown: &str) -> String {
+  let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES);
+  let mut html_output = String::new();
+  html::push_html(&mut html_output, parser);
+  remove_paragraph_tag_if_single_occurrence(html_output.trim()).to_owned()
+}
+
 // If the match is composed of a single paragraph, we remove the tag to avoid
 // a forced "newline" on some editors. In other words, we assume that if the snippet
 // is composed of a single paragraph, then it should be inlined.
@@ -103,4 +119,29 @@ mod tests {
       "<p>multi</p> <p>occurrence</p>"
     );
   }
+
+  #[test]
+  fn gfm_fenced_code_block_is_preserved() {
+    assert_eq!(
+      gfm_to_html("```rust\nif a < b {\n    run();\n}\n```"),
+      "<pre><code class=\"language-rust\">if a &lt; b {\n    run();\n}\n</code></pre>"
+    );
+    assert_eq!(
+      gfm_to_html("~~~\nunclosed"),
+      "<pre><code>unclosed</code></pre>"
+    );
+  }
+
+  #[test]
+  fn gfm_table_is_converted() {
+    assert_eq!(
+      gfm_to_html("| Name | Qty |\n| :--- | ---: |\n| apple | 3 |"),
+      "<table><thead><tr><th style=\"text-align: left\">Name</th><th style=\"text-align: right\">Qty</th></tr></thead><tbody>\n<tr><td style=\"text-align: left\">apple</td><td style=\"text-align: right\">3</td></tr>\n</tbody></table>"
+    );
+  }
+
+  #[test]
+  fn gfm_without_blocks_matches_standard() {
+    assert_eq!(gfm_to_html("a | b"), standard_to_html("a | b"));
+  }
 }
@@ -30,6 +30,7 @@ use crate::{
 
 pub trait MatchFilter {
   fn filter_active(&self, matches_ids: &[i32]) -> Vec<i32>;
+  fn is_exclusive(&self, match_id: i32) -> bool;
 }
 
 pub trait MatchSelector {
@@ -66,7 +67,15 @@ impl<'a> Middleware for MatchSelectMiddleware<'a> {
       let matches_ids: Vec<i32> = m_event.matches.iter().map(|m| m.id).collect();
 
       // Find the matches that are actually valid in the current context
-      let valid_ids = self.match_filter.filter_active(&matches_ids);
+      let mut valid_ids = self.match_filter.filter_active(&matches_ids);
+
+      // Exclusive matches suppress all the other candidates
+      if valid_ids
+        .iter()
+        .any(|id| self.match_filter.is_exclusive(*id))
+      {
+        valid_ids.retain(|id| self.match_filter.is_exclusive(*id));
+      }
 
       return match valid_ids.len() {
         0 => Event::caused_by(event.source_id, EventType::NOOP), // No valid matches, consume the event
@@ -129,4 +138,86 @@ impl<'a> Middleware for MatchSelectMiddleware<'a> {
   }
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, collections::HashMap};
+
+  use super::*;
+  use crate::event::internal::{DetectedMatch, MatchesDetectedEvent};
+
+  struct MockFilter {
+    exclusive_ids: Vec<i32>,
+  }
+
+  impl MatchFilter for MockFilter {
+    fn filter_active(&self, matches_ids: &[i32]) -> Vec<i32> {
+      matches_ids.to_vec()
+    }
+
+    fn is_exclusive(&self, match_id: i32) -> bool {
+      self.exclusive_ids.contains(&match_id)
+    }
+  }
+
+  struct MockSelector {
+    offered: RefCell<Vec<i32>>,
+  }
+
+  impl MatchSelector for MockSelector {
+    fn select(&self, matches_ids: &[i32], _: bool) -> Option<i32> {
+      *self.offered.borrow_mut() = matches_ids.to_vec();
+      matches_ids.first().copied()
+    }
+  }
+
+  struct MockSequenceProvider;
+
+  impl EventSequenceProvider for MockSequenceProvider {
+    fn get_next_id(&self) -> u32 {
+      0
+    }
+  }
+
+  fn detected(id: i32, trigger: &str) -> DetectedMatch {
+    DetectedMatch {
+      id,
+      trigger: Some(trigger.to_string()),
+      left_separator: None,
+      right_separator: None,
+      args: HashMap::new(),
+    }
+  }
+
+  fn run(exclusive_ids: Vec<i32>) -> (Event, Vec<i32>) {
+    let filter = MockFilter { exclusive_ids };
+    let selector = MockSelector {
+      offered: RefCell::new(Vec::new()),
+    };
+    let middleware = MatchSelectMiddleware::new(&filter, &selector, &MockSequenceProvider);
+
+    let event = Event::caused_by(
+      0,
+      EventType::MatchesDetected(MatchesDetectedEvent {
+        matches: vec![detected(1, ":a"), detected(2, ":ab")],
+        is_search: false,
+      }),
+    );
+    let result = middleware.next(event, &mut |_| {});
+    let offered = selector.offered.borrow().clone();
+    (result, offered)
+  }
+
+  #[test]
+  fn multiple_candidates_are_offered_to_the_selector() {
+    let (result, offered) = run(Vec::new());
+    assert_eq!(offered, vec![1, 2]);
+    assert!(matches!(result.etype, EventType::MatchSelected(m) if m.chosen.id == 1));
+  }
+
+  #[test]
+  fn exclusive_match_suppresses_other_candidates() {
+    let (result, offered) = run(vec![2]);
+    assert!(offered.is_empty());
+    assert!(matches!(result.etype, EventType::MatchSelected(m) if m.chosen.id == 2));
+  }
+}
@@ -55,6 +55,7 @@ pub struct MatchResult {
 
 pub trait MatcherMiddlewareConfigProvider {
   fn max_history_size(&self) -> usize;
+  fn max_propagation_depth(&self) -> usize;
 }
 
 pub trait ModifierStateProvider {
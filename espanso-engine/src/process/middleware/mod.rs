@@ -28,13 +28,17 @@ pub mod exit;
 pub mod hotkey;
 pub mod icon_status;
 pub mod image_resolve;
+pub mod key_sequence;
 pub mod markdown;
 pub mod match_exec;
 pub mod match_select;
 pub mod matcher;
 pub mod multiplex;
 pub mod notification;
+pub mod propagate;
 pub mod render;
 pub mod search;
+pub mod secure_field;
 pub mod suppress;
 pub mod undo;
+pub mod usage;
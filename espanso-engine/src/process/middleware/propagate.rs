@@ -0,0 +1,295 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::cell::RefCell;
+
+use log::debug;
+
+use super::super::Middleware;
+use crate::event::{
+  input::{Key, KeyboardEvent, Status},
+  internal::TextFormat,
+  Event, EventType, SourceId,
+};
+use crate::process::{EventSequenceProvider, MatchInfoProvider, MatcherMiddlewareConfigProvider};
+
+/// This middleware feeds the output of the matches marked with `propagate`
+/// back to the matchers, so that the injected text can trigger other matches.
+/// To avoid infinite loops, the number of chained expansions is capped by
+/// the `max_propagation_depth` option.
+pub struct PropagateMiddleware<'a> {
+  match_info_provider: &'a dyn MatchInfoProvider,
+  event_sequence_provider: &'a dyn EventSequenceProvider,
+  max_depth: usize,
+  pending: RefCell<Option<PendingPropagation>>,
+  last_batch: RefCell<Option<PropagatedBatch>>,
+}
+
+impl<'a> PropagateMiddleware<'a> {
+  pub fn new(
+    match_info_provider: &'a dyn MatchInfoProvider,
+    options_provider: &'a dyn MatcherMiddlewareConfigProvider,
+    event_sequence_provider: &'a dyn EventSequenceProvider,
+  ) -> Self {
+    Self {
+      match_info_provider,
+      event_sequence_provider,
+      max_depth: options_provider.max_propagation_depth(),
+      pending: RefCell::new(None),
+      last_batch: RefCell::new(None),
+    }
+  }
+
+  fn depth_of(&self, source_id: SourceId) -> usize {
+    match &*self.last_batch.borrow() {
+      Some(batch) if (batch.start_id..=batch.end_id).contains(&source_id) => batch.depth,
+      _ => 0,
+    }
+  }
+}
+
+impl<'a> Middleware for PropagateMiddleware<'a> {
+  fn name(&self) -> &'static str {
+    "propagate"
+  }
+
+  fn next(&self, event: Event, dispatch: &mut dyn FnMut(Event)) -> Event {
+    match &event.etype {
+      EventType::Rendered(m_event)
+        if m_event.format == TextFormat::Plain
+          && self.match_info_provider.should_propagate(m_event.match_id) =>
+      {
+        let depth = self.depth_of(event.source_id);
+        if depth < self.max_depth {
+          *self.pending.borrow_mut() = Some(PendingPropagation {
+            source_id: event.source_id,
+            body: m_event.body.clone(),
+            depth: depth + 1,
+          });
+        } else {
+          debug!(
+            "maximum propagation depth reached for match {}, not propagating",
+            m_event.match_id
+          );
+        }
+      }
+      EventType::MatchInjected => {
+        // We wait for the injection to be requested before feeding the text back,
+        // so that the new events are not discarded along with the previous ones
+        let mut pending = self.pending.borrow_mut();
+        if matches!(&*pending, Some(p) if p.source_id == event.source_id) {
+          let propagation = pending.take().expect("missing pending propagation");
+
+          let mut batch: Option<PropagatedBatch> = None;
+          for c in propagation.body.chars() {
+            let id = self.event_sequence_provider.get_next_id();
+            batch = Some(PropagatedBatch {
+              start_id: batch.map_or(id, |batch| batch.start_id),
+              end_id: id,
+              depth: propagation.depth,
+            });
+
+            dispatch(Event::caused_by(
+              id,
+              EventType::Keyboard(KeyboardEvent {
+                key: Key::Other(0),
+                value: Some(c.to_string()),
+                status: Status::Pressed,
+                variant: None,
+              }),
+            ));
+          }
+
+          if batch.is_some() {
+            *self.last_batch.borrow_mut() = batch;
+          }
+        }
+      }
+      _ => {}
+    }
+
+    event
+  }
+}
+
+struct PendingPropagation {
+  source_id: SourceId,
+  body: String,
+  depth: usize,
+}
+
+struct PropagatedBatch {
+  start_id: SourceId,
+  end_id: SourceId,
+  depth: usize,
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+
+  use super::*;
+  use crate::event::{
+    effect::{MarkdownFlavor, TextInjectMode},
+    internal::RenderedEvent,
+  };
+
+  struct MockMatchInfoProvider;
+
+  impl MatchInfoProvider for MockMatchInfoProvider {
+    fn get_force_mode(&self, _: i32) -> Option<TextInjectMode> {
+      None
+    }
+
+    fn should_propagate(&self, match_id: i32) -> bool {
+      match_id == 1
+    }
+
+    fn should_keep_trigger(&self, _: i32) -> bool {
+      false
+    }
+
+    fn get_markdown_flavor(&self, _: i32) -> MarkdownFlavor {
+      MarkdownFlavor::Standard
+    }
+
+    fn should_inject_newline_as_key(&self, _: i32) -> bool {
+      false
+    }
+
+    fn get_notification(&self, _: i32) -> Option<String> {
+      None
+    }
+  }
+
+  struct MockConfigProvider;
+
+  impl MatcherMiddlewareConfigProvider for MockConfigProvider {
+    fn max_history_size(&self) -> usize {
+      10
+    }
+
+    fn max_propagation_depth(&self) -> usize {
+      2
+    }
+  }
+
+  struct MockSequenceProvider {
+    next_id: Cell<u32>,
+  }
+
+  impl EventSequenceProvider for MockSequenceProvider {
+    fn get_next_id(&self) -> u32 {
+      let id = self.next_id.get();
+      self.next_id.set(id + 1);
+      id
+    }
+  }
+
+  fn rendered(source_id: SourceId, match_id: i32, body: &str) -> Event {
+    Event::caused_by(
+      source_id,
+      EventType::Rendered(RenderedEvent {
+        match_id,
+        body: body.to_string(),
+        format: TextFormat::Plain,
+      }),
+    )
+  }
+
+  // Simulates the injection of a match, returning the propagated events
+  fn inject(middleware: &PropagateMiddleware, event: Event) -> Vec<Event> {
+    let source_id = event.source_id;
+    let mut dispatched = Vec::new();
+    middleware.next(event, &mut |e| dispatched.push(e));
+    middleware.next(
+      Event::caused_by(source_id, EventType::MatchInjected),
+      &mut |e| dispatched.push(e),
+    );
+    dispatched
+  }
+
+  fn chars(events: &[Event]) -> String {
+    events
+      .iter()
+      .map(|event| match &event.etype {
+        EventType::Keyboard(keyboard_event) => keyboard_event.value.clone().unwrap(),
+        _ => panic!("unexpected event: {:?}", event),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn propagated_match_feeds_back_output() {
+    let sequence_provider = MockSequenceProvider {
+      next_id: Cell::new(100),
+    };
+    let middleware = PropagateMiddleware::new(
+      &MockMatchInfoProvider,
+      &MockConfigProvider,
+      &sequence_provider,
+    );
+
+    let events = inject(&middleware, rendered(1, 1, ":b"));
+    assert_eq!(chars(&events), ":b");
+    assert_eq!(events[0].source_id, 100);
+    assert_eq!(events[1].source_id, 101);
+  }
+
+  #[test]
+  fn non_propagated_match_is_ignored() {
+    let sequence_provider = MockSequenceProvider {
+      next_id: Cell::new(100),
+    };
+    let middleware = PropagateMiddleware::new(
+      &MockMatchInfoProvider,
+      &MockConfigProvider,
+      &sequence_provider,
+    );
+
+    assert!(inject(&middleware, rendered(1, 2, ":b")).is_empty());
+  }
+
+  #[test]
+  fn chained_propagation_stops_at_max_depth() {
+    let sequence_provider = MockSequenceProvider {
+      next_id: Cell::new(100),
+    };
+    let middleware = PropagateMiddleware::new(
+      &MockMatchInfoProvider,
+      &MockConfigProvider,
+      &sequence_provider,
+    );
+
+    // The first expansion is propagated
+    let first = inject(&middleware, rendered(1, 1, ":a"));
+    assert_eq!(chars(&first), ":a");
+
+    // The chained expansion is caused by the last propagated event
+    let second = inject(&middleware, rendered(first[1].source_id, 1, ":a"));
+    assert_eq!(chars(&second), ":a");
+
+    // The depth limit has been reached, so the chain stops here
+    let third = inject(&middleware, rendered(second[1].source_id, 1, ":a"));
+    assert!(third.is_empty());
+
+    // A new expansion typed by the user starts a new chain
+    let fourth = inject(&middleware, rendered(500, 1, ":a"));
+    assert_eq!(chars(&fourth), ":a");
+  }
+}
@@ -17,9 +17,13 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::collections::HashMap;
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  time::{Duration, Instant},
+};
 
-use log::error;
+use log::{debug, error, log_enabled, Level};
 
 use super::super::Middleware;
 use crate::event::{effect::TextInjectRequest, internal::RenderedEvent, Event, EventType};
@@ -35,6 +39,11 @@ pub trait Renderer<'a> {
   ) -> Result<String>;
 }
 
+pub trait TransformProvider {
+  // Apply the transforms of the match, in order, to its rendered body
+  fn apply_transforms(&self, match_id: i32, body: String) -> String;
+}
+
 #[derive(Error, Debug)]
 pub enum RendererError {
   #[error("rendering error")]
@@ -47,13 +56,55 @@ pub enum RendererError {
   Aborted,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+  pub count: u32,
+  pub total: Duration,
+  pub max: Duration,
+}
+
 pub struct RenderMiddleware<'a> {
   renderer: &'a dyn Renderer<'a>,
+  transform_provider: &'a dyn TransformProvider,
+  stats: RefCell<HashMap<i32, RenderStats>>,
 }
 
 impl<'a> RenderMiddleware<'a> {
-  pub fn new(renderer: &'a dyn Renderer<'a>) -> Self {
-    Self { renderer }
+  pub fn new(
+    renderer: &'a dyn Renderer<'a>,
+    transform_provider: &'a dyn TransformProvider,
+  ) -> Self {
+    Self {
+      renderer,
+      transform_provider,
+      stats: RefCell::new(HashMap::new()),
+    }
+  }
+
+  #[cfg(test)]
+  fn stats(&self, match_id: i32) -> Option<RenderStats> {
+    self.stats.borrow().get(&match_id).copied()
+  }
+
+  // Keep track of the render latency of each match, useful to spot
+  // slow matches (for example, those using shell or script variables)
+  fn record_render_time(&self, match_id: i32, elapsed: Duration) {
+    let mut stats = self.stats.borrow_mut();
+    let entry = stats.entry(match_id).or_default();
+    entry.count += 1;
+    entry.total += elapsed;
+    entry.max = entry.max.max(elapsed);
+
+    if log_enabled!(Level::Debug) {
+      debug!(
+        "rendered match {} in {:?} (avg: {:?}, max: {:?}, count: {})",
+        match_id,
+        elapsed,
+        entry.total / entry.count,
+        entry.max,
+        entry.count
+      );
+    }
   }
 }
 
@@ -64,12 +115,20 @@ impl<'a> Middleware for RenderMiddleware<'a> {
 
   fn next(&self, event: Event, dispatch: &mut dyn FnMut(Event)) -> Event {
     if let EventType::RenderingRequested(m_event) = event.etype {
-      match self.renderer.render(
+      let start = Instant::now();
+      let result = self.renderer.render(
         m_event.match_id,
         m_event.trigger.as_deref(),
         m_event.trigger_args,
-      ) {
+      );
+      self.record_render_time(m_event.match_id, start.elapsed());
+
+      match result {
         Ok(body) => {
+          // The separator is added afterwards, so that it's not affected
+          let body = self
+            .transform_provider
+            .apply_transforms(m_event.match_id, body);
           let body = if let Some(right_separator) = m_event.right_separator {
             format!("{}{}", body, right_separator)
           } else {
@@ -110,4 +169,92 @@ impl<'a> Middleware for RenderMiddleware<'a> {
   }
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::internal::{RenderingRequestedEvent, TextFormat};
+
+  struct MockRenderer;
+
+  impl<'a> Renderer<'a> for MockRenderer {
+    fn render(
+      &'a self,
+      match_id: i32,
+      _: Option<&str>,
+      _: HashMap<String, String>,
+    ) -> Result<String> {
+      Ok(format!("match {}", match_id))
+    }
+  }
+
+  struct MockTransformProvider {
+    uppercase: bool,
+  }
+
+  impl TransformProvider for MockTransformProvider {
+    fn apply_transforms(&self, _: i32, body: String) -> String {
+      if self.uppercase {
+        body.to_uppercase()
+      } else {
+        body
+      }
+    }
+  }
+
+  fn no_transforms() -> MockTransformProvider {
+    MockTransformProvider { uppercase: false }
+  }
+
+  fn rendering_request(match_id: i32) -> Event {
+    Event::caused_by(
+      0,
+      EventType::RenderingRequested(RenderingRequestedEvent {
+        match_id,
+        trigger: None,
+        left_separator: None,
+        right_separator: None,
+        trigger_args: HashMap::new(),
+        format: TextFormat::Plain,
+      }),
+    )
+  }
+
+  #[test]
+  fn render_time_is_recorded_per_match() {
+    let renderer = MockRenderer;
+    let transform_provider = no_transforms();
+    let middleware = RenderMiddleware::new(&renderer, &transform_provider);
+    assert_eq!(middleware.stats(1), None);
+
+    let start = Instant::now();
+    middleware.next(rendering_request(1), &mut |_| {});
+    middleware.next(rendering_request(1), &mut |_| {});
+    middleware.next(rendering_request(2), &mut |_| {});
+    let elapsed = start.elapsed();
+
+    let stats = middleware.stats(1).unwrap();
+    assert_eq!(stats.count, 2);
+    assert!(stats.max <= stats.total);
+    assert!(stats.total <= elapsed);
+    assert_eq!(middleware.stats(2).unwrap().count, 1);
+    assert_eq!(middleware.stats(3), None);
+  }
+
+  #[test]
+  fn transforms_are_applied_to_the_rendered_body() {
+    let renderer = MockRenderer;
+    let transform_provider = MockTransformProvider { uppercase: true };
+    let middleware = RenderMiddleware::new(&renderer, &transform_provider);
+
+    let mut request = rendering_request(1);
+    if let EventType::RenderingRequested(m_event) = &mut request.etype {
+      m_event.right_separator = Some(" ".to_string());
+    }
+
+    let event = middleware.next(request, &mut |_| {});
+    match event.etype {
+      EventType::Rendered(rendered) => assert_eq!(rendered.body, "MATCH 1 "),
+      other => panic!("unexpected event: {:?}", other),
+    }
+  }
+}
@@ -0,0 +1,126 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use log::warn;
+
+use super::super::Middleware;
+use crate::event::{Event, EventType};
+
+pub trait SecureFieldDetector {
+  // True if the currently focused element is a password (or otherwise secure) field
+  fn is_focused_field_secure(&self) -> bool;
+}
+
+// Drops the matches detected from a typed trigger while a secure field is
+// focused, so that nothing is typed into it. Matches picked from the search
+// bar are explicitly chosen by the user, and are therefore always expanded
+pub struct SecureFieldMiddleware<'a> {
+  detector: &'a dyn SecureFieldDetector,
+}
+
+impl<'a> SecureFieldMiddleware<'a> {
+  pub fn new(detector: &'a dyn SecureFieldDetector) -> Self {
+    Self { detector }
+  }
+}
+
+impl<'a> Middleware for SecureFieldMiddleware<'a> {
+  fn name(&self) -> &'static str {
+    "secure_field"
+  }
+
+  fn next(&self, event: Event, _: &mut dyn FnMut(Event)) -> Event {
+    if let EventType::MatchesDetected(m_event) = &event.etype {
+      let is_trigger_expansion =
+        !m_event.is_search && m_event.matches.iter().any(|m| m.trigger.is_some());
+
+      if is_trigger_expansion && self.detector.is_focused_field_secure() {
+        warn!("the focused field is a secure field, refusing to expand the match");
+        return Event::caused_by(event.source_id, EventType::NOOP);
+      }
+    }
+
+    event
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::internal::{DetectedMatch, MatchesDetectedEvent};
+
+  struct MockDetector {
+    is_secure: bool,
+  }
+
+  impl SecureFieldDetector for MockDetector {
+    fn is_focused_field_secure(&self) -> bool {
+      self.is_secure
+    }
+  }
+
+  fn detected_event(trigger: Option<&str>, is_search: bool) -> Event {
+    Event::caused_by(
+      0,
+      EventType::MatchesDetected(MatchesDetectedEvent {
+        matches: vec![DetectedMatch {
+          id: 1,
+          trigger: trigger.map(String::from),
+          ..Default::default()
+        }],
+        is_search,
+      }),
+    )
+  }
+
+  fn is_blocked(detector: &MockDetector, event: Event) -> bool {
+    let middleware = SecureFieldMiddleware::new(detector);
+    matches!(middleware.next(event, &mut |_| {}).etype, EventType::NOOP)
+  }
+
+  #[test]
+  fn trigger_expansion_is_blocked_in_secure_fields() {
+    let detector = MockDetector { is_secure: true };
+    assert!(is_blocked(&detector, detected_event(Some(":pwd"), false)));
+  }
+
+  #[test]
+  fn trigger_expansion_is_allowed_in_regular_fields() {
+    let detector = MockDetector { is_secure: false };
+    assert!(!is_blocked(&detector, detected_event(Some(":pwd"), false)));
+  }
+
+  #[test]
+  fn search_bar_picks_are_allowed_in_secure_fields() {
+    let detector = MockDetector { is_secure: true };
+    assert!(!is_blocked(&detector, detected_event(None, true)));
+  }
+
+  #[test]
+  fn other_events_are_ignored() {
+    let detector = MockDetector { is_secure: true };
+    let middleware = SecureFieldMiddleware::new(&detector);
+    assert!(matches!(
+      middleware
+        .next(Event::caused_by(0, EventType::Heartbeat), &mut |_| {})
+        .etype,
+      EventType::Heartbeat
+    ));
+  }
+}
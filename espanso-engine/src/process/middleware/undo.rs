@@ -19,6 +19,8 @@
 
 use std::cell::RefCell;
 
+use log::warn;
+
 use super::super::Middleware;
 use crate::event::{
   input::{Key, Status},
@@ -28,6 +30,7 @@ use crate::event::{
 
 pub trait UndoEnabledProvider {
   fn is_undo_enabled(&self) -> bool;
+  fn max_backspaces_per_expansion(&self) -> usize;
 }
 
 pub struct UndoMiddleware<'a> {
@@ -75,6 +78,17 @@ impl<'a> Middleware for UndoMiddleware<'a> {
               (record.trigger, record.injected_text, record.match_id)
             {
               if self.undo_enabled_provider.is_undo_enabled() {
+                // The backspace that triggered the undo already removed the last char
+                let backspace_count = injected_text.chars().count().saturating_sub(1);
+                let max_backspaces = self.undo_enabled_provider.max_backspaces_per_expansion();
+                if backspace_count > max_backspaces {
+                  warn!(
+                    "undo refused, as it would inject {} backspaces, exceeding the max_backspaces_per_expansion limit of {}",
+                    backspace_count, max_backspaces
+                  );
+                  return event;
+                }
+
                 return Event::caused_by(
                   event.source_id,
                   EventType::Undo(UndoEvent {
@@ -111,4 +125,108 @@ struct InjectionRecord {
   injected_text: Option<String>,
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::{
+    effect::TriggerCompensationEvent, input::KeyboardEvent, internal::RenderedEvent,
+  };
+
+  struct MockUndoEnabledProvider {
+    enabled: bool,
+    max_backspaces: usize,
+  }
+
+  impl UndoEnabledProvider for MockUndoEnabledProvider {
+    fn is_undo_enabled(&self) -> bool {
+      self.enabled
+    }
+
+    fn max_backspaces_per_expansion(&self) -> usize {
+      self.max_backspaces
+    }
+  }
+
+  fn run(middleware: &UndoMiddleware, event: Event) -> Event {
+    middleware.next(event, &mut |_| {})
+  }
+
+  fn expand_then_backspace(middleware: &UndoMiddleware, body: &str) -> Event {
+    run(
+      middleware,
+      Event::caused_by(
+        1,
+        EventType::TriggerCompensation(TriggerCompensationEvent {
+          trigger: ":hi".to_string(),
+          left_separator: None,
+        }),
+      ),
+    );
+    run(
+      middleware,
+      Event::caused_by(
+        1,
+        EventType::Rendered(RenderedEvent {
+          match_id: 7,
+          body: body.to_string(),
+          format: TextFormat::Plain,
+        }),
+      ),
+    );
+    run(
+      middleware,
+      Event::caused_by(
+        2,
+        EventType::Keyboard(KeyboardEvent {
+          key: Key::Backspace,
+          value: None,
+          status: Status::Pressed,
+          variant: None,
+        }),
+      ),
+    )
+  }
+
+  #[test]
+  fn backspace_after_expansion_triggers_undo() {
+    let provider = MockUndoEnabledProvider {
+      enabled: true,
+      max_backspaces: 100,
+    };
+    let middleware = UndoMiddleware::new(&provider);
+
+    let event = expand_then_backspace(&middleware, "hello");
+    match event.etype {
+      EventType::Undo(undo) => {
+        assert_eq!(undo.match_id, 7);
+        assert_eq!(undo.trigger, ":hi");
+        assert_eq!(undo.replace, "hello");
+      }
+      other => panic!("expected undo event, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn undo_is_not_triggered_when_disabled() {
+    let provider = MockUndoEnabledProvider {
+      enabled: false,
+      max_backspaces: 100,
+    };
+    let middleware = UndoMiddleware::new(&provider);
+
+    let event = expand_then_backspace(&middleware, "hello");
+    assert!(matches!(event.etype, EventType::Keyboard(_)));
+  }
+
+  #[test]
+  fn undo_is_refused_when_exceeding_max_backspaces() {
+    let provider = MockUndoEnabledProvider {
+      enabled: true,
+      max_backspaces: 3,
+    };
+    let middleware = UndoMiddleware::new(&provider);
+
+    let event = expand_then_backspace(&middleware, "hello");
+    assert!(matches!(event.etype, EventType::Keyboard(_)));
+  }
+}
@@ -0,0 +1,96 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::super::Middleware;
+use crate::event::{Event, EventType};
+
+pub trait MatchUsageRecorder {
+  fn record_usage(&self, match_id: i32);
+}
+
+pub struct UsageMiddleware<'a> {
+  usage_recorders: &'a [&'a dyn MatchUsageRecorder],
+}
+
+impl<'a> UsageMiddleware<'a> {
+  pub fn new(usage_recorders: &'a [&'a dyn MatchUsageRecorder]) -> Self {
+    Self { usage_recorders }
+  }
+}
+
+impl<'a> Middleware for UsageMiddleware<'a> {
+  fn name(&self) -> &'static str {
+    "usage"
+  }
+
+  fn next(&self, event: Event, _: &mut dyn FnMut(Event)) -> Event {
+    if let EventType::Rendered(m_event) = &event.etype {
+      for usage_recorder in self.usage_recorders {
+        usage_recorder.record_usage(m_event.match_id);
+      }
+    }
+
+    event
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::event::internal::{RenderedEvent, TextFormat};
+  use std::cell::RefCell;
+
+  struct MockUsageRecorder {
+    recorded: RefCell<Vec<i32>>,
+  }
+
+  impl MatchUsageRecorder for MockUsageRecorder {
+    fn record_usage(&self, match_id: i32) {
+      self.recorded.borrow_mut().push(match_id);
+    }
+  }
+
+  #[test]
+  fn rendered_matches_are_recorded() {
+    let recorder = MockUsageRecorder {
+      recorded: RefCell::new(Vec::new()),
+    };
+    let other_recorder = MockUsageRecorder {
+      recorded: RefCell::new(Vec::new()),
+    };
+    let recorders: Vec<&dyn MatchUsageRecorder> = vec![&recorder, &other_recorder];
+    let middleware = UsageMiddleware::new(&recorders);
+
+    middleware.next(
+      Event::caused_by(
+        0,
+        EventType::Rendered(RenderedEvent {
+          match_id: 42,
+          body: "body".to_string(),
+          format: TextFormat::Plain,
+        }),
+      ),
+      &mut |_| {},
+    );
+    middleware.next(Event::caused_by(0, EventType::NOOP), &mut |_| {});
+
+    assert_eq!(*recorder.recorded.borrow(), vec![42]);
+    assert_eq!(*other_recorder.recorded.borrow(), vec![42]);
+  }
+}
@@ -33,22 +33,25 @@ pub trait Processor {
 
 // Dependency inversion entities
 
-pub use middleware::action::{EventSequenceProvider, MatchInfoProvider};
+pub use middleware::action::{EventSequenceProvider, ExpansionSoundProvider, MatchInfoProvider};
 pub use middleware::delay_modifiers::ModifierStatusProvider;
 pub use middleware::disable::DisableOptions;
 pub use middleware::image_resolve::PathProvider;
+pub use middleware::key_sequence::KeySequence;
 pub use middleware::match_exec::MatchResolver;
-pub use middleware::match_select::{MatchFilter, MatchSelector};
+pub use middleware::match_select::{MatchFilter, MatchSelectMiddleware, MatchSelector};
 pub use middleware::matcher::{
   MatchResult, Matcher, MatcherEvent, MatcherMiddlewareConfigProvider, ModifierState,
   ModifierStateProvider,
 };
 pub use middleware::multiplex::Multiplexer;
 pub use middleware::notification::NotificationManager;
-pub use middleware::render::{Renderer, RendererError};
+pub use middleware::render::{Renderer, RendererError, TransformProvider};
 pub use middleware::search::MatchProvider;
+pub use middleware::secure_field::SecureFieldDetector;
 pub use middleware::suppress::EnabledStatusProvider;
 pub use middleware::undo::UndoEnabledProvider;
+pub use middleware::usage::MatchUsageRecorder;
 
 #[allow(clippy::too_many_arguments)]
 pub fn default<'a, MatcherState>(
@@ -62,6 +65,7 @@ pub fn default<'a, MatcherState>(
   event_sequence_provider: &'a dyn EventSequenceProvider,
   path_provider: &'a dyn PathProvider,
   disable_options: DisableOptions,
+  key_sequences: Vec<KeySequence>,
   matcher_options_provider: &'a dyn MatcherMiddlewareConfigProvider,
   match_provider: &'a dyn MatchProvider,
   undo_enabled_provider: &'a dyn UndoEnabledProvider,
@@ -69,6 +73,10 @@ pub fn default<'a, MatcherState>(
   modifier_state_provider: &'a dyn ModifierStateProvider,
   match_resolver: &'a dyn MatchResolver,
   notification_manager: &'a dyn NotificationManager,
+  usage_recorders: &'a [&'a dyn MatchUsageRecorder],
+  expansion_sound_provider: &'a dyn ExpansionSoundProvider,
+  transform_provider: &'a dyn TransformProvider,
+  secure_field_detector: &'a dyn SecureFieldDetector,
 ) -> impl Processor + 'a {
   default::DefaultProcessor::new(
     matchers,
@@ -81,6 +89,7 @@ pub fn default<'a, MatcherState>(
     event_sequence_provider,
     path_provider,
     disable_options,
+    key_sequences,
     matcher_options_provider,
     match_provider,
     undo_enabled_provider,
@@ -88,5 +97,9 @@ pub fn default<'a, MatcherState>(
     modifier_state_provider,
     match_resolver,
     notification_manager,
+    usage_recorders,
+    expansion_sound_provider,
+    transform_provider,
+    secure_field_detector,
   )
 }
@@ -29,6 +29,8 @@ fn cc_config() {
 
   println!("cargo:rustc-link-lib=static=espansoinfo");
   println!("cargo:rustc-link-lib=dylib=user32");
+  println!("cargo:rustc-link-lib=dylib=ole32");
+  println!("cargo:rustc-link-lib=dylib=oleaut32");
   #[cfg(target_env = "gnu")]
   println!("cargo:rustc-link-lib=dylib=stdc++");
 }
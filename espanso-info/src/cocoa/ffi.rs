@@ -25,4 +25,6 @@ extern "C" {
   pub fn info_get_title_fallback(buffer: *mut c_char, buffer_size: i32) -> i32;
   pub fn info_get_exec(buffer: *mut c_char, buffer_size: i32) -> i32;
   pub fn info_get_class(buffer: *mut c_char, buffer_size: i32) -> i32;
+  pub fn info_get_focused_field_role(buffer: *mut c_char, buffer_size: i32) -> i32;
+  pub fn info_is_focused_field_secure() -> i32;
 }
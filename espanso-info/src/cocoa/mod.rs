@@ -19,9 +19,12 @@
 
 use std::{ffi::CStr, os::raw::c_char};
 
-use crate::{AppInfo, AppInfoProvider};
+use crate::{AppInfo, AppInfoProvider, FocusedFieldProvider};
 
-use self::ffi::{info_get_class, info_get_exec, info_get_title, info_get_title_fallback};
+use self::ffi::{
+  info_get_class, info_get_exec, info_get_focused_field_role, info_get_title,
+  info_get_title_fallback, info_is_focused_field_secure,
+};
 
 mod ffi;
 
@@ -105,3 +108,35 @@ impl CocoaAppInfoProvider {
     }
   }
 }
+
+pub struct CocoaFocusedFieldProvider {}
+
+impl CocoaFocusedFieldProvider {
+  pub fn new() -> Self {
+    Self {}
+  }
+}
+
+// The role is reported as "AXRole:AXDescription", for example
+// "AXTextField:Address and search bar", using the Accessibility API
+impl FocusedFieldProvider for CocoaFocusedFieldProvider {
+  fn get_focused_field_role(&self) -> Option<String> {
+    let mut buffer: [c_char; 2048] = [0; 2048];
+    if unsafe { info_get_focused_field_role(buffer.as_mut_ptr(), (buffer.len() - 1) as i32) } > 0 {
+      let string = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+      let string = string.to_string_lossy();
+      if !string.is_empty() {
+        Some(string.to_string())
+      } else {
+        None
+      }
+    } else {
+      None
+    }
+  }
+
+  // Password fields have the AXSecureTextField role (or subrole)
+  fn is_focused_field_secure(&self) -> bool {
+    unsafe { info_is_focused_field_secure() > 0 }
+  }
+}
@@ -26,5 +26,7 @@ extern "C" int32_t info_get_title(char * buffer, int32_t buffer_size);
 extern "C" int32_t info_get_title_fallback(char * buffer, int32_t buffer_size);
 extern "C" int32_t info_get_exec(char * buffer, int32_t buffer_size);
 extern "C" int32_t info_get_class(char * buffer, int32_t buffer_size);
+extern "C" int32_t info_get_focused_field_role(char * buffer, int32_t buffer_size);
+extern "C" int32_t info_is_focused_field_secure();
 
 #endif //ESPANSO_INFO_H
\ No newline at end of file
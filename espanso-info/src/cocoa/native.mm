@@ -120,4 +120,95 @@ int32_t info_get_class(char *buffer, int32_t buffer_size)
   }
 
   return 1;
-}
\ No newline at end of file
+}
+// Returns the focused UI element of the frontmost application, which must
+// be released by the caller, or NULL if it can't be determined
+static AXUIElementRef copy_focused_element()
+{
+  NSRunningApplication* app = [[NSWorkspace sharedWorkspace] frontmostApplication];
+  pid_t pid = [app processIdentifier];
+
+  AXUIElementRef appElem = AXUIElementCreateApplication(pid);
+  if (!appElem) {
+    return NULL;
+  }
+
+  AXUIElementRef focused = NULL;
+  if (AXUIElementCopyAttributeValue(appElem,
+        kAXFocusedUIElementAttribute, (CFTypeRef*)&focused) != kAXErrorSuccess) {
+    focused = NULL;
+  }
+  CFRelease(appElem);
+
+  return focused;
+}
+
+// Returns the role of the focused UI element, followed by its description
+// (if any), which usually identifies special fields such as address bars
+int32_t info_get_focused_field_role(char *buffer, int32_t buffer_size)
+{
+  @autoreleasepool {
+    AXUIElementRef focused = copy_focused_element();
+    if (!focused) {
+      return -1;
+    }
+
+    CFStringRef role = NULL;
+    if (AXUIElementCopyAttributeValue(focused, kAXRoleAttribute,
+          (CFTypeRef*)&role) != kAXErrorSuccess) {
+      CFRelease(focused);
+      return -3;
+    }
+
+    CFStringRef description = NULL;
+    AXError descriptionResult = AXUIElementCopyAttributeValue(focused, kAXDescriptionAttribute,
+                                  (CFTypeRef*)&description);
+    CFRelease(focused);
+
+    NSString *fieldRole = (__bridge NSString *) role;
+    if (descriptionResult == kAXErrorSuccess && description) {
+      fieldRole = [NSString stringWithFormat:@"%@:%@", fieldRole, (__bridge NSString *) description];
+    }
+
+    snprintf(buffer, buffer_size, "%s", [fieldRole UTF8String]);
+
+    CFRelease(role);
+    if (description) {
+      CFRelease(description);
+    }
+  }
+
+  return 1;
+}
+
+// Returns 1 if the focused UI element is a password field, 0 if it's not
+// and a negative value if it can't be determined
+int32_t info_is_focused_field_secure()
+{
+  int32_t result = 0;
+
+  @autoreleasepool {
+    AXUIElementRef focused = copy_focused_element();
+    if (!focused) {
+      return -1;
+    }
+
+    // Password fields are reported either with the AXSecureTextField
+    // subrole of AXTextField, or (in some applications) as the role itself
+    CFStringRef attributes[] = { kAXRoleAttribute, kAXSubroleAttribute };
+    for (CFStringRef attribute : attributes) {
+      CFStringRef value = NULL;
+      if (AXUIElementCopyAttributeValue(focused, attribute,
+            (CFTypeRef*)&value) == kAXErrorSuccess && value) {
+        if (CFStringCompare(value, kAXSecureTextFieldSubrole, 0) == kCFCompareEqualTo) {
+          result = 1;
+        }
+        CFRelease(value);
+      }
+    }
+
+    CFRelease(focused);
+  }
+
+  return result;
+}
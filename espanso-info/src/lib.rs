@@ -38,6 +38,30 @@ pub trait AppInfoProvider {
   fn get_info(&self) -> AppInfo;
 }
 
+// Describes the text field that currently has focus, such as a browser
+// address bar. The returned role is platform-specific, and None means that
+// the platform (or the focused application) doesn't expose it.
+pub trait FocusedFieldProvider {
+  fn get_focused_field_role(&self) -> Option<String>;
+
+  // True if the focused field is a password field. Platforms (and applications)
+  // that don't expose this information always report a regular field.
+  fn is_focused_field_secure(&self) -> bool;
+}
+
+// Used on the platforms without a way to detect the focused field
+pub struct NoopFocusedFieldProvider {}
+
+impl FocusedFieldProvider for NoopFocusedFieldProvider {
+  fn get_focused_field_role(&self) -> Option<String> {
+    None
+  }
+
+  fn is_focused_field_secure(&self) -> bool {
+    false
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppInfo {
   pub title: Option<String>,
@@ -70,3 +94,19 @@ pub fn get_provider() -> Result<Box<dyn AppInfoProvider>> {
   info!("using WaylandAppInfoProvider");
   Ok(Box::new(wayland::WaylandAppInfoProvider::new()))
 }
+
+#[cfg(target_os = "macos")]
+pub fn get_focused_field_provider() -> Box<dyn FocusedFieldProvider> {
+  Box::new(cocoa::CocoaFocusedFieldProvider::new())
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_focused_field_provider() -> Box<dyn FocusedFieldProvider> {
+  Box::new(win32::WinFocusedFieldProvider::new())
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_focused_field_provider() -> Box<dyn FocusedFieldProvider> {
+  info!("focused field detection is not supported on this platform");
+  Box::new(NoopFocusedFieldProvider {})
+}
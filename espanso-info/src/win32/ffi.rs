@@ -21,4 +21,5 @@
 extern "C" {
   pub fn info_get_title(buffer: *mut u16, buffer_size: i32) -> i32;
   pub fn info_get_exec(buffer: *mut u16, buffer_size: i32) -> i32;
+  pub fn info_is_focused_field_password() -> i32;
 }
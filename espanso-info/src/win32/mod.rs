@@ -19,9 +19,9 @@
 
 use widestring::U16CStr;
 
-use crate::{AppInfo, AppInfoProvider};
+use crate::{AppInfo, AppInfoProvider, FocusedFieldProvider};
 
-use self::ffi::{info_get_exec, info_get_title};
+use self::ffi::{info_get_exec, info_get_title, info_is_focused_field_password};
 
 mod ffi;
 
@@ -74,3 +74,22 @@ impl WinAppInfoProvider {
     }
   }
 }
+
+pub struct WinFocusedFieldProvider {}
+
+impl WinFocusedFieldProvider {
+  pub fn new() -> Self {
+    Self {}
+  }
+}
+
+// Password fields are detected with the IsPassword property of UI Automation
+impl FocusedFieldProvider for WinFocusedFieldProvider {
+  fn get_focused_field_role(&self) -> Option<String> {
+    None
+  }
+
+  fn is_focused_field_secure(&self) -> bool {
+    unsafe { info_is_focused_field_password() > 0 }
+  }
+}
@@ -39,6 +39,7 @@
 #include <strsafe.h>
 
 #include <Windows.h>
+#include <UIAutomation.h>
 
 int32_t info_get_title(wchar_t *buffer, int32_t buffer_size)
 {
@@ -62,4 +63,50 @@ int32_t info_get_exec(wchar_t *buffer, int32_t buffer_size)
   CloseHandle(process);
 
   return res;
-}
\ No newline at end of file
+}
+
+// The UI Automation instance is created once and reused for the lifetime of the process.
+// COM is initialized in the multithreaded apartment, so the instance can be used
+// from any thread. NULL if the instance can't be created
+static IUIAutomation *get_automation()
+{
+  static IUIAutomation *automation = []() -> IUIAutomation * {
+    CoInitializeEx(NULL, COINIT_MULTITHREADED);
+
+    IUIAutomation *instance = NULL;
+    HRESULT hr = CoCreateInstance(__uuidof(CUIAutomation), NULL, CLSCTX_INPROC_SERVER,
+                                  __uuidof(IUIAutomation), (void **)&instance);
+    if (FAILED(hr))
+    {
+      return NULL;
+    }
+    return instance;
+  }();
+
+  return automation;
+}
+
+// Returns 1 if the focused element is a password field, 0 if it's not
+// and a negative value if it can't be determined
+int32_t info_is_focused_field_password()
+{
+  IUIAutomation *automation = get_automation();
+  if (!automation)
+  {
+    return -1;
+  }
+
+  int32_t result = -2;
+  IUIAutomationElement *focused = NULL;
+  if (SUCCEEDED(automation->GetFocusedElement(&focused)) && focused)
+  {
+    BOOL isPassword = FALSE;
+    if (SUCCEEDED(focused->get_CurrentIsPassword(&isPassword)))
+    {
+      result = isPassword ? 1 : 0;
+    }
+    focused->Release();
+  }
+
+  return result;
+}
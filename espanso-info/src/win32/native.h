@@ -24,5 +24,6 @@
 
 extern "C" int32_t info_get_title(wchar_t * buffer, int32_t buffer_size);
 extern "C" int32_t info_get_exec(wchar_t * buffer, int32_t buffer_size);
+extern "C" int32_t info_is_focused_field_password();
 
 #endif //ESPANSO_INFO_H
\ No newline at end of file
@@ -317,6 +317,16 @@ impl Injector for EVDEVInjector {
     Ok(())
   }
 
+  fn can_type_char(&self, c: char) -> bool {
+    self.char_map.contains_key(&c.to_string())
+  }
+
+  // The uinput device can only emit key codes, so there is no way to
+  // produce a char that is not part of the keymap
+  fn send_unicode_string(&self, string: &str, _: InjectionOptions) -> Result<()> {
+    Err(EVDEVInjectorError::UnicodeInjectionUnsupported(string.to_string()).into())
+  }
+
   fn send_keys(&self, keys: &[keys::Key], options: InjectionOptions) -> Result<()> {
     // Compute all the key record sequence first to make sure a mapping is available
     let syms = convert_to_sym_array(keys)?;
@@ -382,4 +392,7 @@ pub enum EVDEVInjectorError {
 
   #[error("missing record mapping for sym `{0}`")]
   SymMappingFailure(u32),
+
+  #[error("unicode injection is not supported, unable to inject `{0}`")]
+  UnicodeInjectionUnsupported(String),
 }
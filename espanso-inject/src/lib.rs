@@ -45,6 +45,18 @@ pub trait Injector {
   fn send_string(&self, string: &str, options: InjectionOptions) -> Result<()>;
   fn send_keys(&self, keys: &[keys::Key], options: InjectionOptions) -> Result<()>;
   fn send_key_combination(&self, keys: &[keys::Key], options: InjectionOptions) -> Result<()>;
+
+  // Check whether the given char can be produced with the current keyboard layout.
+  // Backends that inject text through unicode events can produce any char.
+  fn can_type_char(&self, _c: char) -> bool {
+    true
+  }
+
+  // Inject the given string bypassing the keyboard layout, used for chars
+  // that can't be produced by it.
+  fn send_unicode_string(&self, string: &str, options: InjectionOptions) -> Result<()> {
+    self.send_string(string, options)
+  }
 }
 
 #[allow(dead_code)]
@@ -82,4 +82,23 @@ extern "C" {
   ) -> c_int;
   pub fn XSync(display: *mut Display, discard: c_int) -> c_int;
   pub fn XQueryKeymap(display: *mut Display, keys_return: *mut u8);
+  pub fn XDisplayKeycodes(
+    display: *mut Display,
+    min_keycodes_return: *mut c_int,
+    max_keycodes_return: *mut c_int,
+  ) -> c_int;
+  pub fn XGetKeyboardMapping(
+    display: *mut Display,
+    first_keycode: KeyCode,
+    keycode_count: c_int,
+    keysyms_per_keycode_return: *mut c_int,
+  ) -> *mut KeySym;
+  pub fn XChangeKeyboardMapping(
+    display: *mut Display,
+    first_keycode: c_int,
+    keysyms_per_keycode: c_int,
+    keysyms: *const KeySym,
+    num_codes: c_int,
+  ) -> c_int;
+  pub fn XFree(data: *mut c_void) -> c_int;
 }
@@ -27,9 +27,10 @@ use std::{
 };
 
 use ffi::{
-  Display, KeyCode, KeyPress, KeyRelease, KeySym, Window, XCloseDisplay, XDefaultRootWindow,
-  XFlush, XFreeModifiermap, XGetInputFocus, XGetModifierMapping, XKeyEvent, XLookupString,
-  XQueryKeymap, XSendEvent, XSync, XTestFakeKeyEvent,
+  Display, KeyCode, KeyPress, KeyRelease, KeySym, Window, XChangeKeyboardMapping, XCloseDisplay,
+  XDefaultRootWindow, XDisplayKeycodes, XFlush, XFree, XFreeModifiermap, XGetInputFocus,
+  XGetKeyboardMapping, XGetModifierMapping, XKeyEvent, XLookupString, XQueryKeymap, XSendEvent,
+  XSync, XTestFakeKeyEvent,
 };
 use log::error;
 
@@ -289,6 +290,50 @@ impl X11Injector {
     }
   }
 
+  // Find a keycode without any keysym assigned, which can be temporarily
+  // remapped to inject chars that are not available in the current layout
+  fn find_unused_keycode(&self) -> Option<u32> {
+    let mut min_keycode = 0;
+    let mut max_keycode = 0;
+    unsafe {
+      XDisplayKeycodes(self.display, &mut min_keycode, &mut max_keycode);
+    }
+
+    let keycode_count = max_keycode - min_keycode + 1;
+    let mut keysyms_per_keycode = 0;
+    let keysyms = unsafe {
+      XGetKeyboardMapping(
+        self.display,
+        min_keycode as KeyCode,
+        keycode_count,
+        &mut keysyms_per_keycode,
+      )
+    };
+    if keysyms.is_null() {
+      return None;
+    }
+
+    let mapping =
+      unsafe { slice::from_raw_parts(keysyms, (keycode_count * keysyms_per_keycode) as usize) };
+    let unused_keycode = mapping
+      .chunks(keysyms_per_keycode as usize)
+      .position(|syms| syms.iter().all(|sym| *sym == 0))
+      .map(|index| (min_keycode + index as i32) as u32);
+
+    unsafe {
+      XFree(keysyms as *mut _);
+    }
+
+    unused_keycode
+  }
+
+  fn remap_keycode(&self, keycode: u32, sym: KeySym) {
+    unsafe {
+      XChangeKeyboardMapping(self.display, keycode as i32, 1, &sym, 1);
+      XSync(self.display, 0);
+    }
+  }
+
   fn xtest_release_all_keys(&self) {
     let mut keys: [u8; 32] = [0; 32];
     unsafe {
@@ -356,6 +401,35 @@ impl Injector for X11Injector {
     Ok(())
   }
 
+  fn can_type_char(&self, c: char) -> bool {
+    self.char_map.contains_key(&c.to_string())
+  }
+
+  fn send_unicode_string(&self, string: &str, options: InjectionOptions) -> Result<()> {
+    let keycode = self
+      .find_unused_keycode()
+      .ok_or(X11InjectorError::NoUnusedKeycode())?;
+
+    let delay_us = options.delay as u32 * 1000; // Convert to micro seconds
+    let record = KeyRecord {
+      code: keycode,
+      state: 0,
+    };
+
+    // The char is injected by temporarily binding its keysym to an unused keycode.
+    // XTest is needed here, as clients might not have processed the new mapping
+    // when receiving a synthetic event.
+    for c in string.chars() {
+      self.remap_keycode(keycode, convert_char_to_keysym(c));
+      self.xtest_send_key(&record, true, delay_us);
+      self.xtest_send_key(&record, false, delay_us);
+    }
+
+    self.remap_keycode(keycode, 0);
+
+    Ok(())
+  }
+
   fn send_keys(&self, keys: &[keys::Key], options: InjectionOptions) -> Result<()> {
     let focused_window = self.get_focused_window();
 
@@ -420,6 +494,17 @@ impl Injector for X11Injector {
   }
 }
 
+// Latin-1 chars have the same keysym as their code point, while all the other
+// unicode chars are mapped to the 0x01000000 keysym range
+fn convert_char_to_keysym(c: char) -> KeySym {
+  let code_point = c as KeySym;
+  if code_point < 0x100 {
+    code_point
+  } else {
+    0x0100_0000 + code_point
+  }
+}
+
 #[derive(Error, Debug)]
 pub enum X11InjectorError {
   #[error("failed to initialize x11 display")]
@@ -430,4 +515,7 @@ pub enum X11InjectorError {
 
   #[error("missing record mapping for sym `{0}`")]
   SymMapping(u64),
+
+  #[error("no unused keycode available to inject unicode chars")]
+  NoUnusedKeycode(),
 }
@@ -29,6 +29,9 @@ use crate::{event::Event, MatchResult};
 pub struct RegexMatch<Id> {
   pub id: Id,
   pub regex: String,
+
+  // If specified, the regex is only evaluated against the last N chars of the buffer
+  pub max_lookback: Option<usize>,
 }
 
 impl<Id> RegexMatch<Id> {
@@ -36,8 +39,19 @@ impl<Id> RegexMatch<Id> {
     Self {
       id,
       regex: regex.to_string(),
+      max_lookback: None,
     }
   }
+
+  pub fn with_max_lookback(mut self, max_lookback: Option<usize>) -> Self {
+    self.max_lookback = max_lookback;
+    self
+  }
+
+  // Compiles the regex as done by the matcher, returning the error if it's invalid
+  pub fn validate(&self) -> Result<(), regex::Error> {
+    Regex::new(&self.regex).map(|_| ())
+  }
 }
 
 #[derive(Clone)]
@@ -72,6 +86,7 @@ pub struct RegexMatcher<Id> {
 
   // The single regexes are then used to find the captures
   regexes: Vec<Regex>,
+  max_lookbacks: Vec<Option<usize>>,
 
   max_buffer_size: usize,
 }
@@ -110,7 +125,12 @@ where
 
       for index in self.regex_set.matches(&buffer) {
         if let (Some(id), Some(regex)) = (self.ids.get(index), self.regexes.get(index)) {
-          if let Some(captures) = regex.captures(&buffer) {
+          let haystack = match self.max_lookbacks.get(index).copied().flatten() {
+            Some(max_lookback) => last_chars(&buffer, max_lookback),
+            None => &buffer,
+          };
+
+          if let Some(captures) = regex.captures(haystack) {
             let full_match = captures.get(0).map_or("", |m| m.as_str());
             if !full_match.is_empty() {
               // Now extract the captured names as variables
@@ -153,6 +173,7 @@ impl<Id: Clone> RegexMatcher<Id> {
   pub fn new(matches: &[RegexMatch<Id>], opt: RegexMatcherOptions) -> Self {
     let mut ids = Vec::new();
     let mut regexes = Vec::new();
+    let mut max_lookbacks = Vec::new();
     let mut good_regexes = Vec::new();
 
     for m in matches {
@@ -161,6 +182,7 @@ impl<Id: Clone> RegexMatcher<Id> {
           ids.push(m.id.clone());
           good_regexes.push(&m.regex);
           regexes.push(regex);
+          max_lookbacks.push(m.max_lookback);
         }
         Err(err) => {
           error!("unable to compile regex: '{}', error: {:?}", m.regex, err);
@@ -174,11 +196,23 @@ impl<Id: Clone> RegexMatcher<Id> {
       ids,
       regex_set,
       regexes,
+      max_lookbacks,
       max_buffer_size: opt.max_buffer_size,
     }
   }
 }
 
+fn last_chars(text: &str, count: usize) -> &str {
+  if count == 0 {
+    return "";
+  }
+
+  match text.char_indices().rev().nth(count - 1) {
+    Some((index, _)) => &text[index..],
+    None => text,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -273,4 +307,32 @@ mod tests {
       vec![]
     );
   }
+
+  #[test]
+  fn matcher_max_lookback() {
+    let matcher = RegexMatcher::new(
+      &[RegexMatch::new(1, "hello\\((?P<name>.*?)\\)").with_max_lookback(Some(10))],
+      RegexMatcherOptions::default(),
+    );
+    assert_eq!(
+      get_matches_after_str("say hello(bob)", &matcher),
+      vec![match_result(1, "hello(bob)", &[("name", "bob")])]
+    );
+    assert_eq!(get_matches_after_str("say hello(mary)", &matcher), vec![]);
+  }
+
+  #[test]
+  fn validate_reports_invalid_regexes() {
+    assert!(RegexMatch::new(1, "hello\\((?P<name>.*?)\\)")
+      .validate()
+      .is_ok());
+    assert!(RegexMatch::new(1, "hello(").validate().is_err());
+  }
+
+  #[test]
+  fn last_chars_handles_multibyte_chars() {
+    assert_eq!(last_chars("héllo", 4), "éllo");
+    assert_eq!(last_chars("héllo", 10), "héllo");
+    assert_eq!(last_chars("héllo", 0), "");
+  }
 }
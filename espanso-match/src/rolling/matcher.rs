@@ -68,12 +68,18 @@ pub struct RollingMatcher<Id> {
   char_word_separators: Vec<String>,
   key_word_separators: Vec<Key>,
 
+  // Matches defining their own char word separators. While walking the tree,
+  // the union of all the separators is used, and the ones of the specific
+  // match are then checked once it's found
+  word_separator_overrides: Vec<(Id, Vec<String>)>,
+  all_char_word_separators: Vec<String>,
+
   root: MatcherTreeNode<Id>,
 }
 
 impl<'a, Id> Matcher<'a, RollingMatcherState<'a, Id>, Id> for RollingMatcher<Id>
 where
-  Id: Clone,
+  Id: Clone + PartialEq,
 {
   fn process(
     &'a self,
@@ -112,8 +118,9 @@ where
       match node_ref {
         MatcherTreeRef::Matches(matches) => {
           let (trigger, left_separator, right_separator) = extract_string_from_events(&events);
-          let results = matches
+          let results: Vec<MatchResult<Id>> = matches
             .iter()
+            .filter(|id| self.accepts_separators(id, &events))
             .map(|id| MatchResult {
               id: id.clone(),
               trigger: trigger.clone(),
@@ -122,6 +129,9 @@ where
               vars: HashMap::new(),
             })
             .collect();
+          if results.is_empty() {
+            continue;
+          }
 
           // Reset the state and return the matches
           return (RollingMatcherState::default(), results);
@@ -141,13 +151,33 @@ where
   }
 }
 
-impl<Id: Clone> RollingMatcher<Id> {
+impl<Id: Clone + PartialEq> RollingMatcher<Id> {
   pub fn new(matches: &[RollingMatch<Id>], opt: RollingMatcherOptions) -> Self {
     let root = MatcherTreeNode::from_matches(matches);
+
+    let word_separator_overrides: Vec<(Id, Vec<String>)> = matches
+      .iter()
+      .filter_map(|m| {
+        m.word_separators
+          .as_ref()
+          .map(|separators| (m.id.clone(), separators.clone()))
+      })
+      .collect();
+    let mut all_char_word_separators = opt.char_word_separators.clone();
+    for (_, separators) in word_separator_overrides.iter() {
+      for separator in separators {
+        if !all_char_word_separators.contains(separator) {
+          all_char_word_separators.push(separator.clone());
+        }
+      }
+    }
+
     Self {
       root,
       char_word_separators: opt.char_word_separators,
       key_word_separators: opt.key_word_separators,
+      word_separator_overrides,
+      all_char_word_separators,
     }
   }
 
@@ -189,24 +219,38 @@ impl<Id: Clone> RollingMatcher<Id> {
       }
     }
 
-    // If there is no previous state, we handle it as a word separator, exploring a step forward
-    // in the state.
+    // Line breaks are reported as separators, so that they are not deleted along with the trigger
+    if is_line_break(event) {
+      if let Some(node_ref) = node.line_starts.as_ref() {
+        refs.push((node_ref, true))
+      }
+    }
+
+    // If there is no previous state, we handle it as a word separator (and as the
+    // start of a line), exploring a step forward in the state.
     if !has_previous_state {
       if let Some(MatcherTreeRef::Node(node)) = node.word_separators.as_ref() {
         refs.extend(self.find_refs(&*node, event, true));
       }
+      if let Some(MatcherTreeRef::Node(node)) = node.line_starts.as_ref() {
+        refs.extend(self.find_refs(node, event, true));
+      }
     }
 
     refs
   }
 
   fn is_word_separator(&self, event: &Event) -> bool {
+    self.is_word_separator_in(event, &self.all_char_word_separators)
+  }
+
+  fn is_word_separator_in(&self, event: &Event, char_word_separators: &[String]) -> bool {
     match event {
       Event::Key { key, chars } => {
         if self.key_word_separators.contains(key) {
           true
         } else if let Some(char) = chars {
-          self.char_word_separators.contains(char)
+          is_unicode_word_separator(char, char_word_separators)
         } else {
           false
         }
@@ -214,6 +258,50 @@ impl<Id: Clone> RollingMatcher<Id> {
       Event::VirtualSeparator => true,
     }
   }
+
+  // Checks the separators found along the path against the ones of the given match
+  fn accepts_separators(&self, id: &Id, events: &[(Event, IsWordSeparator)]) -> bool {
+    if self.word_separator_overrides.is_empty() {
+      return true;
+    }
+
+    let char_word_separators = self
+      .word_separator_overrides
+      .iter()
+      .find(|(override_id, _)| override_id == id)
+      .map_or(&self.char_word_separators, |(_, separators)| separators);
+
+    events
+      .iter()
+      .filter(|(event, is_word_separator)| *is_word_separator && !is_line_break(event))
+      .all(|(event, _)| self.is_word_separator_in(event, char_word_separators))
+  }
+}
+
+fn is_line_break(event: &Event) -> bool {
+  match event {
+    Event::Key {
+      key: Key::Enter, ..
+    } => true,
+    Event::Key {
+      chars: Some(chars), ..
+    } => chars == "\n" || chars == "\r" || chars == "\r\n",
+    _ => false,
+  }
+}
+
+// Letters and digits (including accented and non-latin ones) are always part of
+// a word, while any Unicode whitespace (such as the non-breaking space) always
+// delimits one, in addition to the configured separators.
+fn is_unicode_word_separator(chars: &str, char_word_separators: &[String]) -> bool {
+  if chars.chars().any(char::is_alphanumeric) {
+    return false;
+  }
+
+  char_word_separators
+    .iter()
+    .any(|separator| separator == chars)
+    || (!chars.is_empty() && chars.chars().all(char::is_whitespace))
 }
 
 #[cfg(test)]
@@ -361,4 +449,154 @@ mod tests {
       vec![match_result(3, "ARTY")]
     );
   }
+
+  #[test]
+  fn matcher_process_word_matches_unicode() {
+    let word_options = StringMatchOptions {
+      left_word: true,
+      right_word: true,
+      ..Default::default()
+    };
+    let matcher = RollingMatcher::new(
+      &[
+        RollingMatch::from_string(1, "ete", &word_options),
+        RollingMatch::from_string(2, "café", &word_options),
+      ],
+      RollingMatcherOptions {
+        char_word_separators: vec![" ".to_string(), ",".to_string(), "é".to_string()],
+        ..Default::default()
+      },
+    );
+
+    // Accented letters are part of the word, even if configured as separators
+    assert_eq!(get_matches_after_str("éete ", &matcher), vec![]);
+    assert_eq!(get_matches_after_str("ñete ", &matcher), vec![]);
+    assert_eq!(get_matches_after_str(" eteé", &matcher), vec![]);
+    assert_eq!(
+      get_matches_after_str(" ete ", &matcher),
+      vec![match_result_with_sep(1, " ete ", Some(" "), Some(" "))]
+    );
+
+    // Unicode whitespace delimits words
+    assert_eq!(
+      get_matches_after_str("\u{a0}ete\u{3000}", &matcher),
+      vec![match_result_with_sep(
+        1,
+        "\u{a0}ete\u{3000}",
+        Some("\u{a0}"),
+        Some("\u{3000}")
+      )]
+    );
+
+    // Triggers containing accented letters
+    assert_eq!(
+      get_matches_after_str(" café,", &matcher),
+      vec![match_result_with_sep(2, " café,", Some(" "), Some(","))]
+    );
+    assert_eq!(get_matches_after_str("écafé,", &matcher), vec![]);
+  }
+
+  #[test]
+  fn matcher_process_word_separator_overrides() {
+    let word_options = StringMatchOptions {
+      left_word: true,
+      right_word: true,
+      ..Default::default()
+    };
+    let matcher = RollingMatcher::new(
+      &[
+        RollingMatch::from_string(1, "hi", &word_options),
+        RollingMatch::from_string(
+          2,
+          "ok",
+          &StringMatchOptions {
+            word_separators: Some(vec![" ".to_string(), "-".to_string()]),
+            ..word_options
+          },
+        ),
+      ],
+      RollingMatcherOptions {
+        char_word_separators: vec![" ".to_string(), ".".to_string()],
+        ..Default::default()
+      },
+    );
+
+    // Global separators
+    assert_eq!(
+      get_matches_after_str(" hi.", &matcher),
+      vec![match_result_with_sep(1, " hi.", Some(" "), Some("."))]
+    );
+    assert_eq!(get_matches_after_str(" hi-", &matcher), vec![]);
+
+    // Separators overridden by the match
+    assert_eq!(
+      get_matches_after_str(" ok-", &matcher),
+      vec![match_result_with_sep(2, " ok-", Some(" "), Some("-"))]
+    );
+    assert_eq!(get_matches_after_str(" ok.", &matcher), vec![]);
+    assert_eq!(get_matches_after_str(".ok ", &matcher), vec![]);
+  }
+
+  #[test]
+  fn matcher_process_line_start() {
+    let matcher = RollingMatcher::new(
+      &[RollingMatch::from_string(
+        1,
+        ":todo",
+        &StringMatchOptions {
+          line_start: true,
+          ..Default::default()
+        },
+      )],
+      RollingMatcherOptions {
+        char_word_separators: vec![" ".to_string()],
+        ..Default::default()
+      },
+    );
+
+    // Start of the buffer
+    assert_eq!(
+      get_matches_after_str(":todo", &matcher),
+      vec![match_result(1, ":todo")]
+    );
+
+    // After a new line
+    assert_eq!(
+      get_matches_after_str("text\n:todo", &matcher),
+      vec![match_result_with_sep(1, "\n:todo", Some("\n"), None)]
+    );
+
+    // After the Enter key
+    let (state, _) = matcher.process(
+      None,
+      Event::Key {
+        key: Key::Other,
+        chars: Some("a".to_string()),
+      },
+    );
+    let (mut state, _) = matcher.process(
+      Some(&state),
+      Event::Key {
+        key: Key::Enter,
+        chars: None,
+      },
+    );
+    let mut matches = Vec::new();
+    for c in ":todo".chars() {
+      let (next_state, next_matches) = matcher.process(
+        Some(&state),
+        Event::Key {
+          key: Key::Other,
+          chars: Some(c.to_string()),
+        },
+      );
+      state = next_state;
+      matches = next_matches;
+    }
+    assert_eq!(matches, vec![match_result(1, ":todo")]);
+
+    // In the middle of a line
+    assert_eq!(get_matches_after_str("text :todo", &matcher), vec![]);
+    assert_eq!(get_matches_after_str("a:todo", &matcher), vec![]);
+  }
 }
@@ -26,6 +26,7 @@ mod util;
 #[derive(Debug, Clone, PartialEq)]
 pub enum RollingItem {
   WordSeparator,
+  LineStart,
   Key(Key),
   Char(String),
   CharInsensitive(String),
@@ -35,17 +36,27 @@ pub enum RollingItem {
 pub struct RollingMatch<Id> {
   pub id: Id,
   pub items: Vec<RollingItem>,
+
+  // If set, the char word separators of this match, in place of the global ones
+  pub word_separators: Option<Vec<String>>,
 }
 
 impl<Id> RollingMatch<Id> {
   pub fn new(id: Id, items: Vec<RollingItem>) -> Self {
-    Self { id, items }
+    Self {
+      id,
+      items,
+      word_separators: None,
+    }
   }
 
   pub fn from_string(id: Id, string: &str, opt: &StringMatchOptions) -> Self {
     let mut items = Vec::new();
 
-    if opt.left_word {
+    // A line start already delimits the word on the left
+    if opt.line_start {
+      items.push(RollingItem::LineStart);
+    } else if opt.left_word {
       items.push(RollingItem::WordSeparator);
     }
 
@@ -61,13 +72,18 @@ impl<Id> RollingMatch<Id> {
       items.push(RollingItem::WordSeparator);
     }
 
-    Self { id, items }
+    Self {
+      id,
+      items,
+      word_separators: opt.word_separators.clone(),
+    }
   }
 
   pub fn from_items(id: Id, items: &[RollingItem]) -> Self {
     Self {
       id,
       items: items.to_vec(),
+      word_separators: None,
     }
   }
 }
@@ -76,6 +92,8 @@ pub struct StringMatchOptions {
   pub case_insensitive: bool,
   pub left_word: bool,
   pub right_word: bool,
+  pub line_start: bool,
+  pub word_separators: Option<Vec<String>>,
 }
 
 impl Default for StringMatchOptions {
@@ -84,6 +102,8 @@ impl Default for StringMatchOptions {
       case_insensitive: false,
       left_word: false,
       right_word: false,
+      line_start: false,
+      word_separators: None,
     }
   }
 }
@@ -103,7 +123,8 @@ mod tests {
           RollingItem::Char("e".to_string()),
           RollingItem::Char("s".to_string()),
           RollingItem::Char("t".to_string()),
-        ]
+        ],
+        word_separators: None,
       }
     )
   }
@@ -127,7 +148,8 @@ mod tests {
           RollingItem::Char("e".to_string()),
           RollingItem::Char("s".to_string()),
           RollingItem::Char("t".to_string()),
-        ]
+        ],
+        word_separators: None,
       }
     )
   }
@@ -151,7 +173,34 @@ mod tests {
           RollingItem::Char("s".to_string()),
           RollingItem::Char("t".to_string()),
           RollingItem::WordSeparator,
-        ]
+        ],
+        word_separators: None,
+      }
+    )
+  }
+
+  #[test]
+  fn test_match_from_string_line_start() {
+    assert_eq!(
+      RollingMatch::from_string(
+        1,
+        "test",
+        &StringMatchOptions {
+          line_start: true,
+          left_word: true,
+          ..Default::default()
+        }
+      ),
+      RollingMatch {
+        id: 1,
+        items: vec![
+          RollingItem::LineStart,
+          RollingItem::Char("t".to_string()),
+          RollingItem::Char("e".to_string()),
+          RollingItem::Char("s".to_string()),
+          RollingItem::Char("t".to_string()),
+        ],
+        word_separators: None,
       }
     )
   }
@@ -174,7 +223,8 @@ mod tests {
           RollingItem::CharInsensitive("e".to_string()),
           RollingItem::CharInsensitive("s".to_string()),
           RollingItem::CharInsensitive("t".to_string()),
-        ]
+        ],
+        word_separators: None,
       }
     )
   }
@@ -32,6 +32,7 @@ pub(crate) enum MatcherTreeRef<Id> {
 #[derive(Debug, PartialEq)]
 pub(crate) struct MatcherTreeNode<Id> {
   pub word_separators: Option<MatcherTreeRef<Id>>,
+  pub line_starts: Option<MatcherTreeRef<Id>>,
   pub keys: Vec<(Key, MatcherTreeRef<Id>)>,
   pub chars: Vec<(String, MatcherTreeRef<Id>)>,
   pub chars_insensitive: Vec<(UniCase<String>, MatcherTreeRef<Id>)>,
@@ -41,6 +42,7 @@ impl<Id> Default for MatcherTreeNode<Id> {
   fn default() -> Self {
     Self {
       word_separators: None,
+      line_starts: None,
       keys: Vec::new(),
       chars: Vec::new(),
       chars_insensitive: Vec::new(),
@@ -77,6 +79,14 @@ fn insert_items_recursively<Id>(id: Id, node: &mut MatcherTreeNode<Id>, items: &
         new_matches.push(id);
         node.word_separators = Some(MatcherTreeRef::Matches(new_matches))
       }
+      RollingItem::LineStart => {
+        let mut new_matches = Vec::new();
+        if let Some(MatcherTreeRef::Matches(matches)) = node.line_starts.take() {
+          new_matches.extend(matches);
+        }
+        new_matches.push(id);
+        node.line_starts = Some(MatcherTreeRef::Matches(new_matches))
+      }
       RollingItem::Key(key) => {
         if let Some(entry) = node.keys.iter_mut().find(|(_key, _)| _key == key) {
           if let MatcherTreeRef::Matches(matches) = &mut entry.1 {
@@ -135,6 +145,17 @@ fn insert_items_recursively<Id>(id: Id, node: &mut MatcherTreeNode<Id>, items: &
         }
         _ => {}
       },
+      RollingItem::LineStart => match node.line_starts.as_mut() {
+        Some(MatcherTreeRef::Node(next_node)) => {
+          insert_items_recursively(id, next_node.as_mut(), &items[1..])
+        }
+        None => {
+          let mut next_node = Box::new(MatcherTreeNode::default());
+          insert_items_recursively(id, next_node.as_mut(), &items[1..]);
+          node.line_starts = Some(MatcherTreeRef::Node(next_node));
+        }
+        _ => {}
+      },
       RollingItem::Key(key) => {
         if let Some(entry) = node.keys.iter_mut().find(|(_key, _)| _key == key) {
           if let MatcherTreeRef::Node(next_node) = &mut entry.1 {
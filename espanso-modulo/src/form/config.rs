@@ -44,11 +44,24 @@ pub struct FormConfig {
 
   #[serde(default = "default_fields")]
   pub fields: HashMap<String, FieldConfig>,
+
+  // Explicit ordering of the form fields, fields not listed here
+  // follow in the order they appear in the layout
+  #[serde(default)]
+  pub order: Vec<String>,
+
+  // Number of seconds after which the form is automatically cancelled,
+  // as if the user closed it without submitting
+  #[serde(default)]
+  pub timeout: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Clone)]
 pub struct FieldConfig {
   pub field_type: FieldTypeConfig,
+
+  // If true, the form can't be submitted while the field is empty
+  pub required: bool,
 }
 
 impl Default for FieldConfig {
@@ -57,6 +70,7 @@ impl Default for FieldConfig {
       field_type: FieldTypeConfig::Text(TextFieldConfig {
         ..Default::default()
       }),
+      required: false,
     }
   }
 }
@@ -72,6 +86,9 @@ pub enum FieldTypeConfig {
 pub struct TextFieldConfig {
   pub default: String,
   pub multiline: bool,
+
+  // Regex the value must fully match before the form can be submitted
+  pub validate: Option<String>,
 }
 
 impl Default for TextFieldConfig {
@@ -79,6 +96,7 @@ impl Default for TextFieldConfig {
     Self {
       default: "".to_owned(),
       multiline: false,
+      validate: None,
     }
   }
 }
@@ -87,6 +105,9 @@ impl Default for TextFieldConfig {
 pub struct ChoiceFieldConfig {
   pub values: Vec<String>,
   pub default: String,
+
+  // If true, a random value is preselected instead of the default one
+  pub random_default: bool,
 }
 
 impl Default for ChoiceFieldConfig {
@@ -94,6 +115,7 @@ impl Default for ChoiceFieldConfig {
     Self {
       values: Vec::new(),
       default: "".to_owned(),
+      random_default: false,
     }
   }
 }
@@ -134,12 +156,14 @@ impl<'a> From<&'a AutoFieldConfig> for FieldConfig {
         }
 
         config.multiline = other.multiline;
+        config.validate = other.validate.clone();
 
         FieldTypeConfig::Text(config)
       }
       "choice" => {
         let mut config = ChoiceFieldConfig {
           values: other.values.clone(),
+          random_default: other.random_default,
           ..Default::default()
         };
 
@@ -166,7 +190,10 @@ impl<'a> From<&'a AutoFieldConfig> for FieldConfig {
       }
     };
 
-    Self { field_type }
+    Self {
+      field_type,
+      required: other.required,
+    }
   }
 }
 
@@ -199,4 +226,63 @@ pub struct AutoFieldConfig {
 
   #[serde(default = "default_values")]
   pub values: Vec<String>,
+
+  #[serde(default)]
+  pub required: bool,
+
+  #[serde(default)]
+  pub validate: Option<String>,
+
+  #[serde(default)]
+  pub random_default: bool,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validation_params_are_parsed() {
+    let config: FormConfig = serde_json::from_str(
+      r#"{
+        "layout": "[[email]] [[choice]] [[name]]",
+        "fields": {
+          "email": { "type": "text", "required": true, "validate": ".+@.+" },
+          "choice": { "type": "choice", "values": ["a", "b"], "required": true },
+          "name": { "type": "text", "validate": null }
+        }
+      }"#,
+    )
+    .unwrap();
+
+    let email = &config.fields["email"];
+    assert!(email.required);
+    match &email.field_type {
+      FieldTypeConfig::Text(text) => assert_eq!(text.validate.as_deref(), Some(".+@.+")),
+      _ => panic!("expected a text field"),
+    }
+
+    assert!(config.fields["choice"].required);
+    match &config.fields["choice"].field_type {
+      FieldTypeConfig::Choice(choice) => assert!(!choice.random_default),
+      _ => panic!("expected a choice field"),
+    }
+
+    let name = &config.fields["name"];
+    assert!(!name.required);
+    match &name.field_type {
+      FieldTypeConfig::Text(text) => assert_eq!(text.validate, None),
+      _ => panic!("expected a text field"),
+    }
+  }
+
+  #[test]
+  fn timeout_is_parsed() {
+    let config: FormConfig = serde_json::from_str(r#"{ "layout": "[[name]]" }"#).unwrap();
+    assert_eq!(config.timeout, None);
+
+    let config: FormConfig =
+      serde_json::from_str(r#"{ "layout": "[[name]]", "timeout": 30 }"#).unwrap();
+    assert_eq!(config.timeout, Some(30));
+  }
 }
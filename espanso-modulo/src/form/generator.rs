@@ -20,15 +20,22 @@
 use super::config::{FieldConfig, FieldTypeConfig, FormConfig};
 use super::parser::layout::Token;
 use crate::sys::form::types::*;
+use anyhow::{Context, Result};
+use regex::Regex;
 use std::collections::HashMap;
 
-pub fn generate(config: FormConfig) -> Form {
+pub fn generate(config: FormConfig) -> Result<Form> {
   let structure = super::parser::layout::parse_layout(&config.layout);
   build_form(config, structure)
 }
 
-fn create_field(token: &Token, field_map: &HashMap<String, FieldConfig>) -> Field {
-  match token {
+// The whole value must match the pattern, not just a part of it
+pub fn compile_validate_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+  Regex::new(&format!("^(?:{})$", pattern))
+}
+
+fn create_field(token: &Token, field_map: &HashMap<String, FieldConfig>) -> Result<Field> {
+  Ok(match token {
     Token::Text(text) => Field {
       field_type: FieldType::Label(LabelMetadata { text: text.clone() }),
       ..Default::default()
@@ -44,41 +51,51 @@ fn create_field(token: &Token, field_map: &HashMap<String, FieldConfig>) -> Fiel
         FieldTypeConfig::Text(config) => FieldType::Text(TextMetadata {
           default_text: config.default.clone(),
           multiline: config.multiline,
+          validate: match &config.validate {
+            Some(pattern) => Some(compile_validate_pattern(pattern).with_context(|| {
+              format!("invalid validate pattern for field '{}': {}", name, pattern)
+            })?),
+            None => None,
+          },
         }),
         FieldTypeConfig::Choice(config) => FieldType::Choice(ChoiceMetadata {
           values: config.values.clone(),
           choice_type: ChoiceType::Dropdown,
           default_value: config.default.clone(),
+          random_default: config.random_default,
         }),
         FieldTypeConfig::List(config) => FieldType::Choice(ChoiceMetadata {
           values: config.values.clone(),
           choice_type: ChoiceType::List,
           default_value: config.default.clone(),
+          random_default: false,
         }),
       };
 
       Field {
         id: Some(name.clone()),
         field_type,
+        required: config.required,
       }
     }
-  }
+  })
 }
 
-fn build_form(form: FormConfig, structure: Vec<Vec<Token>>) -> Form {
+fn build_form(form: FormConfig, structure: Vec<Vec<Token>>) -> Result<Form> {
+  let order = resolve_order(&form.order, &structure);
   let field_map = form.fields;
   let mut fields = Vec::new();
 
   for row in structure.iter() {
     let current_field = if row.len() == 1 {
       // Single field
-      create_field(&row[0], &field_map)
+      create_field(&row[0], &field_map)?
     } else {
       // Row field
       let inner_fields = row
         .iter()
         .map(|token| create_field(token, &field_map))
-        .collect();
+        .collect::<Result<Vec<Field>>>()?;
 
       Field {
         field_type: FieldType::Row(RowMetadata {
@@ -91,9 +108,150 @@ fn build_form(form: FormConfig, structure: Vec<Vec<Token>>) -> Form {
     fields.push(current_field)
   }
 
-  Form {
+  Ok(Form {
     title: form.title,
     icon: form.icon,
     fields,
+    order,
+    timeout: form.timeout,
+  })
+}
+
+// Explicitly ordered fields come first, then the remaining ones in layout order.
+// Names that don't appear in the layout are ignored.
+fn resolve_order(explicit_order: &[String], structure: &[Vec<Token>]) -> Vec<String> {
+  let layout_fields: Vec<&String> = structure
+    .iter()
+    .flatten()
+    .filter_map(|token| match token {
+      Token::Field(name) => Some(name),
+      Token::Text(_) => None,
+    })
+    .collect();
+
+  let mut order: Vec<String> = Vec::new();
+  for name in explicit_order.iter().chain(layout_fields.iter().copied()) {
+    if layout_fields.contains(&name) && !order.contains(name) {
+      order.push(name.clone());
+    }
+  }
+  order
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::form::config::{ChoiceFieldConfig, TextFieldConfig};
+
+  fn config(layout: &str, order: &[&str]) -> FormConfig {
+    FormConfig {
+      title: "espanso".to_string(),
+      icon: None,
+      layout: layout.to_string(),
+      fields: HashMap::new(),
+      order: order.iter().map(|name| name.to_string()).collect(),
+      timeout: None,
+    }
+  }
+
+  #[test]
+  fn order_defaults_to_layout_order() {
+    let form = generate(config("Hey [[name]] [[surname]]\n[[city]]", &[])).unwrap();
+    assert_eq!(form.order, vec!["name", "surname", "city"]);
+  }
+
+  #[test]
+  fn order_follows_declared_order() {
+    let form = generate(config(
+      "Hey [[name]] [[surname]]\n[[city]]",
+      &["city", "missing", "name", "city"],
+    ))
+    .unwrap();
+    assert_eq!(form.order, vec!["city", "name", "surname"]);
+    // The layout itself is not affected by the ordering
+    assert_eq!(form.fields.len(), 2);
+  }
+
+  #[test]
+  fn validation_is_carried_to_fields() {
+    let mut form_config = config("[[email]]", &[]);
+    form_config.fields.insert(
+      "email".to_string(),
+      FieldConfig {
+        field_type: FieldTypeConfig::Text(TextFieldConfig {
+          validate: Some(".+@.+".to_string()),
+          ..Default::default()
+        }),
+        required: true,
+      },
+    );
+
+    let form = generate(form_config).unwrap();
+    assert!(form.fields[0].required);
+    match &form.fields[0].field_type {
+      FieldType::Text(metadata) => {
+        let regex = metadata.validate.as_ref().unwrap();
+        assert!(regex.is_match("user@example.com"));
+        assert!(!regex.is_match("user"));
+      }
+      _ => panic!("expected a text field"),
+    }
+  }
+
+  #[test]
+  fn invalid_validate_pattern_is_rejected() {
+    let mut form_config = config("[[email]]", &[]);
+    form_config.fields.insert(
+      "email".to_string(),
+      FieldConfig {
+        field_type: FieldTypeConfig::Text(TextFieldConfig {
+          validate: Some("(.+@".to_string()),
+          ..Default::default()
+        }),
+        required: false,
+      },
+    );
+
+    let err = generate(form_config).unwrap_err();
+    assert!(err.to_string().contains("email"));
+  }
+
+  #[test]
+  fn validate_pattern_must_match_whole_value() {
+    let regex = compile_validate_pattern("[0-9]+").unwrap();
+    assert!(regex.is_match("123"));
+    assert!(!regex.is_match("a123"));
+    assert!(!regex.is_match("123a"));
+  }
+
+  #[test]
+  fn random_default_is_carried_to_choice_fields() {
+    let mut form_config = config("[[prompt]]", &[]);
+    form_config.fields.insert(
+      "prompt".to_string(),
+      FieldConfig {
+        field_type: FieldTypeConfig::Choice(ChoiceFieldConfig {
+          values: vec!["first".to_string(), "second".to_string()],
+          random_default: true,
+          ..Default::default()
+        }),
+        required: false,
+      },
+    );
+
+    let form = generate(form_config).unwrap();
+    match &form.fields[0].field_type {
+      FieldType::Choice(metadata) => assert!(metadata.random_default),
+      _ => panic!("expected a choice field"),
+    }
+  }
+
+  #[test]
+  fn timeout_is_carried_to_form() {
+    assert_eq!(generate(config("[[name]]", &[])).unwrap().timeout, None);
+
+    let mut form_config = config("[[name]]", &[]);
+    form_config.timeout = Some(30);
+    assert_eq!(generate(form_config).unwrap().timeout, Some(30));
   }
 }
@@ -19,6 +19,7 @@
 
 pub mod config;
 pub mod generator;
+pub mod output;
 pub mod parser;
 
 pub use crate::sys::form::show;
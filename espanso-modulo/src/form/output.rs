@@ -0,0 +1,81 @@
+/*
+ * This file is part of modulo.
+ *
+ * Copyright (C) 2020-2021 Federico Terzi
+ *
+ * modulo is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * modulo is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with modulo.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::collections::HashMap;
+
+// Serializes the form values as a map, following the given field order.
+// Values not mentioned in the order are appended sorted by name.
+pub struct OrderedValues<'a> {
+  order: &'a [String],
+  values: &'a HashMap<String, String>,
+}
+
+impl<'a> OrderedValues<'a> {
+  pub fn new(order: &'a [String], values: &'a HashMap<String, String>) -> Self {
+    Self { order, values }
+  }
+
+  fn keys(&self) -> Vec<&'a String> {
+    let mut keys: Vec<&String> = self
+      .order
+      .iter()
+      .filter(|name| self.values.contains_key(*name))
+      .collect();
+    let mut remaining: Vec<&String> = self
+      .values
+      .keys()
+      .filter(|name| !self.order.contains(name))
+      .collect();
+    remaining.sort();
+    keys.extend(remaining);
+    keys
+  }
+}
+
+impl<'a> Serialize for OrderedValues<'a> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let keys = self.keys();
+    let mut map = serializer.serialize_map(Some(keys.len()))?;
+    for key in keys {
+      map.serialize_entry(key, &self.values[key])?;
+    }
+    map.end()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn values_are_serialized_in_order() {
+    let order = vec!["surname".to_string(), "name".to_string()];
+    let mut values = HashMap::new();
+    values.insert("name".to_string(), "John".to_string());
+    values.insert("city".to_string(), "Rome".to_string());
+    values.insert("surname".to_string(), "Doe".to_string());
+
+    let json = serde_json::to_string(&OrderedValues::new(&order, &values)).unwrap();
+    assert_eq!(json, r#"{"surname":"Doe","name":"John","city":"Rome"}"#);
+  }
+}
@@ -28,6 +28,7 @@ pub fn get_algorithm(
   let search_algorithm: Box<dyn Fn(&str, &[SearchItem]) -> Vec<usize>> = match name {
     "exact" => Box::new(exact_match),
     "iexact" => Box::new(case_insensitive_exact_match),
+    "key" => Box::new(case_sensitive_keyword),
     "ikey" => Box::new(case_insensitive_keyword),
     _ => panic!("unknown search algorithm: {}", name),
   };
@@ -39,12 +40,23 @@ pub fn get_algorithm(
   }
 }
 
+// Keep only the first results when the search defines `max_results`,
+// as rendering thousands of items makes the search bar sluggish
+pub fn limit_results(mut indexes: Vec<usize>, max_results: Option<usize>) -> Vec<usize> {
+  if let Some(max_results) = max_results {
+    indexes.truncate(max_results);
+  }
+  indexes
+}
+
 fn exact_match(query: &str, items: &[SearchItem]) -> Vec<usize> {
   items
     .iter()
     .enumerate()
     .filter(|(_, item)| {
-      item.label.contains(query) || item.trigger.as_deref().map_or(false, |t| t.contains(query))
+      item.label.contains(query)
+        || item.trigger.as_deref().map_or(false, |t| t.contains(query))
+        || item.search_terms.iter().any(|term| term.contains(query))
     })
     .map(|(i, _)| i)
     .collect()
@@ -61,24 +73,49 @@ fn case_insensitive_exact_match(query: &str, items: &[SearchItem]) -> Vec<usize>
           .trigger
           .as_deref()
           .map_or(false, |t| t.to_lowercase().contains(query))
+        || item
+          .search_terms
+          .iter()
+          .any(|term| term.to_lowercase().contains(&lowercase_query))
     })
     .map(|(i, _)| i)
     .collect()
 }
 
+fn case_sensitive_keyword(query: &str, items: &[SearchItem]) -> Vec<usize> {
+  keyword_match(query, items, true)
+}
+
 fn case_insensitive_keyword(query: &str, items: &[SearchItem]) -> Vec<usize> {
-  let lowercase_query = query.to_lowercase();
-  let keywords: Vec<&str> = lowercase_query.split_whitespace().collect();
+  keyword_match(query, items, false)
+}
+
+// Every keyword of the query must be found in the label, trigger or search terms
+fn keyword_match(query: &str, items: &[SearchItem], case_sensitive: bool) -> Vec<usize> {
+  let normalize = |text: &str| -> String {
+    if case_sensitive {
+      text.to_string()
+    } else {
+      text.to_lowercase()
+    }
+  };
+
+  let query = normalize(query);
+  let keywords: Vec<&str> = query.split_whitespace().collect();
   items
     .iter()
     .enumerate()
     .filter(|(_, item)| {
       for keyword in keywords.iter() {
-        if !item.label.to_lowercase().contains(keyword)
+        if !normalize(&item.label).contains(keyword)
           && !item
             .trigger
             .as_deref()
-            .map_or(false, |t| t.to_lowercase().contains(keyword))
+            .map_or(false, |t| normalize(t).contains(keyword))
+          && !item
+            .search_terms
+            .iter()
+            .any(|term| normalize(term).contains(keyword))
         {
           return false;
         }
@@ -124,3 +161,89 @@ fn command_filter(
       .collect()
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item(label: &str, trigger: &str, search_terms: &[&str]) -> SearchItem {
+    SearchItem {
+      id: label.to_string(),
+      label: label.to_string(),
+      trigger: Some(trigger.to_string()),
+      group: None,
+      search_terms: search_terms.iter().map(|term| term.to_string()).collect(),
+      is_builtin: false,
+    }
+  }
+
+  #[test]
+  fn match_is_found_by_search_term() {
+    let items = vec![
+      item("Bill to ACME Corp.", ":inv", &["Invoice", "billing"]),
+      item("Best regards", ":br", &[]),
+    ];
+
+    for name in ["exact", "iexact", "key", "ikey"] {
+      let algorithm = get_algorithm(name, true);
+      let query = if name == "exact" || name == "key" {
+        "Invoice"
+      } else {
+        "invoice"
+      };
+      assert_eq!(algorithm(query, &items), vec![0], "algorithm: {}", name);
+    }
+  }
+
+  #[test]
+  fn keywords_can_combine_label_and_search_terms() {
+    let items = vec![
+      item("Bill to ACME Corp.", ":inv", &["invoice"]),
+      item("Invoice reminder", ":rem", &[]),
+    ];
+
+    let algorithm = get_algorithm("ikey", true);
+    assert_eq!(algorithm("invoice acme", &items), vec![0]);
+  }
+
+  fn keyword_items() -> Vec<SearchItem> {
+    vec![
+      item("Call the API", ":call", &[]),
+      item("Fix the api client", ":fix", &[]),
+      item("Best regards", ":br", &["Api"]),
+    ]
+  }
+
+  #[test]
+  fn case_sensitive_keywords_distinguish_case() {
+    let algorithm = get_algorithm("key", true);
+    let items = keyword_items();
+
+    assert_eq!(algorithm("API", &items), vec![0]);
+    assert_eq!(algorithm("api", &items), vec![1]);
+    assert_eq!(algorithm("Api", &items), vec![2]);
+    assert_eq!(algorithm("the API", &items), vec![0]);
+  }
+
+  #[test]
+  fn case_insensitive_keywords_ignore_case() {
+    let algorithm = get_algorithm("ikey", true);
+    let items = keyword_items();
+
+    assert_eq!(algorithm("API", &items), vec![0, 1, 2]);
+    assert_eq!(algorithm("api", &items), vec![0, 1, 2]);
+    assert_eq!(algorithm("the API", &items), vec![0, 1]);
+  }
+
+  #[test]
+  fn results_are_limited_to_max_results() {
+    assert_eq!(limit_results(vec![3, 1, 2], Some(2)), vec![3, 1]);
+    assert_eq!(limit_results(vec![3, 1], Some(5)), vec![3, 1]);
+    assert!(limit_results(vec![3, 1], Some(0)).is_empty());
+  }
+
+  #[test]
+  fn results_are_not_limited_without_max_results() {
+    assert_eq!(limit_results(vec![3, 1, 2], None), vec![3, 1, 2]);
+  }
+}
@@ -51,6 +51,9 @@ pub struct SearchConfig {
 
   #[serde(default)]
   pub hint: Option<String>,
+
+  #[serde(default)]
+  pub max_results: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -59,6 +62,12 @@ pub struct SearchItem {
   pub label: String,
   pub trigger: Option<String>,
 
+  #[serde(default)]
+  pub group: Option<String>,
+
+  #[serde(default)]
+  pub search_terms: Vec<String>,
+
   #[serde(default)]
   pub is_builtin: bool,
 }
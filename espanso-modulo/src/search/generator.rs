@@ -28,16 +28,25 @@ pub fn generate(config: SearchConfig) -> types::Search {
       id: item.id,
       label: item.label,
       trigger: item.trigger,
+      group: item.group,
+      search_terms: item.search_terms,
       is_builtin: item.is_builtin,
     })
     .collect();
 
-  items.sort_by(|a, b| a.label.as_str().cmp(b.label.as_str()));
+  // Items are sorted by group first, so that the ones belonging
+  // to the same group are displayed under the same header
+  items.sort_by(|a, b| {
+    a.group
+      .cmp(&b.group)
+      .then_with(|| a.label.as_str().cmp(b.label.as_str()))
+  });
 
   types::Search {
     title: config.title,
     items,
     icon: config.icon,
     hint: config.hint,
+    max_results: config.max_results,
   }
 }
@@ -25,6 +25,7 @@
 #include <vector>
 #include <memory>
 #include <unordered_map>
+#include <random>
 
 // https://docs.wxwidgets.org/stable/classwx_frame.html
 const long DEFAULT_STYLE = wxSTAY_ON_TOP | wxCLOSE_BOX | wxCAPTION;
@@ -35,6 +36,9 @@ const int MULTILINE_MIN_WIDTH = 100;
 
 FormMetadata *formMetadata = nullptr;
 std::vector<ValuePair> values;
+// Checks the value of a field before submitting, returning one of the FORM_VALIDATION_* results
+int (*validateCallback)(const char *id, const char *value, const void *rules) = nullptr;
+const void *validateRules = nullptr;
 
 // Field Wrappers
 
@@ -90,19 +94,23 @@ public:
     std::unordered_map<const char *, std::unique_ptr<FieldWrapper>> idMap;
     wxButton *submit;
     wxStaticText *helpText;
+    wxTimer *timeoutTimer;
     bool hasFocusedMultilineControl;
 private:
     void AddComponent(wxPanel *parent, wxBoxSizer *sizer, FieldMetadata meta);
     void Submit();
+    bool ValidateFields(wxString &error);
     void OnSubmitBtn(wxCommandEvent& event);
     void OnCharHook(wxKeyEvent& event);
+    void OnTimeout(wxTimerEvent& event);
     void UpdateHelpText();
     void HandleNormalFocus(wxFocusEvent& event);
     void HandleMultilineFocus(wxFocusEvent& event);
 };
 enum
 {
-    ID_Submit = 20000
+    ID_Submit = 20000,
+    ID_Timeout = 20001
 };
 
 bool FormApp::OnInit()
@@ -143,6 +151,12 @@ FormFrame::FormFrame(const wxString& title, const wxPoint& pos, const wxSize& si
     Bind(wxEVT_CHAR_HOOK, &FormFrame::OnCharHook, this, wxID_ANY);
     // TODO: register ESC click handler: https://forums.wxwidgets.org/viewtopic.php?t=41926
 
+    timeoutTimer = new wxTimer(this, ID_Timeout);
+    Bind(wxEVT_TIMER, &FormFrame::OnTimeout, this, ID_Timeout);
+    if (formMetadata->timeout > 0) {
+        timeoutTimer->StartOnce(formMetadata->timeout * 1000);
+    }
+
     this->SetClientSize(panel->GetBestSize());
     this->CentreOnScreen();
 }
@@ -197,6 +211,13 @@ void FormFrame::AddComponent(wxPanel *parent, wxBoxSizer *sizer, FieldMetadata m
                 }
             }
 
+            // Preselect a random value, overriding the default one
+            if (choiceMeta->randomDefault && choiceMeta->valueSize > 0) {
+                std::random_device device;
+                std::uniform_int_distribution<int> distribution(0, choiceMeta->valueSize - 1);
+                selectedItem = distribution(device);
+            }
+
             void * choice = nullptr;
             if (choiceMeta->choiceType == ChoiceType::DROPDOWN) {
                 choice = (void*) new wxChoice(parent, wxID_ANY, wxDefaultPosition, wxDefaultSize, choices);
@@ -255,7 +276,33 @@ void FormFrame::AddComponent(wxPanel *parent, wxBoxSizer *sizer, FieldMetadata m
     }
 }
 
+bool FormFrame::ValidateFields(wxString &error) {
+    for (auto& field: idMap) {
+        FieldWrapper * fieldWrapper = (FieldWrapper*) field.second.get();
+        wxString value {fieldWrapper->getValue()};
+        wxCharBuffer buffer {value.ToUTF8()};
+        wxString name = wxString::FromUTF8(field.first);
+
+        int result = validateCallback(field.first, buffer.data(), validateRules);
+        if (result == FORM_VALIDATION_REQUIRED) {
+            error = wxString::Format("The field '%s' is required.", name);
+            return false;
+        } else if (result == FORM_VALIDATION_INVALID) {
+            error = wxString::Format("The field '%s' has an invalid value.", name);
+            return false;
+        }
+    }
+
+    return true;
+}
+
 void FormFrame::Submit() {
+    wxString error;
+    if (!ValidateFields(error)) {
+        wxMessageBox(error, "espanso", wxOK | wxICON_WARNING, this);
+        return;
+    }
+
     for (auto& field: idMap) {
         FieldWrapper * fieldWrapper = (FieldWrapper*) field.second.get();
         wxString value {fieldWrapper->getValue()};
@@ -311,13 +358,20 @@ void FormFrame::OnCharHook(wxKeyEvent& event) {
     }
 }
 
-extern "C" void interop_show_form(FormMetadata * _metadata, void (*callback)(ValuePair *values, int size, void *data), void *data) {
+// The form is closed without collecting the values, so the expansion is aborted
+void FormFrame::OnTimeout(wxTimerEvent &event) {
+    Close(true);
+}
+
+extern "C" void interop_show_form(FormMetadata * _metadata, void (*callback)(ValuePair *values, int size, void *data), void *data, int (*_validateCallback)(const char *id, const char *value, const void *rules), const void *_validateRules) {
     // Setup high DPI support on Windows
     #ifdef __WXMSW__
         SetProcessDPIAware();
     #endif
     
     formMetadata = _metadata;
+    validateCallback = _validateCallback;
+    validateRules = _validateRules;
 
     wxApp::SetInstance(new FormApp());
     int argc = 0;
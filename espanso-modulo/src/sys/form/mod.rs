@@ -19,7 +19,7 @@
 
 use std::collections::HashMap;
 use std::ffi::CStr;
-use std::os::raw::c_int;
+use std::os::raw::{c_char, c_int};
 
 // Form schema
 
@@ -29,12 +29,15 @@ pub mod types {
     pub title: String,
     pub icon: Option<String>,
     pub fields: Vec<Field>,
+    pub order: Vec<String>,
+    pub timeout: Option<u64>,
   }
 
   #[derive(Debug)]
   pub struct Field {
     pub id: Option<String>,
     pub field_type: FieldType,
+    pub required: bool,
   }
 
   impl Default for Field {
@@ -42,6 +45,7 @@ pub mod types {
       Self {
         id: None,
         field_type: FieldType::Unknown,
+        required: false,
       }
     }
   }
@@ -69,6 +73,8 @@ pub mod types {
   pub struct TextMetadata {
     pub default_text: String,
     pub multiline: bool,
+    // Compiled from the pattern the whole value must match
+    pub validate: Option<regex::Regex>,
   }
 
   #[derive(Debug)]
@@ -82,6 +88,7 @@ pub mod types {
     pub values: Vec<String>,
     pub choice_type: ChoiceType,
     pub default_value: String,
+    pub random_default: bool,
   }
 }
 
@@ -136,6 +143,8 @@ mod interop {
         iconPath: icon_path_ptr,
         fields: _metadata.as_ptr(),
         fieldSize: fields.len() as c_int,
+        // The timeout is converted to milliseconds on the other side
+        timeout: form.timeout.unwrap_or(0).min((c_int::MAX / 1000) as u64) as c_int,
       });
 
       Self {
@@ -298,6 +307,7 @@ mod interop {
         valueSize: values.len() as c_int,
         choiceType: choice_type,
         defaultValue: default_value.as_ptr(),
+        randomDefault: if metadata.random_default { 1 } else { 0 },
       });
       Self {
         values,
@@ -349,6 +359,9 @@ pub fn show(form: types::Form) -> HashMap<String, String> {
   use super::interop::*;
   use std::os::raw::c_void;
 
+  let mut rules: HashMap<String, ValidationRule> = HashMap::new();
+  collect_validation_rules(&form.fields, &mut rules);
+
   let owned_form: interop::OwnedForm = form.into();
   let metadata: *const FormMetadata = owned_form.as_ptr() as *const FormMetadata;
 
@@ -370,14 +383,145 @@ pub fn show(form: types::Form) -> HashMap<String, String> {
     }
   }
 
+  extern "C" fn validate_callback(
+    id: *const c_char,
+    value: *const c_char,
+    rules: *const c_void,
+  ) -> c_int {
+    let rules = rules as *const HashMap<String, ValidationRule>;
+    let rules = unsafe { &*rules };
+    let (id, value) = unsafe {
+      (
+        CStr::from_ptr(id).to_string_lossy(),
+        CStr::from_ptr(value).to_string_lossy(),
+      )
+    };
+
+    match rules.get(id.as_ref()).map(|rule| rule.validate(&value)) {
+      Some(ValidationResult::MissingRequired) => FORM_VALIDATION_REQUIRED,
+      Some(ValidationResult::Invalid) => FORM_VALIDATION_INVALID,
+      Some(ValidationResult::Valid) | None => FORM_VALIDATION_VALID,
+    }
+  }
+
   unsafe {
     // TODO: Nested rows should fail, add check
     interop_show_form(
       metadata,
       callback,
       &mut value_map as *mut HashMap<String, String> as *mut c_void,
+      validate_callback,
+      &rules as *const HashMap<String, ValidationRule> as *const c_void,
     );
   }
 
   value_map
 }
+
+// Checked on submit, the form can't be submitted until all the fields are valid
+#[derive(Debug, Default)]
+struct ValidationRule {
+  required: bool,
+  validate: Option<regex::Regex>,
+}
+
+#[derive(Debug, PartialEq)]
+enum ValidationResult {
+  Valid,
+  MissingRequired,
+  Invalid,
+}
+
+impl ValidationRule {
+  fn validate(&self, value: &str) -> ValidationResult {
+    if value.is_empty() {
+      if self.required {
+        ValidationResult::MissingRequired
+      } else {
+        ValidationResult::Valid
+      }
+    } else if matches!(&self.validate, Some(regex) if !regex.is_match(value)) {
+      ValidationResult::Invalid
+    } else {
+      ValidationResult::Valid
+    }
+  }
+}
+
+fn collect_validation_rules(fields: &[types::Field], rules: &mut HashMap<String, ValidationRule>) {
+  for field in fields {
+    match (&field.id, &field.field_type) {
+      (_, types::FieldType::Row(row)) => collect_validation_rules(&row.fields, rules),
+      (Some(id), types::FieldType::Text(text)) => {
+        rules.insert(
+          id.clone(),
+          ValidationRule {
+            required: field.required,
+            validate: text.validate.clone(),
+          },
+        );
+      }
+      (Some(id), types::FieldType::Choice(_)) => {
+        rules.insert(
+          id.clone(),
+          ValidationRule {
+            required: field.required,
+            validate: None,
+          },
+        );
+      }
+      _ => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validation_rules_are_collected_from_rows() {
+    let fields = vec![types::Field {
+      field_type: types::FieldType::Row(types::RowMetadata {
+        fields: vec![
+          types::Field {
+            id: Some("email".to_string()),
+            field_type: types::FieldType::Text(types::TextMetadata {
+              default_text: String::new(),
+              multiline: false,
+              validate: Some(crate::form::generator::compile_validate_pattern(".+@.+").unwrap()),
+            }),
+            required: true,
+          },
+          types::Field {
+            field_type: types::FieldType::Label(types::LabelMetadata {
+              text: "label".to_string(),
+            }),
+            ..Default::default()
+          },
+        ],
+      }),
+      ..Default::default()
+    }];
+
+    let mut rules = HashMap::new();
+    collect_validation_rules(&fields, &mut rules);
+    assert_eq!(rules.len(), 1);
+
+    let rule = &rules["email"];
+    assert_eq!(rule.validate(""), ValidationResult::MissingRequired);
+    assert_eq!(rule.validate("user"), ValidationResult::Invalid);
+    assert_eq!(rule.validate("user@example.com"), ValidationResult::Valid);
+  }
+
+  #[test]
+  fn optional_fields_can_be_left_empty() {
+    let rule = ValidationRule {
+      required: false,
+      validate: Some(crate::form::generator::compile_validate_pattern("[0-9]+").unwrap()),
+    };
+    assert_eq!(rule.validate(""), ValidationResult::Valid);
+    assert_eq!(rule.validate("12a"), ValidationResult::Invalid);
+    assert_eq!(rule.validate("12"), ValidationResult::Valid);
+  }
+}
@@ -46,6 +46,7 @@ typedef struct ChoiceMetadata {
   const int valueSize;
   const char *defaultValue;
   const ChoiceType choiceType;
+  const int randomDefault;
 } ChoiceMetadata;
 
 typedef struct FieldMetadata {
@@ -64,8 +65,15 @@ typedef struct FormMetadata {
   const char *iconPath;
   const FieldMetadata *fields;
   const int fieldSize;
+  // Seconds before the form is cancelled automatically, 0 to disable
+  const int timeout;
 } FormMetadata;
 
+// Results of the validation callback, checked before submitting the form
+const int FORM_VALIDATION_VALID = 0;
+const int FORM_VALIDATION_REQUIRED = 1;
+const int FORM_VALIDATION_INVALID = 2;
+
 typedef struct ValuePair {
   const char *id;
   const char *value;
@@ -77,6 +85,7 @@ typedef struct SearchItem {
   const char *id;
   const char *label;
   const char *trigger;
+  const char *group;
 } SearchItem;
 
 typedef struct SearchResults {
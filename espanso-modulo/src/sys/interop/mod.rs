@@ -52,6 +52,7 @@ pub struct ChoiceMetadata {
   pub valueSize: ::std::os::raw::c_int,
   pub defaultValue: *const ::std::os::raw::c_char,
   pub choiceType: ChoiceType,
+  pub randomDefault: ::std::os::raw::c_int,
 }
 
 #[repr(C)]
@@ -76,6 +77,7 @@ pub struct FormMetadata {
   pub iconPath: *const ::std::os::raw::c_char,
   pub fields: *const FieldMetadata,
   pub fieldSize: ::std::os::raw::c_int,
+  pub timeout: ::std::os::raw::c_int,
 }
 
 #[repr(C)]
@@ -91,6 +93,7 @@ pub struct SearchItem {
   pub id: *const ::std::os::raw::c_char,
   pub label: *const ::std::os::raw::c_char,
   pub trigger: *const ::std::os::raw::c_char,
+  pub group: *const ::std::os::raw::c_char,
 }
 
 #[repr(C)]
@@ -108,6 +111,10 @@ pub struct SearchMetadata {
   pub hintText: *const ::std::os::raw::c_char,
 }
 
+pub const FORM_VALIDATION_VALID: i32 = 0;
+pub const FORM_VALIDATION_REQUIRED: i32 = 1;
+pub const FORM_VALIDATION_INVALID: i32 = 2;
+
 pub const WIZARD_MIGRATE_RESULT_SUCCESS: i32 = 0;
 pub const WIZARD_MIGRATE_RESULT_CLEAN_FAILURE: i32 = 1;
 pub const WIZARD_MIGRATE_RESULT_DIRTY_FAILURE: i32 = 2;
@@ -199,6 +206,12 @@ extern "C" {
     metadata: *const FormMetadata,
     callback: extern "C" fn(values: *const ValuePair, size: c_int, map: *mut c_void),
     map: *mut c_void,
+    validate_callback: extern "C" fn(
+      id: *const c_char,
+      value: *const c_char,
+      rules: *const c_void,
+    ) -> c_int,
+    rules: *const c_void,
   );
 
   // SEARCH
@@ -20,12 +20,16 @@
 use std::ffi::CStr;
 use std::os::raw::{c_char, c_int, c_void};
 
+use crate::search::algorithm::limit_results;
+
 pub mod types {
   #[derive(Debug)]
   pub struct SearchItem {
     pub id: String,
     pub label: String,
     pub trigger: Option<String>,
+    pub group: Option<String>,
+    pub search_terms: Vec<String>,
     pub is_builtin: bool,
   }
 
@@ -35,6 +39,7 @@ pub mod types {
     pub icon: Option<String>,
     pub hint: Option<String>,
     pub items: Vec<SearchItem>,
+    pub max_results: Option<usize>,
   }
 }
 
@@ -117,6 +122,7 @@ mod interop {
     id: CString,
     label: CString,
     trigger: CString,
+    group: CString,
   }
 
   impl OwnedSearchItem {
@@ -125,6 +131,7 @@ mod interop {
         id: self.id.as_ptr(),
         label: self.label.as_ptr(),
         trigger: self.trigger.as_ptr(),
+        group: self.group.as_ptr(),
       }
     }
   }
@@ -141,7 +148,18 @@ mod interop {
         CString::new("".to_string()).expect("unable to convert item trigger to CString")
       };
 
-      Self { id, label, trigger }
+      let group = if let Some(group) = item.group.as_deref() {
+        CString::new(group.to_string()).expect("unable to convert item group to CString")
+      } else {
+        CString::new("".to_string()).expect("unable to convert item group to CString")
+      };
+
+      Self {
+        id,
+        label,
+        trigger,
+        group,
+      }
     }
   }
 }
@@ -150,6 +168,7 @@ struct SearchData {
   owned_search: interop::OwnedSearch,
   items: Vec<types::SearchItem>,
   algorithm: Box<dyn Fn(&str, &[types::SearchItem]) -> Vec<usize>>,
+  max_results: Option<usize>,
 }
 
 pub fn show(
@@ -165,6 +184,7 @@ pub fn show(
     owned_search,
     items: search.items,
     algorithm,
+    max_results: search.max_results,
   };
 
   extern "C" fn search_callback(query: *const c_char, app: *const c_void, data: *const c_void) {
@@ -174,7 +194,10 @@ pub fn show(
     let search_data = data as *const SearchData;
     let search_data = unsafe { &*search_data };
 
-    let indexes = (*search_data.algorithm)(&query, &search_data.items);
+    let indexes = limit_results(
+      (*search_data.algorithm)(&query, &search_data.items),
+      search_data.max_results,
+    );
     let items: Vec<SearchItem> = indexes
       .into_iter()
       .map(|index| search_data.owned_search.interop_items[index])
@@ -63,6 +63,7 @@ void *data = nullptr;
 void *resultData = nullptr;
 wxArrayString wxItems;
 wxArrayString wxTriggers;
+wxArrayString wxGroups;
 wxArrayString wxIds;
 
 // App Code
@@ -129,7 +130,15 @@ wxString ResultListBox::OnGetItem(size_t n) const
 {
     wxString textColor = isDark ? "white" : "";
     wxString shortcut = (n < 8) ? wxString::Format(wxT("Alt+%i"), (int)n + 1) : " ";
-    return wxString::Format(wxT("<font color='%s'><table width='100%%'><tr><td>%s</td><td align='right'><b>%s</b> <font color='#636e72'> %s</font></td></tr></table></font>"), textColor, wxItems[n], wxTriggers[n], shortcut);
+
+    // Show a header before the first item of each group
+    wxString header = "";
+    if (!wxGroups[n].IsEmpty() && (n == 0 || wxGroups[n] != wxGroups[n - 1]))
+    {
+        header = wxString::Format(wxT("<font color='#636e72' size='-1'><b>%s</b></font><br>"), wxGroups[n]);
+    }
+
+    return wxString::Format(wxT("%s<font color='%s'><table width='100%%'><tr><td>%s</td><td align='right'><b>%s</b> <font color='#636e72'> %s</font></td></tr></table></font>"), header, textColor, wxItems[n], wxTriggers[n], shortcut);
 }
 
 class SearchFrame : public wxFrame
@@ -398,6 +407,7 @@ void SearchFrame::SetItems(SearchItem *items, int itemSize)
     wxItems.Clear();
     wxIds.Clear();
     wxTriggers.Clear();
+    wxGroups.Clear();
 
     for (int i = 0; i < itemSize; i++)
     {
@@ -409,6 +419,9 @@ void SearchFrame::SetItems(SearchItem *items, int itemSize)
 
         wxString trigger = wxString::FromUTF8(items[i].trigger);
         wxTriggers.Add(trigger);
+
+        wxString group = wxString::FromUTF8(items[i].group);
+        wxGroups.Add(group);
     }
 
     resultBox->SetItemCount(itemSize);
@@ -17,11 +17,13 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use anyhow::Result;
 use log::{debug, info};
 use std::{
   fs::create_dir_all,
   path::{Path, PathBuf},
 };
+use thiserror::Error;
 
 #[derive(Debug, Clone)]
 pub struct Paths {
@@ -37,17 +39,7 @@ pub fn resolve_paths(
   force_package_dir: Option<&Path>,
   force_runtime_dir: Option<&Path>,
 ) -> Paths {
-  let config_dir = if let Some(config_dir) = force_config_dir {
-    config_dir.to_path_buf()
-  } else if let Some(config_dir) = get_config_dir() {
-    config_dir
-  } else {
-    // Create the config directory if not already present
-    let config_dir = get_default_config_path();
-    info!("creating config directory in {:?}", config_dir);
-    create_dir_all(&config_dir).expect("unable to create config directory");
-    config_dir
-  };
+  let config_dir = resolve_config_dir(force_config_dir);
 
   let runtime_dir = if let Some(runtime_dir) = force_runtime_dir {
     runtime_dir.to_path_buf()
@@ -88,6 +80,40 @@ pub fn resolve_paths(
   }
 }
 
+pub fn resolve_config_dir(force_config_dir: Option<&Path>) -> PathBuf {
+  if let Some(config_dir) = force_config_dir {
+    config_dir.to_path_buf()
+  } else if let Some(config_dir) = get_config_dir() {
+    config_dir
+  } else {
+    // Create the config directory if not already present
+    let config_dir = get_default_config_path();
+    info!("creating config directory in {:?}", config_dir);
+    create_dir_all(&config_dir).expect("unable to create config directory");
+    config_dir
+  }
+}
+
+// Profiles are alternative config roots (each one with its own "config"
+// and "match" folders) stored in the "profiles" folder of the main
+// config directory, for example: $CONFIG/espanso/profiles/work
+pub fn resolve_profile_dir(config_dir: &Path, profile: &str) -> Result<PathBuf> {
+  let is_valid_name = !profile.is_empty()
+    && profile
+      .chars()
+      .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+  if !is_valid_name {
+    return Err(ProfileError::InvalidName(profile.to_string()).into());
+  }
+
+  let profile_dir = config_dir.join("profiles").join(profile);
+  if !profile_dir.is_dir() {
+    return Err(ProfileError::Missing(profile_dir).into());
+  }
+
+  Ok(profile_dir)
+}
+
 fn get_config_dir() -> Option<PathBuf> {
   if let Some(portable_dir) = get_portable_config_dir() {
     // Portable mode
@@ -319,3 +345,57 @@ fn is_legacy_runtime_dir(path: &Path) -> bool {
 
   false
 }
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+  #[error("invalid profile name `{0}`, only letters, digits, '-' and '_' are allowed")]
+  InvalidName(String),
+
+  #[error("profile directory {0:?} doesn't exist, make sure to create it first")]
+  Missing(PathBuf),
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempdir::TempDir;
+
+  #[test]
+  fn resolve_profile_dir_uses_selected_profile() {
+    let config_dir = TempDir::new("espanso-config").unwrap();
+    let work_dir = config_dir.path().join("profiles").join("work");
+    create_dir_all(&work_dir).unwrap();
+
+    assert_eq!(
+      resolve_profile_dir(config_dir.path(), "work").unwrap(),
+      work_dir
+    );
+  }
+
+  #[test]
+  fn resolve_profile_dir_missing_profile() {
+    let config_dir = TempDir::new("espanso-config").unwrap();
+
+    assert!(matches!(
+      resolve_profile_dir(config_dir.path(), "personal")
+        .unwrap_err()
+        .downcast_ref::<ProfileError>(),
+      Some(ProfileError::Missing(_))
+    ));
+  }
+
+  #[test]
+  fn resolve_profile_dir_invalid_name() {
+    let config_dir = TempDir::new("espanso-config").unwrap();
+    create_dir_all(config_dir.path().join("profiles").join("work")).unwrap();
+
+    for name in &["", "..", "../work", "work/sub"] {
+      assert!(matches!(
+        resolve_profile_dir(config_dir.path(), name)
+          .unwrap_err()
+          .downcast_ref::<ProfileError>(),
+        Some(ProfileError::InvalidName(_))
+      ));
+    }
+  }
+}
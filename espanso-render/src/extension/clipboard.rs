@@ -18,7 +18,7 @@
  */
 
 use crate::{Extension, ExtensionOutput, ExtensionResult, Params};
-use thiserror::Error;
+use log::debug;
 
 pub trait ClipboardProvider {
   fn get_text(&self) -> Option<String>;
@@ -41,18 +41,14 @@ impl<'a> Extension for ClipboardExtension<'a> {
   }
 
   fn calculate(&self, _: &crate::Context, _: &crate::Scope, _: &Params) -> crate::ExtensionResult {
-    if let Some(clipboard) = self.provider.get_text() {
-      ExtensionResult::Success(ExtensionOutput::Single(clipboard))
-    } else {
-      ExtensionResult::Error(ClipboardExtensionError::MissingClipboard.into())
-    }
-  }
-}
+    // An empty (or non-text) clipboard shouldn't prevent the expansion
+    let clipboard = self.provider.get_text().unwrap_or_else(|| {
+      debug!("clipboard is empty or doesn't contain text, expanding to empty string");
+      String::new()
+    });
 
-#[derive(Error, Debug)]
-pub enum ClipboardExtensionError {
-  #[error("clipboard provider returned error")]
-  MissingClipboard,
+    ExtensionResult::Success(ExtensionOutput::Single(clipboard))
+  }
 }
 
 #[cfg(test)]
@@ -88,13 +84,16 @@ mod tests {
   }
 
   #[test]
-  fn none_clipboard_produces_error() {
+  fn none_clipboard_produces_empty_string() {
     let provider = MockClipboardProvider { return_none: true };
     let extension = ClipboardExtension::new(&provider);
 
-    assert!(matches!(
-      extension.calculate(&Default::default(), &Default::default(), &Params::new()),
-      ExtensionResult::Error(_)
-    ));
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &Params::new())
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single(String::new())
+    );
   }
 }
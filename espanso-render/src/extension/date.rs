@@ -17,7 +17,8 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use chrono::{DateTime, Duration, Local};
+use chrono::{DateTime, Duration, Local, Locale};
+use thiserror::Error;
 
 use crate::{Extension, ExtensionOutput, ExtensionResult, Number, Params, Value};
 
@@ -49,13 +50,23 @@ impl Extension for DateExtension {
     let offset = params.get("offset");
     if let Some(Value::Number(Number::Integer(offset))) = offset {
       let offset = Duration::seconds(*offset);
-      now = now + offset;
+      now += offset;
     }
 
     let format = params.get("format");
 
     let date = if let Some(Value::String(format)) = format {
-      now.format(format).to_string()
+      match params.get("locale") {
+        Some(Value::String(locale)) => match parse_locale(locale) {
+          Some(locale) => now.format_localized(format, locale).to_string(),
+          None => {
+            return ExtensionResult::Error(
+              DateExtensionError::UnsupportedLocale(locale.clone()).into(),
+            )
+          }
+        },
+        _ => now.format(format).to_string(),
+      }
     } else {
       now.to_rfc2822()
     };
@@ -74,6 +85,30 @@ impl DateExtension {
   }
 }
 
+// Locales are specified as "de_DE" or "de-DE". A language alone is resolved
+// to the country with the same code, so "de" is formatted as "de_DE", while
+// "en" is formatted as "en_US"
+fn parse_locale(locale: &str) -> Option<Locale> {
+  let locale = locale.replace('-', "_");
+  if locale.contains('_') {
+    return locale.parse().ok();
+  }
+
+  let language = locale.to_lowercase();
+  if language == "en" {
+    return Some(Locale::en_US);
+  }
+  format!("{}_{}", language, language.to_uppercase())
+    .parse()
+    .ok()
+}
+
+#[derive(Error, Debug)]
+pub enum DateExtensionError {
+  #[error("unsupported 'locale' parameter: {0}, expected a locale such as en_US or de_DE")]
+  UnsupportedLocale(String),
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -82,7 +117,7 @@ mod tests {
   #[test]
   fn date_formatted_correctly() {
     let mut extension = DateExtension::new();
-    extension.fixed_date = Some(Local.ymd(2014, 7, 8).and_hms(9, 10, 11));
+    extension.fixed_date = Some(Local.with_ymd_and_hms(2014, 7, 8, 9, 10, 11).unwrap());
 
     let param = vec![("format".to_string(), Value::String("%H:%M:%S".to_string()))]
       .into_iter()
@@ -99,7 +134,7 @@ mod tests {
   #[test]
   fn offset_works_correctly() {
     let mut extension = DateExtension::new();
-    extension.fixed_date = Some(Local.ymd(2014, 7, 8).and_hms(9, 10, 11));
+    extension.fixed_date = Some(Local.with_ymd_and_hms(2014, 7, 8, 9, 10, 11).unwrap());
 
     let param = vec![
       ("format".to_string(), Value::String("%H:%M:%S".to_string())),
@@ -115,4 +150,146 @@ mod tests {
       ExtensionOutput::Single("10:10:11".to_string())
     );
   }
+
+  #[test]
+  fn date_with_multiple_format_strings() {
+    let mut extension = DateExtension::new();
+    extension.fixed_date = Some(Local.with_ymd_and_hms(2014, 7, 8, 9, 10, 11).unwrap());
+
+    let param = vec![("format".to_string(), Value::String("%Y-%m-%d".to_string()))]
+      .into_iter()
+      .collect::<Params>();
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &param)
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("2014-07-08".to_string())
+    );
+
+    let param = vec![(
+      "format".to_string(),
+      Value::String("%d/%m/%y %I%p".to_string()),
+    )]
+    .into_iter()
+    .collect::<Params>();
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &param)
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("08/07/14 09AM".to_string())
+    );
+  }
+
+  #[test]
+  fn negative_offset_crosses_day_boundary() {
+    let mut extension = DateExtension::new();
+    extension.fixed_date = Some(Local.with_ymd_and_hms(2014, 7, 8, 9, 10, 11).unwrap());
+
+    let param = vec![
+      (
+        "format".to_string(),
+        Value::String("%Y-%m-%d %H:%M".to_string()),
+      ),
+      ("offset".to_string(), Value::Number(Number::Integer(-36000))),
+    ]
+    .into_iter()
+    .collect::<Params>();
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &param)
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("2014-07-07 23:10".to_string())
+    );
+  }
+
+  #[test]
+  fn missing_format_defaults_to_rfc2822() {
+    let mut extension = DateExtension::new();
+    let date = Local.with_ymd_and_hms(2014, 7, 8, 9, 10, 11).unwrap();
+    extension.fixed_date = Some(date);
+
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &Params::new())
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single(date.to_rfc2822())
+    );
+  }
+
+  fn format_with_locale(format: &str, locale: &str) -> ExtensionResult {
+    let mut extension = DateExtension::new();
+    extension.fixed_date = Some(Local.with_ymd_and_hms(2014, 7, 8, 9, 10, 11).unwrap());
+
+    let param = vec![
+      ("format".to_string(), Value::String(format.to_string())),
+      ("locale".to_string(), Value::String(locale.to_string())),
+    ]
+    .into_iter()
+    .collect::<Params>();
+    extension.calculate(&Default::default(), &Default::default(), &param)
+  }
+
+  #[test]
+  fn names_are_localized() {
+    assert_eq!(
+      format_with_locale("%A %d %B %Y", "it")
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("martedì 08 luglio 2014".to_string())
+    );
+    assert_eq!(
+      format_with_locale("%a, %d. %b %H:%M", "de_DE")
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("Di, 08. Jul 09:10".to_string())
+    );
+  }
+
+  #[test]
+  fn escaped_percent_is_not_localized() {
+    assert_eq!(
+      format_with_locale("%%B %B", "fr").into_success().unwrap(),
+      ExtensionOutput::Single("%B juillet".to_string())
+    );
+  }
+
+  #[test]
+  fn english_locale_keeps_the_default_names() {
+    assert_eq!(
+      format_with_locale("%A %B", "en-US").into_success().unwrap(),
+      ExtensionOutput::Single("Tuesday July".to_string())
+    );
+  }
+
+  #[test]
+  fn language_is_resolved_to_its_main_locale() {
+    assert_eq!(
+      format_with_locale("%B", "pt").into_success().unwrap(),
+      ExtensionOutput::Single("julho".to_string())
+    );
+    assert_eq!(
+      format_with_locale("%B", "pt-BR").into_success().unwrap(),
+      ExtensionOutput::Single("julho".to_string())
+    );
+    assert_eq!(
+      format_with_locale("%B", "en").into_success().unwrap(),
+      ExtensionOutput::Single("July".to_string())
+    );
+  }
+
+  #[test]
+  fn unsupported_locale_is_an_error() {
+    assert!(matches!(
+      format_with_locale("%B", "xx_XX"),
+      ExtensionResult::Error(_)
+    ));
+    assert!(matches!(
+      format_with_locale("%B", "ja"),
+      ExtensionResult::Error(_)
+    ));
+  }
 }
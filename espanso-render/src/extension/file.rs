@@ -0,0 +1,173 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use crate::{Extension, ExtensionOutput, ExtensionResult, Params, Value};
+use log::warn;
+use thiserror::Error;
+
+pub struct FileExtension {
+  config_path: PathBuf,
+  // Contents of the files read so far, along with their modification time,
+  // so that a file is read again only after it changes
+  cache: RefCell<HashMap<PathBuf, (SystemTime, String)>>,
+}
+
+#[allow(clippy::new_without_default)]
+impl FileExtension {
+  pub fn new(config_path: &Path) -> Self {
+    Self {
+      config_path: config_path.to_owned(),
+      cache: RefCell::new(HashMap::new()),
+    }
+  }
+
+  fn read(&self, path: &Path) -> std::io::Result<String> {
+    let modified = std::fs::metadata(path)?.modified()?;
+
+    if let Some((cached_modified, content)) = self.cache.borrow().get(path) {
+      if *cached_modified == modified {
+        return Ok(content.clone());
+      }
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    self
+      .cache
+      .borrow_mut()
+      .insert(path.to_owned(), (modified, content.clone()));
+    Ok(content)
+  }
+}
+
+impl Extension for FileExtension {
+  fn name(&self) -> &str {
+    "file"
+  }
+
+  fn calculate(
+    &self,
+    _: &crate::Context,
+    _: &crate::Scope,
+    params: &Params,
+  ) -> crate::ExtensionResult {
+    if let Some(Value::String(path)) = params.get("path") {
+      // Relative paths are resolved from the config directory
+      let path = self.config_path.join(path);
+
+      let content = match self.read(&path) {
+        Ok(content) => content,
+        Err(error) => {
+          warn!(
+            "unable to read file {:?}, expanding to an empty string: {}",
+            path, error
+          );
+          String::new()
+        }
+      };
+
+      let trim = params
+        .get("trim")
+        .and_then(|v| v.as_bool())
+        .copied()
+        .unwrap_or(true);
+
+      let output = if trim {
+        content.trim().to_owned()
+      } else {
+        content
+      };
+
+      ExtensionResult::Success(ExtensionOutput::Single(output))
+    } else {
+      ExtensionResult::Error(FileExtensionError::MissingPathParameter.into())
+    }
+  }
+}
+
+#[derive(Error, Debug)]
+pub enum FileExtensionError {
+  #[error("missing 'path' parameter")]
+  MissingPathParameter,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempdir::TempDir;
+
+  fn path_param(path: &str) -> Params {
+    vec![("path".to_string(), Value::String(path.to_string()))]
+      .into_iter()
+      .collect::<Params>()
+  }
+
+  #[test]
+  fn reads_file_relative_to_config_dir() {
+    let config_dir = TempDir::new("espanso-file-extension").unwrap();
+    std::fs::write(config_dir.path().join("signature.txt"), "Best,\nJohn\n").unwrap();
+    let extension = FileExtension::new(config_dir.path());
+
+    assert_eq!(
+      extension
+        .calculate(
+          &Default::default(),
+          &Default::default(),
+          &path_param("signature.txt")
+        )
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("Best,\nJohn".to_string())
+    );
+  }
+
+  #[test]
+  fn missing_file_expands_to_empty_string() {
+    let config_dir = TempDir::new("espanso-file-extension").unwrap();
+    let extension = FileExtension::new(config_dir.path());
+
+    assert_eq!(
+      extension
+        .calculate(
+          &Default::default(),
+          &Default::default(),
+          &path_param("missing.txt")
+        )
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single(String::new())
+    );
+  }
+
+  #[test]
+  fn missing_path_parameter() {
+    let extension = FileExtension::new(Path::new("."));
+
+    assert!(matches!(
+      extension.calculate(&Default::default(), &Default::default(), &Params::new()),
+      ExtensionResult::Error(_)
+    ));
+  }
+}
@@ -23,10 +23,6 @@ use thiserror::Error;
 
 use crate::{Extension, ExtensionOutput, ExtensionResult, Params, Value};
 
-lazy_static! {
-  static ref EMPTY_PARAMS: Params = Params::new();
-}
-
 pub trait FormProvider {
   fn show(&self, layout: &str, fields: &Params, options: &Params) -> FormProviderResult;
 }
@@ -71,7 +67,12 @@ impl<'a> Extension for FormExtension<'a> {
       Params::new()
     };
 
-    match self.provider.show(layout, &fields, &EMPTY_PARAMS) {
+    let mut options = Params::new();
+    if let Some(order @ Value::Array(_)) = params.get("order") {
+      options.insert("order".to_string(), order.clone());
+    }
+
+    match self.provider.show(layout, &fields, &options) {
       FormProviderResult::Success(values) => {
         ExtensionResult::Success(ExtensionOutput::Multiple(values))
       }
@@ -86,3 +87,48 @@ pub enum FormExtensionError {
   #[error("missing layout parameter")]
   MissingLayout,
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::RefCell;
+
+  struct MockFormProvider {
+    options: RefCell<Option<Params>>,
+  }
+
+  impl super::FormProvider for MockFormProvider {
+    fn show(&self, _: &str, _: &Params, options: &Params) -> FormProviderResult {
+      *self.options.borrow_mut() = Some(options.clone());
+      FormProviderResult::Success(HashMap::new())
+    }
+  }
+
+  #[test]
+  fn order_is_forwarded_to_provider() {
+    let provider = MockFormProvider {
+      options: RefCell::new(None),
+    };
+    let extension = FormExtension::new(&provider);
+
+    let order = Value::Array(vec![
+      Value::String("surname".to_string()),
+      Value::String("name".to_string()),
+    ]);
+    let mut params = Params::new();
+    params.insert(
+      "layout".to_string(),
+      Value::String("[[name]] [[surname]]".to_string()),
+    );
+    params.insert("order".to_string(), order.clone());
+
+    assert!(extension
+      .calculate(&Default::default(), &Default::default(), &params)
+      .into_success()
+      .is_ok());
+    assert_eq!(
+      provider.options.borrow().as_ref().unwrap().get("order"),
+      Some(&order)
+    );
+  }
+}
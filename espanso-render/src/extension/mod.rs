@@ -20,6 +20,7 @@
 pub mod clipboard;
 pub mod date;
 pub mod echo;
+pub mod file;
 pub mod form;
 pub mod random;
 pub mod script;
@@ -20,6 +20,7 @@
 use std::{
   path::{Path, PathBuf},
   process::Command,
+  time::Duration,
 };
 
 use crate::{Extension, ExtensionOutput, ExtensionResult, Params, Value};
@@ -30,15 +31,22 @@ pub struct ScriptExtension {
   home_path: PathBuf,
   config_path: PathBuf,
   packages_path: PathBuf,
+  default_timeout: Option<Duration>,
 }
 
 #[allow(clippy::new_without_default)]
 impl ScriptExtension {
-  pub fn new(config_path: &Path, home_path: &Path, packages_path: &Path) -> Self {
+  pub fn new(
+    config_path: &Path,
+    home_path: &Path,
+    packages_path: &Path,
+    default_timeout: Option<Duration>,
+  ) -> Self {
     Self {
       config_path: config_path.to_owned(),
       home_path: home_path.to_owned(),
       packages_path: packages_path.to_owned(),
+      default_timeout,
     }
   }
 }
@@ -96,14 +104,22 @@ impl Extension for ScriptExtension {
       // Set the OS-specific flags
       super::util::set_command_flags(&mut command);
 
-      let output = if args.len() > 1 {
-        command.args(&args[1..]).output()
-      } else {
-        command.output()
-      };
+      if args.len() > 1 {
+        command.args(&args[1..]);
+      }
+
+      let timeout = super::util::resolve_timeout(params, self.default_timeout);
 
-      match output {
-        Ok(output) => {
+      match super::util::output_with_timeout(&mut command, timeout) {
+        Ok(None) => {
+          warn!(
+            "script {:?} did not complete within {:?} and was killed, expanding to an empty string",
+            args,
+            timeout.unwrap_or_default()
+          );
+          ExtensionResult::Success(ExtensionOutput::Single(String::new()))
+        }
+        Ok(Some(output)) => {
           let output_str = String::from_utf8_lossy(&output.stdout);
           let error_str = String::from_utf8_lossy(&output.stderr);
 
@@ -183,7 +199,7 @@ mod tests {
   use crate::Scope;
 
   fn get_extension() -> ScriptExtension {
-    ScriptExtension::new(&PathBuf::new(), &PathBuf::new(), &PathBuf::new())
+    ScriptExtension::new(&PathBuf::new(), &PathBuf::new(), &PathBuf::new(), None)
   }
 
   #[test]
@@ -334,4 +350,35 @@ mod tests {
       ExtensionOutput::Single("".to_string())
     );
   }
+
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn timeout_kills_script() {
+    let extension = ScriptExtension::new(
+      &PathBuf::new(),
+      &PathBuf::new(),
+      &PathBuf::new(),
+      Some(Duration::from_millis(200)),
+    );
+
+    let param = vec![(
+      "args".to_string(),
+      Value::Array(vec![
+        Value::String("sleep".to_string()),
+        Value::String("10".to_string()),
+      ]),
+    )]
+    .into_iter()
+    .collect::<Params>();
+
+    let start = std::time::Instant::now();
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &param)
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("".to_string())
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+  }
 }
@@ -21,10 +21,11 @@ use std::{
   collections::HashMap,
   path::{Path, PathBuf},
   process::{Command, Output},
+  time::Duration,
 };
 
 use crate::{Extension, ExtensionOutput, ExtensionResult, Params, Value};
-use log::{error, info};
+use log::{error, info, warn};
 use thiserror::Error;
 
 #[allow(clippy::upper_case_acronyms)]
@@ -38,7 +39,12 @@ pub enum Shell {
 }
 
 impl Shell {
-  fn execute_cmd(&self, cmd: &str, vars: &HashMap<String, String>) -> std::io::Result<Output> {
+  fn execute_cmd(
+    &self,
+    cmd: &str,
+    vars: &HashMap<String, String>,
+    timeout: Option<Duration>,
+  ) -> std::io::Result<Option<Output>> {
     let mut is_wsl = false;
 
     let mut command = match self {
@@ -99,7 +105,7 @@ impl Shell {
       command.env("WSLENV", wsl_env);
     }
 
-    command.output()
+    super::util::output_with_timeout(&mut command, timeout)
   }
 
   fn from_string(shell: &str) -> Option<Shell> {
@@ -131,13 +137,15 @@ impl Default for Shell {
 
 pub struct ShellExtension {
   config_path: PathBuf,
+  default_timeout: Option<Duration>,
 }
 
 #[allow(clippy::new_without_default)]
 impl ShellExtension {
-  pub fn new(config_path: &Path) -> Self {
+  pub fn new(config_path: &Path, default_timeout: Option<Duration>) -> Self {
     Self {
       config_path: config_path.to_owned(),
+      default_timeout,
     }
   }
 }
@@ -172,8 +180,18 @@ impl Extension for ShellExtension {
         self.config_path.to_string_lossy().to_string(),
       );
 
-      match shell.execute_cmd(cmd, &env_variables) {
-        Ok(output) => {
+      let timeout = super::util::resolve_timeout(params, self.default_timeout);
+
+      match shell.execute_cmd(cmd, &env_variables, timeout) {
+        Ok(None) => {
+          warn!(
+            "shell command '{}' did not complete within {:?} and was killed, expanding to an empty string",
+            cmd,
+            timeout.unwrap_or_default()
+          );
+          ExtensionResult::Success(ExtensionOutput::Single(String::new()))
+        }
+        Ok(Some(output)) => {
           let output_str = String::from_utf8_lossy(&output.stdout);
           let error_str = String::from_utf8_lossy(&output.stderr);
 
@@ -248,7 +266,7 @@ mod tests {
 
   #[test]
   fn shell_not_trimmed() {
-    let extension = ShellExtension::new(&PathBuf::new());
+    let extension = ShellExtension::new(&PathBuf::new(), None);
 
     let param = vec![
       (
@@ -280,7 +298,7 @@ mod tests {
 
   #[test]
   fn shell_trimmed() {
-    let extension = ShellExtension::new(&PathBuf::new());
+    let extension = ShellExtension::new(&PathBuf::new(), None);
 
     let param = vec![(
       "cmd".to_string(),
@@ -301,7 +319,7 @@ mod tests {
   #[test]
   #[cfg(not(target_os = "windows"))]
   fn pipes() {
-    let extension = ShellExtension::new(&PathBuf::new());
+    let extension = ShellExtension::new(&PathBuf::new(), None);
 
     let param = vec![(
       "cmd".to_string(),
@@ -320,7 +338,7 @@ mod tests {
 
   #[test]
   fn var_injection() {
-    let extension = ShellExtension::new(&PathBuf::new());
+    let extension = ShellExtension::new(&PathBuf::new(), None);
 
     let param = if cfg!(not(target_os = "windows")) {
       vec![(
@@ -353,7 +371,7 @@ mod tests {
 
   #[test]
   fn invalid_command() {
-    let extension = ShellExtension::new(&PathBuf::new());
+    let extension = ShellExtension::new(&PathBuf::new(), None);
 
     let param = vec![(
       "cmd".to_string(),
@@ -370,7 +388,7 @@ mod tests {
   #[test]
   #[cfg(not(target_os = "windows"))]
   fn exit_error() {
-    let extension = ShellExtension::new(&PathBuf::new());
+    let extension = ShellExtension::new(&PathBuf::new(), None);
 
     let param = vec![("cmd".to_string(), Value::String("exit 1".to_string()))]
       .into_iter()
@@ -380,4 +398,50 @@ mod tests {
       ExtensionResult::Error(_)
     ));
   }
+
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn timeout_kills_command() {
+    let extension = ShellExtension::new(&PathBuf::new(), Some(Duration::from_secs(30)));
+
+    let param = vec![
+      ("cmd".to_string(), Value::String("sleep 10".to_string())),
+      (
+        "timeout".to_string(),
+        Value::Number(crate::Number::Integer(200)),
+      ),
+    ]
+    .into_iter()
+    .collect::<Params>();
+
+    let start = std::time::Instant::now();
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &param)
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single(String::new())
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+  }
+
+  #[test]
+  #[cfg(not(target_os = "windows"))]
+  fn command_completing_within_timeout() {
+    let extension = ShellExtension::new(&PathBuf::new(), Some(Duration::from_secs(5)));
+
+    let param = vec![(
+      "cmd".to_string(),
+      Value::String("echo \"hello world\"".to_string()),
+    )]
+    .into_iter()
+    .collect::<Params>();
+    assert_eq!(
+      extension
+        .calculate(&Default::default(), &Default::default(), &param)
+        .into_success()
+        .unwrap(),
+      ExtensionOutput::Single("hello world".to_string())
+    );
+  }
 }
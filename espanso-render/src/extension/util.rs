@@ -17,8 +17,16 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::{ExtensionOutput, Scope};
-use std::{collections::HashMap, process::Command};
+use crate::{ExtensionOutput, Number, Params, Scope, Value};
+use log::error;
+use std::{
+  collections::HashMap,
+  io::Read,
+  process::{Child, Command, Output, Stdio},
+  thread::JoinHandle,
+  time::Duration,
+};
+use wait_timeout::ChildExt;
 
 pub fn convert_to_env_variables(scope: &Scope) -> HashMap<String, String> {
   let mut output = HashMap::new();
@@ -41,6 +49,106 @@ pub fn convert_to_env_variables(scope: &Scope) -> HashMap<String, String> {
   output
 }
 
+// Determine the execution timeout of a command, giving precedence to the
+// `timeout` param (in milliseconds) over the given default. A value of 0
+// disables the timeout.
+pub fn resolve_timeout(params: &Params, default_timeout: Option<Duration>) -> Option<Duration> {
+  match params.get("timeout") {
+    Some(Value::Number(Number::Integer(0))) => None,
+    Some(Value::Number(Number::Integer(millis))) if *millis > 0 => {
+      Some(Duration::from_millis(*millis as u64))
+    }
+    _ => default_timeout,
+  }
+}
+
+// Execute the command and collect its output, as `Command::output` would.
+// If the command doesn't terminate within the timeout, the child process
+// and the ones it spawned are killed, and None is returned.
+pub fn output_with_timeout(
+  command: &mut Command,
+  timeout: Option<Duration>,
+) -> std::io::Result<Option<Output>> {
+  let timeout = match timeout {
+    Some(timeout) => timeout,
+    None => return command.output().map(Some),
+  };
+
+  set_process_group(command);
+
+  let mut child = command
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()?;
+
+  // The pipes have to be drained while waiting, otherwise a command
+  // producing a lot of output would block once the pipe buffer is full
+  let stdout_reader = spawn_pipe_reader(child.stdout.take());
+  let stderr_reader = spawn_pipe_reader(child.stderr.take());
+
+  match child.wait_timeout(timeout)? {
+    Some(status) => Ok(Some(Output {
+      status,
+      stdout: stdout_reader.join().unwrap_or_default(),
+      stderr: stderr_reader.join().unwrap_or_default(),
+    })),
+    None => {
+      kill_process_tree(&mut child)?;
+      child.wait()?;
+
+      // The reader threads are not joined, as the pipes might still be held
+      // open by processes spawned by the command itself
+      Ok(None)
+    }
+  }
+}
+
+fn spawn_pipe_reader<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+  std::thread::spawn(move || {
+    let mut buffer = Vec::new();
+    if let Some(mut pipe) = pipe {
+      if let Err(err) = pipe.read_to_end(&mut buffer) {
+        error!("unable to read command output: {}", err);
+      }
+    }
+    buffer
+  })
+}
+
+// Run the command in its own process group, so that the processes it spawns
+// (such as the ones of a shell pipeline) can be killed along with it
+#[cfg(unix)]
+fn set_process_group(command: &mut Command) {
+  use std::os::unix::process::CommandExt;
+  command.process_group(0);
+}
+
+#[cfg(not(unix))]
+fn set_process_group(_: &mut Command) {}
+
+#[cfg(unix)]
+fn kill_process_tree(child: &mut Child) -> std::io::Result<()> {
+  // A negative pid targets the whole process group, whose id is the one of the child
+  let result = unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+  if result != 0 {
+    return Err(std::io::Error::last_os_error());
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn kill_process_tree(child: &mut Child) -> std::io::Result<()> {
+  // Windows has no process groups, but taskkill can terminate the child processes too
+  let mut taskkill = Command::new("taskkill");
+  taskkill.args(&["/T", "/F", "/PID", &child.id().to_string()]);
+  set_command_flags(&mut taskkill);
+  match taskkill.output() {
+    Ok(output) if output.status.success() => Ok(()),
+    _ => child.kill(),
+  }
+}
+
 #[cfg(target_os = "windows")]
 pub fn set_command_flags(command: &mut Command) {
   use std::os::windows::process::CommandExt;
@@ -72,4 +180,60 @@ mod tests {
     assert_eq!(output.get("ESPANSO_FORM1_LASTNAME").unwrap(), "Snow");
     assert_eq!(output.get("ESPANSO_VAR1").unwrap(), "test");
   }
+
+  #[test]
+  fn resolve_timeout_param_takes_precedence() {
+    let default_timeout = Some(Duration::from_millis(1000));
+    let mut params = Params::new();
+    assert_eq!(resolve_timeout(&params, default_timeout), default_timeout);
+
+    params.insert("timeout".to_string(), Value::Number(Number::Integer(200)));
+    assert_eq!(
+      resolve_timeout(&params, default_timeout),
+      Some(Duration::from_millis(200))
+    );
+
+    params.insert("timeout".to_string(), Value::Number(Number::Integer(0)));
+    assert_eq!(resolve_timeout(&params, default_timeout), None);
+  }
+
+  // Killed processes are zombies until their new parent reaps them
+  #[cfg(target_os = "linux")]
+  fn is_running(pid: libc::pid_t) -> bool {
+    let exists = unsafe { libc::kill(pid, 0) } == 0;
+    exists
+      && !std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .map(|stat| stat.contains(") Z "))
+        .unwrap_or(false)
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn timeout_kills_spawned_processes() {
+    let dir = tempdir::TempDir::new("espanso-timeout").unwrap();
+    let pid_file = dir.path().join("pid");
+
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(format!(
+      "sleep 30 & echo $! > {}; wait",
+      pid_file.to_string_lossy()
+    ));
+    let output = output_with_timeout(&mut command, Some(Duration::from_millis(500))).unwrap();
+    assert!(output.is_none());
+
+    let pid: libc::pid_t = std::fs::read_to_string(&pid_file)
+      .unwrap()
+      .trim()
+      .parse()
+      .unwrap();
+
+    let start = std::time::Instant::now();
+    while is_running(pid) {
+      assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "spawned process is still alive"
+      );
+      std::thread::sleep(Duration::from_millis(50));
+    }
+  }
 }
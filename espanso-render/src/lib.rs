@@ -31,6 +31,8 @@ pub trait Renderer {
     -> RenderResult;
 }
 
+pub use renderer::apply_casing_style;
+
 pub fn create(extensions: Vec<&dyn Extension>) -> impl Renderer + '_ {
   renderer::DefaultRenderer::new(extensions)
 }
@@ -75,6 +77,14 @@ pub enum CasingStyle {
   Capitalize,
   CapitalizeWords,
   Uppercase,
+
+  // The case of each letter of the trigger is applied to the corresponding
+  // letter of the output, as long as they have the same number of letters.
+  // Otherwise, the fallback style is used instead.
+  Pattern {
+    uppercase: Vec<bool>,
+    fallback: Box<CasingStyle>,
+  },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -96,22 +106,27 @@ impl Default for Template {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Variable {
+  pub id: i32,
   pub name: String,
   pub var_type: String,
   pub inject_vars: bool,
   pub params: Params,
   // Name of the variables this variable depends on
   pub depends_on: Vec<String>,
+  // If true, the output is computed once and cached for the whole session
+  pub persist: bool,
 }
 
 impl Default for Variable {
   fn default() -> Self {
     Self {
+      id: 0,
       name: "".to_string(),
       var_type: "".to_string(),
       inject_vars: true,
       params: Params::new(),
       depends_on: Vec::new(),
+      persist: false,
     }
   }
 }
@@ -141,7 +156,7 @@ pub trait Extension {
 
 pub type Scope<'a> = HashMap<&'a str, ExtensionOutput>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ExtensionOutput {
   Single(String),
   Multiple(HashMap<String, String>),
@@ -27,8 +27,10 @@ use log::{error, warn};
 use regex::{Captures, Regex};
 use thiserror::Error;
 
+use self::persist::PersistedVars;
 use self::util::{inject_variables_into_params, render_variables};
 
+mod persist;
 mod resolve;
 mod util;
 
@@ -40,6 +42,7 @@ lazy_static! {
 
 pub(crate) struct DefaultRenderer<'a> {
   extensions: HashMap<String, &'a dyn Extension>,
+  persisted_vars: PersistedVars,
 }
 
 impl<'a> DefaultRenderer<'a> {
@@ -48,7 +51,10 @@ impl<'a> DefaultRenderer<'a> {
       .into_iter()
       .map(|ext| (ext.name().to_string(), ext))
       .collect();
-    Self { extensions }
+    Self {
+      extensions,
+      persisted_vars: PersistedVars::default(),
+    }
   }
 }
 
@@ -97,6 +103,13 @@ impl<'a> Renderer for DefaultRenderer<'a> {
       // Compute the variable outputs
       let mut scope = Scope::new();
       for variable in variables {
+        if variable.persist {
+          if let Some(output) = self.persisted_vars.get(variable.id) {
+            scope.insert(&variable.name, output);
+            continue;
+          }
+        }
+
         if variable.var_type == "match" {
           // Recursive call
           // Call render recursively
@@ -139,6 +152,9 @@ impl<'a> Renderer for DefaultRenderer<'a> {
 
           match extension.calculate(context, &scope, &variable_params) {
             ExtensionResult::Success(output) => {
+              if variable.persist {
+                self.persisted_vars.insert(variable.id, output.clone());
+              }
               scope.insert(&variable.name, output);
             }
             ExtensionResult::Aborted => {
@@ -172,39 +188,69 @@ impl<'a> Renderer for DefaultRenderer<'a> {
         }
       }
     } else {
-      template.body.clone()
+      util::unescape_variable_inections(&template.body)
     };
 
-    let body = util::unescape_variable_inections(&body);
-
     // Process the casing style
-    let body_with_casing = match options.casing_style {
-      CasingStyle::None => body,
-      CasingStyle::Uppercase => body.to_uppercase(),
-      CasingStyle::Capitalize => {
-        // Capitalize the first letter
-        let mut v: Vec<char> = body.chars().collect();
-        v[0] = v[0].to_uppercase().next().unwrap();
-        v.into_iter().collect()
+    let body_with_casing = apply_casing_style(body, &options.casing_style);
+
+    RenderResult::Success(body_with_casing)
+  }
+}
+
+pub fn apply_casing_style(body: String, casing_style: &CasingStyle) -> String {
+  match casing_style {
+    CasingStyle::None => body,
+    CasingStyle::Uppercase => body.to_uppercase(),
+    CasingStyle::Capitalize => {
+      // Capitalize the first letter
+      let mut chars = body.chars();
+      match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => body,
       }
-      CasingStyle::CapitalizeWords => {
-        // Capitalize the first letter of each word
-        WORD_REGEX
-          .replace_all(&body, |caps: &Captures| {
-            if let Some(word_match) = caps.get(0) {
-              let mut v: Vec<char> = word_match.as_str().chars().collect();
-              v[0] = v[0].to_uppercase().next().unwrap();
-              let capitalized_word: String = v.into_iter().collect();
-              capitalized_word
-            } else {
-              "".to_string()
-            }
-          })
-          .to_string()
+    }
+    CasingStyle::CapitalizeWords => {
+      // Capitalize the first letter of each word
+      WORD_REGEX
+        .replace_all(&body, |caps: &Captures| {
+          if let Some(word_match) = caps.get(0) {
+            let mut v: Vec<char> = word_match.as_str().chars().collect();
+            v[0] = v[0].to_uppercase().next().unwrap();
+            let capitalized_word: String = v.into_iter().collect();
+            capitalized_word
+          } else {
+            "".to_string()
+          }
+        })
+        .to_string()
+    }
+    CasingStyle::Pattern {
+      uppercase,
+      fallback,
+    } => {
+      let letter_count = body.chars().filter(|c| c.is_alphabetic()).count();
+      if letter_count != uppercase.len() {
+        return apply_casing_style(body, fallback);
       }
-    };
 
-    RenderResult::Success(body_with_casing)
+      // Non-alphabetic characters are kept as they are
+      let mut pattern = uppercase.iter();
+      body
+        .chars()
+        .map(|c| {
+          if !c.is_alphabetic() {
+            return c.to_string();
+          }
+
+          if *pattern.next().unwrap_or(&false) {
+            c.to_uppercase().to_string()
+          } else {
+            c.to_lowercase().to_string()
+          }
+        })
+        .collect()
+    }
   }
 }
 
@@ -384,6 +430,58 @@ mod tests {
     assert!(matches!(res, RenderResult::Success(str) if str == "PLAIN BODY"));
   }
 
+  #[test]
+  fn no_variable_pattern() {
+    let renderer = get_renderer();
+    let res = renderer.render(
+      &template_for_str("the"),
+      &Default::default(),
+      &RenderOptions {
+        casing_style: CasingStyle::Pattern {
+          uppercase: vec![false, true, false],
+          fallback: Box::new(CasingStyle::None),
+        },
+      },
+    );
+    assert!(matches!(res, RenderResult::Success(str) if str == "tHe"));
+  }
+
+  #[test]
+  fn pattern_skips_non_alphabetic_characters() {
+    assert_eq!(
+      apply_casing_style(
+        "it's".to_string(),
+        &CasingStyle::Pattern {
+          uppercase: vec![true, false, true],
+          fallback: Box::new(CasingStyle::None),
+        }
+      ),
+      "It'S"
+    );
+  }
+
+  #[test]
+  fn capitalize_empty_body() {
+    assert_eq!(
+      apply_casing_style("".to_string(), &CasingStyle::Capitalize),
+      ""
+    );
+  }
+
+  #[test]
+  fn pattern_with_different_length_uses_fallback() {
+    assert_eq!(
+      apply_casing_style(
+        "because".to_string(),
+        &CasingStyle::Pattern {
+          uppercase: vec![true, true, false],
+          fallback: Box::new(CasingStyle::Capitalize),
+        }
+      ),
+      "Because"
+    );
+  }
+
   #[test]
   fn basic_variable() {
     let renderer = get_renderer();
@@ -947,4 +1045,86 @@ mod tests {
     let res = renderer.render(&template, &Default::default(), &Default::default());
     assert!(matches!(res, RenderResult::Success(str) if str == "hello {{var}}"));
   }
+
+  #[test]
+  fn variable_escape_mixed_with_variables() {
+    let renderer = get_renderer();
+    let template = template(
+      "\\{\\{var\\}\\} is replaced with {{var}}",
+      &[("var", "world")],
+    );
+    let res = renderer.render(&template, &Default::default(), &Default::default());
+    assert!(matches!(res, RenderResult::Success(str) if str == "{{var}} is replaced with world"));
+  }
+
+  #[test]
+  fn variable_escape_preserves_variable_output() {
+    let renderer = get_renderer();
+    let mut template = template("hello {{var}}", &[("var", "\\{\\{raw\\}\\}")]);
+    template.vars[0].inject_vars = false;
+    let res = renderer.render(&template, &Default::default(), &Default::default());
+    assert!(matches!(res, RenderResult::Success(str) if str == "hello \\{\\{raw\\}\\}"));
+  }
+
+  // Returns an increasing number on each call
+  #[derive(Default)]
+  struct CounterExtension {
+    count: std::sync::atomic::AtomicUsize,
+  }
+
+  impl Extension for CounterExtension {
+    fn name(&self) -> &str {
+      "counter"
+    }
+
+    fn calculate(&self, _: &Context, _: &Scope, _: &crate::Params) -> ExtensionResult {
+      let count = self.count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+      ExtensionResult::Success(ExtensionOutput::Single(count.to_string()))
+    }
+  }
+
+  fn counter_template(id: i32, persist: bool) -> Template {
+    Template {
+      ids: vec!["id".to_string()],
+      body: "{{count}}".to_string(),
+      vars: vec![Variable {
+        id,
+        name: "count".to_string(),
+        var_type: "counter".to_string(),
+        persist,
+        ..Default::default()
+      }],
+    }
+  }
+
+  fn render_to_string(renderer: &impl Renderer, template: &Template) -> String {
+    match renderer.render(template, &Default::default(), &Default::default()) {
+      RenderResult::Success(output) => output,
+      result => panic!("unexpected render result: {:?}", result),
+    }
+  }
+
+  #[test]
+  fn persisted_variable_is_stable_across_renders() {
+    let extension = CounterExtension::default();
+    let renderer = DefaultRenderer::new(vec![&extension]);
+    let template = counter_template(1, true);
+
+    assert_eq!(render_to_string(&renderer, &template), "0");
+    assert_eq!(render_to_string(&renderer, &template), "0");
+
+    // Other persisted variables are cached separately
+    assert_eq!(render_to_string(&renderer, &counter_template(2, true)), "1");
+    assert_eq!(render_to_string(&renderer, &counter_template(2, true)), "1");
+  }
+
+  #[test]
+  fn non_persisted_variable_is_evaluated_on_each_render() {
+    let extension = CounterExtension::default();
+    let renderer = DefaultRenderer::new(vec![&extension]);
+    let template = counter_template(1, false);
+
+    assert_eq!(render_to_string(&renderer, &template), "0");
+    assert_eq!(render_to_string(&renderer, &template), "1");
+  }
 }
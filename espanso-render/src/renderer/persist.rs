@@ -0,0 +1,71 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::ExtensionOutput;
+
+// Outputs of the variables marked with `persist: true`, keyed by variable id.
+// They are computed on the first render and then reused for the whole
+// lifetime of the renderer (that is, the current session).
+#[derive(Default)]
+pub(crate) struct PersistedVars {
+  outputs: Mutex<HashMap<i32, ExtensionOutput>>,
+}
+
+impl PersistedVars {
+  pub fn get(&self, id: i32) -> Option<ExtensionOutput> {
+    self
+      .outputs
+      .lock()
+      .expect("unable to lock persisted vars")
+      .get(&id)
+      .cloned()
+  }
+
+  pub fn insert(&self, id: i32, output: ExtensionOutput) {
+    self
+      .outputs
+      .lock()
+      .expect("unable to lock persisted vars")
+      .insert(id, output);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn persisted_vars_are_keyed_by_id() {
+    let vars = PersistedVars::default();
+    assert_eq!(vars.get(1), None);
+
+    vars.insert(1, ExtensionOutput::Single("first".to_string()));
+    vars.insert(2, ExtensionOutput::Single("second".to_string()));
+    assert_eq!(
+      vars.get(1),
+      Some(ExtensionOutput::Single("first".to_string()))
+    );
+    assert_eq!(
+      vars.get(2),
+      Some(ExtensionOutput::Single("second".to_string()))
+    );
+  }
+}
@@ -19,11 +19,11 @@
 
 use crate::{renderer::RendererError, ExtensionOutput, Params, Scope, Value};
 use anyhow::Result;
-use log::error;
+use log::{error, warn};
 use regex::Captures;
 
 use super::VAR_REGEX;
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
 
 pub(crate) fn get_body_variable_names(body: &str) -> HashSet<&str> {
   let mut variables = HashSet::new();
@@ -68,48 +68,96 @@ fn get_value_variable_names_recursively(value: &Value) -> HashSet<&str> {
 
 pub(crate) fn render_variables(body: &str, scope: &Scope) -> Result<String> {
   let mut replacing_error = None;
-  let output = VAR_REGEX
-    .replace_all(body, |caps: &Captures| {
-      let var_name = caps.name("name").unwrap().as_str();
-      let var_subname = caps.name("subname");
-      match scope.get(var_name) {
-        Some(output) => match output {
-          ExtensionOutput::Single(output) => output,
-          ExtensionOutput::Multiple(results) => match var_subname {
-            Some(var_subname) => {
-              let var_subname = var_subname.as_str();
-              results.get(var_subname).map_or("", |value| &*value)
-            }
-            None => {
-              error!(
-                "nested name missing from multi-value variable: {}",
-                var_name
-              );
-              replacing_error = Some(RendererError::MissingVariable(format!(
-                "nested name missing from multi-value variable: {}",
-                var_name
-              )));
-              ""
-            }
-          },
-        },
+  let mut output = String::with_capacity(body.len());
+  let mut last_end = 0;
+
+  for caps in VAR_REGEX.captures_iter(body) {
+    let var_match = caps.get(0).unwrap();
+
+    // Escaped braces are only converted in the template text, so that literal
+    // braces produced by a variable are preserved.
+    output.push_str(&unescape_variable_inections(
+      &body[last_end..var_match.start()],
+    ));
+    output.push_str(&render_variable(&caps, scope, &mut replacing_error));
+    last_end = var_match.end();
+  }
+  output.push_str(&unescape_variable_inections(&body[last_end..]));
+
+  if let Some(error) = replacing_error {
+    return Err(error.into());
+  }
+
+  Ok(output)
+}
+
+fn render_variable<'a>(
+  caps: &Captures,
+  scope: &'a Scope,
+  replacing_error: &mut Option<RendererError>,
+) -> Cow<'a, str> {
+  let var_name = caps.name("name").unwrap().as_str();
+  let var_subname = caps.name("subname");
+  match scope.get(var_name) {
+    Some(output) => match output {
+      ExtensionOutput::Single(output) => match var_subname {
+        Some(var_subname) => Cow::Owned(extract_json_field(var_name, output, var_subname.as_str())),
+        None => Cow::Borrowed(output),
+      },
+      ExtensionOutput::Multiple(results) => match var_subname {
+        Some(var_subname) => {
+          let var_subname = var_subname.as_str();
+          Cow::Borrowed(results.get(var_subname).map_or("", |value| &*value))
+        }
         None => {
-          replacing_error = Some(RendererError::MissingVariable(format!(
-            "variable '{}' is missing",
+          error!(
+            "nested name missing from multi-value variable: {}",
+            var_name
+          );
+          *replacing_error = Some(RendererError::MissingVariable(format!(
+            "nested name missing from multi-value variable: {}",
             var_name
           )));
-          ""
+          Cow::Borrowed("")
         }
-      }
-    })
-    .to_string();
-
-  if let Some(error) = replacing_error {
-    return Err(error.into());
+      },
+    },
+    None => {
+      *replacing_error = Some(RendererError::MissingVariable(format!(
+        "variable '{}' is missing",
+        var_name
+      )));
+      Cow::Borrowed("")
+    }
   }
+}
 
-  let unescaped_output = unescape_variable_inections(&output);
-  Ok(unescaped_output)
+// Single-value outputs (such as the one of a shell command) can be accessed
+// with `{{var.field}}` when they contain a JSON object. Strings are inserted
+// without quotes, while other values are inserted as JSON.
+fn extract_json_field(var_name: &str, output: &str, field: &str) -> String {
+  let json: serde_json::Value = match serde_json::from_str(output) {
+    Ok(json) => json,
+    Err(err) => {
+      warn!(
+        "unable to extract field '{}' from variable '{}', the value is not valid JSON: {}",
+        field, var_name, err
+      );
+      return String::new();
+    }
+  };
+
+  match json.get(field) {
+    Some(serde_json::Value::String(value)) => value.clone(),
+    Some(value) => value.to_string(),
+    None => {
+      warn!(
+        "field '{}' is missing from the JSON value of variable '{}'",
+        field, var_name
+      );
+      String::new()
+    }
+  }
 }
 
 pub(crate) fn unescape_variable_inections(body: &str) -> String {
@@ -212,4 +260,37 @@ mod tests {
       matches!(result.get("field4").unwrap(), Value::Object(fields) if fields.get("subfield1").unwrap() == &Value::String("also contains one".to_string()))
     );
   }
+
+  #[test]
+  fn render_variables_extracts_json_fields() {
+    let mut scope = Scope::new();
+    let output =
+      ExtensionOutput::Single(r#"{"name": "espanso", "stars": 42, "tags": ["a"]}"#.to_string());
+    scope.insert("repo", output);
+
+    assert_eq!(
+      render_variables("{{repo.name}} has {{repo.stars}} stars", &scope).unwrap(),
+      "espanso has 42 stars"
+    );
+    assert_eq!(
+      render_variables("{{repo.tags}}", &scope).unwrap(),
+      r#"["a"]"#
+    );
+  }
+
+  #[test]
+  fn render_variables_missing_json_field_is_empty() {
+    let mut scope = Scope::new();
+    let json = ExtensionOutput::Single(r#"{"name": "espanso"}"#.to_string());
+    let plain = ExtensionOutput::Single("not json".to_string());
+    scope.insert("repo", json);
+    scope.insert("plain", plain);
+
+    assert_eq!(
+      render_variables("[{{repo.missing}}]", &scope).unwrap(),
+      "[]"
+    );
+    assert_eq!(render_variables("[{{plain.name}}]", &scope).unwrap(), "[]");
+    assert_eq!(render_variables("{{plain}}", &scope).unwrap(), "not json");
+  }
 }
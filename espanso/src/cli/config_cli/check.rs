@@ -0,0 +1,111 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::path::Path;
+
+use espanso_config::error::{ErrorLevel, NonFatalErrorSet};
+use espanso_path::Paths;
+
+use crate::exit_code::{
+  CONFIG_CHECK_ERRORS_FOUND, CONFIG_CHECK_SUCCESS, CONFIG_CHECK_UNEXPECTED_FAILURE,
+};
+
+pub fn check_main(paths: &Paths) -> i32 {
+  check_config_dir(&paths.config, &paths.packages)
+}
+
+fn check_config_dir(config_dir: &Path, packages_dir: &Path) -> i32 {
+  // Legacy configs can only be loaded as a whole
+  if espanso_config::is_legacy_config(config_dir) {
+    return match espanso_config::load_legacy(config_dir, packages_dir) {
+      Ok(_) => CONFIG_CHECK_SUCCESS,
+      Err(err) => {
+        eprintln!("unable to load legacy config: {:?}", err);
+        CONFIG_CHECK_ERRORS_FOUND
+      }
+    };
+  }
+
+  let error_sets = match espanso_config::check(config_dir) {
+    Ok(error_sets) => error_sets,
+    Err(err) => {
+      eprintln!("unable to check config: {:?}", err);
+      return CONFIG_CHECK_UNEXPECTED_FAILURE;
+    }
+  };
+
+  report_errors(&error_sets);
+
+  if has_errors(&error_sets) {
+    CONFIG_CHECK_ERRORS_FOUND
+  } else {
+    CONFIG_CHECK_SUCCESS
+  }
+}
+
+fn report_errors(error_sets: &[NonFatalErrorSet]) {
+  for error_set in error_sets {
+    eprintln!(">>> {}", error_set.file.to_string_lossy());
+    for record in &error_set.errors {
+      let level = match record.level {
+        ErrorLevel::Error => "error",
+        ErrorLevel::Warning => "warning",
+      };
+      eprintln!("[{}] {:?}", level, record.error);
+    }
+  }
+}
+
+// Warnings are reported, but don't make the check fail
+fn has_errors(error_sets: &[NonFatalErrorSet]) -> bool {
+  error_sets.iter().any(|error_set| {
+    error_set
+      .errors
+      .iter()
+      .any(|record| record.level == ErrorLevel::Error)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::tests::load_test_config;
+
+  #[test]
+  fn valid_config_succeeds() {
+    let config = load_test_config("matches:\n  - trigger: hello\n    replace: world\n");
+    let dir = config.dir.path();
+
+    assert_eq!(
+      check_config_dir(dir, &dir.join("packages")),
+      CONFIG_CHECK_SUCCESS
+    );
+  }
+
+  #[test]
+  fn broken_match_file_fails() {
+    let config = load_test_config("matches:\n  - trigger: \"broken\n");
+    let dir = config.dir.path();
+
+    assert_eq!(
+      check_config_dir(dir, &dir.join("packages")),
+      CONFIG_CHECK_ERRORS_FOUND
+    );
+  }
+}
@@ -0,0 +1,44 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use espanso_path::Paths;
+
+pub fn export_main(cli_args: &ArgMatches, paths: &Paths) -> Result<()> {
+  let output = cli_args.value_of("output").expect("missing output path");
+
+  let config = crate::config::load_config(&paths.config, &paths.packages, &paths.runtime)?;
+  let match_set = config.match_store.query(&config.match_store.loaded_paths());
+
+  let yaml = espanso_config::matches::export::export_yaml(&match_set)?;
+  std::fs::write(Path::new(output), yaml)
+    .with_context(|| format!("unable to write export file: {}", output))?;
+
+  println!(
+    "exported {} matches and {} global variables to: {}",
+    match_set.matches.len(),
+    match_set.global_vars.len(),
+    output
+  );
+
+  Ok(())
+}
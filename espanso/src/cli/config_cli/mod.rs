@@ -0,0 +1,50 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::{CliModule, CliModuleArgs};
+
+mod check;
+mod export;
+
+pub fn new() -> CliModule {
+  CliModule {
+    requires_paths: true,
+    subcommand: "config".to_string(),
+    entry: config_main,
+    ..Default::default()
+  }
+}
+
+fn config_main(args: CliModuleArgs) -> i32 {
+  let cli_args = args.cli_args.expect("missing cli_args");
+  let paths = args.paths.expect("missing paths");
+
+  if cli_args.subcommand_matches("check").is_some() {
+    return check::check_main(&paths);
+  } else if let Some(sub_args) = cli_args.subcommand_matches("export") {
+    if let Err(err) = export::export_main(sub_args, &paths) {
+      eprintln!("unable to export matches: {:?}", err);
+      return 1;
+    }
+    return 0;
+  }
+
+  eprintln!("Invalid use, please run 'espanso config --help' to get more information.");
+  1
+}
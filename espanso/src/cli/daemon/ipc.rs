@@ -26,35 +26,78 @@ use log::{error, warn};
 
 use crate::{exit_code::DAEMON_SUCCESS, ipc::IPCEvent};
 
-pub fn initialize_and_spawn(runtime_dir: &Path, exit_notify: Sender<i32>) -> Result<()> {
+pub fn initialize_and_spawn(
+  runtime_dir: &Path,
+  exit_notify: Sender<i32>,
+  reload_notify: Sender<()>,
+) -> Result<()> {
   let server = crate::ipc::create_daemon_ipc_server(runtime_dir)?;
 
   std::thread::Builder::new()
     .name("daemon-ipc-handler".to_string())
     .spawn(move || {
       server
-        .run(Box::new(move |event| match event {
-          IPCEvent::Exit => {
-            if let Err(err) = exit_notify.send(DAEMON_SUCCESS) {
-              error!(
-                "experienced error while sending exit signal from daemon ipc handler: {}",
-                err
-              );
-            }
-
-            EventHandlerResponse::NoResponse
-          }
-          unexpected_event => {
-            warn!(
-              "received unexpected event in daemon ipc handler: {:?}",
-              unexpected_event
-            );
-
-            EventHandlerResponse::NoResponse
-          }
+        .run(Box::new(move |event| {
+          handle_event(event, &exit_notify, &reload_notify)
         }))
         .expect("unable to start IPC handler");
     })?;
 
   Ok(())
 }
+
+fn handle_event(
+  event: IPCEvent,
+  exit_notify: &Sender<i32>,
+  reload_notify: &Sender<()>,
+) -> EventHandlerResponse<IPCEvent> {
+  match event {
+    IPCEvent::Exit => {
+      if let Err(err) = exit_notify.send(DAEMON_SUCCESS) {
+        error!(
+          "experienced error while sending exit signal from daemon ipc handler: {}",
+          err
+        );
+      }
+
+      EventHandlerResponse::NoResponse
+    }
+    IPCEvent::ReloadRequest => {
+      // Unlike the changes detected by the watcher, this is applied even
+      // if `auto_restart` is disabled
+      if let Err(err) = reload_notify.send(()) {
+        error!(
+          "experienced error while sending reload signal from daemon ipc handler: {}",
+          err
+        );
+      }
+
+      EventHandlerResponse::NoResponse
+    }
+    unexpected_event => {
+      warn!(
+        "received unexpected event in daemon ipc handler: {:?}",
+        unexpected_event
+      );
+
+      EventHandlerResponse::NoResponse
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crossbeam::channel::unbounded;
+
+  #[test]
+  fn reload_request_notifies_reload_channel() {
+    let (exit_notify, exit_signal) = unbounded::<i32>();
+    let (reload_notify, reload_signal) = unbounded::<()>();
+
+    handle_event(IPCEvent::ReloadRequest, &exit_notify, &reload_notify);
+
+    assert!(reload_signal.try_recv().is_ok());
+    assert!(exit_signal.try_recv().is_err());
+  }
+}
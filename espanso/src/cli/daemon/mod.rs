@@ -44,6 +44,8 @@ use super::{CliModule, CliModuleArgs, PathsOverrides};
 
 mod ipc;
 mod keyboard_layout_watcher;
+#[cfg(unix)]
+mod sighup;
 mod troubleshoot;
 mod watcher;
 
@@ -93,8 +95,46 @@ fn daemon_main(args: CliModuleArgs) -> i32 {
 
   let (watcher_notify, watcher_signal) = unbounded::<()>();
 
-  watcher::initialize_and_spawn(&paths.config, watcher_notify)
-    .expect("unable to initialize config watcher thread");
+  // Reload requests received through IPC (`espanso reload`) or, on Unix, with
+  // a SIGHUP, have their own channel, as they should be applied even if
+  // `auto_restart` is disabled
+  let (reload_notify, reload_signal) = unbounded::<()>();
+
+  #[cfg(unix)]
+  if let Err(err) = sighup::initialize_and_spawn(reload_notify.clone()) {
+    error!(
+      "unable to initialize SIGHUP handler, reloading with SIGHUP is disabled: {:?}",
+      err
+    );
+  }
+
+  // The config directory might have been removed after the launcher populated it
+  if !paths.config.is_dir() {
+    if let Err(err) = std::fs::create_dir_all(&paths.config) {
+      error!(
+        "unable to create config directory {:?}: {}",
+        paths.config, err
+      );
+    }
+  }
+  // Without the watcher, config changes can still be applied with `espanso reload`
+  let mut watched_dirs = crate::config::base_config_dirs();
+  watched_dirs.push(paths.config.clone());
+  // Populated once the config is loaded, as they depend on the secrets_path
+  // and watcher_quiet_period options
+  let ignored_paths = watcher::IgnoredPaths::default();
+  let quiet_period = watcher::QuietPeriod::default();
+  if let Err(err) = watcher::initialize_and_spawn(
+    &watched_dirs,
+    ignored_paths.clone(),
+    quiet_period.clone(),
+    watcher_notify,
+  ) {
+    error!(
+      "unable to initialize config watcher, automatic reload is disabled: {:?}",
+      err
+    );
+  }
 
   let (_keyboard_layout_watcher_notify, keyboard_layout_watcher_signal) = unbounded::<()>();
 
@@ -114,6 +154,7 @@ fn daemon_main(args: CliModuleArgs) -> i32 {
     ) {
       Ok((result, guard)) => {
         _current_troubleshoot_guard = guard;
+        update_watcher(&ignored_paths, &quiet_period, &result, &paths.config);
         result.config_store
       }
       Err(err) => {
@@ -133,39 +174,39 @@ fn daemon_main(args: CliModuleArgs) -> i32 {
 
   spawn_worker(&paths_overrides, exit_notify.clone(), None);
 
-  ipc::initialize_and_spawn(&paths.runtime, exit_notify.clone())
+  ipc::initialize_and_spawn(&paths.runtime, exit_notify.clone(), reload_notify)
     .expect("unable to initialize ipc server for daemon");
 
   loop {
     select! {
       recv(watcher_signal) -> _ => {
+        // Manual reloads use their own channel, so they are not affected by this
         if !config_store.default().auto_restart() {
           continue;
         }
 
         info!("configuration change detected, restarting worker process...");
-
-        // Before killing the previous worker, we make sure there is no fatal error
-        // in the configs.
-        let should_restart_worker = match troubleshoot::load_config_or_troubleshoot(&paths, &paths_overrides) {
-          troubleshoot::LoadResult::Correct(_) => {
-            _current_troubleshoot_guard = None;
-            true
-          },
-          troubleshoot::LoadResult::Warning(_, guard) => {
-            _current_troubleshoot_guard = guard;
-            true
-          }
-          troubleshoot::LoadResult::Fatal(guard) => {
-            _current_troubleshoot_guard = Some(guard);
-            error!("critical error while loading config, could not restart worker");
-            false
-          }
-        };
-
-        if should_restart_worker {
-          restart_worker(&paths, &paths_overrides, exit_notify.clone(), Some(WORKER_START_REASON_CONFIG_CHANGED.to_string()));
-        }
+        reload_config_and_restart_worker(
+          &paths,
+          &paths_overrides,
+          &ignored_paths,
+          &quiet_period,
+          &mut _current_troubleshoot_guard,
+          exit_notify.clone(),
+          WORKER_START_REASON_CONFIG_CHANGED,
+        );
+      }
+      recv(reload_signal) -> _ => {
+        info!("reload requested, restarting worker process...");
+        reload_config_and_restart_worker(
+          &paths,
+          &paths_overrides,
+          &ignored_paths,
+          &quiet_period,
+          &mut _current_troubleshoot_guard,
+          exit_notify.clone(),
+          WORKER_START_REASON_MANUAL,
+        );
       }
       recv(keyboard_layout_watcher_signal) -> _ => {
         info!("keyboard layout change detected, restarting worker...");
@@ -201,6 +242,20 @@ fn daemon_main(args: CliModuleArgs) -> i32 {
   DAEMON_SUCCESS
 }
 
+// The secrets file can live inside the config directory, but changing it
+// shouldn't restart the worker, as it's only read at startup
+fn update_watcher(
+  ignored_paths: &watcher::IgnoredPaths,
+  quiet_period: &watcher::QuietPeriod,
+  result: &crate::config::ConfigLoadResult,
+  config_dir: &Path,
+) {
+  let default_config = result.config_store.default();
+  let secrets_path = crate::config::secrets_path(&*default_config, config_dir);
+  ignored_paths.set(secrets_path.into_iter().collect());
+  quiet_period.set(default_config.watcher_quiet_period());
+}
+
 fn terminate_worker_if_already_running(runtime_dir: &Path) {
   let lock_file = acquire_worker_lock(runtime_dir);
   if lock_file.is_some() {
@@ -283,6 +338,46 @@ fn spawn_worker(
     .expect("Unable to spawn worker monitor thread");
 }
 
+// Before killing the previous worker, we make sure there is no fatal error
+// in the configs.
+fn reload_config_and_restart_worker(
+  paths: &Paths,
+  paths_overrides: &PathsOverrides,
+  ignored_paths: &watcher::IgnoredPaths,
+  quiet_period: &watcher::QuietPeriod,
+  current_troubleshoot_guard: &mut Option<troubleshoot::TroubleshootGuard>,
+  exit_notify: Sender<i32>,
+  start_reason: &str,
+) {
+  let should_restart_worker =
+    match troubleshoot::load_config_or_troubleshoot(paths, paths_overrides) {
+      troubleshoot::LoadResult::Correct(result) => {
+        *current_troubleshoot_guard = None;
+        update_watcher(ignored_paths, quiet_period, &result, &paths.config);
+        true
+      }
+      troubleshoot::LoadResult::Warning(result, guard) => {
+        *current_troubleshoot_guard = guard;
+        update_watcher(ignored_paths, quiet_period, &result, &paths.config);
+        true
+      }
+      troubleshoot::LoadResult::Fatal(guard) => {
+        *current_troubleshoot_guard = Some(guard);
+        error!("critical error while loading config, could not restart worker");
+        false
+      }
+    };
+
+  if should_restart_worker {
+    restart_worker(
+      paths,
+      paths_overrides,
+      exit_notify,
+      Some(start_reason.to_string()),
+    );
+  }
+}
+
 fn restart_worker(
   paths: &Paths,
   paths_overrides: &PathsOverrides,
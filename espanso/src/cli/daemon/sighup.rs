@@ -0,0 +1,105 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{bail, Result};
+use crossbeam::channel::Sender;
+use log::{error, info};
+
+const SIGHUP_POLL_INTERVAL_MS: u64 = 250;
+
+static SIGHUP_RECEIVED: AtomicBool = AtomicBool::new(false);
+
+// Sending a SIGHUP to the daemon forces a config reload, even if the file
+// watcher missed the change (for example, on network file systems) or
+// `auto_restart` is disabled. The signal is forwarded on the same channel
+// used by `espanso reload`, and multiple signals received within a poll
+// interval only trigger one reload.
+pub fn initialize_and_spawn(reload_notify: Sender<()>) -> Result<()> {
+  register_handler()?;
+
+  std::thread::Builder::new()
+    .name("sighup-watcher".to_string())
+    .spawn(move || {
+      watcher_main(&reload_notify);
+    })?;
+
+  Ok(())
+}
+
+extern "C" fn handle_sighup(_: libc::c_int) {
+  // Only async-signal-safe operations are allowed here, so we just
+  // raise a flag and let the watcher thread do the rest.
+  SIGHUP_RECEIVED.store(true, Ordering::SeqCst);
+}
+
+fn register_handler() -> Result<()> {
+  let handler = handle_sighup as extern "C" fn(libc::c_int);
+  let result = unsafe { libc::signal(libc::SIGHUP, handler as libc::sighandler_t) };
+  if result == libc::SIG_ERR {
+    bail!("unable to register SIGHUP handler");
+  }
+
+  Ok(())
+}
+
+fn watcher_main(reload_notify: &Sender<()>) {
+  loop {
+    std::thread::sleep(std::time::Duration::from_millis(SIGHUP_POLL_INTERVAL_MS));
+
+    if !check_and_forward(reload_notify) {
+      break;
+    }
+  }
+}
+
+// Returns false if the receiving end of the channel has been dropped
+fn check_and_forward(reload_notify: &Sender<()>) -> bool {
+  if SIGHUP_RECEIVED.swap(false, Ordering::SeqCst) {
+    info!("received SIGHUP, requesting config reload");
+
+    if let Err(error) = reload_notify.send(()) {
+      error!("unable to send SIGHUP reload event: {}", error);
+      return false;
+    }
+  }
+
+  true
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crossbeam::channel::unbounded;
+
+  #[test]
+  fn sighup_is_forwarded_to_reload_channel() {
+    let (notify, signal) = unbounded::<()>();
+    register_handler().unwrap();
+
+    assert!(check_and_forward(&notify));
+    assert!(signal.try_recv().is_err());
+
+    unsafe { libc::raise(libc::SIGHUP) };
+
+    assert!(check_and_forward(&notify));
+    assert!(signal.try_recv().is_ok());
+    assert!(!SIGHUP_RECEIVED.load(Ordering::SeqCst));
+  }
+}
@@ -17,53 +17,188 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::{path::Path, time::Duration};
+use std::{
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+  },
+  time::{Duration, Instant},
+};
 
 use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
 
 use anyhow::Result;
-use crossbeam::{channel::Sender, select};
+use crossbeam::channel::{Receiver, RecvTimeoutError, Sender};
+use espanso_config::matches::is_match_group_file;
 use log::{error, info, warn};
+use thiserror::Error;
 
 const WATCHER_NOTIFY_DELAY_MS: u64 = 500;
-const WATCHER_DEBOUNCE_DURATION_MS: u64 = 1000;
 
-pub fn initialize_and_spawn(config_dir: &Path, watcher_notify: Sender<()>) -> Result<()> {
-  let config_dir = config_dir.to_path_buf();
+// Used until the config is loaded
+const DEFAULT_WATCHER_QUIET_PERIOD_MS: u64 = 1000;
+
+// How often the watched roots are checked for existence, as some platforms
+// don't report when the root itself is renamed
+const ROOT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const REWATCH_INITIAL_DELAY: Duration = Duration::from_secs(1);
+const REWATCH_MAX_DELAY: Duration = Duration::from_secs(60);
+
+// Files inside the watched directories whose changes never trigger a reload,
+// such as the secrets file. The list can be updated after the watcher has
+// been spawned, as it depends on the loaded config.
+#[derive(Clone, Default)]
+pub struct IgnoredPaths {
+  paths: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl IgnoredPaths {
+  pub fn set(&self, paths: Vec<PathBuf>) {
+    let paths = paths.iter().map(|path| normalize_path(path)).collect();
+    *self.paths.lock().expect("unable to lock ignored paths") = paths;
+  }
+
+  pub fn contains(&self, path: &Path) -> bool {
+    let path = normalize_path(path);
+    self
+      .paths
+      .lock()
+      .expect("unable to lock ignored paths")
+      .iter()
+      .any(|ignored| ignored == &path)
+  }
+}
+
+// Time without further changes after which a reload is triggered. Like the
+// ignored paths, it can be updated once the config has been loaded.
+#[derive(Clone)]
+pub struct QuietPeriod {
+  millis: Arc<AtomicU64>,
+}
+
+impl Default for QuietPeriod {
+  fn default() -> Self {
+    Self {
+      millis: Arc::new(AtomicU64::new(DEFAULT_WATCHER_QUIET_PERIOD_MS)),
+    }
+  }
+}
+
+impl QuietPeriod {
+  pub fn set(&self, millis: u64) {
+    self.millis.store(millis, Ordering::Relaxed);
+  }
+
+  pub fn get(&self) -> Duration {
+    Duration::from_millis(self.millis.load(Ordering::Relaxed))
+  }
+}
+
+// The file might not exist (for example, after a removal), so we canonicalize
+// the parent directory when possible
+fn normalize_path(path: &Path) -> PathBuf {
+  if let Ok(path) = std::fs::canonicalize(path) {
+    return path;
+  }
+
+  if let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) {
+    if let Ok(parent) = std::fs::canonicalize(parent) {
+      return parent.join(file_name);
+    }
+  }
+
+  path.to_path_buf()
+}
+
+// All the given directories are watched, so that changes
+// to any of the config roots trigger a reload
+pub fn initialize_and_spawn(
+  config_dirs: &[PathBuf],
+  ignored_paths: IgnoredPaths,
+  quiet_period: QuietPeriod,
+  watcher_notify: Sender<()>,
+) -> Result<()> {
+  if let Some(missing_dir) = config_dirs.iter().find(|dir| !dir.is_dir()) {
+    return Err(WatcherError::MissingDir(missing_dir.clone()).into());
+  }
+
+  let config_dirs = config_dirs.to_vec();
 
   let (debounce_tx, debounce_rx) = crossbeam::channel::unbounded();
+  let (init_tx, init_rx) = crossbeam::channel::unbounded();
 
   std::thread::Builder::new()
     .name("watcher".to_string())
     .spawn(move || {
-      watcher_main(&config_dir, debounce_tx);
+      watcher_main(&config_dirs, &ignored_paths, debounce_tx, init_tx);
     })?;
 
+  // Wait for the initialization status, so that errors are reported to the caller
+  match init_rx.recv() {
+    Ok(Ok(())) => {}
+    Ok(Err(error)) => return Err(WatcherError::InitFailed(error).into()),
+    Err(_) => return Err(WatcherError::InitFailed("watcher thread terminated".to_string()).into()),
+  }
+
   std::thread::Builder::new()
     .name("watcher-debouncer".to_string())
     .spawn(move || {
-      debouncer_main(debounce_rx, &watcher_notify);
+      debouncer_main(debounce_rx, &quiet_period, &watcher_notify);
     })?;
 
   Ok(())
 }
 
-fn watcher_main(config_dir: &Path, debounce_tx: crossbeam::channel::Sender<()>) {
+fn watcher_main(
+  config_dirs: &[PathBuf],
+  ignored_paths: &IgnoredPaths,
+  debounce_tx: crossbeam::channel::Sender<()>,
+  init_tx: Sender<std::result::Result<(), String>>,
+) {
   let (tx, rx) = std::sync::mpsc::channel();
 
-  let mut watcher: RecommendedWatcher =
-    Watcher::new(tx, Duration::from_millis(WATCHER_NOTIFY_DELAY_MS))
-      .expect("unable to create file watcher");
+  let watcher: notify::Result<RecommendedWatcher> =
+    Watcher::new(tx, Duration::from_millis(WATCHER_NOTIFY_DELAY_MS));
+  let init_result = watcher.and_then(|mut watcher| {
+    for config_dir in config_dirs {
+      watcher.watch(config_dir, RecursiveMode::Recursive)?;
+    }
+    Ok(watcher)
+  });
+
+  // The watcher must be kept alive for the whole loop below
+  let mut watcher = match init_result {
+    Ok(watcher) => {
+      if init_tx.send(Ok(())).is_err() {
+        error!("unable to send watcher initialization status");
+      }
+      watcher
+    }
+    Err(error) => {
+      if init_tx.send(Err(error.to_string())).is_err() {
+        error!("unable to send watcher initialization status");
+      }
+      return;
+    }
+  };
 
-  watcher
-    .watch(&config_dir, RecursiveMode::Recursive)
-    .expect("unable to start file watcher");
+  info!("watching for changes in paths: {:?}", config_dirs);
 
-  info!("watching for changes in path: {:?}", config_dir);
+  let mut lost_roots = LostRoots::default();
+  let mut last_root_check = Instant::now();
 
   loop {
-    let should_reload = match rx.recv() {
+    let mut should_reload = match rx.recv_timeout(lost_roots.poll_interval()) {
       Ok(event) => {
+        if let Some(root) = lost_root(&event, config_dirs) {
+          warn!(
+            "watched directory {:?} was removed or renamed, espanso will try to watch it again when it reappears",
+            root
+          );
+          lost_roots.lose(root);
+        }
+
         let path = match event {
           DebouncedEvent::Create(path) => Some(path),
           DebouncedEvent::Write(path) => Some(path),
@@ -72,30 +207,47 @@ fn watcher_main(config_dir: &Path, debounce_tx: crossbeam::channel::Sender<()>)
           _ => None,
         };
 
-        if let Some(path) = path {
-          let extension = path
-            .extension()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_ascii_lowercase();
-
-          if ["yml", "yaml"].iter().any(|ext| ext == &extension) {
-            // Only load non-hidden yml files
-            !is_file_hidden(&path)
-          } else {
-            // If there is no extension, it's probably a folder
-            extension.is_empty()
-          }
-        } else {
-          false
-        }
+        path
+          .map(|path| should_reload(&path, ignored_paths))
+          .unwrap_or(false)
       }
+      Err(std::sync::mpsc::RecvTimeoutError::Timeout) => false,
       Err(e) => {
         warn!("error while watching files: {:?}", e);
         false
       }
     };
 
+    if last_root_check.elapsed() >= ROOT_CHECK_INTERVAL {
+      last_root_check = Instant::now();
+      for root in config_dirs {
+        if !root.is_dir() && !lost_roots.contains(root) {
+          warn!(
+            "watched directory {:?} no longer exists, espanso will try to watch it again when it reappears",
+            root
+          );
+          lost_roots.lose(root.clone());
+        }
+      }
+    }
+
+    for root in lost_roots.due(Instant::now()) {
+      // The old watch is stale, so errors while removing it are expected
+      let _ = watcher.unwatch(&root);
+      match watcher.watch(&root, RecursiveMode::Recursive) {
+        Ok(_) => {
+          info!("watching for changes in {:?} again", root);
+          lost_roots.recovered(&root);
+          // The content might have changed while the directory was gone
+          should_reload = true;
+        }
+        Err(error) => {
+          warn!("unable to watch {:?} again: {}", root, error);
+          lost_roots.retry_later(&root, Instant::now());
+        }
+      }
+    }
+
     if should_reload {
       if let Err(error) = debounce_tx.send(()) {
         error!(
@@ -107,23 +259,130 @@ fn watcher_main(config_dir: &Path, debounce_tx: crossbeam::channel::Sender<()>)
   }
 }
 
-fn debouncer_main(debounce_rx: crossbeam::channel::Receiver<()>, watcher_notify: &Sender<()>) {
-  let mut has_received_event = false;
+// Returns the watched root affected by the event, if the event reports that
+// the root itself was removed or moved away
+fn lost_root(event: &DebouncedEvent, config_dirs: &[PathBuf]) -> Option<PathBuf> {
+  let path = match event {
+    DebouncedEvent::Remove(path) => path,
+    DebouncedEvent::Rename(from, _) => from,
+    _ => return None,
+  };
 
-  loop {
-    select! {
-      recv(debounce_rx) -> _ => {
-        has_received_event = true;
-      },
-      default(Duration::from_millis(WATCHER_DEBOUNCE_DURATION_MS)) => {
-        if has_received_event {
-          if let Err(error) = watcher_notify.send(()) {
-            error!("unable to send watcher file changed event: {}", error);
-          }
-        }
+  config_dirs.iter().find(|dir| *dir == path).cloned()
+}
+
+struct LostRoot {
+  path: PathBuf,
+  delay: Duration,
+  next_attempt: Instant,
+}
 
-        has_received_event = false;
-      },
+// Watched roots that disappeared, together with the time of the next attempt
+// to watch them again. The delay doubles after each failed attempt.
+#[derive(Default)]
+struct LostRoots {
+  roots: Vec<LostRoot>,
+}
+
+impl LostRoots {
+  fn lose(&mut self, path: PathBuf) {
+    if self.contains(&path) {
+      return;
+    }
+
+    self.roots.push(LostRoot {
+      path,
+      delay: REWATCH_INITIAL_DELAY,
+      next_attempt: Instant::now() + REWATCH_INITIAL_DELAY,
+    });
+  }
+
+  fn contains(&self, path: &Path) -> bool {
+    self.roots.iter().any(|root| root.path == path)
+  }
+
+  // Returns the roots that should be watched again, which are the ones
+  // whose attempt is due and that exist on disk again
+  fn due(&mut self, now: Instant) -> Vec<PathBuf> {
+    let mut due = Vec::new();
+    for root in self.roots.iter_mut() {
+      if root.next_attempt > now {
+        continue;
+      }
+
+      if root.path.is_dir() {
+        due.push(root.path.clone());
+      } else {
+        Self::backoff(root, now);
+      }
+    }
+    due
+  }
+
+  fn recovered(&mut self, path: &Path) {
+    self.roots.retain(|root| root.path != path);
+  }
+
+  fn retry_later(&mut self, path: &Path, now: Instant) {
+    if let Some(root) = self.roots.iter_mut().find(|root| root.path == path) {
+      Self::backoff(root, now);
+    }
+  }
+
+  fn backoff(root: &mut LostRoot, now: Instant) {
+    root.delay = (root.delay * 2).min(REWATCH_MAX_DELAY);
+    root.next_attempt = now + root.delay;
+  }
+
+  fn poll_interval(&self) -> Duration {
+    if self.roots.is_empty() {
+      ROOT_CHECK_INTERVAL
+    } else {
+      REWATCH_INITIAL_DELAY
+    }
+  }
+}
+
+fn should_reload(path: &Path, ignored_paths: &IgnoredPaths) -> bool {
+  if ignored_paths.contains(path) {
+    return false;
+  }
+
+  // Config files are YAML, which is also a match group format
+  if is_match_group_file(path) {
+    // Only load non-hidden files
+    !is_file_hidden(path)
+  } else {
+    // If there is no extension, it's probably a folder
+    path.extension().is_none()
+  }
+}
+
+fn debouncer_main(
+  debounce_rx: Receiver<()>,
+  quiet_period: &QuietPeriod,
+  watcher_notify: &Sender<()>,
+) {
+  while wait_for_coalesced_events(&debounce_rx, quiet_period.get()) {
+    if let Err(error) = watcher_notify.send(()) {
+      error!("unable to send watcher file changed event: {}", error);
+    }
+  }
+}
+
+// Block until an event is received, and then keep consuming the following ones
+// until no new event arrives for the whole quiet period (the timer is reset on
+// each event). This way, a burst of changes (for example, after a git checkout)
+// results in a single reload. Returns false when the channel is closed.
+fn wait_for_coalesced_events(rx: &Receiver<()>, quiet_period: Duration) -> bool {
+  if rx.recv().is_err() {
+    return false;
+  }
+
+  loop {
+    match rx.recv_timeout(quiet_period) {
+      Ok(_) => continue,
+      Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => return true,
     }
   }
 }
@@ -154,3 +413,221 @@ fn has_hidden_attribute(path: &Path) -> bool {
 fn has_hidden_attribute(_: &Path) -> bool {
   false
 }
+
+#[derive(Error, Debug)]
+pub enum WatcherError {
+  #[error("config directory does not exist: {0:?}")]
+  MissingDir(PathBuf),
+
+  #[error("unable to start file watcher: {0}")]
+  InitFailed(String),
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Instant;
+  use tempdir::TempDir;
+
+  #[test]
+  fn initialize_and_spawn_missing_dir_returns_error() {
+    let dir = TempDir::new("espanso-watcher").unwrap();
+    let missing = dir.path().join("missing");
+    let (tx, _rx) = crossbeam::channel::unbounded();
+
+    let result = initialize_and_spawn(
+      &[dir.path().to_path_buf(), missing],
+      IgnoredPaths::default(),
+      QuietPeriod::default(),
+      tx,
+    );
+
+    assert!(matches!(
+      result.unwrap_err().downcast_ref::<WatcherError>(),
+      Some(WatcherError::MissingDir(_))
+    ));
+  }
+
+  #[test]
+  fn initialize_and_spawn_existing_dir_succeeds() {
+    let dir = TempDir::new("espanso-watcher").unwrap();
+    let (tx, _rx) = crossbeam::channel::unbounded();
+
+    assert!(initialize_and_spawn(
+      &[dir.path().to_path_buf()],
+      IgnoredPaths::default(),
+      QuietPeriod::default(),
+      tx
+    )
+    .is_ok());
+  }
+
+  #[test]
+  fn should_reload_yaml_files_and_folders() {
+    let ignored_paths = IgnoredPaths::default();
+    assert!(should_reload(
+      Path::new("/config/default.yml"),
+      &ignored_paths
+    ));
+    assert!(should_reload(Path::new("/match/base.YAML"), &ignored_paths));
+    assert!(should_reload(Path::new("/match/folder"), &ignored_paths));
+    assert!(!should_reload(
+      Path::new("/match/.hidden.yml"),
+      &ignored_paths
+    ));
+    assert!(!should_reload(
+      Path::new("/match/notes.txt"),
+      &ignored_paths
+    ));
+  }
+
+  #[test]
+  fn should_reload_json_and_toml_match_files() {
+    let ignored_paths = IgnoredPaths::default();
+    assert!(should_reload(Path::new("/match/base.json"), &ignored_paths));
+    assert!(should_reload(Path::new("/match/base.toml"), &ignored_paths));
+    assert!(!should_reload(
+      Path::new("/match/.hidden.json"),
+      &ignored_paths
+    ));
+  }
+
+  #[test]
+  fn should_reload_skips_secrets_file() {
+    let dir = TempDir::new("espanso-watcher").unwrap();
+    let secrets_path = dir.path().join("secrets.yml");
+    std::fs::write(&secrets_path, "api_key: abc").unwrap();
+    let other_path = dir.path().join("other.yml");
+
+    let ignored_paths = IgnoredPaths::default();
+    assert!(should_reload(&secrets_path, &ignored_paths));
+
+    ignored_paths.set(vec![secrets_path.clone()]);
+    assert!(!should_reload(&secrets_path, &ignored_paths));
+    assert!(should_reload(&other_path, &ignored_paths));
+
+    // Removed files are still ignored
+    std::fs::remove_file(&secrets_path).unwrap();
+    assert!(!should_reload(&secrets_path, &ignored_paths));
+  }
+
+  #[test]
+  fn lost_root_detects_removal_and_rename_of_root() {
+    let root = PathBuf::from("/config");
+    let config_dirs = vec![root.clone()];
+
+    assert_eq!(
+      lost_root(&DebouncedEvent::Remove(root.clone()), &config_dirs),
+      Some(root.clone())
+    );
+    assert_eq!(
+      lost_root(
+        &DebouncedEvent::Rename(root.clone(), PathBuf::from("/config.bak")),
+        &config_dirs
+      ),
+      Some(root.clone())
+    );
+    assert_eq!(
+      lost_root(&DebouncedEvent::Remove(root.join("base.yml")), &config_dirs),
+      None
+    );
+    assert_eq!(lost_root(&DebouncedEvent::Write(root), &config_dirs), None);
+  }
+
+  #[test]
+  fn lost_roots_rewatch_only_when_root_reappears() {
+    let dir = TempDir::new("espanso-watcher").unwrap();
+    let root = dir.path().join("config");
+    std::fs::create_dir(&root).unwrap();
+
+    let mut lost_roots = LostRoots::default();
+    std::fs::remove_dir(&root).unwrap();
+    lost_roots.lose(root.clone());
+    lost_roots.lose(root.clone());
+    assert_eq!(lost_roots.roots.len(), 1);
+    assert_eq!(lost_roots.poll_interval(), REWATCH_INITIAL_DELAY);
+
+    // Not due yet
+    let now = Instant::now();
+    assert!(lost_roots.due(now).is_empty());
+
+    // Due, but the root is still missing, so the delay is increased
+    let later = now + REWATCH_INITIAL_DELAY * 2;
+    assert!(lost_roots.due(later).is_empty());
+    assert_eq!(lost_roots.roots[0].delay, REWATCH_INITIAL_DELAY * 2);
+    assert!(lost_roots.due(later + REWATCH_INITIAL_DELAY).is_empty());
+
+    // Once the root reappears, it should be watched again
+    std::fs::create_dir(&root).unwrap();
+    let even_later = later + REWATCH_INITIAL_DELAY * 4;
+    assert_eq!(lost_roots.due(even_later), vec![root.clone()]);
+
+    lost_roots.recovered(&root);
+    assert!(!lost_roots.contains(&root));
+    assert_eq!(lost_roots.poll_interval(), ROOT_CHECK_INTERVAL);
+  }
+
+  #[test]
+  fn lost_roots_backoff_is_capped() {
+    let mut lost_roots = LostRoots::default();
+    let root = PathBuf::from("/espanso-missing-config-dir");
+    lost_roots.lose(root.clone());
+
+    let now = Instant::now();
+    for _ in 0..20 {
+      lost_roots.retry_later(&root, now);
+    }
+    assert_eq!(lost_roots.roots[0].delay, REWATCH_MAX_DELAY);
+  }
+
+  #[test]
+  fn quiet_period_updates_are_shared_with_clones() {
+    let quiet_period = QuietPeriod::default();
+    let watcher_quiet_period = quiet_period.clone();
+    assert_eq!(
+      watcher_quiet_period.get(),
+      Duration::from_millis(DEFAULT_WATCHER_QUIET_PERIOD_MS)
+    );
+
+    quiet_period.set(250);
+    assert_eq!(watcher_quiet_period.get(), Duration::from_millis(250));
+  }
+
+  #[test]
+  fn wait_for_coalesced_events_consumes_burst() {
+    let (tx, rx) = crossbeam::channel::unbounded();
+    for _ in 0..5 {
+      tx.send(()).unwrap();
+    }
+
+    assert!(wait_for_coalesced_events(&rx, Duration::from_millis(50)));
+    assert!(rx.is_empty());
+  }
+
+  #[test]
+  fn wait_for_coalesced_events_resets_timer_on_each_event() {
+    let (tx, rx) = crossbeam::channel::unbounded();
+    let sender = std::thread::spawn(move || {
+      for _ in 0..5 {
+        tx.send(()).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+      }
+      tx
+    });
+
+    let start = Instant::now();
+    assert!(wait_for_coalesced_events(&rx, Duration::from_millis(200)));
+    assert!(start.elapsed() >= Duration::from_millis(280));
+
+    let _tx = sender.join().unwrap();
+    assert!(rx.is_empty());
+  }
+
+  #[test]
+  fn wait_for_coalesced_events_returns_false_when_closed() {
+    let (tx, rx) = crossbeam::channel::unbounded::<()>();
+    drop(tx);
+
+    assert!(!wait_for_coalesced_events(&rx, Duration::from_millis(50)));
+  }
+}
@@ -18,9 +18,20 @@
  */
 
 use std::io::BufRead;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{fs::File, io::BufReader};
 
+use anyhow::{anyhow, bail, Result};
+use espanso_ipc::IPCClient;
+
 use super::{CliModule, CliModuleArgs};
+use crate::{
+  ipc::{create_ipc_client_to_worker, ExpansionRecord, IPCEvent},
+  lock::acquire_worker_lock,
+};
+
+const DEFAULT_EXPANSIONS_TAIL: usize = 20;
 
 pub fn new() -> CliModule {
   CliModule {
@@ -33,6 +44,19 @@ pub fn new() -> CliModule {
 
 fn log_main(args: CliModuleArgs) -> i32 {
   let paths = args.paths.expect("missing paths argument");
+  let cli_args = args.cli_args.expect("missing cli_args");
+
+  if let Some(sub_args) = cli_args.subcommand_matches("expansions") {
+    let count = match sub_args.value_of("tail").map(parse_tail).transpose() {
+      Ok(count) => count.unwrap_or(DEFAULT_EXPANSIONS_TAIL),
+      Err(err) => {
+        eprintln!("{}", err);
+        return 1;
+      }
+    };
+    return print_expansions(&paths.runtime, count);
+  }
+
   let log_file = paths.runtime.join(crate::LOG_FILE_NAME);
 
   if !log_file.exists() {
@@ -53,3 +77,90 @@ fn log_main(args: CliModuleArgs) -> i32 {
 
   0
 }
+
+fn print_expansions(runtime_dir: &Path, count: usize) -> i32 {
+  let records = match query_expansions(runtime_dir, count) {
+    Ok(records) => records,
+    Err(err) => {
+      eprintln!("unable to retrieve the expansions: {:?}", err);
+      return 1;
+    }
+  };
+
+  if records.is_empty() {
+    // The history is kept by the worker, so it's cleared when the worker restarts
+    println!("No expansions recorded since espanso was last started or reloaded.");
+    return 0;
+  }
+
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs())
+    .unwrap_or_default();
+  for record in records {
+    println!(
+      "{:>10}  id: {:<6} {}",
+      format_age(now.saturating_sub(record.timestamp)),
+      record.match_id,
+      record.trigger.as_deref().unwrap_or("-")
+    );
+  }
+
+  0
+}
+
+fn query_expansions(runtime_dir: &Path, count: usize) -> Result<Vec<ExpansionRecord>> {
+  if acquire_worker_lock(runtime_dir).is_some() {
+    bail!("Worker process is not running, please start Espanso first.")
+  }
+
+  let mut client = create_ipc_client_to_worker(runtime_dir)?;
+  match client.send_sync(IPCEvent::ExpansionHistoryRequest(count))? {
+    IPCEvent::ExpansionHistory(records) => Ok(records),
+    unexpected => bail!("received unexpected response from worker: {:?}", unexpected),
+  }
+}
+
+fn parse_tail(value: &str) -> Result<usize> {
+  value.parse::<usize>().map_err(|_| {
+    anyhow!(
+      "invalid --tail value: '{}', expected a non-negative number of expansions",
+      value
+    )
+  })
+}
+
+fn format_age(seconds: u64) -> String {
+  if seconds < 60 {
+    format!("{}s ago", seconds)
+  } else if seconds < 60 * 60 {
+    format!("{}m ago", seconds / 60)
+  } else if seconds < 60 * 60 * 24 {
+    format!("{}h ago", seconds / (60 * 60))
+  } else {
+    format!("{}d ago", seconds / (60 * 60 * 24))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tail_is_parsed() {
+    assert_eq!(parse_tail("5").unwrap(), 5);
+    assert_eq!(parse_tail("0").unwrap(), 0);
+  }
+
+  #[test]
+  fn invalid_tail_is_rejected() {
+    for value in ["abc", "-1", ""] {
+      let err = parse_tail(value).unwrap_err();
+      assert!(
+        err.to_string().contains("invalid --tail value"),
+        "{}",
+        value
+      );
+    }
+  }
+}
@@ -55,6 +55,8 @@ pub fn print_matches_as_plain(match_list: &[&Match], only_triggers: bool, preser
       MatchCause::None => vec!["(none)".to_string()],
       MatchCause::Trigger(trigger_cause) => trigger_cause.triggers.clone(),
       MatchCause::Regex(regex_cause) => vec![regex_cause.regex.clone()],
+      MatchCause::HotKey(hotkey_cause) => vec![hotkey_cause.hotkey.clone()],
+      MatchCause::KeySequence(key_sequence_cause) => vec![key_sequence_cause.to_spec()],
     };
 
     for trigger in triggers {
@@ -86,6 +88,8 @@ pub fn print_matches_as_json(match_list: &[&Match]) -> Result<()> {
       MatchCause::None => vec!["(none)".to_string()],
       MatchCause::Trigger(trigger_cause) => trigger_cause.triggers.clone(),
       MatchCause::Regex(regex_cause) => vec![regex_cause.regex.clone()],
+      MatchCause::HotKey(hotkey_cause) => vec![hotkey_cause.hotkey.clone()],
+      MatchCause::KeySequence(key_sequence_cause) => vec![key_sequence_cause.to_spec()],
     };
 
     entries.push(JsonMatchEntry {
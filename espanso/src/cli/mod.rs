@@ -24,6 +24,7 @@ use espanso_config::{config::ConfigStore, error::NonFatalErrorSet, matches::stor
 use espanso_path::Paths;
 
 pub mod cmd;
+pub mod config_cli;
 pub mod daemon;
 pub mod edit;
 pub mod env_path;
@@ -34,7 +35,10 @@ pub mod migrate;
 pub mod modulo;
 pub mod package;
 pub mod path;
+pub mod regex_test;
+pub mod reload;
 pub mod service;
+pub mod test;
 pub mod util;
 pub mod workaround;
 pub mod worker;
@@ -50,10 +50,12 @@ pub fn form_main(matches: &ArgMatches, _icon_paths: &IconPaths) -> i32 {
     .as_deref()
     .map(|path| path.to_string_lossy().to_string());
 
-  let form = generator::generate(config);
+  let form = generator::generate(config).expect("invalid form configuration");
+  let order = form.order.clone();
   let values = show(form);
 
-  let output = serde_json::to_string(&values).expect("unable to encode values as JSON");
+  let output = serde_json::to_string(&output::OrderedValues::new(&order, &values))
+    .expect("unable to encode values as JSON");
   println!("{}", output);
 
   0
@@ -39,7 +39,7 @@ pub fn troubleshoot_main(paths: &Paths, icon_paths: &IconPaths) -> i32 {
   });
 
   let (is_fatal_error, error_sets) =
-    match crate::config::load_config(&paths.config, &paths.packages) {
+    match crate::config::load_config(&paths.config, &paths.packages, &paths.runtime) {
       Ok(config_result) => {
         let error_sets = config_result
           .non_fatal_errors
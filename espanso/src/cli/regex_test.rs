@@ -0,0 +1,141 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use anyhow::{anyhow, Result};
+use espanso_match::{
+  event::{Event, Key},
+  regex::{RegexMatch, RegexMatcher, RegexMatcherOptions},
+  MatchResult, Matcher,
+};
+
+use super::{CliModule, CliModuleArgs};
+
+pub fn new() -> CliModule {
+  CliModule {
+    requires_paths: true,
+    requires_config: true,
+    subcommand: "regex-test".to_string(),
+    entry: regex_test_main,
+    ..Default::default()
+  }
+}
+
+fn regex_test_main(args: CliModuleArgs) -> i32 {
+  let cli_args = args.cli_args.expect("missing cli_args");
+  let config_store = args.config_store.expect("missing config_store");
+
+  let pattern = cli_args.value_of("pattern").expect("missing pattern");
+  let input = cli_args.value_of("input").expect("missing input");
+
+  match test_regex(pattern, input, config_store.default().regex_max_lookback()) {
+    Ok(Some(result)) => {
+      print!("{}", format_result(&result));
+      0
+    }
+    Ok(None) => {
+      eprintln!("the regex '{}' does not match the given input", pattern);
+      2
+    }
+    Err(err) => {
+      eprintln!("{}", err);
+      1
+    }
+  }
+}
+
+// The input is fed to the regex matcher one char at a time, as if it was typed,
+// so the result is the first match espanso would detect
+fn test_regex(
+  pattern: &str,
+  input: &str,
+  max_buffer_size: usize,
+) -> Result<Option<MatchResult<i32>>> {
+  let regex_match = RegexMatch::new(0, pattern);
+  regex_match
+    .validate()
+    .map_err(|err| anyhow!("invalid regex '{}': {}", pattern, err))?;
+
+  let matcher = RegexMatcher::new(&[regex_match], RegexMatcherOptions { max_buffer_size });
+  let mut prev_state = None;
+  for c in input.chars() {
+    let (state, matches) = matcher.process(
+      prev_state.as_ref(),
+      Event::Key {
+        key: Key::Other,
+        chars: Some(c.to_string()),
+      },
+    );
+
+    if let Some(result) = matches.into_iter().next() {
+      return Ok(Some(result));
+    }
+    prev_state = Some(state);
+  }
+
+  Ok(None)
+}
+
+fn format_result(result: &MatchResult<i32>) -> String {
+  let mut output = format!("matched: '{}'\n", result.trigger);
+
+  let mut captures: Vec<(&String, &String)> = result.vars.iter().collect();
+  captures.sort();
+  if !captures.is_empty() {
+    output.push_str("captures:\n");
+    for (name, value) in captures {
+      output.push_str(&format!("  {}: '{}'\n", name, value));
+    }
+  }
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn named_captures_are_printed() {
+    let result = test_regex(
+      r":greet\((?P<name>\w+), (?P<greeting>\w+)\)",
+      "say :greet(Bob, hi)",
+      30,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(result.trigger, ":greet(Bob, hi)");
+    assert_eq!(
+      format_result(&result),
+      "matched: ':greet(Bob, hi)'\ncaptures:\n  greeting: 'hi'\n  name: 'Bob'\n"
+    );
+  }
+
+  #[test]
+  fn no_match() {
+    assert!(test_regex(r":greet\((?P<name>\w+)\)", "say hi", 30)
+      .unwrap()
+      .is_none());
+  }
+
+  #[test]
+  fn invalid_regex() {
+    assert!(test_regex(":greet(", ":greet(", 30).is_err());
+  }
+}
@@ -0,0 +1,90 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+  ipc::{create_ipc_client_to_daemon, IPCEvent},
+  lock::acquire_daemon_lock,
+};
+
+use super::{CliModule, CliModuleArgs};
+use anyhow::Result;
+use espanso_ipc::IPCClient;
+
+pub fn new() -> CliModule {
+  CliModule {
+    requires_paths: true,
+    subcommand: "reload".to_string(),
+    entry: reload_main,
+    ..Default::default()
+  }
+}
+
+fn reload_main(args: CliModuleArgs) -> i32 {
+  let paths = args.paths.expect("missing paths");
+
+  if acquire_daemon_lock(&paths.runtime).is_some() {
+    eprintln!("espanso is not running, please start it first.");
+    return 1;
+  }
+
+  let result = create_ipc_client_to_daemon(&paths.runtime)
+    .and_then(|mut client| send_reload_request(&mut client));
+  if let Err(error) = result {
+    eprintln!("unable to send reload request, error: {:?}", error);
+    return 2;
+  }
+
+  println!("reload requested");
+  0
+}
+
+fn send_reload_request(client: &mut impl IPCClient<IPCEvent>) -> Result<()> {
+  client.send_async(IPCEvent::ReloadRequest)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Default)]
+  struct MockIPCClient {
+    sent: Vec<IPCEvent>,
+  }
+
+  impl IPCClient<IPCEvent> for MockIPCClient {
+    fn send_sync(&mut self, event: IPCEvent) -> Result<IPCEvent> {
+      self.sent.push(event);
+      Ok(IPCEvent::Exit)
+    }
+
+    fn send_async(&mut self, event: IPCEvent) -> Result<()> {
+      self.sent.push(event);
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn reload_request_is_sent_to_daemon() {
+    let mut client = MockIPCClient::default();
+    send_reload_request(&mut client).unwrap();
+
+    assert_eq!(client.sent.len(), 1);
+    assert!(matches!(client.sent[0], IPCEvent::ReloadRequest));
+  }
+}
@@ -0,0 +1,394 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use espanso_config::matches::{store::MatchSet, Match, MatchCause, MatchEffect};
+use espanso_info::AppInfo;
+use espanso_render::{
+  Context, Params, RenderOptions, RenderResult, Renderer, Template, Value, Variable,
+};
+
+use super::{
+  worker::engine::process::middleware::render::{
+    convert_to_template, convert_var, load_lazy_replace, select_replacement,
+  },
+  CliModule, CliModuleArgs,
+};
+
+pub fn new() -> CliModule {
+  CliModule {
+    requires_paths: true,
+    requires_config: true,
+    subcommand: "test".to_string(),
+    entry: test_main,
+    ..Default::default()
+  }
+}
+
+fn test_main(args: CliModuleArgs) -> i32 {
+  let cli_args = args.cli_args.expect("missing cli_args");
+  let config_store = args.config_store.expect("missing config_store");
+  let match_store = args.match_store.expect("missing match_store");
+  let paths = args.paths.expect("missing paths");
+
+  // "espanso render" is an alias, so its arguments are nested
+  let cli_args = cli_args.subcommand_matches("render").unwrap_or(&cli_args);
+
+  let trigger = cli_args.value_of("trigger").expect("missing trigger");
+  let overrides = match parse_var_overrides(cli_args.values_of("var").into_iter().flatten()) {
+    Ok(overrides) => overrides,
+    Err(err) => {
+      eprintln!("{}", err);
+      return 1;
+    }
+  };
+
+  let config = config_store.default();
+  let match_set = match_store.query(config.match_paths());
+
+  let home_path = dirs::home_dir().expect("unable to obtain home dir path");
+  let date_extension = espanso_render::extension::date::DateExtension::new();
+  let echo_extension = espanso_render::extension::echo::EchoExtension::new();
+  let dummy_extension = espanso_render::extension::echo::EchoExtension::new_with_alias("dummy");
+  let random_extension = espanso_render::extension::random::RandomExtension::new();
+  let file_extension = espanso_render::extension::file::FileExtension::new(&paths.config);
+  let shell_timeout = match config.shell_timeout() {
+    0 => None,
+    millis => Some(std::time::Duration::from_millis(millis as u64)),
+  };
+  let script_extension = espanso_render::extension::script::ScriptExtension::new(
+    &paths.config,
+    &home_path,
+    &paths.packages,
+    shell_timeout,
+  );
+  let shell_extension =
+    espanso_render::extension::shell::ShellExtension::new(&paths.config, shell_timeout);
+  // Extensions requiring user interaction (such as forms and clipboard) are
+  // not available, as this command is meant to be used non-interactively
+  let renderer = espanso_render::create(vec![
+    &date_extension,
+    &echo_extension,
+    &dummy_extension,
+    &random_extension,
+    &file_extension,
+    &script_extension,
+    &shell_extension,
+  ]);
+
+  match render_trigger(trigger, &match_set, &renderer, &overrides) {
+    Ok(Some(output)) => {
+      println!("{}", output);
+      if let Some(note) = find_match(trigger, &match_set).and_then(describe_alternatives) {
+        eprintln!("note: {}", note);
+      }
+      0
+    }
+    Ok(None) => {
+      eprintln!("no match found for trigger '{}'", trigger);
+      2
+    }
+    Err(err) => {
+      eprintln!("unable to render match: {:?}", err);
+      1
+    }
+  }
+}
+
+fn parse_var_overrides<'a>(
+  values: impl Iterator<Item = &'a str>,
+) -> Result<HashMap<String, String>> {
+  let mut overrides = HashMap::new();
+  for value in values {
+    match value.split_once('=') {
+      Some((name, value)) if !name.is_empty() => {
+        overrides.insert(name.to_string(), value.to_string());
+      }
+      _ => bail!(
+        "invalid format for variable '{}', you should follow the 'name=value' format",
+        value
+      ),
+    }
+  }
+  Ok(overrides)
+}
+
+fn find_match<'a>(trigger: &str, match_set: &MatchSet<'a>) -> Option<&'a Match> {
+  match_set
+    .matches
+    .iter()
+    .find(|m| match &m.cause {
+      MatchCause::Trigger(cause) => cause.triggers.iter().any(|t| t == trigger),
+      _ => false,
+    })
+    .copied()
+}
+
+// The output only shows one of the replacements a match can expand to
+fn describe_alternatives(m: &Match) -> Option<String> {
+  match &m.effect {
+    MatchEffect::Text(effect) if !effect.variants.is_empty() => Some(format!(
+      "the match defines {} app-specific variant(s), which are not applied outside of their applications",
+      effect.variants.len()
+    )),
+    MatchEffect::Text(effect) if !effect.replace_random.is_empty() => Some(format!(
+      "the match chooses randomly among {} replacements, expanding it again might produce a different output",
+      effect.replace_random.len()
+    )),
+    _ => None,
+  }
+}
+
+fn render_trigger(
+  trigger: &str,
+  match_set: &MatchSet,
+  renderer: &dyn Renderer,
+  overrides: &HashMap<String, String>,
+) -> Result<Option<String>> {
+  let m = match find_match(trigger, match_set) {
+    Some(m) => m,
+    None => return Ok(None),
+  };
+
+  let mut template = convert_to_template(m)
+    .ok_or_else(|| anyhow!("the match for trigger '{}' is not a text match", trigger))?;
+
+  // The replacement is selected as in the worker, but there is no active
+  // application, so the variants never apply (they always have filters)
+  let no_app = || AppInfo {
+    title: None,
+    exec: None,
+    class: None,
+  };
+  let replace = match select_replacement(m, no_app, &mut rand::thread_rng()) {
+    Some(replace) => Some(replace.clone()),
+    None => load_lazy_replace(m)?,
+  };
+  if let Some(replace) = replace {
+    template.body = replace;
+  }
+
+  // Overridden variables replace the ones with the same name defined by the match,
+  // and shadow the global ones
+  for (name, value) in overrides {
+    template.vars.retain(|var| &var.name != name);
+
+    let mut params = Params::new();
+    params.insert("echo".to_string(), Value::String(value.clone()));
+    template.vars.insert(
+      0,
+      Variable {
+        name: name.clone(),
+        var_type: "echo".to_string(),
+        params,
+        ..Default::default()
+      },
+    );
+  }
+
+  let templates: Vec<Template> = match_set
+    .matches
+    .iter()
+    .filter_map(|m| convert_to_template(m))
+    .collect();
+  let global_vars: Vec<Variable> = match_set
+    .global_vars
+    .iter()
+    .map(|var| convert_var((*var).clone()))
+    .collect();
+  let context = Context {
+    global_vars: global_vars.iter().collect(),
+    templates: templates.iter().collect(),
+  };
+
+  match renderer.render(&template, &context, &RenderOptions::default()) {
+    RenderResult::Success(body) => Ok(Some(body)),
+    RenderResult::Aborted => bail!("rendering was aborted"),
+    RenderResult::Error(err) => Err(err),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::tests::{load_test_config, load_test_config_with};
+
+  fn with_test_config(callback: impl FnOnce(&MatchSet)) {
+    let config = load_test_config(
+      r#"
+      global_vars:
+        - name: company
+          type: echo
+          params:
+            echo: "ACME"
+
+      matches:
+        - trigger: ":sig"
+          replace: "Best regards, {{name}} from {{company}}"
+          vars:
+            - name: name
+              type: echo
+              params:
+                echo: "John"
+        - trigger: ":img"
+          image_path: "image.png"
+      "#,
+    );
+
+    let match_set = config
+      .match_store
+      .query(config.config_store.default().match_paths());
+    callback(&match_set);
+  }
+
+  #[test]
+  fn known_trigger_renders_expected_text() {
+    with_test_config(|match_set| {
+      let echo_extension = espanso_render::extension::echo::EchoExtension::new();
+      let renderer = espanso_render::create(vec![&echo_extension]);
+
+      assert_eq!(
+        render_trigger(":sig", match_set, &renderer, &HashMap::new()).unwrap(),
+        Some("Best regards, John from ACME".to_string())
+      );
+    });
+  }
+
+  #[test]
+  fn unknown_trigger_returns_none() {
+    with_test_config(|match_set| {
+      let renderer = espanso_render::create(vec![]);
+
+      assert_eq!(
+        render_trigger(":unknown", match_set, &renderer, &HashMap::new()).unwrap(),
+        None
+      );
+    });
+  }
+
+  #[test]
+  fn non_text_match_returns_error() {
+    with_test_config(|match_set| {
+      let renderer = espanso_render::create(vec![]);
+
+      assert!(render_trigger(":img", match_set, &renderer, &HashMap::new()).is_err());
+    });
+  }
+
+  #[test]
+  fn overridden_variables_are_rendered() {
+    with_test_config(|match_set| {
+      let echo_extension = espanso_render::extension::echo::EchoExtension::new();
+      let renderer = espanso_render::create(vec![&echo_extension]);
+
+      let overrides =
+        parse_var_overrides(vec!["name=Jane", "company=Initech"].into_iter()).unwrap();
+      assert_eq!(
+        render_trigger(":sig", match_set, &renderer, &overrides).unwrap(),
+        Some("Best regards, Jane from Initech".to_string())
+      );
+    });
+  }
+
+  #[test]
+  fn alternative_replacements_are_selected_and_reported() {
+    let config = load_test_config(
+      r#"
+      matches:
+        - trigger: ":greet"
+          replace_random:
+            - "hi"
+            - "hey"
+        - trigger: ":sign"
+          replace: "Best regards"
+          variants:
+            - filter_exec: "thunderbird"
+              replace: "Kind regards"
+            - filter_class: "Slack"
+              replace: "Cheers"
+        - trigger: ":plain"
+          replace: "plain"
+      "#,
+    );
+    let match_set = config
+      .match_store
+      .query(config.config_store.default().match_paths());
+    let renderer = espanso_render::create(Vec::new());
+
+    let greeting = render_trigger(":greet", &match_set, &renderer, &HashMap::new())
+      .unwrap()
+      .unwrap();
+    assert!(greeting == "hi" || greeting == "hey");
+
+    // Without an active application, no variant applies
+    assert_eq!(
+      render_trigger(":sign", &match_set, &renderer, &HashMap::new()).unwrap(),
+      Some("Best regards".to_string())
+    );
+
+    let describe = |trigger| find_match(trigger, &match_set).and_then(describe_alternatives);
+    assert!(describe(":greet").unwrap().contains("among 2 replacements"));
+    assert!(describe(":sign")
+      .unwrap()
+      .contains("2 app-specific variant(s)"));
+    assert!(describe(":plain").is_none());
+  }
+
+  #[test]
+  fn lazily_loaded_replace_is_rendered() {
+    let body = "Hello {{name}}, ".repeat(2000);
+    let config = load_test_config_with(
+      "lazy_replace: true",
+      &[(
+        "base.yml",
+        &format!(
+          "matches:\n  - trigger: \":long\"\n    replace: {:?}\n    vars:\n      - name: name\n        type: echo\n        params:\n          echo: \"John\"\n",
+          body
+        ),
+      )],
+    );
+    let match_set = config
+      .match_store
+      .query(config.config_store.default().match_paths());
+    assert!(match_set.matches[0]
+      .effect
+      .as_text()
+      .unwrap()
+      .lazy_replace
+      .is_some());
+
+    let echo_extension = espanso_render::extension::echo::EchoExtension::new();
+    let renderer = espanso_render::create(vec![&echo_extension]);
+    assert_eq!(
+      render_trigger(":long", &match_set, &renderer, &HashMap::new()).unwrap(),
+      Some("Hello John, ".repeat(2000))
+    );
+  }
+
+  #[test]
+  fn parse_var_overrides_rejects_invalid_format() {
+    assert!(parse_var_overrides(vec!["name"].into_iter()).is_err());
+    assert!(parse_var_overrides(vec!["=value"].into_iter()).is_err());
+    assert_eq!(
+      parse_var_overrides(vec!["url=a=b"].into_iter()).unwrap()["url"],
+      "a=b"
+    );
+  }
+}
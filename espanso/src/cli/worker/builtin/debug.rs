@@ -36,6 +36,7 @@ pub fn create_match_paste_active_config_info() -> BuiltInMatch {
       EventType::TextInject(TextInjectRequest {
         text: dump,
         force_mode: None,
+        newline_as_key: false,
       })
     },
     ..Default::default()
@@ -60,6 +61,7 @@ pub fn create_match_paste_active_app_info() -> BuiltInMatch {
       EventType::TextInject(TextInjectRequest {
         text: dump,
         force_mode: None,
+        newline_as_key: false,
       })
     },
     ..Default::default()
@@ -36,6 +36,7 @@ pub struct BuiltInMatch {
   pub label: &'static str,
   pub triggers: Vec<String>,
   pub hotkey: Option<String>,
+  pub key_sequence: Option<String>,
   pub action: fn(context: &dyn Context) -> EventType,
 }
 
@@ -46,6 +47,7 @@ impl Default for BuiltInMatch {
       label: "",
       triggers: Vec::new(),
       hotkey: None,
+      key_sequence: None,
       action: |_| EventType::NOOP,
     }
   }
@@ -59,10 +61,14 @@ pub fn get_builtin_matches(config: &dyn Config) -> Vec<BuiltInMatch> {
     process::create_match_restart(),
   ];
 
-  if config.search_trigger().is_some() || config.search_shortcut().is_some() {
+  if config.search_trigger().is_some()
+    || config.search_shortcut().is_some()
+    || config.search_key_sequence().is_some()
+  {
     matches.push(search::create_match_trigger_search_bar(
       config.search_trigger(),
       config.search_shortcut(),
+      config.search_key_sequence(),
     ));
   }
 
@@ -26,12 +26,14 @@ use super::BuiltInMatch;
 pub fn create_match_trigger_search_bar(
   trigger: Option<String>,
   hotkey: Option<String>,
+  key_sequence: Option<String>,
 ) -> BuiltInMatch {
   BuiltInMatch {
     id: generate_next_builtin_id(),
     label: "Open search bar",
     triggers: trigger.map(|trigger| vec![trigger]).unwrap_or_default(),
     hotkey,
+    key_sequence,
     action: |_| EventType::ShowSearchBar,
   }
 }
@@ -21,16 +21,21 @@ use std::{collections::HashSet, sync::Arc};
 
 use espanso_config::{
   config::{AppProperties, Config, ConfigStore},
-  matches::store::{MatchSet, MatchStore},
+  matches::{
+    store::{MatchSet, MatchStore},
+    Match,
+  },
 };
-use espanso_info::{AppInfo, AppInfoProvider};
+use espanso_info::{AppInfo, AppInfoProvider, FocusedFieldProvider};
 
-use super::builtin::is_builtin_match;
+use super::{builtin::is_builtin_match, runtime_matches::RuntimeMatches};
 
 pub struct ConfigManager<'a> {
   config_store: &'a dyn ConfigStore,
   match_store: &'a dyn MatchStore,
   app_info_provider: &'a dyn AppInfoProvider,
+  focused_field_provider: &'a dyn FocusedFieldProvider,
+  runtime_matches: RuntimeMatches,
 }
 
 impl<'a> ConfigManager<'a> {
@@ -38,14 +43,24 @@ impl<'a> ConfigManager<'a> {
     config_store: &'a dyn ConfigStore,
     match_store: &'a dyn MatchStore,
     app_info_provider: &'a dyn AppInfoProvider,
+    focused_field_provider: &'a dyn FocusedFieldProvider,
   ) -> Self {
     Self {
       config_store,
       match_store,
       app_info_provider,
+      focused_field_provider,
+      runtime_matches: RuntimeMatches::new(),
     }
   }
 
+  // Matches added through IPC are not part of any match file, so they are
+  // always active
+  pub fn with_runtime_matches(mut self, runtime_matches: RuntimeMatches) -> Self {
+    self.runtime_matches = runtime_matches;
+    self
+  }
+
   pub fn active(&self) -> Arc<dyn Config> {
     let current_app = self.app_info_provider.get_info();
     let info = to_app_properties(&current_app);
@@ -76,13 +91,17 @@ impl<'a> espanso_engine::process::MatchFilter for ConfigManager<'a> {
   fn filter_active(&self, matches_ids: &[i32]) -> Vec<i32> {
     let ids_set: HashSet<i32> = matches_ids.iter().copied().collect::<HashSet<_>>();
     let (_, match_set) = self.active_context();
+    let runtime_matches = self.runtime_matches.all();
 
-    let active_user_defined_matches: Vec<i32> = match_set
+    let candidates: Vec<&Match> = match_set
       .matches
       .iter()
+      .chain(runtime_matches.iter())
       .filter(|m| ids_set.contains(&m.id))
-      .map(|m| m.id)
+      .copied()
       .collect();
+    let active_user_defined_matches =
+      filter_by_focused_field(&candidates, self.focused_field_provider);
 
     let builtin_matches: Vec<i32> = matches_ids
       .iter()
@@ -94,6 +113,39 @@ impl<'a> espanso_engine::process::MatchFilter for ConfigManager<'a> {
     output.extend(builtin_matches);
     output
   }
+
+  fn is_exclusive(&self, match_id: i32) -> bool {
+    let (_, match_set) = self.active_context();
+    match_set
+      .matches
+      .iter()
+      .any(|m| m.id == match_id && m.exclusive)
+      || matches!(self.runtime_matches.get(match_id), Some(m) if m.exclusive)
+  }
+}
+
+// Matches with a `filter_field` are kept only if the focused field role matches it.
+// When the role can't be detected (or the platform doesn't support it), they are
+// dropped, as we can't tell whether the user is typing in the right field
+fn filter_by_focused_field(
+  matches: &[&Match],
+  focused_field_provider: &dyn FocusedFieldProvider,
+) -> Vec<i32> {
+  // Querying the role might be expensive, so we only do it when needed
+  let role = if matches.iter().any(|m| m.filter_field.is_some()) {
+    focused_field_provider.get_focused_field_role()
+  } else {
+    None
+  };
+
+  matches
+    .iter()
+    .filter(|m| match &role {
+      Some(role) => m.is_field_allowed(role),
+      None => m.filter_field.is_none(),
+    })
+    .map(|m| m.id)
+    .collect()
 }
 
 impl<'a> super::engine::process::middleware::render::ConfigProvider<'a> for ConfigManager<'a> {
@@ -112,6 +164,10 @@ impl<'a> super::engine::process::middleware::render::ConfigProvider<'a> for Conf
   fn active(&self) -> (Arc<dyn Config>, MatchSet) {
     self.active_context()
   }
+
+  fn active_app(&self) -> AppInfo {
+    self.app_info_provider.get_info()
+  }
 }
 
 impl<'a> espanso_engine::dispatch::ModeProvider for ConfigManager<'a> {
@@ -125,6 +181,15 @@ impl<'a> espanso_engine::dispatch::ModeProvider for ConfigManager<'a> {
       },
     }
   }
+
+  fn forced_keys_fallback_threshold(&self) -> Option<usize> {
+    let config = self.active();
+    if config.keys_clipboard_fallback() {
+      Some(config.keys_clipboard_fallback_threshold())
+    } else {
+      None
+    }
+  }
 }
 
 impl<'a> super::engine::dispatch::executor::clipboard_injector::ClipboardParamsProvider
@@ -139,6 +204,7 @@ impl<'a> super::engine::dispatch::executor::clipboard_injector::ClipboardParamsP
       disable_x11_fast_inject: active.disable_x11_fast_inject(),
       restore_clipboard: active.preserve_clipboard(),
       restore_clipboard_delay: active.restore_clipboard_delay(),
+      paste_focus_retries: active.paste_focus_retries(),
     }
   }
 }
@@ -151,14 +217,39 @@ impl<'a> super::engine::dispatch::executor::InjectParamsProvider for ConfigManag
       inject_delay: active.inject_delay(),
       key_delay: active.key_delay(),
       evdev_modifier_delay: active.evdev_modifier_delay(),
+      inject_chunk_size: active.inject_chunk_size(),
+      inject_chunk_delay: active.inject_chunk_delay(),
+      trace_injected_text: active.trace_injected_text(),
     }
   }
 }
 
+impl<'a> espanso_engine::dispatch::BackspaceLimitProvider for ConfigManager<'a> {
+  fn max_backspaces_per_expansion(&self) -> usize {
+    self.active().max_backspaces_per_expansion()
+  }
+}
+
+impl<'a> espanso_engine::dispatch::PostBackspaceDelayProvider for ConfigManager<'a> {
+  fn post_backspace_delay(&self) -> usize {
+    self.active().post_backspace_delay()
+  }
+}
+
 impl<'a> espanso_engine::process::MatcherMiddlewareConfigProvider for ConfigManager<'a> {
   fn max_history_size(&self) -> usize {
     self.default().backspace_limit()
   }
+
+  fn max_propagation_depth(&self) -> usize {
+    self.default().max_propagation_depth()
+  }
+}
+
+impl<'a> espanso_engine::process::ExpansionSoundProvider for ConfigManager<'a> {
+  fn get_expansion_sound(&self) -> Option<String> {
+    self.active().expansion_sound()
+  }
 }
 
 impl<'a> espanso_engine::process::UndoEnabledProvider for ConfigManager<'a> {
@@ -177,6 +268,16 @@ impl<'a> espanso_engine::process::UndoEnabledProvider for ConfigManager<'a> {
 
     self.active().undo_backspace()
   }
+
+  fn max_backspaces_per_expansion(&self) -> usize {
+    self.active().max_backspaces_per_expansion()
+  }
+}
+
+impl<'a> crate::gui::modulo::form::FormTimeoutProvider for ConfigManager<'a> {
+  fn form_timeout(&self) -> u64 {
+    self.active().form_timeout()
+  }
 }
 
 impl<'a> espanso_engine::process::EnabledStatusProvider for ConfigManager<'a> {
@@ -184,3 +285,75 @@ impl<'a> espanso_engine::process::EnabledStatusProvider for ConfigManager<'a> {
     self.active().enable()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use espanso_config::matches::MatchRegex;
+  use std::cell::Cell;
+
+  struct MockFocusedFieldProvider {
+    role: Option<&'static str>,
+    queries: Cell<usize>,
+  }
+
+  impl MockFocusedFieldProvider {
+    fn new(role: Option<&'static str>) -> Self {
+      Self {
+        role,
+        queries: Cell::new(0),
+      }
+    }
+  }
+
+  impl FocusedFieldProvider for MockFocusedFieldProvider {
+    fn get_focused_field_role(&self) -> Option<String> {
+      self.queries.set(self.queries.get() + 1);
+      self.role.map(String::from)
+    }
+
+    fn is_focused_field_secure(&self) -> bool {
+      false
+    }
+  }
+
+  fn create_match(id: i32, filter_field: Option<&str>) -> Match {
+    Match {
+      id,
+      filter_field: filter_field.map(|filter| MatchRegex::new(filter).unwrap()),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn field_filter_restricts_matches() {
+    let url_match = create_match(1, Some("(?i)address"));
+    let plain_match = create_match(2, None);
+    let matches = vec![&url_match, &plain_match];
+
+    let provider = MockFocusedFieldProvider::new(Some("AXTextField:Address and search bar"));
+    assert_eq!(filter_by_focused_field(&matches, &provider), vec![1, 2]);
+
+    let provider = MockFocusedFieldProvider::new(Some("AXTextArea"));
+    assert_eq!(filter_by_focused_field(&matches, &provider), vec![2]);
+  }
+
+  #[test]
+  fn field_filter_drops_matches_when_role_is_unknown() {
+    let url_match = create_match(1, Some("(?i)address"));
+    let plain_match = create_match(2, None);
+    let provider = MockFocusedFieldProvider::new(None);
+    assert_eq!(
+      filter_by_focused_field(&[&url_match, &plain_match], &provider),
+      vec![2]
+    );
+  }
+
+  #[test]
+  fn field_role_is_not_queried_without_filters() {
+    let plain_match = create_match(1, None);
+    let provider = MockFocusedFieldProvider::new(Some("AXTextArea"));
+    assert_eq!(filter_by_focused_field(&[&plain_match], &provider), vec![1]);
+    assert_eq!(provider.queries.get(), 0);
+  }
+}
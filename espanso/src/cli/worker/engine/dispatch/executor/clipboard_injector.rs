@@ -17,11 +17,13 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::{convert::TryInto, path::PathBuf};
+use std::{convert::TryInto, path::PathBuf, time::Duration};
 
 use espanso_clipboard::Clipboard;
+use espanso_info::{AppInfo, AppInfoProvider};
 use espanso_inject::{keys::Key, InjectionOptions, Injector};
-use log::error;
+use log::{error, warn};
+use thiserror::Error;
 
 use espanso_engine::{
   dispatch::HtmlInjector,
@@ -39,12 +41,18 @@ pub struct ClipboardParams {
   pub disable_x11_fast_inject: bool,
   pub restore_clipboard: bool,
   pub restore_clipboard_delay: usize,
+  pub paste_focus_retries: usize,
 }
 
+// Initial delay used when waiting for the original application to regain focus,
+// doubled after every failed attempt
+const FOCUS_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
 pub struct ClipboardInjectorAdapter<'a> {
   injector: &'a dyn Injector,
   clipboard: &'a dyn Clipboard,
   params_provider: &'a dyn ClipboardParamsProvider,
+  app_info_provider: &'a dyn AppInfoProvider,
 }
 
 impl<'a> ClipboardInjectorAdapter<'a> {
@@ -52,37 +60,35 @@ impl<'a> ClipboardInjectorAdapter<'a> {
     injector: &'a dyn Injector,
     clipboard: &'a dyn Clipboard,
     params_provider: &'a dyn ClipboardParamsProvider,
+    app_info_provider: &'a dyn AppInfoProvider,
   ) -> Self {
     Self {
       injector,
       clipboard,
       params_provider,
+      app_info_provider,
     }
   }
 
-  fn send_paste_combination(&self) -> anyhow::Result<()> {
+  fn send_paste_combination(&self, focus: &AppInfo) -> anyhow::Result<()> {
     let params = self.params_provider.get();
 
     std::thread::sleep(std::time::Duration::from_millis(
       params.pre_paste_delay.try_into().unwrap(),
     ));
 
-    let mut custom_combination = None;
-    if let Some(custom_shortcut) = params.paste_shortcut {
-      if let Some(combination) = parse_combination(&custom_shortcut) {
-        custom_combination = Some(combination);
-      } else {
-        error!("'{}' is not a valid paste shortcut", custom_shortcut);
-      }
+    // On slow machines the focus might move to another application while the
+    // clipboard is being populated, and we don't want to paste into it
+    if !wait_for_focus(
+      self.app_info_provider,
+      focus,
+      params.paste_focus_retries,
+      FOCUS_RETRY_BASE_DELAY,
+    ) {
+      return Err(ClipboardInjectorError::FocusChanged.into());
     }
 
-    let combination = if let Some(custom_combination) = custom_combination {
-      custom_combination
-    } else if cfg!(target_os = "macos") {
-      vec![Key::Meta, Key::V]
-    } else {
-      vec![Key::Control, Key::V]
-    };
+    let combination = paste_combination(params.paste_shortcut.as_deref());
 
     self.injector.send_key_combination(
       &combination,
@@ -116,11 +122,12 @@ impl<'a> TextInjector for ClipboardInjectorAdapter<'a> {
   }
 
   fn inject_text(&self, text: &str) -> anyhow::Result<()> {
+    let focus = self.app_info_provider.get_info();
     let _guard = self.restore_clipboard_guard();
 
     self.clipboard.set_text(text)?;
 
-    self.send_paste_combination()?;
+    self.send_paste_combination(&focus)?;
 
     Ok(())
   }
@@ -128,18 +135,19 @@ impl<'a> TextInjector for ClipboardInjectorAdapter<'a> {
 
 impl<'a> HtmlInjector for ClipboardInjectorAdapter<'a> {
   fn inject_html(&self, html: &str, fallback_text: &str) -> anyhow::Result<()> {
+    let focus = self.app_info_provider.get_info();
     let _guard = self.restore_clipboard_guard();
 
     self.clipboard.set_html(html, Some(fallback_text))?;
 
-    self.send_paste_combination()?;
+    self.send_paste_combination(&focus)?;
 
     Ok(())
   }
 }
 
 impl<'a> ImageInjector for ClipboardInjectorAdapter<'a> {
-  fn inject_image(&self, image_path: &str) -> anyhow::Result<()> {
+  fn inject_image(&self, image_path: &str, preserve_format: bool) -> anyhow::Result<()> {
     let path = PathBuf::from(image_path);
     if !path.is_file() {
       return Err(
@@ -151,11 +159,16 @@ impl<'a> ImageInjector for ClipboardInjectorAdapter<'a> {
       );
     }
 
+    let focus = self.app_info_provider.get_info();
     let _guard = self.restore_clipboard_guard();
 
-    self.clipboard.set_image(&path)?;
+    if preserve_format {
+      self.clipboard.set_image_preserving_format(&path)?;
+    } else {
+      self.clipboard.set_image(&path)?;
+    }
 
-    self.send_paste_combination()?;
+    self.send_paste_combination(&focus)?;
 
     Ok(())
   }
@@ -196,12 +209,290 @@ impl<'a> Drop for ClipboardRestoreGuard<'a> {
   }
 }
 
+// Returns the custom paste shortcut if valid, or the OS default one otherwise
+fn paste_combination(custom_shortcut: Option<&str>) -> Vec<Key> {
+  if let Some(custom_shortcut) = custom_shortcut {
+    if let Some(combination) = parse_combination(custom_shortcut) {
+      return combination;
+    }
+    error!("'{}' is not a valid paste shortcut", custom_shortcut);
+  }
+
+  if cfg!(target_os = "macos") {
+    vec![Key::Meta, Key::V]
+  } else {
+    vec![Key::Control, Key::V]
+  }
+}
+
+// Parse a shortcut such as "CTRL+SHIFT+V", ignoring spaces around the keys
 fn parse_combination(combination: &str) -> Option<Vec<Key>> {
   let tokens = combination.split('+');
   let mut keys: Vec<Key> = Vec::new();
   for token in tokens {
-    keys.push(Key::parse(token)?);
+    keys.push(Key::parse(&token.trim().to_uppercase())?);
   }
 
   Some(keys)
 }
+
+// Returns true if the focused application in the two snapshots is different.
+// The title alone is not reliable, as many applications change it while in use,
+// so it's only considered when neither the executable nor the class are available.
+fn has_focus_changed(before: &AppInfo, after: &AppInfo) -> bool {
+  if before.exec.is_none() && before.class.is_none() {
+    return before.title != after.title;
+  }
+
+  before.exec != after.exec || before.class != after.class
+}
+
+// Check whether the original application is still focused, waiting for it to
+// regain focus up to `retries` times (with an exponential backoff) otherwise
+fn wait_for_focus(
+  provider: &dyn AppInfoProvider,
+  original: &AppInfo,
+  retries: usize,
+  base_delay: Duration,
+) -> bool {
+  let mut delay = base_delay;
+  for attempt in 0..=retries {
+    let current = provider.get_info();
+    if !has_focus_changed(original, &current) {
+      return true;
+    }
+
+    if attempt < retries {
+      warn!(
+        "focus changed during clipboard injection (from {:?} to {:?}), retrying in {:?}",
+        original, current, delay
+      );
+      std::thread::sleep(delay);
+      delay *= 2;
+    }
+  }
+
+  false
+}
+
+#[derive(Error, Debug)]
+pub enum ClipboardInjectorError {
+  #[error("focus changed during clipboard injection, aborting to avoid pasting into the wrong application")]
+  FocusChanged,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::RefCell;
+
+  fn app(title: Option<&str>, exec: Option<&str>, class: Option<&str>) -> AppInfo {
+    AppInfo {
+      title: title.map(String::from),
+      exec: exec.map(String::from),
+      class: class.map(String::from),
+    }
+  }
+
+  struct MockProvider {
+    infos: RefCell<Vec<AppInfo>>,
+  }
+
+  impl AppInfoProvider for MockProvider {
+    fn get_info(&self) -> AppInfo {
+      let mut infos = self.infos.borrow_mut();
+      if infos.len() > 1 {
+        infos.remove(0)
+      } else {
+        infos[0].clone()
+      }
+    }
+  }
+
+  #[derive(Default)]
+  struct MockInjector {
+    combinations: RefCell<Vec<Vec<Key>>>,
+  }
+
+  impl Injector for MockInjector {
+    fn send_string(&self, _: &str, _: InjectionOptions) -> anyhow::Result<()> {
+      Ok(())
+    }
+
+    fn send_keys(&self, _: &[Key], _: InjectionOptions) -> anyhow::Result<()> {
+      Ok(())
+    }
+
+    fn send_key_combination(&self, keys: &[Key], _: InjectionOptions) -> anyhow::Result<()> {
+      self.combinations.borrow_mut().push(keys.to_vec());
+      Ok(())
+    }
+  }
+
+  #[derive(Default)]
+  struct MockClipboard {
+    text: RefCell<Option<String>>,
+  }
+
+  impl Clipboard for MockClipboard {
+    fn get_text(&self) -> Option<String> {
+      self.text.borrow().clone()
+    }
+
+    fn set_text(&self, text: &str) -> anyhow::Result<()> {
+      *self.text.borrow_mut() = Some(text.to_string());
+      Ok(())
+    }
+
+    fn set_image(&self, _: &std::path::Path) -> anyhow::Result<()> {
+      Ok(())
+    }
+
+    fn set_html(&self, _: &str, _: Option<&str>) -> anyhow::Result<()> {
+      Ok(())
+    }
+  }
+
+  struct MockParamsProvider {
+    paste_shortcut: Option<String>,
+  }
+
+  impl ClipboardParamsProvider for MockParamsProvider {
+    fn get(&self) -> ClipboardParams {
+      ClipboardParams {
+        pre_paste_delay: 0,
+        paste_shortcut_event_delay: 0,
+        paste_shortcut: self.paste_shortcut.clone(),
+        disable_x11_fast_inject: false,
+        restore_clipboard: false,
+        restore_clipboard_delay: 0,
+        paste_focus_retries: 0,
+      }
+    }
+  }
+
+  fn keys_to_string(keys: &[Key]) -> String {
+    format!("{:?}", keys)
+  }
+
+  #[test]
+  fn parse_combination_custom_shortcut() {
+    assert_eq!(
+      keys_to_string(&parse_combination("CTRL+SHIFT+V").unwrap()),
+      keys_to_string(&[Key::Control, Key::Shift, Key::V])
+    );
+    assert_eq!(
+      keys_to_string(&parse_combination("shift + insert").unwrap()),
+      keys_to_string(&[Key::Shift, Key::Insert])
+    );
+    assert!(parse_combination("CTRL+").is_none());
+    assert!(parse_combination("CTRL+INVALID").is_none());
+  }
+
+  #[test]
+  fn paste_combination_falls_back_to_default_when_invalid() {
+    assert_eq!(
+      keys_to_string(&paste_combination(Some("CTRL+INVALID"))),
+      keys_to_string(&paste_combination(None))
+    );
+  }
+
+  #[test]
+  fn custom_paste_shortcut_is_used_when_injecting() {
+    let injector = MockInjector::default();
+    let clipboard = MockClipboard::default();
+    let params_provider = MockParamsProvider {
+      paste_shortcut: Some("CTRL+SHIFT+V".to_string()),
+    };
+    let app_info_provider = MockProvider {
+      infos: RefCell::new(vec![app(None, Some("terminal"), None)]),
+    };
+    let adapter =
+      ClipboardInjectorAdapter::new(&injector, &clipboard, &params_provider, &app_info_provider);
+
+    adapter.inject_text("hello").unwrap();
+
+    assert_eq!(clipboard.get_text().as_deref(), Some("hello"));
+    assert_eq!(
+      keys_to_string(&injector.combinations.borrow()[0]),
+      keys_to_string(&[Key::Control, Key::Shift, Key::V])
+    );
+  }
+
+  #[test]
+  fn focus_unchanged_when_only_title_differs() {
+    let before = app(Some("notes.txt"), Some("/usr/bin/gedit"), Some("Gedit"));
+    let after = app(Some("*notes.txt"), Some("/usr/bin/gedit"), Some("Gedit"));
+    assert!(!has_focus_changed(&before, &after));
+  }
+
+  #[test]
+  fn focus_changed_when_app_differs() {
+    let before = app(Some("notes.txt"), Some("/usr/bin/gedit"), Some("Gedit"));
+    let after = app(Some("notes.txt"), Some("/usr/bin/firefox"), Some("Firefox"));
+    assert!(has_focus_changed(&before, &after));
+  }
+
+  #[test]
+  fn focus_falls_back_to_title_when_nothing_else_is_available() {
+    let before = app(Some("Terminal"), None, None);
+    assert!(!has_focus_changed(
+      &before,
+      &app(Some("Terminal"), None, None)
+    ));
+    assert!(has_focus_changed(
+      &before,
+      &app(Some("Browser"), None, None)
+    ));
+    assert!(!has_focus_changed(
+      &app(None, None, None),
+      &app(None, None, None)
+    ));
+  }
+
+  #[test]
+  fn wait_for_focus_retries_until_focus_is_back() {
+    let original = app(None, Some("editor"), None);
+    let provider = MockProvider {
+      infos: RefCell::new(vec![app(None, Some("popup"), None), original.clone()]),
+    };
+    assert!(wait_for_focus(
+      &provider,
+      &original,
+      1,
+      Duration::from_millis(0)
+    ));
+  }
+
+  #[test]
+  fn wait_for_focus_aborts_when_retries_are_exhausted() {
+    let original = app(None, Some("editor"), None);
+    let provider = MockProvider {
+      infos: RefCell::new(vec![
+        app(None, Some("popup"), None),
+        app(None, Some("popup"), None),
+        original.clone(),
+      ]),
+    };
+    assert!(!wait_for_focus(
+      &provider,
+      &original,
+      1,
+      Duration::from_millis(0)
+    ));
+  }
+
+  #[test]
+  fn wait_for_focus_without_retries_checks_once() {
+    let original = app(None, Some("editor"), None);
+    let provider = MockProvider {
+      infos: RefCell::new(vec![app(None, Some("popup"), None), original.clone()]),
+    };
+    assert!(!wait_for_focus(
+      &provider,
+      &original,
+      0,
+      Duration::from_millis(0)
+    ));
+  }
+}
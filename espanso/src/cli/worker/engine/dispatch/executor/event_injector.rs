@@ -69,18 +69,236 @@ impl<'a> TextInjector for EventInjectorAdapter<'a> {
         .unwrap(),
     };
 
-    // We don't use the lines() method because it skips emtpy lines, which is not what we want.
-    for (i, line) in text.split(split_sequence).enumerate() {
-      // We simulate an Return press between lines
-      if i > 0 {
-        self
-          .injector
-          .send_keys(&[espanso_inject::keys::Key::Enter], injection_options)?
+    // Long texts can be injected in chunks, as some applications (such as
+    // Electron-based ones) drop characters when receiving them too quickly
+    for (index, chunk) in split_text_into_chunks(text, params.inject_chunk_size)
+      .into_iter()
+      .enumerate()
+    {
+      if index > 0 && params.inject_chunk_delay > 0 {
+        std::thread::sleep(std::time::Duration::from_millis(
+          params.inject_chunk_delay as u64,
+        ));
       }
 
-      self.injector.send_string(line, injection_options)?;
+      // We don't use the lines() method because it skips emtpy lines, which is not what we want.
+      for (i, line) in chunk.split(split_sequence).enumerate() {
+        // We simulate an Return press between lines
+        if i > 0 {
+          self
+            .injector
+            .send_keys(&[espanso_inject::keys::Key::Enter], injection_options)?
+        }
+
+        // Chars that can't be produced with the current keyboard layout
+        // are injected through the platform's unicode input method
+        for (typeable, run) in split_by_layout(line, self.injector) {
+          if typeable {
+            self.injector.send_string(run, injection_options)?;
+          } else {
+            self.injector.send_unicode_string(run, injection_options)?;
+          }
+        }
+      }
     }
 
     Ok(())
   }
 }
+
+// A chunk size of 0 disables the chunking. Chunks are split on char boundaries,
+// and never between the two chars of a CRLF line ending
+fn split_text_into_chunks(text: &str, chunk_size: usize) -> Vec<&str> {
+  if chunk_size == 0 || text.is_empty() {
+    return vec![text];
+  }
+
+  let mut chunks = Vec::new();
+  let mut chunk_start = 0;
+  let mut chunk_len = 0;
+  let mut prev_char = None;
+  for (offset, c) in text.char_indices() {
+    if chunk_len >= chunk_size && !(prev_char == Some('\r') && c == '\n') {
+      chunks.push(&text[chunk_start..offset]);
+      chunk_start = offset;
+      chunk_len = 0;
+    }
+
+    chunk_len += 1;
+    prev_char = Some(c);
+  }
+  chunks.push(&text[chunk_start..]);
+
+  chunks
+}
+
+// Split the text into consecutive runs of chars that either can or can't
+// be produced with the current keyboard layout
+fn split_by_layout<'t>(text: &'t str, injector: &dyn Injector) -> Vec<(bool, &'t str)> {
+  let mut runs = Vec::new();
+  let mut run_start = 0;
+  let mut run_typeable = true;
+  for (offset, c) in text.char_indices() {
+    let typeable = injector.can_type_char(c);
+    if offset > run_start && typeable != run_typeable {
+      runs.push((run_typeable, &text[run_start..offset]));
+      run_start = offset;
+    }
+    if offset == run_start {
+      run_typeable = typeable;
+    }
+  }
+  if run_start < text.len() {
+    runs.push((run_typeable, &text[run_start..]));
+  }
+
+  runs
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::InjectParams;
+  use super::*;
+  use std::cell::RefCell;
+
+  #[derive(Default)]
+  struct MockInjector {
+    calls: RefCell<Vec<String>>,
+    // Chars that are not available in the mocked keyboard layout
+    untypeable_chars: &'static str,
+  }
+
+  impl Injector for MockInjector {
+    fn send_string(&self, string: &str, _: InjectionOptions) -> anyhow::Result<()> {
+      self.calls.borrow_mut().push(format!("string: {}", string));
+      Ok(())
+    }
+
+    fn send_keys(
+      &self,
+      keys: &[espanso_inject::keys::Key],
+      _: InjectionOptions,
+    ) -> anyhow::Result<()> {
+      self.calls.borrow_mut().push(format!("keys: {:?}", keys));
+      Ok(())
+    }
+
+    fn send_key_combination(
+      &self,
+      keys: &[espanso_inject::keys::Key],
+      _: InjectionOptions,
+    ) -> anyhow::Result<()> {
+      self
+        .calls
+        .borrow_mut()
+        .push(format!("combination: {:?}", keys));
+      Ok(())
+    }
+
+    fn can_type_char(&self, c: char) -> bool {
+      !self.untypeable_chars.contains(c)
+    }
+
+    fn send_unicode_string(&self, string: &str, _: InjectionOptions) -> anyhow::Result<()> {
+      self.calls.borrow_mut().push(format!("unicode: {}", string));
+      Ok(())
+    }
+  }
+
+  struct MockParamsProvider {
+    inject_chunk_size: usize,
+  }
+
+  impl InjectParamsProvider for MockParamsProvider {
+    fn get(&self) -> InjectParams {
+      InjectParams {
+        inject_delay: None,
+        key_delay: None,
+        disable_x11_fast_inject: false,
+        evdev_modifier_delay: None,
+        inject_chunk_size: self.inject_chunk_size,
+        inject_chunk_delay: 1,
+        trace_injected_text: false,
+      }
+    }
+  }
+
+  fn inject(text: &str, inject_chunk_size: usize) -> Vec<String> {
+    inject_with_layout(text, inject_chunk_size, "")
+  }
+
+  fn inject_with_layout(
+    text: &str,
+    inject_chunk_size: usize,
+    untypeable_chars: &'static str,
+  ) -> Vec<String> {
+    let injector = MockInjector {
+      untypeable_chars,
+      ..Default::default()
+    };
+    let params_provider = MockParamsProvider { inject_chunk_size };
+    let adapter = EventInjectorAdapter::new(&injector, &params_provider);
+    adapter.inject_text(text).unwrap();
+    injector.calls.into_inner()
+  }
+
+  #[test]
+  fn text_is_split_into_chunks() {
+    assert_eq!(
+      split_text_into_chunks("hello world", 4),
+      vec!["hell", "o wo", "rld"]
+    );
+    assert_eq!(split_text_into_chunks("héllo", 2), vec!["hé", "ll", "o"]);
+    assert_eq!(split_text_into_chunks("a\r\nb", 2), vec!["a\r\n", "b"]);
+    assert_eq!(split_text_into_chunks("hello", 0), vec!["hello"]);
+  }
+
+  #[test]
+  fn text_is_injected_in_chunks() {
+    assert_eq!(
+      inject("hello\nworld", 4),
+      vec![
+        "string: hell",
+        "string: o",
+        "keys: [Enter]",
+        "string: wo",
+        "string: rld",
+      ]
+    );
+  }
+
+  #[test]
+  fn text_is_injected_line_by_line_without_chunks() {
+    assert_eq!(
+      inject("hello\r\n\r\nworld", 0),
+      vec![
+        "string: hello",
+        "keys: [Enter]",
+        "keys: [Enter]",
+        "string: world",
+      ]
+    );
+  }
+
+  #[test]
+  fn chars_missing_from_layout_use_unicode_injection() {
+    assert_eq!(
+      inject_with_layout("a✓b\n✓✗", 0, "✓✗"),
+      vec![
+        "string: a",
+        "unicode: ✓",
+        "string: b",
+        "keys: [Enter]",
+        "unicode: ✓✗",
+      ]
+    );
+  }
+
+  #[test]
+  fn unicode_injection_is_chunked() {
+    assert_eq!(
+      inject_with_layout("é✓✓x", 2, "✓"),
+      vec!["string: é", "unicode: ✓", "unicode: ✓", "string: x"]
+    );
+  }
+}
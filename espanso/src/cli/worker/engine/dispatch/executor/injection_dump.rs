@@ -0,0 +1,195 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use espanso_engine::{dispatch::InjectionFailureHandler, event::Event};
+use espanso_info::AppInfoProvider;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+pub const INJECTION_FAILURE_DUMP_FILE_NAME: &str = "injection_failure_dump.json";
+
+// Bumped whenever a field is changed or removed
+const DUMP_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct InjectionFailureDump {
+  pub schema_version: u32,
+  pub espanso_version: String,
+  // Seconds since the UNIX epoch
+  pub timestamp: u64,
+  pub backend: String,
+  pub error: String,
+  pub active_window: ActiveWindowDump,
+  pub last_event: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ActiveWindowDump {
+  pub title: Option<String>,
+  pub exec: Option<String>,
+  pub class: Option<String>,
+}
+
+pub struct InjectionFailureDumper<'a> {
+  enabled: bool,
+  dump_path: PathBuf,
+  backend: &'static str,
+  app_info_provider: &'a dyn AppInfoProvider,
+}
+
+impl<'a> InjectionFailureDumper<'a> {
+  pub fn new(
+    enabled: bool,
+    config_dir: &Path,
+    use_evdev_backend: bool,
+    app_info_provider: &'a dyn AppInfoProvider,
+  ) -> Self {
+    Self {
+      enabled,
+      dump_path: config_dir.join(INJECTION_FAILURE_DUMP_FILE_NAME),
+      backend: get_backend_name(use_evdev_backend),
+      app_info_provider,
+    }
+  }
+
+  fn create_dump(&self, event: &Event, error: &anyhow::Error) -> InjectionFailureDump {
+    let app_info = self.app_info_provider.get_info();
+    InjectionFailureDump {
+      schema_version: DUMP_SCHEMA_VERSION,
+      espanso_version: env!("CARGO_PKG_VERSION").to_string(),
+      timestamp: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default(),
+      backend: self.backend.to_string(),
+      error: format!("{:#}", error),
+      active_window: ActiveWindowDump {
+        title: app_info.title,
+        exec: app_info.exec,
+        class: app_info.class,
+      },
+      last_event: format!("{:?}", event.etype),
+    }
+  }
+
+  fn write_dump(&self, dump: &InjectionFailureDump) -> Result<()> {
+    let content = serde_json::to_string_pretty(dump)?;
+    std::fs::write(&self.dump_path, content)
+      .with_context(|| format!("unable to write dump file: {:?}", self.dump_path))
+  }
+}
+
+impl<'a> InjectionFailureHandler for InjectionFailureDumper<'a> {
+  fn on_injection_failure(&self, event: &Event, error: &anyhow::Error) {
+    if !self.enabled {
+      return;
+    }
+
+    // Only the latest failure is kept, to avoid filling up the config directory
+    let dump = self.create_dump(event, error);
+    match self.write_dump(&dump) {
+      Ok(()) => info!("injection failure dump written to: {:?}", self.dump_path),
+      Err(err) => error!("unable to write injection failure dump: {:?}", err),
+    }
+  }
+}
+
+fn get_backend_name(use_evdev_backend: bool) -> &'static str {
+  if cfg!(target_os = "windows") {
+    "win32"
+  } else if cfg!(target_os = "macos") {
+    "mac"
+  } else if use_evdev_backend {
+    "evdev"
+  } else {
+    "x11"
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use espanso_engine::event::{
+    effect::{KeySequenceInjectRequest, KeyStroke},
+    input::Key,
+    EventType,
+  };
+  use espanso_info::AppInfo;
+  use tempdir::TempDir;
+
+  struct MockAppInfoProvider;
+
+  impl AppInfoProvider for MockAppInfoProvider {
+    fn get_info(&self) -> AppInfo {
+      AppInfo {
+        title: Some("Untitled - Editor".to_string()),
+        exec: Some("editor".to_string()),
+        class: None,
+      }
+    }
+  }
+
+  fn inject_event() -> Event {
+    Event::caused_by(
+      1,
+      EventType::KeySequenceInject(KeySequenceInjectRequest {
+        keys: vec![KeyStroke::Key(Key::Backspace)],
+      }),
+    )
+  }
+
+  #[test]
+  fn dump_is_written_on_injection_failure() {
+    let dir = TempDir::new("espanso-dump").unwrap();
+    let dumper = InjectionFailureDumper::new(true, dir.path(), false, &MockAppInfoProvider);
+
+    dumper.on_injection_failure(&inject_event(), &anyhow::anyhow!("simulated failure"));
+
+    let content =
+      std::fs::read_to_string(dir.path().join(INJECTION_FAILURE_DUMP_FILE_NAME)).unwrap();
+    let dump: InjectionFailureDump = serde_json::from_str(&content).unwrap();
+    assert_eq!(dump.schema_version, DUMP_SCHEMA_VERSION);
+    assert_eq!(dump.error, "simulated failure");
+    assert_eq!(
+      dump.active_window,
+      ActiveWindowDump {
+        title: Some("Untitled - Editor".to_string()),
+        exec: Some("editor".to_string()),
+        class: None,
+      }
+    );
+    assert!(dump.last_event.contains("Backspace"));
+  }
+
+  #[test]
+  fn dump_is_not_written_when_disabled() {
+    let dir = TempDir::new("espanso-dump").unwrap();
+    let dumper = InjectionFailureDumper::new(false, dir.path(), false, &MockAppInfoProvider);
+
+    dumper.on_injection_failure(&inject_event(), &anyhow::anyhow!("simulated failure"));
+
+    assert!(!dir.path().join(INJECTION_FAILURE_DUMP_FILE_NAME).exists());
+  }
+}
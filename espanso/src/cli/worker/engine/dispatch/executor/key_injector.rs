@@ -18,9 +18,10 @@
  */
 
 use espanso_inject::{InjectionOptions, Injector};
+use log::{debug, log_enabled, Level};
 use std::convert::TryInto;
 
-use espanso_engine::dispatch::KeyInjector;
+use espanso_engine::{dispatch::KeyInjector, event::effect::KeyStroke};
 
 use super::InjectParamsProvider;
 
@@ -39,7 +40,7 @@ impl<'a> KeyInjectorAdapter<'a> {
 }
 
 impl<'a> KeyInjector for KeyInjectorAdapter<'a> {
-  fn inject_sequence(&self, keys: &[espanso_engine::event::input::Key]) -> anyhow::Result<()> {
+  fn inject_sequence(&self, keys: &[KeyStroke]) -> anyhow::Result<()> {
     let params = self.params_provider.get();
 
     let injection_options = InjectionOptions {
@@ -61,9 +62,101 @@ impl<'a> KeyInjector for KeyInjectorAdapter<'a> {
         .unwrap(),
     };
 
-    let converted_keys: Vec<_> = keys.iter().map(convert_to_inject_key).collect();
-    self.injector.send_keys(&converted_keys, injection_options)
+    for (index, chunk) in split_into_chunks(keys, params.inject_chunk_size)
+      .into_iter()
+      .enumerate()
+    {
+      if index > 0 && params.inject_chunk_delay > 0 {
+        std::thread::sleep(std::time::Duration::from_millis(
+          params.inject_chunk_delay as u64,
+        ));
+      }
+
+      let steps = convert_to_inject_steps(chunk);
+
+      // Debug logs are enabled with the -vv flag. The injected text might contain
+      // sensitive data, so it's only included if `trace_injected_text` is set
+      if log_enabled!(Level::Debug) {
+        debug!(
+          "injecting key sequence: {:?}",
+          describe_steps(&steps, params.trace_injected_text)
+        );
+      }
+
+      for step in steps {
+        match step {
+          InjectStep::Keys(keys) => self.injector.send_keys(&keys, injection_options)?,
+          InjectStep::Combination(keys) => self
+            .injector
+            .send_key_combination(&keys, injection_options)?,
+          InjectStep::Text(text) => self
+            .injector
+            .send_unicode_string(&text, injection_options)?,
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+// A chunk size of 0 disables the chunking. Combinations count as a single key
+fn split_into_chunks(keys: &[KeyStroke], chunk_size: usize) -> Vec<&[KeyStroke]> {
+  if chunk_size == 0 || keys.is_empty() {
+    vec![keys]
+  } else {
+    keys.chunks(chunk_size).collect()
+  }
+}
+
+enum InjectStep {
+  Keys(Vec<espanso_inject::keys::Key>),
+  Combination(Vec<espanso_inject::keys::Key>),
+  Text(String),
+}
+
+// Consecutive keys (and chars) are grouped together to be injected in a single step,
+// while combinations need their own step to hold the modifiers down
+fn convert_to_inject_steps(keys: &[KeyStroke]) -> Vec<InjectStep> {
+  let mut steps = Vec::new();
+
+  for stroke in keys {
+    match stroke {
+      KeyStroke::Key(key) => {
+        if let Some(InjectStep::Keys(keys)) = steps.last_mut() {
+          keys.push(convert_to_inject_key(key));
+        } else {
+          steps.push(InjectStep::Keys(vec![convert_to_inject_key(key)]));
+        }
+      }
+      KeyStroke::Combination { modifiers, key } => {
+        let mut keys: Vec<_> = modifiers.iter().map(convert_to_inject_key).collect();
+        keys.push(convert_to_inject_key(key));
+        steps.push(InjectStep::Combination(keys));
+      }
+      KeyStroke::Char(c) => {
+        if let Some(InjectStep::Text(text)) = steps.last_mut() {
+          text.push(*c);
+        } else {
+          steps.push(InjectStep::Text(c.to_string()));
+        }
+      }
+    }
   }
+
+  steps
+}
+
+fn describe_steps(steps: &[InjectStep], include_text: bool) -> Vec<String> {
+  steps
+    .iter()
+    .map(|step| match step {
+      InjectStep::Keys(keys) => format!("keys: {:?}", keys),
+      InjectStep::Combination(keys) => format!("combination: {:?}", keys),
+      InjectStep::Text(text) if include_text => format!("text: {}", text),
+      InjectStep::Text(text) => format!("text: <{} chars>", text.chars().count()),
+    })
+    .collect()
 }
 
 fn convert_to_inject_key(key: &espanso_engine::event::input::Key) -> espanso_inject::keys::Key {
@@ -110,3 +203,208 @@ fn convert_to_inject_key(key: &espanso_engine::event::input::Key) -> espanso_inj
     espanso_engine::event::input::Key::Other(raw) => espanso_inject::keys::Key::Raw(*raw),
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::super::InjectParams;
+  use super::*;
+  use espanso_engine::event::input::Key;
+  use std::cell::RefCell;
+
+  // Records the single key events, expanding each call the way the
+  // backends do: combinations release their keys in reverse order
+  #[derive(Default)]
+  struct MockInjector {
+    events: RefCell<Vec<String>>,
+  }
+
+  impl Injector for MockInjector {
+    fn send_string(&self, string: &str, _: InjectionOptions) -> anyhow::Result<()> {
+      self.events.borrow_mut().push(format!("string: {}", string));
+      Ok(())
+    }
+
+    fn send_keys(
+      &self,
+      keys: &[espanso_inject::keys::Key],
+      _: InjectionOptions,
+    ) -> anyhow::Result<()> {
+      let mut events = self.events.borrow_mut();
+      for key in keys {
+        events.push(format!("press {:?}", key));
+        events.push(format!("release {:?}", key));
+      }
+      Ok(())
+    }
+
+    fn send_key_combination(
+      &self,
+      keys: &[espanso_inject::keys::Key],
+      _: InjectionOptions,
+    ) -> anyhow::Result<()> {
+      let mut events = self.events.borrow_mut();
+      for key in keys {
+        events.push(format!("press {:?}", key));
+      }
+      for key in keys.iter().rev() {
+        events.push(format!("release {:?}", key));
+      }
+      Ok(())
+    }
+
+    fn send_unicode_string(&self, string: &str, _: InjectionOptions) -> anyhow::Result<()> {
+      self
+        .events
+        .borrow_mut()
+        .push(format!("unicode: {}", string));
+      Ok(())
+    }
+  }
+
+  struct MockParamsProvider {
+    inject_chunk_size: usize,
+  }
+
+  impl InjectParamsProvider for MockParamsProvider {
+    fn get(&self) -> InjectParams {
+      InjectParams {
+        inject_delay: None,
+        key_delay: None,
+        disable_x11_fast_inject: false,
+        evdev_modifier_delay: None,
+        inject_chunk_size: self.inject_chunk_size,
+        inject_chunk_delay: 0,
+        trace_injected_text: false,
+      }
+    }
+  }
+
+  fn inject(keys: &[KeyStroke], inject_chunk_size: usize) -> Vec<String> {
+    let injector = MockInjector::default();
+    let params_provider = MockParamsProvider { inject_chunk_size };
+    let adapter = KeyInjectorAdapter::new(&injector, &params_provider);
+    adapter.inject_sequence(keys).unwrap();
+    injector.events.into_inner()
+  }
+
+  #[test]
+  fn sequence_is_split_into_chunks() {
+    let keys: Vec<KeyStroke> = "hello world".chars().map(KeyStroke::Char).collect();
+
+    let chunks = split_into_chunks(&keys, 4);
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(
+      chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+      vec![4, 4, 3]
+    );
+    assert_eq!(split_into_chunks(&keys, 11).len(), 1);
+  }
+
+  #[test]
+  fn chunking_is_disabled_by_default() {
+    let keys: Vec<KeyStroke> = "hello".chars().map(KeyStroke::Char).collect();
+    let chunks = split_into_chunks(&keys, 0);
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].len(), 5);
+  }
+
+  #[test]
+  fn combination_holds_modifiers_until_key_is_released() {
+    let events = inject(
+      &[
+        Key::ArrowLeft.into(),
+        KeyStroke::Combination {
+          modifiers: vec![Key::Control, Key::Shift],
+          key: Key::Other(86),
+        },
+        Key::Enter.into(),
+      ],
+      0,
+    );
+
+    assert_eq!(
+      events,
+      vec![
+        "press ArrowLeft",
+        "release ArrowLeft",
+        "press Control",
+        "press Shift",
+        "press Raw(86)",
+        "release Raw(86)",
+        "release Shift",
+        "release Control",
+        "press Enter",
+        "release Enter",
+      ]
+    );
+  }
+
+  #[test]
+  fn combination_is_not_split_across_chunks() {
+    let events = inject(
+      &[
+        Key::Tab.into(),
+        KeyStroke::Combination {
+          modifiers: vec![Key::Alt],
+          key: Key::F4,
+        },
+      ],
+      1,
+    );
+
+    assert_eq!(
+      events,
+      vec![
+        "press Tab",
+        "release Tab",
+        "press Alt",
+        "press F4",
+        "release F4",
+        "release Alt",
+      ]
+    );
+  }
+
+  #[test]
+  fn chars_without_key_code_use_unicode_injection() {
+    let events = inject(
+      &[
+        Key::Other(65).into(),
+        KeyStroke::Char('é'),
+        KeyStroke::Char('✓'),
+        Key::Other(66).into(),
+      ],
+      0,
+    );
+
+    assert_eq!(
+      events,
+      vec![
+        "press Raw(65)",
+        "release Raw(65)",
+        "unicode: é✓",
+        "press Raw(66)",
+        "release Raw(66)",
+      ]
+    );
+  }
+
+  #[test]
+  fn describe_steps_hides_text_unless_requested() {
+    let steps = convert_to_inject_steps(&[
+      KeyStroke::Char('p'),
+      KeyStroke::Char('w'),
+      KeyStroke::Char('d'),
+      Key::Enter.into(),
+    ]);
+
+    assert_eq!(
+      describe_steps(&steps, false),
+      vec!["text: <3 chars>".to_string(), "keys: [Enter]".to_string()]
+    );
+    assert_eq!(
+      describe_steps(&steps, true),
+      vec!["text: pwd".to_string(), "keys: [Enter]".to_string()]
+    );
+  }
+}
@@ -21,8 +21,10 @@ pub mod clipboard_injector;
 pub mod context_menu;
 pub mod event_injector;
 pub mod icon;
+pub mod injection_dump;
 pub mod key_injector;
 pub mod secure_input;
+pub mod sound;
 
 pub trait InjectParamsProvider {
   fn get(&self) -> InjectParams;
@@ -33,4 +35,7 @@ pub struct InjectParams {
   pub key_delay: Option<usize>,
   pub disable_x11_fast_inject: bool,
   pub evdev_modifier_delay: Option<usize>,
+  pub inject_chunk_size: usize,
+  pub inject_chunk_delay: usize,
+  pub trace_injected_text: bool,
 }
@@ -0,0 +1,157 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use log::warn;
+
+use espanso_engine::dispatch::SoundPlayer;
+
+// Value of the `expansion_sound` option playing the system sound
+const BEEP_SOUND: &str = "beep";
+
+pub struct SoundPlayerAdapter {
+  config_dir: PathBuf,
+}
+
+impl SoundPlayerAdapter {
+  pub fn new(config_dir: &Path) -> Self {
+    Self {
+      config_dir: config_dir.to_owned(),
+    }
+  }
+}
+
+impl SoundPlayer for SoundPlayerAdapter {
+  fn play(&self, sound: &str) -> Result<()> {
+    let mut command = if sound == BEEP_SOUND {
+      beep_command()
+    } else {
+      let path = resolve_sound_path(sound, &self.config_dir);
+      if !path.is_file() {
+        bail!("expansion sound file does not exist: {:?}", path);
+      }
+      play_file_command(&path)
+    };
+
+    crate::util::set_command_flags(&mut command);
+    let mut child = command
+      .stdin(Stdio::null())
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .spawn()
+      .context("unable to spawn sound player")?;
+
+    // Don't block the dispatch while the sound is playing, but
+    // still reap the child process once it's done
+    std::thread::Builder::new()
+      .name("sound-player".to_string())
+      .spawn(move || {
+        if let Err(err) = child.wait() {
+          warn!("unable to wait for the sound player: {}", err);
+        }
+      })?;
+
+    Ok(())
+  }
+}
+
+// Relative paths are resolved against the espanso config directory
+fn resolve_sound_path(sound: &str, config_dir: &Path) -> PathBuf {
+  let path = PathBuf::from(sound);
+  if path.is_absolute() {
+    path
+  } else {
+    config_dir.join(path)
+  }
+}
+
+#[cfg(target_os = "linux")]
+fn play_file_command(path: &Path) -> Command {
+  let mut command = Command::new("paplay");
+  command.arg(path);
+  command
+}
+
+#[cfg(target_os = "linux")]
+fn beep_command() -> Command {
+  let mut command = Command::new("canberra-gtk-play");
+  command.args(["-i", "bell"]);
+  command
+}
+
+#[cfg(target_os = "macos")]
+fn play_file_command(path: &Path) -> Command {
+  let mut command = Command::new("afplay");
+  command.arg(path);
+  command
+}
+
+#[cfg(target_os = "macos")]
+fn beep_command() -> Command {
+  play_file_command(Path::new("/System/Library/Sounds/Tink.aiff"))
+}
+
+#[cfg(target_os = "windows")]
+fn play_file_command(path: &Path) -> Command {
+  // Single quotes are escaped by doubling them in PowerShell literals
+  let path = path.to_string_lossy().replace('\'', "''");
+  powershell_command(&format!(
+    "(New-Object Media.SoundPlayer '{}').PlaySync()",
+    path
+  ))
+}
+
+#[cfg(target_os = "windows")]
+fn beep_command() -> Command {
+  // The system sound is played asynchronously, so we wait for it to finish
+  powershell_command("[System.Media.SystemSounds]::Beep.Play(); Start-Sleep -Milliseconds 500")
+}
+
+#[cfg(target_os = "windows")]
+fn powershell_command(script: &str) -> Command {
+  let mut command = Command::new("powershell");
+  command.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+  command
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn relative_sound_paths_are_resolved_against_config_dir() {
+    let config_dir = std::env::temp_dir();
+    assert_eq!(
+      resolve_sound_path("sounds/pop.wav", &config_dir),
+      config_dir.join("sounds/pop.wav")
+    );
+  }
+
+  #[test]
+  fn absolute_sound_paths_are_kept() {
+    let absolute = std::env::temp_dir().join("pop.wav");
+    assert_eq!(
+      resolve_sound_path(&absolute.to_string_lossy(), Path::new("config")),
+      absolute
+    );
+  }
+}
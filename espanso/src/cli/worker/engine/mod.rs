@@ -21,7 +21,10 @@ use std::thread::JoinHandle;
 
 use anyhow::Result;
 use crossbeam::channel::Receiver;
-use espanso_config::{config::ConfigStore, matches::store::MatchStore};
+use espanso_config::{
+  config::{Config, ConfigStore},
+  matches::{store::MatchStore, Variable},
+};
 use espanso_detect::SourceCreationOptions;
 use espanso_engine::event::{EventType, ExitMode};
 use espanso_inject::{InjectorCreationOptions, KeyboardStateProvider};
@@ -36,7 +39,8 @@ use crate::{
       dispatch::executor::{
         clipboard_injector::ClipboardInjectorAdapter, context_menu::ContextMenuHandlerAdapter,
         event_injector::EventInjectorAdapter, icon::IconHandlerAdapter,
-        key_injector::KeyInjectorAdapter, secure_input::SecureInputManagerAdapter,
+        injection_dump::InjectionFailureDumper, key_injector::KeyInjectorAdapter,
+        secure_input::SecureInputManagerAdapter, sound::SoundPlayerAdapter,
       },
       process::middleware::{
         image_resolve::PathProviderAdapter,
@@ -45,15 +49,20 @@ use crate::{
           convert::MatchConverter,
           regex::{RegexMatcherAdapter, RegexMatcherAdapterOptions},
           rolling::{RollingMatcherAdapter, RollingMatcherAdapterOptions},
+          runtime::RuntimeMatcherAdapter,
         },
         multiplex::MultiplexAdapter,
         render::{
           extension::{clipboard::ClipboardAdapter, form::FormProviderAdapter},
           RendererAdapter,
         },
+        secure_field::SecureFieldDetectorAdapter,
       },
     },
+    history::{ExpansionHistory, ExpansionHistoryRecorder},
     match_cache::{CombinedMatchCache, MatchCache},
+    runtime_matches::RuntimeMatches,
+    stats::{self, MatchStatsStore, MatchUsageTracker},
     ui::notification::NotificationManager,
   },
   common_flags::{
@@ -83,6 +92,8 @@ pub fn initialize_and_spawn(
   use_evdev_backend: bool,
   start_reason: Option<String>,
   ipc_event_receiver: Receiver<EventType>,
+  expansion_history: ExpansionHistory,
+  runtime_matches: RuntimeMatches,
 ) -> Result<JoinHandle<ExitMode>> {
   let handle = std::thread::Builder::new()
     .name("engine thread".to_string())
@@ -98,14 +109,34 @@ pub fn initialize_and_spawn(
         &*app_info_provider,
         std::time::Duration::from_millis(400),
       );
-      let config_manager =
-        super::config::ConfigManager::new(&*config_store, &*match_store, &cached_app_info_provider);
-      let match_cache = MatchCache::load(&*config_store, &*match_store);
+      let focused_field_provider = espanso_info::get_focused_field_provider();
+      let config_manager = super::config::ConfigManager::new(
+        &*config_store,
+        &*match_store,
+        &cached_app_info_provider,
+        &*focused_field_provider,
+      )
+      .with_runtime_matches(runtime_matches.clone());
+      let match_cache = MatchCache::load(&*config_store, &*match_store)
+        .with_runtime_matches(runtime_matches.clone());
+      let match_stats_store =
+        MatchStatsStore::load(&paths.runtime.join(stats::MATCH_STATS_FILE_NAME));
+      let match_usage_tracker = MatchUsageTracker::new(&match_cache, &match_stats_store);
+      let expansion_history_recorder =
+        ExpansionHistoryRecorder::new(&match_cache, &expansion_history);
       let default_config = &*config_manager.default();
 
+      if default_config.lint_duplicate_triggers() {
+        for duplicate in match_cache.find_duplicate_triggers(&*config_store, &*match_store) {
+          warn!("{}", duplicate);
+        }
+      }
+
       let modulo_manager = crate::gui::modulo::manager::ModuloManager::new();
-      let modulo_form_ui = crate::gui::modulo::form::ModuloFormUI::new(&modulo_manager);
+      let modulo_form_ui =
+        crate::gui::modulo::form::ModuloFormUI::new(&modulo_manager, &config_manager);
       let modulo_search_ui = crate::gui::modulo::search::ModuloSearchUI::new(&modulo_manager);
+      let modulo_confirm_ui = crate::gui::modulo::confirm::ModuloConfirmUI::new(&modulo_form_ui);
 
       let context: Box<dyn Context> = Box::new(super::context::DefaultContext::new(
         &config_manager,
@@ -155,15 +186,22 @@ pub fn initialize_and_spawn(
       let regex_matcher = RegexMatcherAdapter::new(
         &match_converter.get_regex_matches(),
         &RegexMatcherAdapterOptions {
-          max_buffer_size: 30, // TODO: load from configs
+          max_buffer_size: config_manager.default().regex_max_lookback(),
         },
       );
+      let runtime_matcher = RuntimeMatcherAdapter::new(runtime_matches);
       let matchers: Vec<
         &dyn espanso_engine::process::Matcher<
           super::engine::process::middleware::matcher::MatcherState,
         >,
-      > = vec![&rolling_matcher, &regex_matcher];
-      let selector = MatchSelectorAdapter::new(&modulo_search_ui, &combined_match_cache);
+      > = vec![&rolling_matcher, &regex_matcher, &runtime_matcher];
+      let selector = MatchSelectorAdapter::new(
+        &modulo_search_ui,
+        &combined_match_cache,
+        &match_usage_tracker,
+        default_config.search_case_sensitive(),
+        default_config.search_max_results(),
+      );
       let multiplexer = MultiplexAdapter::new(&combined_match_cache, &*context);
 
       let injector = espanso_inject::get_injector(InjectorCreationOptions {
@@ -187,13 +225,20 @@ pub fn initialize_and_spawn(
       // For backwards compatiblity purposes, the echo extension can also be called with "dummy" type
       let dummy_extension = espanso_render::extension::echo::EchoExtension::new_with_alias("dummy");
       let random_extension = espanso_render::extension::random::RandomExtension::new();
+      let file_extension = espanso_render::extension::file::FileExtension::new(&paths.config);
       let home_path = dirs::home_dir().expect("unable to obtain home dir path");
+      let shell_timeout = match config_manager.default().shell_timeout() {
+        0 => None,
+        millis => Some(std::time::Duration::from_millis(millis as u64)),
+      };
       let script_extension = espanso_render::extension::script::ScriptExtension::new(
         &paths.config,
         &home_path,
         &paths.packages,
+        shell_timeout,
       );
-      let shell_extension = espanso_render::extension::shell::ShellExtension::new(&paths.config);
+      let shell_extension =
+        espanso_render::extension::shell::ShellExtension::new(&paths.config, shell_timeout);
       let form_adapter = FormProviderAdapter::new(&modulo_form_ui);
       let form_extension = espanso_render::extension::form::FormExtension::new(&form_adapter);
       let renderer = espanso_render::create(vec![
@@ -202,18 +247,29 @@ pub fn initialize_and_spawn(
         &echo_extension,
         &dummy_extension,
         &random_extension,
+        &file_extension,
         &script_extension,
         &shell_extension,
         &form_extension,
       ]);
-      let renderer_adapter = RendererAdapter::new(&match_cache, &config_manager, &renderer);
+      let secret_vars = load_secret_vars(&*config_manager.default(), &paths.config);
+      let renderer_adapter = RendererAdapter::new(
+        &match_cache,
+        &config_manager,
+        &renderer,
+        &modulo_confirm_ui,
+        secret_vars,
+      );
       let path_provider = PathProviderAdapter::new(&paths);
 
       let disable_options =
         process::middleware::disable::extract_disable_options(&*config_manager.default());
 
       let notification_manager = NotificationManager::new(&*ui_remote, default_config);
+      let secure_field_detector = SecureFieldDetectorAdapter::new(&*focused_field_provider);
 
+      let usage_recorders: Vec<&dyn espanso_engine::process::MatchUsageRecorder> =
+        vec![&match_usage_tracker, &expansion_history_recorder];
       let mut processor = espanso_engine::process::default(
         &matchers,
         &config_manager,
@@ -225,6 +281,7 @@ pub fn initialize_and_spawn(
         &sequencer,
         &path_provider,
         disable_options,
+        match_converter.get_key_sequences(),
         &config_manager,
         &combined_match_cache,
         &config_manager,
@@ -232,25 +289,45 @@ pub fn initialize_and_spawn(
         &modifier_state_store,
         &combined_match_cache,
         &notification_manager,
+        &usage_recorders,
+        &config_manager,
+        &match_cache,
+        &secure_field_detector,
       );
 
       let event_injector = EventInjectorAdapter::new(&*injector, &config_manager);
-      let clipboard_injector =
-        ClipboardInjectorAdapter::new(&*injector, &*clipboard, &config_manager);
+      let clipboard_injector = ClipboardInjectorAdapter::new(
+        &*injector,
+        &*clipboard,
+        &config_manager,
+        &*app_info_provider,
+      );
       let key_injector = KeyInjectorAdapter::new(&*injector, &config_manager);
+      let injection_failure_dumper = InjectionFailureDumper::new(
+        default_config.injection_failure_dumps(),
+        &paths.config,
+        use_evdev_backend,
+        &*app_info_provider,
+      );
       let context_menu_adapter = ContextMenuHandlerAdapter::new(&*ui_remote);
       let icon_adapter = IconHandlerAdapter::new(&*ui_remote);
       let secure_input_adapter = SecureInputManagerAdapter::new();
+      let sound_player = SoundPlayerAdapter::new(&paths.config);
       let dispatcher = espanso_engine::dispatch::default(
         &event_injector,
         &clipboard_injector,
         &config_manager,
         &key_injector,
+        &config_manager,
+        &config_manager,
+        &injection_failure_dumper,
         &clipboard_injector,
         &clipboard_injector,
         &context_menu_adapter,
         &icon_adapter,
         &secure_input_adapter,
+        &notification_manager,
+        &sound_player,
       );
 
       // Disable previously granted linux capabilities if not needed anymore
@@ -262,10 +339,10 @@ pub fn initialize_and_spawn(
 
       match start_reason.as_deref() {
         Some(flag) if flag == WORKER_START_REASON_CONFIG_CHANGED => {
-          notification_manager.notify_config_reloaded(false);
+          notification_manager.notify_config_reloaded(false, match_cache.count());
         }
         Some(flag) if flag == WORKER_START_REASON_MANUAL => {
-          notification_manager.notify_config_reloaded(true);
+          notification_manager.notify_config_reloaded(true, match_cache.count());
         }
         Some(flag) if flag == WORKER_START_REASON_KEYBOARD_LAYOUT_CHANGED => {
           notification_manager.notify_keyboard_layout_reloaded()
@@ -292,6 +369,20 @@ pub fn initialize_and_spawn(
   Ok(handle)
 }
 
+fn load_secret_vars(config: &dyn Config, config_dir: &std::path::Path) -> Vec<Variable> {
+  if let Some(secrets_path) = crate::config::secrets_path(config, config_dir) {
+    match espanso_config::secrets::load_secrets(&secrets_path) {
+      Ok(vars) => {
+        info!("loaded {} secrets from: {:?}", vars.len(), secrets_path);
+        return vars;
+      }
+      Err(err) => error!("unable to load secrets from {:?}: {}", secrets_path, err),
+    }
+  }
+
+  Vec::new()
+}
+
 fn grant_linux_capabilities(use_evdev_backend: bool) -> bool {
   if use_evdev_backend {
     if crate::capabilities::can_use_capabilities() {
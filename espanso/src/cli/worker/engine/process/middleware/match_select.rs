@@ -28,30 +28,54 @@ pub trait MatchProvider<'a> {
   fn get_matches(&self, ids: &[i32]) -> Vec<MatchSummary<'a>>;
 }
 
+// Number of times a match has been expanded, used to display
+// the most used matches first
+pub trait MatchUsageProvider {
+  fn usage_count(&self, match_id: i32) -> u64;
+}
+
 pub struct MatchSummary<'a> {
   pub id: i32,
   pub label: &'a str,
   pub tag: Option<&'a str>,
+  pub group: Option<&'a str>,
+  pub search_terms: &'a [String],
   pub is_builtin: bool,
 }
 
 pub struct MatchSelectorAdapter<'a> {
   search_ui: &'a dyn SearchUI,
   match_provider: &'a dyn MatchProvider<'a>,
+  usage_provider: &'a dyn MatchUsageProvider,
+  case_sensitive: bool,
+  max_results: Option<usize>,
 }
 
 impl<'a> MatchSelectorAdapter<'a> {
-  pub fn new(search_ui: &'a dyn SearchUI, match_provider: &'a dyn MatchProvider<'a>) -> Self {
+  pub fn new(
+    search_ui: &'a dyn SearchUI,
+    match_provider: &'a dyn MatchProvider<'a>,
+    usage_provider: &'a dyn MatchUsageProvider,
+    case_sensitive: bool,
+    max_results: Option<usize>,
+  ) -> Self {
     Self {
       search_ui,
       match_provider,
+      usage_provider,
+      case_sensitive,
+      max_results,
     }
   }
 }
 
 impl<'a> MatchSelector for MatchSelectorAdapter<'a> {
   fn select(&self, matches_ids: &[i32], is_search: bool) -> Option<i32> {
-    let matches = self.match_provider.get_matches(matches_ids);
+    let mut matches = self.match_provider.get_matches(matches_ids);
+
+    // The sort is stable, so matches that were never used keep their order
+    matches.sort_by_key(|m| std::cmp::Reverse(self.usage_provider.usage_count(m.id)));
+
     let search_items: Vec<SearchItem> = matches
       .into_iter()
       .map(|m| {
@@ -61,18 +85,27 @@ impl<'a> MatchSelector for MatchSelectorAdapter<'a> {
           id: m.id.to_string(),
           label: clipped_label.to_string(),
           tag: m.tag.map(String::from),
+          group: m.group.map(String::from),
+          search_terms: m.search_terms.to_vec(),
           is_builtin: m.is_builtin,
         }
       })
       .collect();
 
+    // The Search UI applies the limit after filtering the items with the query,
+    // while the candidates of an ambiguous trigger are always shown
+    let max_results = if is_search { self.max_results } else { None };
+
     let hint = if is_search {
       Some("Search matches by content or trigger (or type > to see commands)")
     } else {
       None
     };
 
-    match self.search_ui.show(&search_items, hint) {
+    match self
+      .search_ui
+      .show(&search_items, hint, self.case_sensitive, max_results)
+    {
       Ok(Some(selected_id)) => match selected_id.parse::<i32>() {
         Ok(id) => Some(id),
         Err(err) => {
@@ -92,4 +125,174 @@ impl<'a> MatchSelector for MatchSelectorAdapter<'a> {
   }
 }
 
-// TODO: test
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::RefCell;
+
+  struct MockSearchUI {
+    items: RefCell<Vec<SearchItem>>,
+    case_sensitive: RefCell<Option<bool>>,
+    max_results: RefCell<Option<Option<usize>>>,
+  }
+
+  impl SearchUI for MockSearchUI {
+    fn show(
+      &self,
+      items: &[SearchItem],
+      _: Option<&str>,
+      case_sensitive: bool,
+      max_results: Option<usize>,
+    ) -> anyhow::Result<Option<String>> {
+      self.case_sensitive.replace(Some(case_sensitive));
+      self.max_results.replace(Some(max_results));
+      self.items.replace(
+        items
+          .iter()
+          .map(|item| SearchItem {
+            id: item.id.clone(),
+            label: item.label.clone(),
+            tag: item.tag.clone(),
+            group: item.group.clone(),
+            search_terms: item.search_terms.clone(),
+            is_builtin: item.is_builtin,
+          })
+          .collect(),
+      );
+      Ok(None)
+    }
+  }
+
+  struct MockMatchProvider;
+
+  impl<'a> MatchProvider<'a> for MockMatchProvider {
+    fn get_matches(&self, _: &[i32]) -> Vec<MatchSummary<'a>> {
+      vec![
+        MatchSummary {
+          id: 1,
+          label: "world",
+          tag: Some("hello"),
+          group: Some("base"),
+          search_terms: &[],
+          is_builtin: false,
+        },
+        MatchSummary {
+          id: 2,
+          label: "Show active config",
+          tag: None,
+          group: None,
+          search_terms: &[],
+          is_builtin: true,
+        },
+      ]
+    }
+  }
+
+  struct MockUsageProvider {
+    counts: Vec<(i32, u64)>,
+  }
+
+  impl MatchUsageProvider for MockUsageProvider {
+    fn usage_count(&self, match_id: i32) -> u64 {
+      self
+        .counts
+        .iter()
+        .find(|(id, _)| *id == match_id)
+        .map_or(0, |(_, count)| *count)
+    }
+  }
+
+  #[test]
+  fn search_items_carry_group() {
+    let search_ui = MockSearchUI {
+      items: RefCell::new(Vec::new()),
+      case_sensitive: RefCell::new(None),
+      max_results: RefCell::new(None),
+    };
+    let match_provider = MockMatchProvider;
+    let usage_provider = MockUsageProvider { counts: Vec::new() };
+    let selector =
+      MatchSelectorAdapter::new(&search_ui, &match_provider, &usage_provider, false, None);
+
+    assert_eq!(selector.select(&[1, 2], true), None);
+
+    let groups: Vec<Option<String>> = search_ui
+      .items
+      .borrow()
+      .iter()
+      .map(|item| item.group.clone())
+      .collect();
+    assert_eq!(groups, vec![Some("base".to_string()), None]);
+  }
+
+  #[test]
+  fn search_items_are_sorted_by_usage() {
+    let search_ui = MockSearchUI {
+      items: RefCell::new(Vec::new()),
+      case_sensitive: RefCell::new(None),
+      max_results: RefCell::new(None),
+    };
+    let match_provider = MockMatchProvider;
+    let usage_provider = MockUsageProvider {
+      counts: vec![(2, 5), (1, 3)],
+    };
+    let selector =
+      MatchSelectorAdapter::new(&search_ui, &match_provider, &usage_provider, false, None);
+
+    assert_eq!(selector.select(&[1, 2], true), None);
+
+    let ids: Vec<String> = search_ui
+      .items
+      .borrow()
+      .iter()
+      .map(|item| item.id.clone())
+      .collect();
+    assert_eq!(ids, vec!["2".to_string(), "1".to_string()]);
+  }
+
+  #[test]
+  fn case_sensitivity_is_forwarded_to_search_ui() {
+    let match_provider = MockMatchProvider;
+    let usage_provider = MockUsageProvider { counts: Vec::new() };
+
+    for case_sensitive in [true, false] {
+      let search_ui = MockSearchUI {
+        items: RefCell::new(Vec::new()),
+        case_sensitive: RefCell::new(None),
+        max_results: RefCell::new(None),
+      };
+      let selector = MatchSelectorAdapter::new(
+        &search_ui,
+        &match_provider,
+        &usage_provider,
+        case_sensitive,
+        None,
+      );
+
+      assert_eq!(selector.select(&[1, 2], true), None);
+      assert_eq!(*search_ui.case_sensitive.borrow(), Some(case_sensitive));
+    }
+  }
+
+  #[test]
+  fn max_results_is_only_forwarded_for_search() {
+    let match_provider = MockMatchProvider;
+    let usage_provider = MockUsageProvider { counts: Vec::new() };
+
+    for (is_search, expected) in [(true, Some(1)), (false, None)] {
+      let search_ui = MockSearchUI {
+        items: RefCell::new(Vec::new()),
+        case_sensitive: RefCell::new(None),
+        max_results: RefCell::new(None),
+      };
+      let selector =
+        MatchSelectorAdapter::new(&search_ui, &match_provider, &usage_provider, false, Some(1));
+
+      assert_eq!(selector.select(&[1, 2], is_search), None);
+
+      // All the items are sent, as the limit is applied after filtering
+      assert_eq!(search_ui.items.borrow().len(), 2);
+      assert_eq!(*search_ui.max_results.borrow(), Some(expected));
+    }
+  }
+}
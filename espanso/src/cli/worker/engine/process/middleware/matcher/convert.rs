@@ -17,14 +17,21 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use std::{collections::BTreeMap, time::Duration};
+
 use espanso_config::{
   config::ConfigStore,
   matches::{
+    parse_key_sequence,
     store::{MatchSet, MatchStore},
-    MatchCause,
+    KeySequenceCause, MatchCause,
   },
 };
 use espanso_detect::hotkey::HotKey;
+use espanso_engine::{
+  event::input::{Key, Variant},
+  process::KeySequence,
+};
 use espanso_match::{
   regex::RegexMatch,
   rolling::{RollingMatch, StringMatchOptions},
@@ -55,19 +62,22 @@ impl<'a> MatchConverter<'a> {
   // TODO: test (might need to move the conversion logic into a separate function)
   pub fn get_rolling_matches(&self) -> Vec<RollingMatch<i32>> {
     let match_set = self.global_match_set();
+    let prefix_aliases = self.config_store.default().trigger_prefix_aliases();
     let mut matches = Vec::new();
 
     // First convert configuration (user-defined) matches
     for m in match_set.matches {
       if let MatchCause::Trigger(cause) = &m.cause {
-        for trigger in cause.triggers.iter() {
+        for trigger in expand_trigger_prefixes(&cause.triggers, &prefix_aliases) {
           matches.push(RollingMatch::from_string(
             m.id,
-            trigger,
+            &trigger,
             &StringMatchOptions {
               case_insensitive: cause.propagate_case,
               left_word: cause.left_word,
               right_word: cause.right_word,
+              line_start: cause.line_start,
+              word_separators: cause.word_separators.clone(),
             },
           ))
         }
@@ -95,7 +105,7 @@ impl<'a> MatchConverter<'a> {
 
     for m in match_set.matches {
       if let MatchCause::Regex(cause) = &m.cause {
-        matches.push(RegexMatch::new(m.id, &cause.regex))
+        matches.push(RegexMatch::new(m.id, &cause.regex).with_max_lookback(cause.max_lookback))
       }
     }
 
@@ -103,9 +113,23 @@ impl<'a> MatchConverter<'a> {
   }
 
   pub fn get_hotkeys(&self) -> Vec<HotKey> {
+    let match_set = self.global_match_set();
     let mut hotkeys = Vec::new();
 
-    // TODO: read user-defined matches
+    // First convert configuration (user-defined) matches
+    for m in match_set.matches {
+      if let MatchCause::HotKey(cause) = &m.cause {
+        match HotKey::new(m.id, &cause.hotkey) {
+          Ok(hotkey) => hotkeys.push(hotkey),
+          Err(err) => {
+            error!(
+              "unable to register hotkey: {}, with error: {}",
+              cause.hotkey, err
+            );
+          }
+        }
+      }
+    }
 
     // Then convert built-in ones
     for m in self.builtin_matches {
@@ -122,6 +146,35 @@ impl<'a> MatchConverter<'a> {
     hotkeys
   }
 
+  pub fn get_key_sequences(&self) -> Vec<KeySequence> {
+    let match_set = self.global_match_set();
+    let mut sequences = Vec::new();
+
+    // First convert configuration (user-defined) matches
+    for m in match_set.matches {
+      if let MatchCause::KeySequence(cause) = &m.cause {
+        sequences.extend(convert_key_sequence(m.id, cause));
+      }
+    }
+
+    // Then convert built-in ones
+    for m in self.builtin_matches {
+      if let Some(spec) = &m.key_sequence {
+        match parse_key_sequence(spec) {
+          Ok(cause) => sequences.extend(convert_key_sequence(m.id, &cause)),
+          Err(err) => {
+            error!(
+              "unable to register key sequence: {}, with error: {}",
+              spec, err
+            );
+          }
+        }
+      }
+    }
+
+    sequences
+  }
+
   fn global_match_set(&self) -> MatchSet {
     let paths = self.config_store.get_all_match_paths();
     self
@@ -129,3 +182,179 @@ impl<'a> MatchConverter<'a> {
       .query(&paths.into_iter().collect::<Vec<_>>())
   }
 }
+
+// Add a variant of each trigger for every alias of the prefix it starts with,
+// so that with `":": [";"]` the trigger `:foo` also matches `;foo`
+fn expand_trigger_prefixes(
+  triggers: &[String],
+  prefix_aliases: &BTreeMap<String, Vec<String>>,
+) -> Vec<String> {
+  let mut expanded = triggers.to_vec();
+
+  for trigger in triggers {
+    for (prefix, aliases) in prefix_aliases {
+      if prefix.is_empty() {
+        continue;
+      }
+
+      if let Some(rest) = trigger.strip_prefix(prefix.as_str()) {
+        for alias in aliases {
+          let variant = format!("{}{}", alias, rest);
+          if !expanded.contains(&variant) {
+            expanded.push(variant);
+          }
+        }
+      }
+    }
+  }
+
+  expanded
+}
+
+fn convert_key_sequence(id: i32, cause: &KeySequenceCause) -> Option<KeySequence> {
+  let keys = cause
+    .keys
+    .iter()
+    .map(|key| convert_key(key))
+    .collect::<Option<Vec<_>>>();
+
+  match keys {
+    Some(keys) => Some(KeySequence {
+      id,
+      keys,
+      window: Duration::from_millis(cause.window_ms),
+    }),
+    None => {
+      error!("unable to register key sequence: {}", cause.to_spec());
+      None
+    }
+  }
+}
+
+// Keys are already normalized by the config parser
+fn convert_key(name: &str) -> Option<(Key, Option<Variant>)> {
+  let (variant, name) = if let Some(name) = name.strip_prefix("LEFT_") {
+    (Some(Variant::Left), name)
+  } else if let Some(name) = name.strip_prefix("RIGHT_") {
+    (Some(Variant::Right), name)
+  } else {
+    (None, name)
+  };
+
+  let key = match name {
+    "CTRL" => Key::Control,
+    "ALT" => Key::Alt,
+    "SHIFT" => Key::Shift,
+    "META" => Key::Meta,
+    "CAPSLOCK" => Key::CapsLock,
+    "ESCAPE" => Key::Escape,
+    "ENTER" => Key::Enter,
+    "TAB" => Key::Tab,
+    "SPACE" => Key::Space,
+    "F1" => Key::F1,
+    "F2" => Key::F2,
+    "F3" => Key::F3,
+    "F4" => Key::F4,
+    "F5" => Key::F5,
+    "F6" => Key::F6,
+    "F7" => Key::F7,
+    "F8" => Key::F8,
+    "F9" => Key::F9,
+    "F10" => Key::F10,
+    "F11" => Key::F11,
+    "F12" => Key::F12,
+    "F13" => Key::F13,
+    "F14" => Key::F14,
+    "F15" => Key::F15,
+    "F16" => Key::F16,
+    "F17" => Key::F17,
+    "F18" => Key::F18,
+    "F19" => Key::F19,
+    "F20" => Key::F20,
+    _ => return None,
+  };
+
+  Some((key, variant))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use espanso_match::{
+    event::{Event, Key as MatchKey},
+    rolling::matcher::{RollingMatcher, RollingMatcherOptions},
+    Matcher,
+  };
+
+  fn aliases(prefix: &str, aliases: &[&str]) -> BTreeMap<String, Vec<String>> {
+    vec![(
+      prefix.to_string(),
+      aliases.iter().map(|alias| alias.to_string()).collect(),
+    )]
+    .into_iter()
+    .collect()
+  }
+
+  fn triggers(triggers: &[&str]) -> Vec<String> {
+    triggers.iter().map(|trigger| trigger.to_string()).collect()
+  }
+
+  fn matches_after_str(matcher: &RollingMatcher<i32>, string: &str) -> Vec<i32> {
+    let mut prev_state = None;
+    let mut matches = Vec::new();
+    for c in string.chars() {
+      let (state, results) = matcher.process(
+        prev_state.as_ref(),
+        Event::Key {
+          key: MatchKey::Other,
+          chars: Some(c.to_string()),
+        },
+      );
+      prev_state = Some(state);
+      matches = results.into_iter().map(|result| result.id).collect();
+    }
+    matches
+  }
+
+  #[test]
+  fn expand_trigger_prefixes_adds_aliases() {
+    assert_eq!(
+      expand_trigger_prefixes(&triggers(&[":foo", "bar"]), &aliases(":", &[";", "//"])),
+      triggers(&[":foo", "bar", ";foo", "//foo"])
+    );
+  }
+
+  #[test]
+  fn expand_trigger_prefixes_without_aliases_is_unchanged() {
+    assert_eq!(
+      expand_trigger_prefixes(&triggers(&[":foo"]), &BTreeMap::new()),
+      triggers(&[":foo"])
+    );
+    assert_eq!(
+      expand_trigger_prefixes(&triggers(&[":foo"]), &aliases("", &[";"])),
+      triggers(&[":foo"])
+    );
+  }
+
+  #[test]
+  fn expand_trigger_prefixes_skips_existing_triggers() {
+    assert_eq!(
+      expand_trigger_prefixes(&triggers(&[":foo", ";foo"]), &aliases(":", &[";"])),
+      triggers(&[":foo", ";foo"])
+    );
+  }
+
+  #[test]
+  fn both_prefixes_match_the_trigger() {
+    let matches: Vec<RollingMatch<i32>> =
+      expand_trigger_prefixes(&triggers(&[":foo"]), &aliases(":", &[";"]))
+        .iter()
+        .map(|trigger| RollingMatch::from_string(1, trigger, &StringMatchOptions::default()))
+        .collect();
+    let matcher = RollingMatcher::new(&matches, RollingMatcherOptions::default());
+
+    assert_eq!(matches_after_str(&matcher, ":foo"), vec![1]);
+    assert_eq!(matches_after_str(&matcher, ";foo"), vec![1]);
+    assert!(matches_after_str(&matcher, ".foo").is_empty());
+  }
+}
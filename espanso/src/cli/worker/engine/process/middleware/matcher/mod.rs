@@ -29,11 +29,13 @@ use enum_as_inner::EnumAsInner;
 pub mod convert;
 pub mod regex;
 pub mod rolling;
+pub mod runtime;
 
 #[derive(Clone, EnumAsInner)]
 pub enum MatcherState<'a> {
   Rolling(RollingMatcherState<'a, i32>),
   Regex(RegexMatcherState),
+  Runtime(RegexMatcherState),
 }
 
 pub fn convert_to_match_event(event: &MatcherEvent) -> espanso_match::event::Event {
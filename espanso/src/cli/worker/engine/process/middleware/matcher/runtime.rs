@@ -0,0 +1,276 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::cell::RefCell;
+
+use espanso_config::matches::MatchCause;
+use espanso_engine::process::{MatchResult, Matcher, MatcherEvent};
+use espanso_match::regex::{RegexMatch, RegexMatcher, RegexMatcherOptions};
+
+use crate::cli::worker::runtime_matches::RuntimeMatches;
+
+use super::{convert_to_engine_result, convert_to_match_event, MatcherState};
+
+// Detects the triggers of the matches added through IPC. The other matchers
+// are built once at startup, so this one is rebuilt whenever a match is added.
+// Triggers are matched at the end of the typed text, without word options.
+pub struct RuntimeMatcherAdapter {
+  runtime_matches: RuntimeMatches,
+  matcher: RefCell<Option<(usize, RegexMatcher<i32>)>>,
+}
+
+impl RuntimeMatcherAdapter {
+  pub fn new(runtime_matches: RuntimeMatches) -> Self {
+    Self {
+      runtime_matches,
+      matcher: RefCell::new(None),
+    }
+  }
+
+  fn refresh(&self) {
+    let count = self.runtime_matches.count();
+    let mut matcher = self.matcher.borrow_mut();
+    if matches!(&*matcher, Some((built_count, _)) if *built_count == count) {
+      return;
+    }
+
+    let mut regex_matches = Vec::new();
+    let mut max_buffer_size = 1;
+    for m in self.runtime_matches.all() {
+      if let MatchCause::Trigger(cause) = &m.cause {
+        for trigger in cause.triggers.iter() {
+          let flags = if cause.propagate_case { "(?i)" } else { "" };
+          let regex = format!("{}{}$", flags, regex::escape(trigger));
+          regex_matches.push(RegexMatch::new(m.id, &regex));
+          max_buffer_size = max_buffer_size.max(trigger.len());
+        }
+      }
+    }
+
+    *matcher = Some((
+      count,
+      RegexMatcher::new(&regex_matches, RegexMatcherOptions { max_buffer_size }),
+    ));
+  }
+}
+
+impl<'a> Matcher<'a, MatcherState<'a>> for RuntimeMatcherAdapter {
+  fn process(
+    &'a self,
+    prev_state: Option<&MatcherState<'a>>,
+    event: &MatcherEvent,
+  ) -> (MatcherState<'a>, Vec<MatchResult>) {
+    use espanso_match::Matcher;
+
+    let prev_state = prev_state.map(|state| {
+      if let Some(state) = state.as_runtime() {
+        state
+      } else {
+        panic!("invalid state type received in RuntimeMatcherAdapter")
+      }
+    });
+    let event = convert_to_match_event(event);
+
+    self.refresh();
+    let matcher = self.matcher.borrow();
+    let (_, matcher) = matcher
+      .as_ref()
+      .expect("runtime matcher is not initialized");
+    let (state, results) = matcher.process(prev_state, event);
+
+    let enum_state = MatcherState::Runtime(state);
+    let results: Vec<MatchResult> = results.into_iter().map(convert_to_engine_result).collect();
+
+    (enum_state, results)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use espanso_engine::{
+    event::{
+      internal::{DetectedMatch, MatchesDetectedEvent},
+      Event, EventType,
+    },
+    process::{EventSequenceProvider, MatchSelectMiddleware, MatchSelector, Middleware, Renderer},
+  };
+  use espanso_info::{AppInfo, AppInfoProvider, NoopFocusedFieldProvider};
+
+  use super::*;
+  use crate::{
+    cli::worker::{
+      config::ConfigManager,
+      engine::process::middleware::render::{
+        tests::{MockConfigProvider, MockConfirmUI},
+        RendererAdapter,
+      },
+      match_cache::MatchCache,
+    },
+    util::tests::load_test_config,
+  };
+
+  struct MockAppInfoProvider {}
+
+  impl AppInfoProvider for MockAppInfoProvider {
+    fn get_info(&self) -> AppInfo {
+      AppInfo {
+        title: None,
+        exec: None,
+        class: None,
+      }
+    }
+  }
+
+  struct MockSelector {}
+
+  impl MatchSelector for MockSelector {
+    fn select(&self, _: &[i32], _: bool) -> Option<i32> {
+      None
+    }
+  }
+
+  struct MockSequenceProvider {}
+
+  impl EventSequenceProvider for MockSequenceProvider {
+    fn get_next_id(&self) -> u32 {
+      0
+    }
+  }
+
+  fn type_str<'a>(
+    matcher: &'a RuntimeMatcherAdapter,
+    prev_state: Option<MatcherState<'a>>,
+    string: &str,
+  ) -> (Option<MatcherState<'a>>, Vec<MatchResult>) {
+    let mut state = prev_state;
+    let mut results = Vec::new();
+    for c in string.chars() {
+      let (new_state, new_results) = matcher.process(
+        state.as_ref(),
+        &MatcherEvent::Key {
+          key: espanso_engine::event::input::Key::Other(0),
+          chars: Some(c.to_string()),
+        },
+      );
+      state = Some(new_state);
+      results = new_results;
+    }
+    (state, results)
+  }
+
+  #[test]
+  fn matches_added_at_runtime_are_detected() {
+    let runtime_matches = RuntimeMatches::new();
+    let matcher = RuntimeMatcherAdapter::new(runtime_matches.clone());
+
+    let (state, results) = type_str(&matcher, None, ":sig");
+    assert!(results.is_empty());
+
+    let id = runtime_matches
+      .add_from_definition(r#"{"trigger": ":sig", "replace": "Best regards"}"#)
+      .unwrap();
+
+    let (_, results) = type_str(&matcher, state, "hey :sig");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, id);
+    assert_eq!(results[0].trigger, ":sig");
+  }
+
+  #[test]
+  fn matches_added_at_runtime_are_expanded() {
+    let config = load_test_config("");
+    let (config_store, match_store) = (&config.config_store, &config.match_store);
+
+    let runtime_matches = RuntimeMatches::new();
+    let match_cache = MatchCache::load(&**config_store, &**match_store)
+      .with_runtime_matches(runtime_matches.clone());
+    let config_provider = MockConfigProvider {
+      config: config_store.default(),
+    };
+    let renderer = espanso_render::create(Vec::new());
+    let confirm_ui = MockConfirmUI::new(true);
+    let renderer_adapter = RendererAdapter::new(
+      &match_cache,
+      &config_provider,
+      &renderer,
+      &confirm_ui,
+      Vec::new(),
+    );
+    let matcher = RuntimeMatcherAdapter::new(runtime_matches.clone());
+
+    // The match is added after the matcher and the renderer are created
+    runtime_matches
+      .add_from_definition(r#"{"trigger": ":sig", "replace": "Best regards"}"#)
+      .unwrap();
+
+    let (_, results) = type_str(&matcher, None, ":sig");
+    assert_eq!(results.len(), 1);
+    let rendered = renderer_adapter
+      .render(results[0].id, Some(&results[0].trigger), HashMap::new())
+      .unwrap();
+    assert_eq!(rendered, "Best regards");
+  }
+
+  #[test]
+  fn matches_added_at_runtime_pass_match_selection() {
+    let config = load_test_config("");
+    let (config_store, match_store) = (&config.config_store, &config.match_store);
+
+    let runtime_matches = RuntimeMatches::new();
+    let app_info_provider = MockAppInfoProvider {};
+    let focused_field_provider = NoopFocusedFieldProvider {};
+    let config_manager = ConfigManager::new(
+      &**config_store,
+      &**match_store,
+      &app_info_provider,
+      &focused_field_provider,
+    )
+    .with_runtime_matches(runtime_matches.clone());
+    let selector = MockSelector {};
+    let sequence_provider = MockSequenceProvider {};
+    let middleware = MatchSelectMiddleware::new(&config_manager, &selector, &sequence_provider);
+    let matcher = RuntimeMatcherAdapter::new(runtime_matches.clone());
+
+    let id = runtime_matches
+      .add_from_definition(r#"{"trigger": ":sig", "replace": "Best regards"}"#)
+      .unwrap();
+
+    let (_, results) = type_str(&matcher, None, ":sig");
+    assert_eq!(results.len(), 1);
+    let event = Event::caused_by(
+      0,
+      EventType::MatchesDetected(MatchesDetectedEvent {
+        matches: vec![DetectedMatch {
+          id: results[0].id,
+          trigger: Some(results[0].trigger.clone()),
+          ..Default::default()
+        }],
+        is_search: false,
+      }),
+    );
+
+    let event = middleware.next(event, &mut |_| {});
+    match event.etype {
+      EventType::MatchSelected(selected) => assert_eq!(selected.chosen.id, id),
+      other => panic!("expected the runtime match to be selected, got {:?}", other),
+    }
+  }
+}
@@ -23,3 +23,4 @@ pub mod match_select;
 pub mod matcher;
 pub mod multiplex;
 pub mod render;
+pub mod secure_field;
@@ -64,6 +64,7 @@ impl<'a> Multiplexer for MultiplexAdapter<'a> {
         MatchEffect::Image(effect) => Some(EventType::ImageRequested(ImageRequestedEvent {
           match_id: detected_match.id,
           image_path: effect.path.clone(),
+          preserve_format: effect.preserve_format,
         })),
         MatchEffect::None => None,
       },
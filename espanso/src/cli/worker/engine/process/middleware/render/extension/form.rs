@@ -19,11 +19,13 @@
 
 use std::collections::HashMap;
 
+use anyhow::{Context, Result};
 use espanso_render::{
   extension::form::{FormProvider, FormProviderResult},
   Params, Value,
 };
 use log::error;
+use regex::Regex;
 
 use crate::gui::{FormField, FormUI};
 
@@ -38,9 +40,13 @@ impl<'a> FormProviderAdapter<'a> {
 }
 
 impl<'a> FormProvider for FormProviderAdapter<'a> {
-  fn show(&self, layout: &str, fields: &Params, _: &Params) -> FormProviderResult {
-    let fields = convert_fields(fields);
-    match self.form_ui.show(layout, &fields) {
+  fn show(&self, layout: &str, fields: &Params, options: &Params) -> FormProviderResult {
+    let fields = match convert_fields(fields) {
+      Ok(fields) => fields,
+      Err(err) => return FormProviderResult::Error(err),
+    };
+    let order = extract_order(options);
+    match self.form_ui.show(layout, &fields, &order) {
       Ok(Some(results)) => FormProviderResult::Success(results),
       Ok(None) => FormProviderResult::Aborted,
       Err(err) => FormProviderResult::Error(err),
@@ -48,24 +54,31 @@ impl<'a> FormProvider for FormProviderAdapter<'a> {
   }
 }
 
-// TODO: test
-fn convert_fields(fields: &Params) -> HashMap<String, FormField> {
+// Invalid validation patterns are reported as errors, instead of letting
+// the user fill in a form that could never be submitted
+fn convert_fields(fields: &Params) -> Result<HashMap<String, FormField>> {
   let mut out = HashMap::new();
   for (name, field) in fields {
     let mut form_field = None;
 
     if let Value::Object(params) = field {
       form_field = match params.get("type") {
-        Some(Value::String(field_type)) if field_type == "choice" => Some(FormField::Choice {
-          default: params
-            .get("default")
-            .and_then(|val| val.as_string())
-            .cloned(),
-          values: params
-            .get("values")
-            .and_then(|v| extract_values(v, params.get("trim_string_values")))
-            .unwrap_or_default(),
-        }),
+        Some(Value::String(field_type))
+          if field_type == "choice" || field_type == "random_choice" =>
+        {
+          Some(FormField::Choice {
+            default: params
+              .get("default")
+              .and_then(|val| val.as_string())
+              .cloned(),
+            values: params
+              .get("values")
+              .and_then(|v| extract_values(v, params.get("trim_string_values")))
+              .unwrap_or_default(),
+            required: extract_required(params),
+            random_default: field_type == "random_choice",
+          })
+        }
         Some(Value::String(field_type)) if field_type == "list" => Some(FormField::List {
           default: params
             .get("default")
@@ -75,6 +88,7 @@ fn convert_fields(fields: &Params) -> HashMap<String, FormField> {
             .get("values")
             .and_then(|v| extract_values(v, params.get("trim_string_values")))
             .unwrap_or_default(),
+          required: extract_required(params),
         }),
         // By default, it's considered type 'text'
         _ => Some(FormField::Text {
@@ -87,6 +101,8 @@ fn convert_fields(fields: &Params) -> HashMap<String, FormField> {
             .and_then(|val| val.as_bool())
             .cloned()
             .unwrap_or(false),
+          required: extract_required(params),
+          validate: extract_validate(name, params)?,
         }),
       }
     }
@@ -97,7 +113,37 @@ fn convert_fields(fields: &Params) -> HashMap<String, FormField> {
       error!("malformed form field format for '{}'", name);
     }
   }
-  out
+  Ok(out)
+}
+
+fn extract_validate(name: &str, params: &Params) -> Result<Option<String>> {
+  match params.get("validate").and_then(|val| val.as_string()) {
+    Some(pattern) => {
+      Regex::new(pattern)
+        .with_context(|| format!("invalid validate pattern for form field '{}'", name))?;
+      Ok(Some(pattern.clone()))
+    }
+    None => Ok(None),
+  }
+}
+
+fn extract_required(params: &Params) -> bool {
+  params
+    .get("required")
+    .and_then(|val| val.as_bool())
+    .cloned()
+    .unwrap_or(false)
+}
+
+fn extract_order(options: &Params) -> Vec<String> {
+  match options.get("order") {
+    Some(Value::Array(names)) => names
+      .iter()
+      .filter_map(|name| name.as_string())
+      .cloned()
+      .collect(),
+    _ => Vec::new(),
+  }
 }
 
 fn extract_values(value: &Value, trim_string_values: Option<&Value>) -> Option<Vec<String>> {
@@ -134,3 +180,102 @@ fn extract_values(value: &Value, trim_string_values: Option<&Value>) -> Option<V
     _ => None,
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn field(params: &[(&str, Value)]) -> Value {
+    Value::Object(
+      params
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn convert_fields_carries_validation_params() {
+    let mut fields = Params::new();
+    fields.insert(
+      "email".to_string(),
+      field(&[
+        ("required", Value::Bool(true)),
+        ("validate", Value::String(".+@.+".to_string())),
+      ]),
+    );
+    fields.insert(
+      "choice".to_string(),
+      field(&[
+        ("type", Value::String("choice".to_string())),
+        ("values", Value::String("a\nb".to_string())),
+        ("required", Value::Bool(true)),
+      ]),
+    );
+    fields.insert("name".to_string(), field(&[]));
+
+    let converted = convert_fields(&fields).unwrap();
+    assert_eq!(
+      converted["email"],
+      FormField::Text {
+        default: None,
+        multiline: false,
+        required: true,
+        validate: Some(".+@.+".to_string()),
+      }
+    );
+    assert_eq!(
+      converted["choice"],
+      FormField::Choice {
+        default: None,
+        values: vec!["a".to_string(), "b".to_string()],
+        required: true,
+        random_default: false,
+      }
+    );
+    assert_eq!(
+      converted["name"],
+      FormField::Text {
+        default: None,
+        multiline: false,
+        required: false,
+        validate: None,
+      }
+    );
+  }
+
+  #[test]
+  fn convert_fields_random_choice() {
+    let mut fields = Params::new();
+    fields.insert(
+      "prompt".to_string(),
+      field(&[
+        ("type", Value::String("random_choice".to_string())),
+        ("values", Value::String("first\nsecond".to_string())),
+      ]),
+    );
+
+    let converted = convert_fields(&fields).unwrap();
+    assert_eq!(
+      converted["prompt"],
+      FormField::Choice {
+        default: None,
+        values: vec!["first".to_string(), "second".to_string()],
+        required: false,
+        random_default: true,
+      }
+    );
+  }
+
+  #[test]
+  fn convert_fields_rejects_invalid_validate_pattern() {
+    let mut fields = Params::new();
+    fields.insert(
+      "email".to_string(),
+      field(&[("validate", Value::String("(.+@".to_string()))]),
+    );
+
+    let err = convert_fields(&fields).unwrap_err();
+    assert!(err.to_string().contains("email"));
+  }
+}
@@ -22,32 +22,48 @@ use std::{cell::RefCell, collections::HashMap, sync::Arc};
 pub mod extension;
 
 use espanso_config::{
-  config::Config,
-  matches::{store::MatchSet, Match, MatchCause, MatchEffect, UpperCasingStyle},
+  config::{AppProperties, Config},
+  matches::{
+    store::MatchSet, Match, MatchCause, MatchEffect, TextEffect, TextTransform, UpperCasingStyle,
+  },
 };
-use espanso_render::{CasingStyle, Context, RenderOptions, Template, Value, Variable};
+use espanso_info::AppInfo;
+use espanso_render::{
+  apply_casing_style, CasingStyle, Context, RenderOptions, Template, Value, Variable,
+};
+use log::debug;
+use rand::{seq::SliceRandom, Rng};
 
 use espanso_engine::process::{Renderer, RendererError};
 
+use crate::gui::ConfirmUI;
+
 pub trait MatchProvider<'a> {
   fn matches(&self) -> Vec<&'a Match>;
   fn get(&self, id: i32) -> Option<&'a Match>;
+  fn source_path(&self, id: i32) -> Option<&'a str>;
 }
 
 pub trait ConfigProvider<'a> {
   fn configs(&self) -> Vec<(Arc<dyn Config>, MatchSet)>;
   fn active(&self) -> (Arc<dyn Config>, MatchSet);
+  fn active_app(&self) -> AppInfo;
 }
 
 pub struct RendererAdapter<'a> {
   renderer: &'a dyn espanso_render::Renderer,
   match_provider: &'a dyn MatchProvider<'a>,
   config_provider: &'a dyn ConfigProvider<'a>,
+  confirm_ui: &'a dyn ConfirmUI,
 
   template_map: HashMap<i32, Option<Template>>,
   global_vars_map: HashMap<i32, Variable>,
+  secret_vars: Vec<Variable>,
 
   context_cache: RefCell<HashMap<i32, Context<'a>>>,
+
+  // Long replacements, loaded the first time the match is expanded
+  lazy_replace_cache: RefCell<HashMap<i32, String>>,
 }
 
 impl<'a> RendererAdapter<'a> {
@@ -55,18 +71,39 @@ impl<'a> RendererAdapter<'a> {
     match_provider: &'a dyn MatchProvider<'a>,
     config_provider: &'a dyn ConfigProvider<'a>,
     renderer: &'a dyn espanso_render::Renderer,
+    confirm_ui: &'a dyn ConfirmUI,
+    secret_vars: Vec<espanso_config::matches::Variable>,
   ) -> Self {
     let template_map = generate_template_map(match_provider);
     let global_vars_map = generate_global_vars_map(config_provider);
+    let secret_vars = convert_vars(secret_vars);
 
     Self {
       renderer,
       config_provider,
       match_provider,
+      confirm_ui,
       template_map,
       global_vars_map,
+      secret_vars,
       context_cache: RefCell::new(HashMap::new()),
+      lazy_replace_cache: RefCell::new(HashMap::new()),
+    }
+  }
+
+  fn load_lazy_replace_cached(&self, m: &Match) -> anyhow::Result<Option<String>> {
+    if let Some(replace) = self.lazy_replace_cache.borrow().get(&m.id) {
+      return Ok(Some(replace.clone()));
+    }
+
+    let replace = load_lazy_replace(m)?;
+    if let Some(replace) = &replace {
+      self
+        .lazy_replace_cache
+        .borrow_mut()
+        .insert(m.id, replace.clone());
     }
+    Ok(replace)
   }
 }
 
@@ -100,9 +137,11 @@ fn generate_context<'a>(
   match_set: &MatchSet,
   template_map: &'a HashMap<i32, Option<Template>>,
   global_vars_map: &'a HashMap<i32, Variable>,
+  secret_vars: &'a [Variable],
 ) -> Context<'a> {
   let mut templates = Vec::new();
-  let mut global_vars = Vec::new();
+  // Secrets come first, so that the other global vars can depend on them
+  let mut global_vars: Vec<&Variable> = secret_vars.iter().collect();
 
   for m in match_set.matches.iter() {
     if let Some(Some(template)) = template_map.get(&m.id) {
@@ -124,7 +163,7 @@ fn generate_context<'a>(
 
 // TODO: move conversion methods to new file?
 
-fn convert_to_template(m: &Match) -> Option<Template> {
+pub fn convert_to_template(m: &Match) -> Option<Template> {
   if let MatchEffect::Text(text_effect) = &m.effect {
     let ids = if let MatchCause::Trigger(cause) = &m.cause {
       cause.triggers.clone()
@@ -146,13 +185,15 @@ fn convert_vars(vars: Vec<espanso_config::matches::Variable>) -> Vec<espanso_ren
   vars.into_iter().map(convert_var).collect()
 }
 
-fn convert_var(var: espanso_config::matches::Variable) -> espanso_render::Variable {
+pub fn convert_var(var: espanso_config::matches::Variable) -> espanso_render::Variable {
   Variable {
+    id: var.id,
     name: var.name,
     var_type: var.var_type,
     params: convert_params(var.params),
     inject_vars: var.inject_vars,
     depends_on: var.depends_on,
+    persist: var.persist,
   }
 }
 
@@ -193,32 +234,89 @@ impl<'a> Renderer<'a> for RendererAdapter<'a> {
     trigger: Option<&str>,
     trigger_vars: HashMap<String, String>,
   ) -> anyhow::Result<String> {
-    if let Some(Some(template)) = self.template_map.get(&match_id) {
+    // Matches added at runtime are not part of the template map, so
+    // their template is generated when needed
+    let runtime_template;
+    let template = match self.template_map.get(&match_id) {
+      Some(template) => template.as_ref(),
+      None => {
+        runtime_template = self
+          .match_provider
+          .get(match_id)
+          .and_then(convert_to_template);
+        runtime_template.as_ref()
+      }
+    };
+
+    if let Some(template) = template {
       let (config, match_set) = self.config_provider.active();
 
       let mut context_cache = self.context_cache.borrow_mut();
-      let context = context_cache
-        .entry(config.id())
-        .or_insert_with(|| generate_context(&match_set, &self.template_map, &self.global_vars_map));
+      let context = context_cache.entry(config.id()).or_insert_with(|| {
+        generate_context(
+          &match_set,
+          &self.template_map,
+          &self.global_vars_map,
+          &self.secret_vars,
+        )
+      });
 
       let raw_match = self.match_provider.get(match_id);
+      if let Some(raw_match) = raw_match {
+        if !confirm_expansion(raw_match, self.confirm_ui)? {
+          debug!("expansion of match {} was not confirmed", match_id);
+          return Err(RendererError::Aborted.into());
+        }
+      }
+
+      debug!(
+        "rendering match {} defined in: {}",
+        match_id,
+        self
+          .match_provider
+          .source_path(match_id)
+          .unwrap_or("unknown")
+      );
       let propagate_case = raw_match.map(is_propagate_case).unwrap_or(false);
       let preferred_uppercasing_style = raw_match.and_then(extract_uppercasing_style);
+      let capture_uppercasing_style = raw_match.and_then(extract_capture_uppercasing_style);
 
       let options = RenderOptions {
         casing_style: if !propagate_case {
           CasingStyle::None
         } else if let Some(trigger) = trigger {
-          calculate_casing_style(trigger, preferred_uppercasing_style)
+          calculate_casing_pattern(trigger, preferred_uppercasing_style)
         } else {
           CasingStyle::None
         },
       };
 
-      // If some trigger vars are specified, augment the template with them
-      let augmented_template = if !trigger_vars.is_empty() {
+      let replace = raw_match
+        .and_then(|raw_match| {
+          select_replacement(
+            raw_match,
+            || self.config_provider.active_app(),
+            &mut rand::thread_rng(),
+          )
+        })
+        .cloned();
+      let replace = match (replace, raw_match) {
+        (None, Some(raw_match)) => self.load_lazy_replace_cached(raw_match)?,
+        (replace, _) => replace,
+      };
+
+      // If some trigger vars are specified (or a different replacement has been chosen),
+      // augment the template with them
+      let augmented_template = if !trigger_vars.is_empty() || replace.is_some() {
         let mut augmented = template.clone();
+        if let Some(body) = replace {
+          augmented.body = body;
+        }
         for (name, value) in trigger_vars {
+          let value = match capture_uppercasing_style {
+            Some(style) => apply_uppercasing_style(&value, style),
+            None => value,
+          };
           let mut params = espanso_render::Params::new();
           params.insert("echo".to_string(), Value::String(value));
           augmented.vars.insert(
@@ -254,6 +352,22 @@ impl<'a> Renderer<'a> for RendererAdapter<'a> {
   }
 }
 
+// Matches marked with `confirm: true` are only expanded if the user accepts.
+// The replacement is never shown, as it might contain sensitive data
+fn confirm_expansion(m: &Match, confirm_ui: &dyn ConfirmUI) -> anyhow::Result<bool> {
+  match &m.effect {
+    MatchEffect::Text(effect) if effect.confirm => {
+      let name = m
+        .label
+        .as_deref()
+        .or_else(|| m.cause.description())
+        .map_or_else(|| "this match".to_string(), |name| format!("'{}'", name));
+      confirm_ui.confirm(&format!("Do you want to expand {}?", name))
+    }
+    _ => Ok(true),
+  }
+}
+
 fn extract_uppercasing_style(m: &Match) -> Option<UpperCasingStyle> {
   if let MatchCause::Trigger(cause) = &m.cause {
     Some(cause.uppercase_style.clone())
@@ -262,6 +376,90 @@ fn extract_uppercasing_style(m: &Match) -> Option<UpperCasingStyle> {
   }
 }
 
+// Regex matches with propagate_case apply the uppercase_style to their captures
+fn extract_capture_uppercasing_style(m: &Match) -> Option<&UpperCasingStyle> {
+  match &m.cause {
+    MatchCause::Regex(cause) if cause.propagate_case => Some(&cause.uppercase_style),
+    _ => None,
+  }
+}
+
+// Built-in transforms applied to the rendered body of a match
+pub fn apply_transform(body: String, transform: TextTransform) -> String {
+  match transform {
+    TextTransform::Trim => body.trim().to_string(),
+    TextTransform::Upper => apply_casing_style(body, &CasingStyle::Uppercase),
+    TextTransform::Lower => body.to_lowercase(),
+    TextTransform::Title => apply_casing_style(body.to_lowercase(), &CasingStyle::CapitalizeWords),
+  }
+}
+
+fn apply_uppercasing_style(text: &str, style: &UpperCasingStyle) -> String {
+  let casing_style = match style {
+    UpperCasingStyle::Uppercase => CasingStyle::Uppercase,
+    UpperCasingStyle::Capitalize => CasingStyle::Capitalize,
+    UpperCasingStyle::CapitalizeWords => CasingStyle::CapitalizeWords,
+  };
+  apply_casing_style(text.to_string(), &casing_style)
+}
+
+// Long replacements are not part of the template, as they
+// are loaded from the source file when needed
+pub fn load_lazy_replace(m: &Match) -> anyhow::Result<Option<String>> {
+  match &m.effect {
+    MatchEffect::Text(TextEffect {
+      lazy_replace: Some(lazy_replace),
+      ..
+    }) => Ok(Some(lazy_replace.load()?)),
+    _ => Ok(None),
+  }
+}
+
+fn extract_replace_random(m: &Match) -> Option<&[String]> {
+  if let MatchEffect::Text(effect) = &m.effect {
+    if !effect.replace_random.is_empty() {
+      return Some(&effect.replace_random);
+    }
+  }
+  None
+}
+
+fn extract_text_effect_with_variants(m: &Match) -> Option<&TextEffect> {
+  if let MatchEffect::Text(effect) = &m.effect {
+    if !effect.variants.is_empty() {
+      return Some(effect);
+    }
+  }
+  None
+}
+
+// Selects the replacement to use in place of `replace`, if any. A variant matching
+// the active application takes precedence over the other replacements, and the
+// app is only queried if the match has variants. When multiple replacements
+// are available, one of them is chosen randomly
+pub fn select_replacement<'a, R: Rng>(
+  m: &'a Match,
+  active_app: impl FnOnce() -> AppInfo,
+  rng: &mut R,
+) -> Option<&'a String> {
+  let variant_replace = extract_text_effect_with_variants(m).and_then(|effect| {
+    let app = active_app();
+    let variant = effect.variant_for(&AppProperties {
+      title: app.title.as_deref(),
+      class: app.class.as_deref(),
+      exec: app.exec.as_deref(),
+    });
+    variant.map(|variant| &variant.replace)
+  });
+
+  variant_replace
+    .or_else(|| extract_replace_random(m).and_then(|choices| choose_replacement(choices, rng)))
+}
+
+fn choose_replacement<'a, R: Rng>(choices: &'a [String], rng: &mut R) -> Option<&'a String> {
+  choices.choose(rng)
+}
+
 fn is_propagate_case(m: &Match) -> bool {
   if let MatchCause::Trigger(cause) = &m.cause {
     cause.propagate_case
@@ -270,50 +468,375 @@ fn is_propagate_case(m: &Match) -> bool {
   }
 }
 
-// TODO: test
 fn calculate_casing_style(
   trigger: &str,
   uppercasing_style: Option<UpperCasingStyle>,
 ) -> CasingStyle {
-  let mut first_alphabetic = None;
-  let mut second_alphabetic = None;
-
-  for c in trigger.chars() {
-    if c.is_alphabetic() {
-      if first_alphabetic.is_none() {
-        first_alphabetic = Some(c);
-      } else if second_alphabetic.is_none() {
-        second_alphabetic = Some(c);
-      } else {
-        break;
+  let alphabetic: Vec<char> = trigger.chars().filter(|c| c.is_alphabetic()).collect();
+
+  match alphabetic.as_slice() {
+    [] => CasingStyle::None,
+    [first] if first.is_uppercase() => match uppercasing_style {
+      Some(UpperCasingStyle::Capitalize) => CasingStyle::Capitalize,
+      Some(UpperCasingStyle::CapitalizeWords) => CasingStyle::CapitalizeWords,
+      _ => CasingStyle::Uppercase,
+    },
+    // An all-caps trigger (such as an acronym) always produces an
+    // uppercase replacement, regardless of the uppercase_style
+    letters if letters.iter().all(|c| c.is_uppercase()) => CasingStyle::Uppercase,
+    // Two leading uppercase letters (as in "HTml") are enough to uppercase the replacement
+    [first, second, ..] if first.is_uppercase() && second.is_uppercase() => CasingStyle::Uppercase,
+    [first, ..] if first.is_uppercase() => match uppercasing_style {
+      Some(UpperCasingStyle::CapitalizeWords) => CasingStyle::CapitalizeWords,
+      _ => CasingStyle::Capitalize,
+    },
+    _ => CasingStyle::None,
+  }
+}
+
+// Mixed-case triggers (such as "tEh") can't be described by the regular styles,
+// so their case is copied letter by letter onto the replacement, falling back
+// to the regular style when the replacement has a different number of letters
+fn calculate_casing_pattern(
+  trigger: &str,
+  uppercasing_style: Option<UpperCasingStyle>,
+) -> CasingStyle {
+  let fallback = calculate_casing_style(trigger, uppercasing_style);
+  let letters: Vec<char> = trigger.chars().filter(|c| c.is_alphabetic()).collect();
+
+  let has_lowercase = letters.iter().any(|c| c.is_lowercase());
+  let has_inner_uppercase = letters.iter().skip(1).any(|c| c.is_uppercase());
+  if has_lowercase && has_inner_uppercase {
+    CasingStyle::Pattern {
+      uppercase: letters.iter().map(|c| c.is_uppercase()).collect(),
+      fallback: Box::new(fallback),
+    }
+  } else {
+    fallback
+  }
+}
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use crate::util::tests::load_test_config_with;
+
+  #[test]
+  fn casing_style_lowercase_trigger() {
+    assert_eq!(calculate_casing_style("html", None), CasingStyle::None);
+    assert_eq!(
+      calculate_casing_style(":html", Some(UpperCasingStyle::CapitalizeWords)),
+      CasingStyle::None
+    );
+  }
+
+  #[test]
+  fn casing_style_capitalized_trigger() {
+    assert_eq!(
+      calculate_casing_style("Html", Some(UpperCasingStyle::Uppercase)),
+      CasingStyle::Capitalize
+    );
+    assert_eq!(
+      calculate_casing_style(":Html", Some(UpperCasingStyle::CapitalizeWords)),
+      CasingStyle::CapitalizeWords
+    );
+    assert_eq!(
+      calculate_casing_style("HTml", Some(UpperCasingStyle::Capitalize)),
+      CasingStyle::Uppercase
+    );
+  }
+
+  #[test]
+  fn casing_style_all_caps_trigger() {
+    assert_eq!(
+      calculate_casing_style("HTML", Some(UpperCasingStyle::Uppercase)),
+      CasingStyle::Uppercase
+    );
+    assert_eq!(
+      calculate_casing_style(":HTML", Some(UpperCasingStyle::Capitalize)),
+      CasingStyle::Uppercase
+    );
+    assert_eq!(
+      calculate_casing_style("HTML", Some(UpperCasingStyle::CapitalizeWords)),
+      CasingStyle::Uppercase
+    );
+  }
+
+  #[test]
+  fn casing_style_single_letter_trigger() {
+    assert_eq!(
+      calculate_casing_style(":A", Some(UpperCasingStyle::Uppercase)),
+      CasingStyle::Uppercase
+    );
+    assert_eq!(
+      calculate_casing_style(":A", Some(UpperCasingStyle::Capitalize)),
+      CasingStyle::Capitalize
+    );
+    assert_eq!(calculate_casing_style(":a", None), CasingStyle::None);
+  }
+
+  #[test]
+  fn casing_pattern_regular_triggers_use_style() {
+    assert_eq!(calculate_casing_pattern("teh", None), CasingStyle::None);
+    assert_eq!(
+      calculate_casing_pattern(":Teh", None),
+      CasingStyle::Capitalize
+    );
+    assert_eq!(
+      calculate_casing_pattern("TEH", None),
+      CasingStyle::Uppercase
+    );
+  }
+
+  #[test]
+  fn casing_pattern_mixed_case_trigger() {
+    assert_eq!(
+      calculate_casing_pattern(":tEh", None),
+      CasingStyle::Pattern {
+        uppercase: vec![false, true, false],
+        fallback: Box::new(CasingStyle::None),
       }
+    );
+    assert_eq!(
+      calculate_casing_pattern("HTml", Some(UpperCasingStyle::CapitalizeWords)),
+      CasingStyle::Pattern {
+        uppercase: vec![true, true, false, false],
+        fallback: Box::new(CasingStyle::Uppercase),
+      }
+    );
+  }
+
+  #[test]
+  fn single_transforms() {
+    assert_eq!(
+      apply_transform("  hi \n".to_string(), TextTransform::Trim),
+      "hi"
+    );
+    assert_eq!(
+      apply_transform("Hi".to_string(), TextTransform::Upper),
+      "HI"
+    );
+    assert_eq!(
+      apply_transform("Hi".to_string(), TextTransform::Lower),
+      "hi"
+    );
+    assert_eq!(
+      apply_transform("hELLO wORLD-wide".to_string(), TextTransform::Title),
+      "Hello World-Wide"
+    );
+  }
+
+  #[test]
+  fn capture_uppercase_style() {
+    assert_eq!(
+      apply_uppercasing_style("john doe", &UpperCasingStyle::Uppercase),
+      "JOHN DOE"
+    );
+  }
+
+  #[test]
+  fn capture_capitalize_style() {
+    assert_eq!(
+      apply_uppercasing_style("john doe", &UpperCasingStyle::Capitalize),
+      "John doe"
+    );
+    assert_eq!(
+      apply_uppercasing_style("", &UpperCasingStyle::Capitalize),
+      ""
+    );
+  }
+
+  #[test]
+  fn capture_capitalize_words_style() {
+    assert_eq!(
+      apply_uppercasing_style("john doe-smith", &UpperCasingStyle::CapitalizeWords),
+      "John Doe-Smith"
+    );
+  }
+
+  #[test]
+  fn capture_style_requires_regex_propagate_case() {
+    use espanso_config::matches::{RegexCause, TriggerCause};
+
+    let regex_match = |propagate_case| Match {
+      cause: MatchCause::Regex(RegexCause {
+        regex: "hi (?P<name>\\w+)".to_string(),
+        propagate_case,
+        uppercase_style: UpperCasingStyle::Capitalize,
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    assert_eq!(
+      extract_capture_uppercasing_style(&regex_match(true)),
+      Some(&UpperCasingStyle::Capitalize)
+    );
+    assert_eq!(extract_capture_uppercasing_style(&regex_match(false)), None);
+
+    let trigger_match = Match {
+      cause: MatchCause::Trigger(TriggerCause {
+        propagate_case: true,
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    assert_eq!(extract_capture_uppercasing_style(&trigger_match), None);
+  }
+
+  #[test]
+  fn choose_replacement_stays_within_choices() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let choices = vec![
+      "first".to_string(),
+      "second".to_string(),
+      "third".to_string(),
+    ];
+    let mut rng = StdRng::seed_from_u64(42);
+
+    for _ in 0..100 {
+      let choice = choose_replacement(&choices, &mut rng).unwrap();
+      assert!(choices.contains(choice));
     }
   }
 
-  if let Some(first) = first_alphabetic {
-    if let Some(second) = second_alphabetic {
-      if first.is_uppercase() {
-        if second.is_uppercase() {
-          CasingStyle::Uppercase
-        } else {
-          match uppercasing_style {
-            Some(UpperCasingStyle::CapitalizeWords) => CasingStyle::CapitalizeWords,
-            _ => CasingStyle::Capitalize,
-          }
-        }
-      } else {
-        CasingStyle::None
+  #[test]
+  fn choose_replacement_empty_choices() {
+    let mut rng = rand::thread_rng();
+    assert!(choose_replacement(&[], &mut rng).is_none());
+  }
+
+  pub struct MockConfirmUI {
+    answer: bool,
+    messages: RefCell<Vec<String>>,
+  }
+
+  impl MockConfirmUI {
+    pub fn new(answer: bool) -> Self {
+      Self {
+        answer,
+        messages: RefCell::new(Vec::new()),
       }
-    } else if first.is_uppercase() {
-      match uppercasing_style {
-        Some(UpperCasingStyle::Capitalize) => CasingStyle::Capitalize,
-        Some(UpperCasingStyle::CapitalizeWords) => CasingStyle::CapitalizeWords,
-        _ => CasingStyle::Uppercase,
+    }
+  }
+
+  impl ConfirmUI for MockConfirmUI {
+    fn confirm(&self, message: &str) -> anyhow::Result<bool> {
+      self.messages.borrow_mut().push(message.to_string());
+      Ok(self.answer)
+    }
+  }
+
+  fn sensitive_match(confirm: bool) -> Match {
+    use espanso_config::matches::TriggerCause;
+
+    Match {
+      cause: MatchCause::Trigger(TriggerCause {
+        triggers: vec![":pwd".to_string()],
+        ..Default::default()
+      }),
+      effect: MatchEffect::Text(TextEffect {
+        replace: "hunter2".to_string(),
+        confirm,
+        ..Default::default()
+      }),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn confirm_expansion_follows_the_answer() {
+    for answer in [true, false] {
+      let confirm_ui = MockConfirmUI::new(answer);
+      assert_eq!(
+        confirm_expansion(&sensitive_match(true), &confirm_ui).unwrap(),
+        answer
+      );
+
+      let messages = confirm_ui.messages.borrow();
+      assert_eq!(messages.len(), 1);
+      assert!(messages[0].contains("':pwd'"));
+      assert!(!messages[0].contains("hunter2"));
+    }
+  }
+
+  #[test]
+  fn confirm_expansion_is_skipped_when_not_required() {
+    let confirm_ui = MockConfirmUI::new(false);
+    assert!(confirm_expansion(&sensitive_match(false), &confirm_ui).unwrap());
+    assert!(confirm_ui.messages.borrow().is_empty());
+  }
+
+  pub struct MockConfigProvider {
+    pub config: Arc<dyn Config>,
+  }
+
+  impl<'a> ConfigProvider<'a> for MockConfigProvider {
+    fn configs(&self) -> Vec<(Arc<dyn Config>, MatchSet<'_>)> {
+      vec![self.active()]
+    }
+
+    fn active(&self) -> (Arc<dyn Config>, MatchSet<'_>) {
+      (
+        self.config.clone(),
+        MatchSet {
+          matches: Vec::new(),
+          global_vars: Vec::new(),
+        },
+      )
+    }
+
+    fn active_app(&self) -> AppInfo {
+      AppInfo {
+        title: None,
+        exec: None,
+        class: None,
       }
-    } else {
-      CasingStyle::None
     }
-  } else {
-    CasingStyle::None
+  }
+
+  #[test]
+  fn lazy_replace_is_loaded_once_when_rendering() {
+    let match_yaml = |body: &str| {
+      format!(
+        "matches:\n  - trigger: \":long\"\n    replace: {:?}\n",
+        body
+      )
+    };
+    let body = "Lorem ipsum\n".repeat(2000);
+    let config = load_test_config_with("lazy_replace: true", &[("base.yml", &match_yaml(&body))]);
+    let config_store = &config.config_store;
+    let match_store = &config.match_store;
+
+    let match_cache =
+      crate::cli::worker::match_cache::MatchCache::load(&**config_store, &**match_store);
+    let config_provider = MockConfigProvider {
+      config: config_store.default(),
+    };
+    let renderer = espanso_render::create(Vec::new());
+    let confirm_ui = MockConfirmUI::new(true);
+    let adapter = RendererAdapter::new(
+      &match_cache,
+      &config_provider,
+      &renderer,
+      &confirm_ui,
+      Vec::new(),
+    );
+
+    let m = match_store
+      .query(config_store.default().match_paths())
+      .matches[0];
+    assert!(m.effect.as_text().unwrap().lazy_replace.is_some());
+    assert_eq!(
+      adapter.render(m.id, Some(":long"), HashMap::new()).unwrap(),
+      body
+    );
+
+    // Once loaded, the replacement is served from the cache even if the source changes
+    std::fs::write(config.match_file("base.yml"), match_yaml("changed")).unwrap();
+    assert_eq!(
+      adapter.render(m.id, Some(":long"), HashMap::new()).unwrap(),
+      body
+    );
   }
 }
@@ -0,0 +1,39 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use espanso_engine::process::SecureFieldDetector;
+use espanso_info::FocusedFieldProvider;
+
+pub struct SecureFieldDetectorAdapter<'a> {
+  focused_field_provider: &'a dyn FocusedFieldProvider,
+}
+
+impl<'a> SecureFieldDetectorAdapter<'a> {
+  pub fn new(focused_field_provider: &'a dyn FocusedFieldProvider) -> Self {
+    Self {
+      focused_field_provider,
+    }
+  }
+}
+
+impl<'a> SecureFieldDetector for SecureFieldDetectorAdapter<'a> {
+  fn is_focused_field_secure(&self) -> bool {
+    self.focused_field_provider.is_focused_field_secure()
+  }
+}
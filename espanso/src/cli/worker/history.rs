@@ -0,0 +1,145 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{
+  collections::VecDeque,
+  sync::{Arc, Mutex},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::ipc::ExpansionRecord;
+
+use super::match_cache::MatchCache;
+
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+// In-memory ring buffer of the latest expansions, shared between the
+// engine (which populates it) and the IPC handler (which queries it)
+#[derive(Clone)]
+pub struct ExpansionHistory {
+  capacity: usize,
+  records: Arc<Mutex<VecDeque<ExpansionRecord>>>,
+}
+
+impl ExpansionHistory {
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+    }
+  }
+
+  pub fn push(&self, record: ExpansionRecord) {
+    let mut records = self
+      .records
+      .lock()
+      .expect("unable to lock expansion history");
+    if records.len() >= self.capacity {
+      records.pop_front();
+    }
+    records.push_back(record);
+  }
+
+  // Returns the last `count` expansions, from the oldest to the most recent
+  pub fn last(&self, count: usize) -> Vec<ExpansionRecord> {
+    let records = self
+      .records
+      .lock()
+      .expect("unable to lock expansion history");
+    let skip = records.len().saturating_sub(count);
+    records.iter().skip(skip).cloned().collect()
+  }
+}
+
+// Adds every expansion to the history, as seen by the engine
+pub struct ExpansionHistoryRecorder<'a> {
+  match_cache: &'a MatchCache<'a>,
+  history: &'a ExpansionHistory,
+}
+
+impl<'a> ExpansionHistoryRecorder<'a> {
+  pub fn new(match_cache: &'a MatchCache<'a>, history: &'a ExpansionHistory) -> Self {
+    Self {
+      match_cache,
+      history,
+    }
+  }
+}
+
+impl<'a> espanso_engine::process::MatchUsageRecorder for ExpansionHistoryRecorder<'a> {
+  fn record_usage(&self, match_id: i32) {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or_default();
+    self.history.push(ExpansionRecord {
+      match_id,
+      trigger: self
+        .match_cache
+        .cause_description(match_id)
+        .map(String::from),
+      timestamp,
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn record(match_id: i32) -> ExpansionRecord {
+    ExpansionRecord {
+      match_id,
+      trigger: Some(format!(":{}", match_id)),
+      timestamp: match_id as u64,
+    }
+  }
+
+  #[test]
+  fn history_retains_last_entries() {
+    let history = ExpansionHistory::new(3);
+    for id in 1..=5 {
+      history.push(record(id));
+    }
+
+    let ids: Vec<i32> = history.last(10).iter().map(|r| r.match_id).collect();
+    assert_eq!(ids, vec![3, 4, 5]);
+  }
+
+  #[test]
+  fn history_last_returns_most_recent() {
+    let history = ExpansionHistory::new(10);
+    for id in 1..=5 {
+      history.push(record(id));
+    }
+
+    let ids: Vec<i32> = history.last(2).iter().map(|r| r.match_id).collect();
+    assert_eq!(ids, vec![4, 5]);
+    assert!(history.last(0).is_empty());
+  }
+
+  #[test]
+  fn history_is_shared_between_clones() {
+    let history = ExpansionHistory::new(10);
+    let handle = history.clone();
+    handle.push(record(1));
+
+    assert_eq!(history.last(10), vec![record(1)]);
+  }
+}
@@ -27,10 +27,14 @@ use log::{error, warn};
 
 use crate::ipc::IPCEvent;
 
+use super::{history::ExpansionHistory, runtime_matches::RuntimeMatches};
+
 pub fn initialize_and_spawn(
   runtime_dir: &Path,
   exit_notify: Sender<ExitMode>,
   event_notify: Sender<EventType>,
+  expansion_history: ExpansionHistory,
+  runtime_matches: RuntimeMatches,
 ) -> Result<()> {
   let server = crate::ipc::create_worker_ipc_server(runtime_dir)?;
 
@@ -38,47 +42,14 @@ pub fn initialize_and_spawn(
     .name("worker-ipc-handler".to_string())
     .spawn(move || {
       server
-        .run(Box::new(move |event| match event {
-          IPCEvent::Exit => {
-            if let Err(err) = exit_notify.send(ExitMode::Exit) {
-              error!(
-                "experienced error while sending exit signal from worker ipc handler: {}",
-                err
-              );
-            }
-
-            EventHandlerResponse::NoResponse
-          }
-          IPCEvent::ExitAllProcesses => {
-            if let Err(err) = exit_notify.send(ExitMode::ExitAllProcesses) {
-              error!(
-                "experienced error while sending exit signal from worker ipc handler: {}",
-                err
-              );
-            }
-
-            EventHandlerResponse::NoResponse
-          }
-          IPCEvent::DisableRequest => send_event(&event_notify, EventType::DisableRequest),
-          IPCEvent::EnableRequest => send_event(&event_notify, EventType::EnableRequest),
-          IPCEvent::ToggleRequest => send_event(&event_notify, EventType::ToggleRequest),
-          IPCEvent::OpenSearchBar => send_event(&event_notify, EventType::ShowSearchBar),
-          IPCEvent::RequestMatchExpansion(payload) => send_event(
+        .run(Box::new(move |event| {
+          handle_event(
+            event,
+            &exit_notify,
             &event_notify,
-            EventType::MatchExecRequest(MatchExecRequestEvent {
-              trigger: payload.trigger,
-              args: payload.args,
-            }),
-          ),
-          #[allow(unreachable_patterns)]
-          unexpected_event => {
-            warn!(
-              "received unexpected event in worker ipc handler: {:?}",
-              unexpected_event
-            );
-
-            EventHandlerResponse::NoResponse
-          }
+            &expansion_history,
+            &runtime_matches,
+          )
         }))
         .expect("unable to spawn IPC server");
     })?;
@@ -86,6 +57,69 @@ pub fn initialize_and_spawn(
   Ok(())
 }
 
+fn handle_event(
+  event: IPCEvent,
+  exit_notify: &Sender<ExitMode>,
+  event_notify: &Sender<EventType>,
+  expansion_history: &ExpansionHistory,
+  runtime_matches: &RuntimeMatches,
+) -> EventHandlerResponse<IPCEvent> {
+  match event {
+    IPCEvent::Exit => {
+      if let Err(err) = exit_notify.send(ExitMode::Exit) {
+        error!(
+          "experienced error while sending exit signal from worker ipc handler: {}",
+          err
+        );
+      }
+
+      EventHandlerResponse::NoResponse
+    }
+    IPCEvent::ExitAllProcesses => {
+      if let Err(err) = exit_notify.send(ExitMode::ExitAllProcesses) {
+        error!(
+          "experienced error while sending exit signal from worker ipc handler: {}",
+          err
+        );
+      }
+
+      EventHandlerResponse::NoResponse
+    }
+    IPCEvent::DisableRequest => send_event(event_notify, EventType::DisableRequest),
+    IPCEvent::EnableRequest => send_event(event_notify, EventType::EnableRequest),
+    IPCEvent::ToggleRequest => send_event(event_notify, EventType::ToggleRequest),
+    IPCEvent::OpenSearchBar => send_event(event_notify, EventType::ShowSearchBar),
+    IPCEvent::RequestMatchExpansion(payload) => send_event(
+      event_notify,
+      EventType::MatchExecRequest(MatchExecRequestEvent {
+        trigger: payload.trigger,
+        args: payload.args,
+      }),
+    ),
+    IPCEvent::ExpansionHistoryRequest(count) => {
+      EventHandlerResponse::Response(IPCEvent::ExpansionHistory(expansion_history.last(count)))
+    }
+    IPCEvent::AddMatchRequest(definition) => {
+      let result = runtime_matches
+        .add_from_definition(&definition)
+        .map_err(|err| {
+          warn!("rejected match definition received through IPC: {:?}", err);
+          format!("{:#}", err)
+        });
+      EventHandlerResponse::Response(IPCEvent::AddMatchResponse(result))
+    }
+    #[allow(unreachable_patterns)]
+    unexpected_event => {
+      warn!(
+        "received unexpected event in worker ipc handler: {:?}",
+        unexpected_event
+      );
+
+      EventHandlerResponse::NoResponse
+    }
+  }
+}
+
 fn send_event(
   event_notify: &Sender<EventType>,
   event: EventType,
@@ -99,3 +133,90 @@ fn send_event(
 
   EventHandlerResponse::NoResponse
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crossbeam::channel::unbounded;
+
+  fn round_trip(event: IPCEvent) -> IPCEvent {
+    let serialized = serde_json::to_string(&event).unwrap();
+    serde_json::from_str(&serialized).unwrap()
+  }
+
+  #[test]
+  fn enable_and_disable_requests_reach_the_engine() {
+    let (exit_notify, exit_signal) = unbounded::<ExitMode>();
+    let (event_notify, event_signal) = unbounded::<EventType>();
+    let history = ExpansionHistory::new(10);
+    let runtime_matches = RuntimeMatches::new();
+
+    handle_event(
+      round_trip(IPCEvent::DisableRequest),
+      &exit_notify,
+      &event_notify,
+      &history,
+      &runtime_matches,
+    );
+    handle_event(
+      round_trip(IPCEvent::EnableRequest),
+      &exit_notify,
+      &event_notify,
+      &history,
+      &runtime_matches,
+    );
+
+    assert!(matches!(
+      event_signal.try_recv().unwrap(),
+      EventType::DisableRequest
+    ));
+    assert!(matches!(
+      event_signal.try_recv().unwrap(),
+      EventType::EnableRequest
+    ));
+    assert!(exit_signal.try_recv().is_err());
+  }
+
+  fn add_match(runtime_matches: &RuntimeMatches, definition: &str) -> Result<i32, String> {
+    let (exit_notify, _) = unbounded::<ExitMode>();
+    let (event_notify, _) = unbounded::<EventType>();
+    let history = ExpansionHistory::new(10);
+
+    match handle_event(
+      round_trip(IPCEvent::AddMatchRequest(definition.to_string())),
+      &exit_notify,
+      &event_notify,
+      &history,
+      runtime_matches,
+    ) {
+      EventHandlerResponse::Response(response) => match round_trip(response) {
+        IPCEvent::AddMatchResponse(result) => result,
+        other => panic!("unexpected response: {:?}", other),
+      },
+      _ => panic!("expected a response"),
+    }
+  }
+
+  #[test]
+  fn add_match_request_inserts_the_match() {
+    let runtime_matches = RuntimeMatches::new();
+
+    let id = add_match(
+      &runtime_matches,
+      r#"{"trigger": ":addr", "replace": "221B Baker Street"}"#,
+    )
+    .unwrap();
+
+    let m = runtime_matches.get(id).unwrap();
+    assert_eq!(m.effect.as_text().unwrap().replace, "221B Baker Street");
+  }
+
+  #[test]
+  fn add_match_request_rejects_invalid_definitions() {
+    let runtime_matches = RuntimeMatches::new();
+
+    let err = add_match(&runtime_matches, "trigger: [").unwrap_err();
+    assert!(err.contains("unable to parse match definition"));
+    assert!(runtime_matches.all().is_empty());
+  }
+}
@@ -17,7 +17,10 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::collections::HashMap;
+use std::{
+  collections::{HashMap, HashSet},
+  path::Path,
+};
 
 use espanso_config::{
   config::ConfigStore,
@@ -25,44 +28,240 @@ use espanso_config::{
 };
 use espanso_engine::event::internal::DetectedMatch;
 
-use super::{builtin::BuiltInMatch, engine::process::middleware::match_select::MatchSummary};
+use super::{
+  builtin::BuiltInMatch,
+  engine::process::middleware::{match_select::MatchSummary, render::apply_transform},
+  runtime_matches::RuntimeMatches,
+};
 
 pub struct MatchCache<'a> {
   cache: HashMap<i32, &'a Match>,
+  sources: HashMap<i32, &'a str>,
+  group_labels: HashMap<i32, &'a str>,
+
+  // Ids of the matches defined in each match group file, so that
+  // a single file can be reloaded without rebuilding the whole cache
+  ids_by_path: HashMap<&'a str, Vec<i32>>,
+
+  // Matches added through IPC, which don't belong to any file
+  runtime_matches: RuntimeMatches,
+}
+
+// The current matches of a match group file, used to update the cache.
+// A group without matches removes the file from the cache.
+pub struct MatchCacheGroup<'a> {
+  pub path: &'a str,
+  pub label: Option<&'a str>,
+  pub matches: Vec<&'a Match>,
 }
 
 impl<'a> MatchCache<'a> {
   pub fn load(config_store: &'a dyn ConfigStore, match_store: &'a dyn MatchStore) -> Self {
     let mut cache = HashMap::new();
+    let mut sources = HashMap::new();
+    let mut group_labels = HashMap::new();
+    let mut ids_by_path: HashMap<&'a str, Vec<i32>> = HashMap::new();
 
     let paths = config_store.get_all_match_paths();
     let global_set = match_store.query(&paths.into_iter().collect::<Vec<_>>());
 
     for m in global_set.matches {
       cache.insert(m.id, m);
+
+      if let Some(source_path) = match_store.source_path(m.id) {
+        sources.insert(m.id, source_path);
+        ids_by_path.entry(source_path).or_default().push(m.id);
+      }
+
+      if let Some(group_label) = match_store.group_label(m.id) {
+        group_labels.insert(m.id, group_label);
+      }
     }
 
-    Self { cache }
+    Self {
+      cache,
+      sources,
+      group_labels,
+      ids_by_path,
+      runtime_matches: RuntimeMatches::new(),
+    }
   }
 
-  fn ids(&self) -> Vec<i32> {
-    self.cache.keys().copied().collect()
+  pub fn with_runtime_matches(mut self, runtime_matches: RuntimeMatches) -> Self {
+    self.runtime_matches = runtime_matches;
+    self
+  }
+
+  fn get(&self, match_id: i32) -> Option<&'a Match> {
+    self
+      .cache
+      .get(&match_id)
+      .copied()
+      .or_else(|| self.runtime_matches.get(match_id))
+  }
+
+  fn all(&self) -> Vec<&'a Match> {
+    let mut matches: Vec<&'a Match> = self.cache.values().copied().collect();
+    matches.extend(self.runtime_matches.all());
+    matches
+  }
+
+  // Replace the matches of the given files, removing the ones that
+  // are no longer defined and inserting the new ones
+  #[allow(dead_code)]
+  pub fn reload(&mut self, groups: Vec<MatchCacheGroup<'a>>) {
+    for group in groups {
+      if let Some(stale_ids) = self.ids_by_path.remove(group.path) {
+        for id in stale_ids {
+          self.cache.remove(&id);
+          self.sources.remove(&id);
+          self.group_labels.remove(&id);
+        }
+      }
+
+      if group.matches.is_empty() {
+        continue;
+      }
+
+      let mut ids = Vec::with_capacity(group.matches.len());
+      for m in group.matches {
+        self.cache.insert(m.id, m);
+        self.sources.insert(m.id, group.path);
+        if let Some(label) = group.label {
+          self.group_labels.insert(m.id, label);
+        }
+        ids.push(m.id);
+      }
+      self.ids_by_path.insert(group.path, ids);
+    }
+  }
+
+  // Number of user-defined matches, excluding the built-in ones
+  pub fn count(&self) -> usize {
+    self.cache.len()
+  }
+
+  // Matches marked with `searchable: false` are excluded from the search bar
+  fn searchable_ids(&self) -> Vec<i32> {
+    self
+      .all()
+      .into_iter()
+      .filter(|m| m.searchable)
+      .map(|m| m.id)
+      .collect()
+  }
+
+  // Path of the match group file in which the match is defined
+  fn source_path(&self, match_id: i32) -> Option<&'a str> {
+    self.sources.get(&match_id).copied()
+  }
+
+  // The group of a match is the label of the file in which it's defined,
+  // falling back to the file name
+  fn group(&self, match_id: i32) -> Option<&'a str> {
+    if let Some(group_label) = self.group_labels.get(&match_id) {
+      return Some(group_label);
+    }
+
+    let source_path = self.source_path(match_id)?;
+    Path::new(source_path).file_stem()?.to_str()
+  }
+
+  // First trigger (or hotkey) of the match, if any
+  pub fn cause_description(&self, match_id: i32) -> Option<&'a str> {
+    self.get(match_id)?.cause.description()
+  }
+
+  // Triggers defined by more than one match of the same configuration.
+  // Matches of different app-specific configurations are never active at
+  // the same time, so they can safely share a trigger.
+  pub fn find_duplicate_triggers(
+    &self,
+    config_store: &dyn ConfigStore,
+    match_store: &dyn MatchStore,
+  ) -> Vec<DuplicateTrigger<'a>> {
+    let mut reported = HashSet::new();
+    let mut duplicates = Vec::new();
+
+    for config in config_store.configs() {
+      let match_set = match_store.query(config.match_paths());
+      let mut first_by_trigger: HashMap<&str, i32> = HashMap::new();
+
+      for m in match_set.matches {
+        if let MatchCause::Trigger(trigger_cause) = &m.cause {
+          for trigger in trigger_cause.triggers.iter() {
+            match first_by_trigger.get(trigger.as_str()) {
+              Some(&first_id) if first_id != m.id => {
+                if reported.insert((trigger.clone(), first_id, m.id)) {
+                  duplicates.push(DuplicateTrigger {
+                    trigger: trigger.clone(),
+                    first_source: self.source_path(first_id),
+                    second_source: self.source_path(m.id),
+                  });
+                }
+              }
+              Some(_) => {}
+              None => {
+                first_by_trigger.insert(trigger, m.id);
+              }
+            }
+          }
+        }
+      }
+    }
+
+    duplicates
+  }
+
+  // Match ids are assigned at load time, so they can't be used to identify
+  // a match across restarts. The source file and the cause are used instead.
+  pub fn usage_key(&self, match_id: i32) -> Option<String> {
+    let m = self.get(match_id)?;
+    let source_path = self.source_path(match_id).unwrap_or_default();
+    let cause = match &m.cause {
+      MatchCause::Regex(regex_cause) => format!("regex: {:?}", regex_cause.regex),
+      MatchCause::None => format!("label: {:?}", m.description()),
+      cause => cause.long_description(),
+    };
+    Some(format!("{}#{}", source_path, cause))
+  }
+}
+
+pub struct DuplicateTrigger<'a> {
+  pub trigger: String,
+  pub first_source: Option<&'a str>,
+  pub second_source: Option<&'a str>,
+}
+
+impl<'a> std::fmt::Display for DuplicateTrigger<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "trigger '{}' is defined by multiple matches, in {} and {}",
+      self.trigger,
+      self.first_source.unwrap_or("<unknown file>"),
+      self.second_source.unwrap_or("<unknown file>")
+    )
   }
 }
 
 impl<'a> super::engine::process::middleware::render::MatchProvider<'a> for MatchCache<'a> {
   fn matches(&self) -> Vec<&'a Match> {
-    self.cache.iter().map(|(_, m)| *m).collect()
+    self.all()
   }
 
   fn get(&self, id: i32) -> Option<&'a Match> {
-    self.cache.get(&id).copied()
+    MatchCache::get(self, id)
+  }
+
+  fn source_path(&self, id: i32) -> Option<&'a str> {
+    MatchCache::source_path(self, id)
   }
 }
 
 impl<'a> espanso_engine::process::MatchInfoProvider for MatchCache<'a> {
   fn get_force_mode(&self, match_id: i32) -> Option<espanso_engine::event::effect::TextInjectMode> {
-    let m = self.cache.get(&match_id)?;
+    let m = self.get(match_id)?;
     if let MatchEffect::Text(text_effect) = &m.effect {
       if let Some(force_mode) = &text_effect.force_mode {
         match force_mode {
@@ -78,6 +277,66 @@ impl<'a> espanso_engine::process::MatchInfoProvider for MatchCache<'a> {
 
     None
   }
+
+  fn should_propagate(&self, match_id: i32) -> bool {
+    if let Some(MatchEffect::Text(text_effect)) = self.get(match_id).map(|m| &m.effect) {
+      text_effect.propagate
+    } else {
+      false
+    }
+  }
+
+  fn should_keep_trigger(&self, match_id: i32) -> bool {
+    if let Some(MatchEffect::Text(text_effect)) = self.get(match_id).map(|m| &m.effect) {
+      text_effect.keep_trigger
+    } else {
+      false
+    }
+  }
+
+  fn should_inject_newline_as_key(&self, match_id: i32) -> bool {
+    if let Some(MatchEffect::Text(text_effect)) = self.get(match_id).map(|m| &m.effect) {
+      text_effect.newline_as_key
+    } else {
+      false
+    }
+  }
+
+  fn get_markdown_flavor(&self, match_id: i32) -> espanso_engine::event::effect::MarkdownFlavor {
+    if let Some(MatchEffect::Text(text_effect)) = self.get(match_id).map(|m| &m.effect) {
+      match text_effect.markdown_flavor {
+        espanso_config::matches::MarkdownFlavor::Standard => {
+          espanso_engine::event::effect::MarkdownFlavor::Standard
+        }
+        espanso_config::matches::MarkdownFlavor::Gfm => {
+          espanso_engine::event::effect::MarkdownFlavor::Gfm
+        }
+      }
+    } else {
+      espanso_engine::event::effect::MarkdownFlavor::Standard
+    }
+  }
+
+  fn get_notification(&self, match_id: i32) -> Option<String> {
+    match &self.get(match_id)?.effect {
+      MatchEffect::Text(text_effect) => text_effect.notify.clone(),
+      MatchEffect::Image(image_effect) => image_effect.notify.clone(),
+      MatchEffect::None => None,
+    }
+  }
+}
+
+impl<'a> espanso_engine::process::TransformProvider for MatchCache<'a> {
+  fn apply_transforms(&self, match_id: i32, body: String) -> String {
+    if let Some(MatchEffect::Text(text_effect)) = self.get(match_id).map(|m| &m.effect) {
+      text_effect
+        .transforms
+        .iter()
+        .fold(body, |body, transform| apply_transform(body, *transform))
+    } else {
+      body
+    }
+  }
 }
 
 pub struct CombinedMatchCache<'a> {
@@ -105,7 +364,7 @@ impl<'a> CombinedMatchCache<'a> {
   }
 
   pub fn get(&self, match_id: i32) -> Option<MatchVariant<'a>> {
-    if let Some(user_match) = self.user_match_cache.cache.get(&match_id).copied() {
+    if let Some(user_match) = self.user_match_cache.get(match_id) {
       return Some(MatchVariant::User(user_match));
     }
 
@@ -128,13 +387,17 @@ impl<'a> super::engine::process::middleware::match_select::MatchProvider<'a>
         MatchVariant::User(m) => MatchSummary {
           id: m.id,
           label: m.description(),
-          tag: m.cause_description(),
+          tag: m.search_tag(),
+          group: self.user_match_cache.group(m.id),
+          search_terms: &m.search_terms,
           is_builtin: false,
         },
         MatchVariant::Builtin(m) => MatchSummary {
           id: m.id,
           label: m.label,
           tag: m.triggers.first().map(String::as_ref),
+          group: None,
+          search_terms: &[],
           is_builtin: true,
         },
       })
@@ -161,7 +424,7 @@ impl<'a> super::engine::process::middleware::multiplex::MatchProvider<'a>
 impl<'a> espanso_engine::process::MatchProvider for CombinedMatchCache<'a> {
   fn get_all_matches_ids(&self) -> Vec<i32> {
     let mut ids: Vec<i32> = self.builtin_match_cache.keys().copied().collect();
-    ids.extend(self.user_match_cache.ids());
+    ids.extend(self.user_match_cache.searchable_ids());
     ids
   }
 }
@@ -170,8 +433,8 @@ impl<'a> espanso_engine::process::MatchResolver for CombinedMatchCache<'a> {
   fn find_matches_from_trigger(&self, trigger: &str) -> Vec<DetectedMatch> {
     let user_matches: Vec<DetectedMatch> = self
       .user_match_cache
-      .cache
-      .values()
+      .all()
+      .into_iter()
       .filter_map(|m| {
         if let MatchCause::Trigger(trigger_cause) = &m.cause {
           if trigger_cause.triggers.iter().any(|t| t == trigger) {
@@ -212,3 +475,381 @@ impl<'a> espanso_engine::process::MatchResolver for CombinedMatchCache<'a> {
     matches
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util::tests::{load_test_config, load_test_config_with};
+
+  #[test]
+  fn match_cache_populates_source_path() {
+    let config = load_test_config(
+      r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+    );
+    let base_file = config.match_file("base.yml");
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store);
+
+    let ids: Vec<i32> = match_cache.cache.keys().copied().collect();
+    assert_eq!(ids.len(), 1);
+    let source_path = match_cache.source_path(ids[0]).unwrap();
+    assert_eq!(
+      std::fs::canonicalize(source_path).unwrap(),
+      std::fs::canonicalize(&base_file).unwrap()
+    );
+    assert_eq!(match_cache.group(ids[0]), Some("base"));
+    assert_eq!(match_cache.source_path(-1), None);
+  }
+
+  #[test]
+  fn match_cache_group_prefers_group_label() {
+    let config = load_test_config(
+      r#"
+      label: "Greetings"
+
+      matches:
+        - trigger: "hello"
+          replace: "world"
+      "#,
+    );
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store);
+
+    let ids: Vec<i32> = match_cache.cache.keys().copied().collect();
+    assert_eq!(match_cache.group(ids[0]), Some("Greetings"));
+  }
+
+  #[test]
+  fn duplicate_triggers_across_groups_are_detected() {
+    let config = load_test_config_with(
+      "",
+      &[
+        (
+          "base.yml",
+          r#"
+      matches:
+        - trigger: ":inv"
+          replace: "invoice"
+        - trigger: ":unique"
+          replace: "unique"
+      "#,
+        ),
+        (
+          "work.yml",
+          r#"
+      matches:
+        - trigger: ":inv"
+          replace: "inventory"
+      "#,
+        ),
+      ],
+    );
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store);
+
+    let duplicates =
+      match_cache.find_duplicate_triggers(&*config.config_store, &*config.match_store);
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].trigger, ":inv");
+
+    let warning = duplicates[0].to_string();
+    assert!(warning.contains("base.yml"));
+    assert!(warning.contains("work.yml"));
+  }
+
+  #[test]
+  fn match_cache_count_matches_loaded_set() {
+    let config = load_test_config_with(
+      "",
+      &[
+        (
+          "base.yml",
+          r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+        - triggers: ["one", "two"]
+          replace: "multiple triggers"
+      "#,
+        ),
+        (
+          "other.yml",
+          r#"
+      matches:
+        - trigger: "another"
+          replace: "file"
+      "#,
+        ),
+      ],
+    );
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store);
+
+    let paths = config.config_store.get_all_match_paths();
+    let loaded = config
+      .match_store
+      .query(&paths.into_iter().collect::<Vec<_>>());
+    assert_eq!(match_cache.count(), loaded.matches.len());
+    assert_eq!(match_cache.count(), 3);
+  }
+
+  #[test]
+  fn non_searchable_matches_are_excluded_from_search() {
+    use espanso_engine::process::{MatchProvider, MatchResolver};
+
+    let config = load_test_config(
+      r#"
+      matches:
+        - trigger: "visible"
+          replace: "visible"
+        - trigger: "hidden"
+          replace: "hidden"
+          searchable: false
+      "#,
+    );
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store);
+    let combined_cache = CombinedMatchCache::load(&match_cache, &[]);
+
+    let hidden_id = match_cache
+      .cache
+      .values()
+      .find(|m| !m.searchable)
+      .unwrap()
+      .id;
+
+    let search_ids = combined_cache.get_all_matches_ids();
+    assert_eq!(search_ids.len(), 1);
+    assert!(!search_ids.contains(&hidden_id));
+
+    // The match is still cached and can be triggered
+    assert_eq!(match_cache.count(), 2);
+    let detected = combined_cache.find_matches_from_trigger("hidden");
+    assert_eq!(detected.len(), 1);
+    assert_eq!(detected[0].id, hidden_id);
+  }
+
+  #[test]
+  fn transforms_are_chained_in_order() {
+    use espanso_engine::process::TransformProvider;
+
+    let config = load_test_config(
+      r#"
+      matches:
+        - trigger: "title"
+          replace: "hello world"
+          transforms: [trim, title]
+        - trigger: "lower"
+          replace: "Hello"
+          transforms: [upper, lower]
+        - trigger: "plain"
+          replace: "Hello"
+      "#,
+    );
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store);
+    let id_of = |trigger: &str| {
+      match_cache
+        .cache
+        .values()
+        .find(|m| matches!(&m.cause, MatchCause::Trigger(cause) if cause.triggers[0] == trigger))
+        .unwrap()
+        .id
+    };
+
+    assert_eq!(
+      match_cache.apply_transforms(id_of("title"), "  hello world \n".to_string()),
+      "Hello World"
+    );
+    assert_eq!(
+      match_cache.apply_transforms(id_of("lower"), "Hello".to_string()),
+      "hello"
+    );
+    assert_eq!(
+      match_cache.apply_transforms(id_of("plain"), " Hello ".to_string()),
+      " Hello "
+    );
+  }
+
+  #[test]
+  fn runtime_matches_are_visible_in_the_cache() {
+    use espanso_engine::process::{MatchProvider, MatchResolver};
+
+    let config = load_test_config("");
+    let runtime_matches = RuntimeMatches::new();
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store)
+      .with_runtime_matches(runtime_matches.clone());
+    let combined_cache = CombinedMatchCache::load(&match_cache, &[]);
+
+    // Matches added after the cache is loaded are picked up as well
+    let id = runtime_matches
+      .add_from_definition(r#"{"trigger": ":pushed", "replace": "pushed"}"#)
+      .unwrap();
+
+    assert!(matches!(
+      combined_cache.get(id),
+      Some(MatchVariant::User(_))
+    ));
+    assert_eq!(combined_cache.get_all_matches_ids(), vec![id]);
+    let detected = combined_cache.find_matches_from_trigger(":pushed");
+    assert_eq!(detected.len(), 1);
+    assert_eq!(detected[0].id, id);
+    assert_eq!(match_cache.source_path(id), None);
+  }
+
+  #[test]
+  fn usage_key_is_stable_across_reloads() {
+    let config = load_test_config(
+      r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+        - regex: "hi(?P<name>.*)"
+          replace: "hello {{name}}"
+      "#,
+    );
+
+    let load_keys = || {
+      let (config_store, match_store, _) = espanso_config::load(config.dir.path()).unwrap();
+      let match_cache = MatchCache::load(&*config_store, &*match_store);
+      let mut keys: Vec<String> = match_cache
+        .cache
+        .keys()
+        .map(|id| match_cache.usage_key(*id).unwrap())
+        .collect();
+      keys.sort();
+      keys
+    };
+
+    let keys = load_keys();
+    assert_eq!(keys.len(), 2);
+    assert_ne!(keys[0], keys[1]);
+    assert_eq!(keys, load_keys());
+  }
+
+  fn create_match(id: i32, trigger: &str) -> Match {
+    Match {
+      id,
+      cause: MatchCause::Trigger(espanso_config::matches::TriggerCause {
+        triggers: vec![trigger.to_string()],
+        ..Default::default()
+      }),
+      ..Default::default()
+    }
+  }
+
+  fn empty_cache<'a>() -> MatchCache<'a> {
+    MatchCache {
+      cache: HashMap::new(),
+      sources: HashMap::new(),
+      group_labels: HashMap::new(),
+      ids_by_path: HashMap::new(),
+      runtime_matches: RuntimeMatches::new(),
+    }
+  }
+
+  fn sorted_ids(match_cache: &MatchCache) -> Vec<i32> {
+    let mut ids: Vec<i32> = match_cache.cache.keys().copied().collect();
+    ids.sort_unstable();
+    ids
+  }
+
+  #[test]
+  fn reload_adds_new_group() {
+    let first = create_match(1, "one");
+    let second = create_match(2, "two");
+    let mut match_cache = empty_cache();
+
+    match_cache.reload(vec![MatchCacheGroup {
+      path: "/match/base.yml",
+      label: Some("Base"),
+      matches: vec![&first, &second],
+    }]);
+
+    assert_eq!(sorted_ids(&match_cache), vec![1, 2]);
+    assert_eq!(match_cache.source_path(1), Some("/match/base.yml"));
+    assert_eq!(match_cache.group(2), Some("Base"));
+    assert_eq!(match_cache.ids_by_path["/match/base.yml"], vec![1, 2]);
+  }
+
+  #[test]
+  fn reload_removes_deleted_group() {
+    let first = create_match(1, "one");
+    let second = create_match(2, "two");
+    let mut match_cache = empty_cache();
+    match_cache.reload(vec![
+      MatchCacheGroup {
+        path: "/match/base.yml",
+        label: None,
+        matches: vec![&first],
+      },
+      MatchCacheGroup {
+        path: "/match/other.yml",
+        label: Some("Other"),
+        matches: vec![&second],
+      },
+    ]);
+
+    match_cache.reload(vec![MatchCacheGroup {
+      path: "/match/other.yml",
+      label: None,
+      matches: Vec::new(),
+    }]);
+
+    assert_eq!(sorted_ids(&match_cache), vec![1]);
+    assert_eq!(match_cache.source_path(2), None);
+    assert!(match_cache.group_labels.is_empty());
+    assert!(!match_cache.ids_by_path.contains_key("/match/other.yml"));
+  }
+
+  #[test]
+  fn reload_replaces_updated_group() {
+    let first = create_match(1, "one");
+    let second = create_match(2, "two");
+    let untouched = create_match(3, "three");
+    let mut match_cache = empty_cache();
+    match_cache.reload(vec![
+      MatchCacheGroup {
+        path: "/match/base.yml",
+        label: Some("Base"),
+        matches: vec![&first, &second],
+      },
+      MatchCacheGroup {
+        path: "/match/other.yml",
+        label: None,
+        matches: vec![&untouched],
+      },
+    ]);
+
+    // The ids are reassigned when a file is loaded again
+    let updated = create_match(4, "one");
+    match_cache.reload(vec![MatchCacheGroup {
+      path: "/match/base.yml",
+      label: None,
+      matches: vec![&updated],
+    }]);
+
+    assert_eq!(sorted_ids(&match_cache), vec![3, 4]);
+    assert_eq!(match_cache.source_path(4), Some("/match/base.yml"));
+    assert_eq!(match_cache.group(4), Some("base"));
+    assert_eq!(match_cache.source_path(3), Some("/match/other.yml"));
+    assert_eq!(match_cache.ids_by_path["/match/base.yml"], vec![4]);
+  }
+
+  #[test]
+  fn match_cache_tracks_ids_by_path() {
+    let config = load_test_config(
+      r#"
+      matches:
+        - trigger: "hello"
+          replace: "world"
+        - trigger: "bye"
+          replace: "world"
+      "#,
+    );
+    let match_cache = MatchCache::load(&*config.config_store, &*config.match_store);
+
+    assert_eq!(match_cache.ids_by_path.len(), 1);
+    let mut ids = match_cache.ids_by_path.values().next().unwrap().clone();
+    ids.sort_unstable();
+    assert_eq!(ids, sorted_ids(&match_cache));
+  }
+}
@@ -38,10 +38,13 @@ mod builtin;
 mod config;
 mod context;
 mod daemon_monitor;
-mod engine;
+pub(crate) mod engine;
+mod history;
 mod ipc;
 mod match_cache;
+mod runtime_matches;
 mod secure_input;
+mod stats;
 mod ui;
 
 pub fn new() -> CliModule {
@@ -118,6 +121,8 @@ fn worker_main(args: CliModuleArgs) -> i32 {
   let (ipc_event_notify, ipc_event_receiver) = unbounded();
   let (engine_ui_event_sender, engine_ui_event_receiver) = unbounded();
   let (engine_secure_input_sender, engine_secure_input_receiver) = unbounded();
+  let expansion_history = history::ExpansionHistory::new(history::DEFAULT_HISTORY_CAPACITY);
+  let runtime_matches = runtime_matches::RuntimeMatches::new();
 
   // Initialize the engine on another thread and start it
   let engine_handle = engine::initialize_and_spawn(
@@ -131,12 +136,20 @@ fn worker_main(args: CliModuleArgs) -> i32 {
     use_evdev_backend,
     start_reason,
     ipc_event_receiver,
+    expansion_history.clone(),
+    runtime_matches.clone(),
   )
   .expect("unable to initialize engine");
 
   // Setup the IPC server
-  ipc::initialize_and_spawn(&paths.runtime, engine_exit_notify.clone(), ipc_event_notify)
-    .expect("unable to initialize IPC server");
+  ipc::initialize_and_spawn(
+    &paths.runtime,
+    engine_exit_notify.clone(),
+    ipc_event_notify,
+    expansion_history,
+    runtime_matches,
+  )
+  .expect("unable to initialize IPC server");
 
   // If specified, automatically monitor the daemon status and
   // terminate the worker if the daemon terminates
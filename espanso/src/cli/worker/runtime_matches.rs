@@ -0,0 +1,146 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+use espanso_config::matches::{Match, MatchCause, MatchEffect};
+use log::warn;
+
+// Runtime matches are never freed, so their number is capped
+pub const MAX_RUNTIME_MATCHES: usize = 100;
+
+// Matches added through IPC while the worker is running, shared between the
+// IPC handler (which inserts them) and the match cache. They only last for
+// the current session and are never written to the match files.
+#[derive(Clone, Default)]
+pub struct RuntimeMatches {
+  matches: Arc<Mutex<Vec<&'static Match>>>,
+}
+
+impl RuntimeMatches {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // Parse the given definition and insert it, returning the id of the new match
+  pub fn add_from_definition(&self, definition: &str) -> Result<i32> {
+    let (m, warnings) = espanso_config::matches::parse_match_definition(definition)?;
+    for warning in warnings {
+      warn!("match definition received through IPC: {}", warning);
+    }
+
+    if matches!(m.effect, MatchEffect::None) {
+      bail!("the match definition doesn't specify a replacement");
+    }
+
+    if let MatchCause::Trigger(cause) = &m.cause {
+      if cause.left_word || cause.right_word || cause.line_start {
+        warn!("word and line_start options are not supported by matches received through IPC");
+      }
+    }
+
+    self.insert(m)
+  }
+
+  // Matches are leaked so that the match cache can reference them for the
+  // rest of the session, just like the ones loaded from the match files
+  pub fn insert(&self, m: Match) -> Result<i32> {
+    let mut matches = self.matches.lock().expect("unable to lock runtime matches");
+    if matches.len() >= MAX_RUNTIME_MATCHES {
+      bail!(
+        "reached the maximum number of matches that can be added at runtime ({})",
+        MAX_RUNTIME_MATCHES
+      );
+    }
+
+    let id = m.id;
+    let m: &'static Match = Box::leak(Box::new(m));
+    matches.push(m);
+    Ok(id)
+  }
+
+  // As matches are never removed, the count also tells whether new ones were added
+  pub fn count(&self) -> usize {
+    self
+      .matches
+      .lock()
+      .expect("unable to lock runtime matches")
+      .len()
+  }
+
+  pub fn get(&self, id: i32) -> Option<&'static Match> {
+    self
+      .matches
+      .lock()
+      .expect("unable to lock runtime matches")
+      .iter()
+      .find(|m| m.id == id)
+      .copied()
+  }
+
+  pub fn all(&self) -> Vec<&'static Match> {
+    self
+      .matches
+      .lock()
+      .expect("unable to lock runtime matches")
+      .clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn valid_definition_is_inserted() {
+    let runtime_matches = RuntimeMatches::new();
+    let id = runtime_matches
+      .add_from_definition(r#"{"trigger": ":sig", "replace": "Best regards"}"#)
+      .unwrap();
+
+    let m = runtime_matches.get(id).unwrap();
+    assert_eq!(m.effect.as_text().unwrap().replace, "Best regards");
+    assert_eq!(runtime_matches.all().len(), 1);
+  }
+
+  #[test]
+  fn invalid_definition_is_rejected() {
+    let runtime_matches = RuntimeMatches::new();
+    assert!(runtime_matches.add_from_definition("trigger: [").is_err());
+    assert!(runtime_matches
+      .add_from_definition(r#"{"trigger": ":sig"}"#)
+      .is_err());
+    assert!(runtime_matches.all().is_empty());
+  }
+
+  #[test]
+  fn number_of_matches_is_capped() {
+    let runtime_matches = RuntimeMatches::new();
+    for i in 0..MAX_RUNTIME_MATCHES {
+      runtime_matches
+        .add_from_definition(&format!(r#"{{"trigger": ":m{}", "replace": "m"}}"#, i))
+        .unwrap();
+    }
+
+    assert!(runtime_matches
+      .add_from_definition(r#"{"trigger": ":over", "replace": "over"}"#)
+      .is_err());
+    assert_eq!(runtime_matches.count(), MAX_RUNTIME_MATCHES);
+  }
+}
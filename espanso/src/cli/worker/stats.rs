@@ -0,0 +1,232 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{
+  cell::{Cell, RefCell},
+  collections::HashMap,
+  path::{Path, PathBuf},
+  time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use log::{error, warn};
+
+use super::match_cache::MatchCache;
+
+pub const MATCH_STATS_FILE_NAME: &str = "match_stats.json";
+
+// Writing the stats after every expansion would hit the disk continuously
+// while typing, so the changes are batched and saved at most this often
+const DEFAULT_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+// Persists how many times each match has been expanded, so that the most used
+// ones can be displayed first in the search bar across restarts
+pub struct MatchStatsStore {
+  path: PathBuf,
+  counts: RefCell<HashMap<String, u64>>,
+
+  save_interval: Duration,
+  last_save: Cell<Option<Instant>>,
+  is_dirty: Cell<bool>,
+}
+
+impl MatchStatsStore {
+  // A missing or corrupted stats file is not fatal, we simply start fresh
+  pub fn load(path: &Path) -> Self {
+    let counts = if path.is_file() {
+      match read_counts(path) {
+        Ok(counts) => counts,
+        Err(err) => {
+          warn!("unable to read match stats, starting fresh: {:?}", err);
+          HashMap::new()
+        }
+      }
+    } else {
+      HashMap::new()
+    };
+
+    Self {
+      path: path.to_path_buf(),
+      counts: RefCell::new(counts),
+      save_interval: DEFAULT_SAVE_INTERVAL,
+      last_save: Cell::new(None),
+      is_dirty: Cell::new(false),
+    }
+  }
+
+  pub fn count(&self, key: &str) -> u64 {
+    self.counts.borrow().get(key).copied().unwrap_or(0)
+  }
+
+  pub fn increment(&self, key: &str) -> Result<()> {
+    *self.counts.borrow_mut().entry(key.to_string()).or_insert(0) += 1;
+    self.is_dirty.set(true);
+
+    let is_save_due = match self.last_save.get() {
+      Some(last_save) => last_save.elapsed() >= self.save_interval,
+      None => true,
+    };
+    if is_save_due {
+      self.flush()
+    } else {
+      Ok(())
+    }
+  }
+
+  // Save the pending changes, if any
+  pub fn flush(&self) -> Result<()> {
+    if !self.is_dirty.get() {
+      return Ok(());
+    }
+
+    let content = serde_json::to_string(&*self.counts.borrow())?;
+
+    // Write to a temporary file first, to avoid corrupting the stats
+    // if espanso is terminated while saving
+    let tmp_path = self.path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)
+      .with_context(|| format!("unable to write match stats to: {:?}", tmp_path))?;
+    std::fs::rename(&tmp_path, &self.path)
+      .with_context(|| format!("unable to replace match stats file: {:?}", self.path))?;
+
+    self.is_dirty.set(false);
+    self.last_save.set(Some(Instant::now()));
+    Ok(())
+  }
+}
+
+impl Drop for MatchStatsStore {
+  fn drop(&mut self) {
+    if let Err(err) = self.flush() {
+      error!("unable to save match stats: {:?}", err);
+    }
+  }
+}
+
+fn read_counts(path: &Path) -> Result<HashMap<String, u64>> {
+  let content = std::fs::read_to_string(path)?;
+  Ok(serde_json::from_str(&content)?)
+}
+
+pub struct MatchUsageTracker<'a> {
+  match_cache: &'a MatchCache<'a>,
+  store: &'a MatchStatsStore,
+}
+
+impl<'a> MatchUsageTracker<'a> {
+  pub fn new(match_cache: &'a MatchCache<'a>, store: &'a MatchStatsStore) -> Self {
+    Self { match_cache, store }
+  }
+}
+
+impl<'a> espanso_engine::process::MatchUsageRecorder for MatchUsageTracker<'a> {
+  fn record_usage(&self, match_id: i32) {
+    // Built-in matches are not included in the persisted stats
+    if let Some(key) = self.match_cache.usage_key(match_id) {
+      if let Err(err) = self.store.increment(&key) {
+        error!("unable to update match stats: {:?}", err);
+      }
+    }
+  }
+}
+
+impl<'a> super::engine::process::middleware::match_select::MatchUsageProvider
+  for MatchUsageTracker<'a>
+{
+  fn usage_count(&self, match_id: i32) -> u64 {
+    self
+      .match_cache
+      .usage_key(match_id)
+      .map_or(0, |key| self.store.count(&key))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempdir::TempDir;
+
+  #[test]
+  fn missing_file_starts_fresh() {
+    let dir = TempDir::new("espanso-stats").unwrap();
+    let store = MatchStatsStore::load(&dir.path().join(MATCH_STATS_FILE_NAME));
+    assert_eq!(store.count("base.yml#triggers: [\":hi\"]"), 0);
+  }
+
+  #[test]
+  fn corrupted_file_starts_fresh() {
+    let dir = TempDir::new("espanso-stats").unwrap();
+    let path = dir.path().join(MATCH_STATS_FILE_NAME);
+    std::fs::write(&path, "{ invalid json").unwrap();
+
+    let store = MatchStatsStore::load(&path);
+    assert_eq!(store.count("key"), 0);
+    store.increment("key").unwrap();
+    assert_eq!(store.count("key"), 1);
+  }
+
+  #[test]
+  fn increment_updates_counts() {
+    let dir = TempDir::new("espanso-stats").unwrap();
+    let store = MatchStatsStore::load(&dir.path().join(MATCH_STATS_FILE_NAME));
+
+    store.increment("first").unwrap();
+    store.increment("first").unwrap();
+    store.increment("second").unwrap();
+
+    assert_eq!(store.count("first"), 2);
+    assert_eq!(store.count("second"), 1);
+    assert_eq!(store.count("third"), 0);
+  }
+
+  #[test]
+  fn counts_survive_reload() {
+    let dir = TempDir::new("espanso-stats").unwrap();
+    let path = dir.path().join(MATCH_STATS_FILE_NAME);
+
+    let store = MatchStatsStore::load(&path);
+    store.increment("first").unwrap();
+    store.increment("first").unwrap();
+    store.increment("second").unwrap();
+    drop(store);
+
+    let reloaded = MatchStatsStore::load(&path);
+    assert_eq!(reloaded.count("first"), 2);
+    assert_eq!(reloaded.count("second"), 1);
+    assert!(!path.with_extension("json.tmp").exists());
+  }
+
+  #[test]
+  fn writes_are_batched_until_the_interval_elapses() {
+    let dir = TempDir::new("espanso-stats").unwrap();
+    let path = dir.path().join(MATCH_STATS_FILE_NAME);
+
+    let mut store = MatchStatsStore::load(&path);
+    store.save_interval = Duration::from_secs(3600);
+    store.increment("first").unwrap();
+    store.increment("first").unwrap();
+    store.increment("first").unwrap();
+
+    // Only the first increment has been saved so far
+    assert_eq!(MatchStatsStore::load(&path).count("first"), 1);
+
+    drop(store);
+    assert_eq!(MatchStatsStore::load(&path).count("first"), 3);
+  }
+}
@@ -40,14 +40,12 @@ impl<'a> NotificationManager<'a> {
     self.notify("Espanso is running!");
   }
 
-  pub fn notify_config_reloaded(&self, is_manual_restart: bool) {
-    if is_manual_restart {
-      self.notify("Configuration reloaded!");
-    } else {
-      self.notify(
-        "Configuration reloaded! Espanso automatically loads new changes as soon as you save them.",
-      );
+  pub fn notify_config_reloaded(&self, is_manual_restart: bool, match_count: usize) {
+    if !self.config.reload_notification() {
+      return;
     }
+
+    self.notify(&reload_message(is_manual_restart, match_count));
   }
 
   pub fn notify_keyboard_layout_reloaded(&self) {
@@ -55,6 +53,23 @@ impl<'a> NotificationManager<'a> {
   }
 }
 
+fn reload_message(is_manual_restart: bool, match_count: usize) -> String {
+  let summary = format!(
+    "Configuration reloaded: {} {}",
+    match_count,
+    if match_count == 1 { "match" } else { "matches" }
+  );
+
+  if is_manual_restart {
+    summary
+  } else {
+    format!(
+      "{}. Espanso automatically loads new changes as soon as you save them.",
+      summary
+    )
+  }
+}
+
 impl<'a> espanso_engine::process::NotificationManager for NotificationManager<'a> {
   fn notify_status_change(&self, enabled: bool) {
     // Don't notify the status change outside Linux for now
@@ -74,3 +89,25 @@ impl<'a> espanso_engine::process::NotificationManager for NotificationManager<'a
       .notify("An error occurred during rendering, please examine the logs for more information.");
   }
 }
+
+impl<'a> espanso_engine::dispatch::NotificationHandler for NotificationManager<'a> {
+  fn show_notification(&self, message: &str) -> anyhow::Result<()> {
+    self.notify(message);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reload_message_contains_match_count() {
+    assert_eq!(
+      reload_message(true, 142),
+      "Configuration reloaded: 142 matches"
+    );
+    assert_eq!(reload_message(true, 1), "Configuration reloaded: 1 match");
+    assert!(reload_message(false, 3).starts_with("Configuration reloaded: 3 matches."));
+  }
+}
@@ -19,16 +19,54 @@
 
 use anyhow::{Context, Result};
 use espanso_config::{
-  config::ConfigStore,
+  config::{Config, ConfigStore},
   error::{ErrorLevel, NonFatalErrorSet},
   matches::store::MatchStore,
+  LoadOptions,
 };
 use log::{error, info, warn};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const DEFAULT_CONFIG_FILE_CONTENT: &str = include_str!("./res/config/default.yml");
 const DEFAULT_MATCH_FILE_CONTENT: &str = include_str!("./res/config/base.yml");
 
+// Set by the --safe flag, so that it's inherited by the spawned processes
+pub const SAFE_MODE_ENV_VAR: &str = "ESPANSO_SAFE_MODE";
+
+pub fn is_safe_mode() -> bool {
+  std::env::var(SAFE_MODE_ENV_VAR)
+    .map(|value| value == "true")
+    .unwrap_or(false)
+}
+
+// Set by the --base_config_dir flag, so that it's inherited by the spawned processes.
+// It contains a list of paths, separated as in the PATH variable
+pub const BASE_CONFIG_DIRS_ENV_VAR: &str = "ESPANSO_BASE_CONFIG_DIRS";
+
+// Config roots loaded before the main config dir, whose files can
+// be overridden (or extended) by the ones in the main config dir
+pub fn base_config_dirs() -> Vec<PathBuf> {
+  std::env::var_os(BASE_CONFIG_DIRS_ENV_VAR)
+    .map(|value| {
+      std::env::split_paths(&value)
+        .filter(|path| !path.as_os_str().is_empty())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+// Path of the secrets file, if any. Relative paths are resolved
+// against the espanso config directory
+pub fn secrets_path(config: &dyn Config, config_dir: &Path) -> Option<PathBuf> {
+  let secrets_path = config.secrets_path()?;
+  let secrets_path = PathBuf::from(secrets_path);
+  if secrets_path.is_absolute() {
+    Some(secrets_path)
+  } else {
+    Some(config_dir.join(secrets_path))
+  }
+}
+
 pub fn populate_default_config(config_dir: &Path) -> Result<()> {
   if !config_dir.is_dir() {
     info!(
@@ -78,7 +116,17 @@ pub struct ConfigLoadResult {
   pub non_fatal_errors: Vec<NonFatalErrorSet>,
 }
 
-pub fn load_config(config_path: &Path, packages_path: &Path) -> Result<ConfigLoadResult> {
+// Hotkeys are registered by the detection backend, so they are
+// validated against the keys it supports
+fn validate_hotkey(hotkey: &str) -> Result<()> {
+  espanso_detect::hotkey::HotKey::new(0, hotkey).map(|_| ())
+}
+
+pub fn load_config(
+  config_path: &Path,
+  packages_path: &Path,
+  runtime_path: &Path,
+) -> Result<ConfigLoadResult> {
   if espanso_config::is_legacy_config(config_path) {
     let (config_store, match_store) = espanso_config::load_legacy(config_path, packages_path)
       .context("unable to load legacy config")?;
@@ -91,8 +139,21 @@ pub fn load_config(config_path: &Path, packages_path: &Path) -> Result<ConfigLoa
       non_fatal_errors: Vec::new(),
     })
   } else {
-    let (config_store, match_store, non_fatal_errors) =
-      espanso_config::load(config_path).context("unable to load config")?;
+    let safe_mode = is_safe_mode();
+    let base_paths = base_config_dirs();
+    if !base_paths.is_empty() {
+      info!("loading base configs from: {:?}", base_paths);
+    }
+    let (config_store, match_store, non_fatal_errors) = espanso_config::load_with_options(
+      config_path,
+      &LoadOptions {
+        safe_mode,
+        base_paths,
+        remote_imports_cache_dir: Some(runtime_path.join("remote-imports")),
+        hotkey_validator: Some(validate_hotkey),
+      },
+    )
+    .context("unable to load config")?;
 
     // TODO: add an option to avoid dumping the errors in the logs
     if !non_fatal_errors.is_empty() {
@@ -111,6 +172,16 @@ pub fn load_config(config_path: &Path, packages_path: &Path) -> Result<ConfigLoa
         }
       }
       warn!("-----------------------------------------------------------");
+
+      if safe_mode {
+        warn!(
+          "safe mode: the following files were skipped or only partially loaded: {:?}",
+          non_fatal_errors
+            .iter()
+            .map(|error_set| &error_set.file)
+            .collect::<Vec<_>>()
+        );
+      }
     }
 
     Ok(ConfigLoadResult {
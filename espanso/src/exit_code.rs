@@ -53,6 +53,10 @@ pub const SERVICE_ALREADY_RUNNING: i32 = 3;
 pub const SERVICE_NOT_RUNNING: i32 = 4;
 pub const SERVICE_TIMED_OUT: i32 = 5;
 
+pub const CONFIG_CHECK_SUCCESS: i32 = 0;
+pub const CONFIG_CHECK_UNEXPECTED_FAILURE: i32 = 1;
+pub const CONFIG_CHECK_ERRORS_FOUND: i32 = 2;
+
 pub const WORKAROUND_SUCCESS: i32 = 0;
 #[allow(dead_code)]
 pub const WORKAROUND_FAILURE: i32 = 1;
@@ -24,7 +24,13 @@ use anyhow::Result;
 pub mod modulo;
 
 pub trait SearchUI {
-  fn show(&self, items: &[SearchItem], hint: Option<&str>) -> Result<Option<String>>;
+  fn show(
+    &self,
+    items: &[SearchItem],
+    hint: Option<&str>,
+    case_sensitive: bool,
+    max_results: Option<usize>,
+  ) -> Result<Option<String>>;
 }
 
 #[derive(Debug)]
@@ -32,29 +38,45 @@ pub struct SearchItem {
   pub id: String,
   pub label: String,
   pub tag: Option<String>,
+  pub group: Option<String>,
+  // Not displayed, but considered when filtering the items
+  pub search_terms: Vec<String>,
   pub is_builtin: bool,
 }
 
+pub trait ConfirmUI {
+  // Returns true if the user accepted
+  fn confirm(&self, message: &str) -> Result<bool>;
+}
+
 pub trait FormUI {
   fn show(
     &self,
     layout: &str,
     fields: &HashMap<String, FormField>,
+    order: &[String],
   ) -> Result<Option<HashMap<String, String>>>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum FormField {
   Text {
     default: Option<String>,
     multiline: bool,
+    required: bool,
+    // Regex the value must fully match before the form can be submitted
+    validate: Option<String>,
   },
   Choice {
     default: Option<String>,
     values: Vec<String>,
+    required: bool,
+    // If true, a random value is preselected each time the form is opened
+    random_default: bool,
   },
   List {
     default: Option<String>,
     values: Vec<String>,
+    required: bool,
   },
 }
@@ -0,0 +1,109 @@
+/*
+ * This file is part of espanso.
+ *
+ * Copyright (C) 2019-2021 Federico Terzi
+ *
+ * espanso is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * espanso is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::collections::HashMap;
+
+use crate::gui::{ConfirmUI, FormField, FormUI};
+
+const CONFIRM_FIELD: &str = "confirm";
+const YES: &str = "Yes";
+const NO: &str = "No";
+
+// Confirmation dialog shown as a modulo form with a Yes/No choice
+pub struct ModuloConfirmUI<'a> {
+  form_ui: &'a dyn FormUI,
+}
+
+impl<'a> ModuloConfirmUI<'a> {
+  pub fn new(form_ui: &'a dyn FormUI) -> Self {
+    Self { form_ui }
+  }
+}
+
+impl<'a> ConfirmUI for ModuloConfirmUI<'a> {
+  fn confirm(&self, message: &str) -> anyhow::Result<bool> {
+    let mut fields = HashMap::new();
+    fields.insert(
+      CONFIRM_FIELD.to_string(),
+      FormField::Choice {
+        default: Some(NO.to_string()),
+        values: vec![YES.to_string(), NO.to_string()],
+        required: true,
+        random_default: false,
+      },
+    );
+    let layout = format!("{}\n\n[[{}]]", message, CONFIRM_FIELD);
+
+    let values = self
+      .form_ui
+      .show(&layout, &fields, &[CONFIRM_FIELD.to_string()])?;
+    Ok(is_confirmed(values.as_ref()))
+  }
+}
+
+// Closing the dialog without answering counts as a refusal
+fn is_confirmed(values: Option<&HashMap<String, String>>) -> bool {
+  values
+    .and_then(|values| values.get(CONFIRM_FIELD))
+    .map(String::as_str)
+    == Some(YES)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct MockFormUI {
+    answer: Option<&'static str>,
+  }
+
+  impl FormUI for MockFormUI {
+    fn show(
+      &self,
+      layout: &str,
+      fields: &HashMap<String, FormField>,
+      _: &[String],
+    ) -> anyhow::Result<Option<HashMap<String, String>>> {
+      assert!(layout.contains("[[confirm]]"));
+      assert!(fields.contains_key(CONFIRM_FIELD));
+      Ok(self.answer.map(|answer| {
+        let mut values = HashMap::new();
+        values.insert(CONFIRM_FIELD.to_string(), answer.to_string());
+        values
+      }))
+    }
+  }
+
+  fn confirm_with(answer: Option<&'static str>) -> bool {
+    let form_ui = MockFormUI { answer };
+    ModuloConfirmUI::new(&form_ui)
+      .confirm("Expand ':pwd'?")
+      .unwrap()
+  }
+
+  #[test]
+  fn confirmation_is_accepted_only_with_yes() {
+    assert!(confirm_with(Some("Yes")));
+    assert!(!confirm_with(Some("No")));
+  }
+
+  #[test]
+  fn closed_dialog_is_a_refusal() {
+    assert!(!confirm_with(None));
+  }
+}
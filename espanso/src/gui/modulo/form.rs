@@ -23,15 +23,28 @@ use std::collections::HashMap;
 
 use crate::gui::{FormField, FormUI};
 
-use super::manager::ModuloManager;
+use super::manager::ModuloInvoker;
+
+pub trait FormTimeoutProvider {
+  // Number of seconds before the form is cancelled, 0 to keep it open
+  // until it's submitted or closed
+  fn form_timeout(&self) -> u64;
+}
 
 pub struct ModuloFormUI<'a> {
-  manager: &'a ModuloManager,
+  manager: &'a dyn ModuloInvoker,
+  timeout_provider: &'a dyn FormTimeoutProvider,
 }
 
 impl<'a> ModuloFormUI<'a> {
-  pub fn new(manager: &'a ModuloManager) -> Self {
-    Self { manager }
+  pub fn new(
+    manager: &'a dyn ModuloInvoker,
+    timeout_provider: &'a dyn FormTimeoutProvider,
+  ) -> Self {
+    Self {
+      manager,
+      timeout_provider,
+    }
   }
 }
 
@@ -40,28 +53,39 @@ impl<'a> FormUI for ModuloFormUI<'a> {
     &self,
     layout: &str,
     fields: &HashMap<String, FormField>,
+    order: &[String],
   ) -> anyhow::Result<Option<HashMap<String, String>>> {
+    // The timeout is read on every invocation, so that the one of the
+    // app-specific config active when the form is shown is used
+    let timeout = match self.timeout_provider.form_timeout() {
+      0 => None,
+      timeout => Some(timeout),
+    };
+
     let modulo_form_config = ModuloFormConfig {
       title: "espanso",
       layout,
       fields: convert_fields_into_object(fields),
+      order,
+      timeout,
     };
 
     let json_config = serde_json::to_string(&modulo_form_config)?;
     let output = self
       .manager
       .invoke(&["form", "-j", "-i", "-"], &json_config)?;
-    let json: Result<HashMap<String, String>, _> = serde_json::from_str(&output);
-    match json {
-      Ok(json) => {
-        if json.is_empty() {
-          Ok(None)
-        } else {
-          Ok(Some(json))
-        }
-      }
-      Err(error) => Err(error.into()),
-    }
+    parse_form_output(&output)
+  }
+}
+
+// Forms closed without submitting (including the ones that timed out)
+// don't return any value, which aborts the expansion
+fn parse_form_output(output: &str) -> anyhow::Result<Option<HashMap<String, String>>> {
+  let json: HashMap<String, String> = serde_json::from_str(output)?;
+  if json.is_empty() {
+    Ok(None)
+  } else {
+    Ok(Some(json))
   }
 }
 
@@ -70,6 +94,9 @@ struct ModuloFormConfig<'a> {
   title: &'a str,
   layout: &'a str,
   fields: Map<String, Value>,
+  order: &'a [String],
+  #[serde(skip_serializing_if = "Option::is_none")]
+  timeout: Option<u64>,
 }
 
 // TODO: test
@@ -77,23 +104,138 @@ fn convert_fields_into_object(fields: &HashMap<String, FormField>) -> Map<String
   let mut obj = Map::new();
   for (name, field) in fields {
     let value = match field {
-      FormField::Text { default, multiline } => json!({
+      FormField::Text {
+        default,
+        multiline,
+        required,
+        validate,
+      } => json!({
         "type": "text",
         "default": default,
         "multiline": multiline,
+        "required": required,
+        "validate": validate,
       }),
-      FormField::Choice { default, values } => json!({
+      FormField::Choice {
+        default,
+        values,
+        required,
+        random_default,
+      } => json!({
         "type": "choice",
         "default": default,
         "values": values,
+        "required": required,
+        "random_default": random_default,
       }),
-      FormField::List { default, values } => json!({
+      FormField::List {
+        default,
+        values,
+        required,
+      } => json!({
         "type": "list",
         "default": default,
         "values": values,
+        "required": required,
       }),
     };
     obj.insert(name.clone(), value);
   }
   obj
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::{Cell, RefCell};
+
+  // Simulates modulo, which prints an empty object when the form
+  // is closed by the timeout without being submitted
+  struct MockModulo {
+    output: &'static str,
+    last_config: RefCell<Option<Value>>,
+  }
+
+  impl MockModulo {
+    fn new(output: &'static str) -> Self {
+      Self {
+        output,
+        last_config: RefCell::new(None),
+      }
+    }
+  }
+
+  impl ModuloInvoker for MockModulo {
+    fn invoke(&self, args: &[&str], body: &str) -> anyhow::Result<String> {
+      assert_eq!(args, &["form", "-j", "-i", "-"]);
+      *self.last_config.borrow_mut() = Some(serde_json::from_str(body).unwrap());
+      Ok(self.output.to_string())
+    }
+  }
+
+  struct MockTimeoutProvider {
+    timeout: Cell<u64>,
+  }
+
+  impl FormTimeoutProvider for MockTimeoutProvider {
+    fn form_timeout(&self) -> u64 {
+      self.timeout.get()
+    }
+  }
+
+  fn show(form_ui: &ModuloFormUI) -> Option<HashMap<String, String>> {
+    form_ui.show("[[name]]", &HashMap::new(), &[]).unwrap()
+  }
+
+  #[test]
+  fn timed_out_form_aborts() {
+    let modulo = MockModulo::new("{}");
+    let timeout_provider = MockTimeoutProvider {
+      timeout: Cell::new(30),
+    };
+    let form_ui = ModuloFormUI::new(&modulo, &timeout_provider);
+
+    assert_eq!(show(&form_ui), None);
+    assert_eq!(
+      modulo.last_config.borrow().as_ref().unwrap()["timeout"],
+      json!(30)
+    );
+  }
+
+  #[test]
+  fn submitted_form_returns_values() {
+    let modulo = MockModulo::new(r#"{"name": "John"}"#);
+    let timeout_provider = MockTimeoutProvider {
+      timeout: Cell::new(30),
+    };
+    let form_ui = ModuloFormUI::new(&modulo, &timeout_provider);
+
+    let values = show(&form_ui).unwrap();
+    assert_eq!(values.get("name").map(String::as_str), Some("John"));
+  }
+
+  #[test]
+  fn timeout_follows_the_active_config() {
+    let modulo = MockModulo::new("{}");
+    let timeout_provider = MockTimeoutProvider {
+      timeout: Cell::new(0),
+    };
+    let form_ui = ModuloFormUI::new(&modulo, &timeout_provider);
+
+    show(&form_ui);
+    assert!(modulo
+      .last_config
+      .borrow()
+      .as_ref()
+      .unwrap()
+      .get("timeout")
+      .is_none());
+
+    timeout_provider.timeout.set(10);
+    show(&form_ui);
+    assert_eq!(
+      modulo.last_config.borrow().as_ref().unwrap()["timeout"],
+      json!(10)
+    );
+  }
+}
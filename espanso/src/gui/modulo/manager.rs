@@ -27,6 +27,12 @@ pub struct ModuloManager {
   is_support_enabled: bool,
 }
 
+// Abstraction over the modulo invocation, so that the UIs can be
+// tested without spawning the process
+pub trait ModuloInvoker {
+  fn invoke(&self, args: &[&str], body: &str) -> Result<String>;
+}
+
 impl ModuloManager {
   pub fn new() -> Self {
     let is_support_enabled = if cfg!(feature = "modulo") {
@@ -38,8 +44,10 @@ impl ModuloManager {
 
     Self { is_support_enabled }
   }
+}
 
-  pub fn invoke(&self, args: &[&str], body: &str) -> Result<String> {
+impl ModuloInvoker for ModuloManager {
+  fn invoke(&self, args: &[&str], body: &str) -> Result<String> {
     if self.is_support_enabled {
       let exec_path = std::env::current_exe().expect("unable to obtain current exec path");
       let mut command = Command::new(exec_path);
@@ -17,6 +17,7 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+pub mod confirm;
 pub mod form;
 pub mod manager;
 pub mod search;
@@ -23,7 +23,7 @@ use std::collections::HashMap;
 
 use crate::gui::{SearchItem, SearchUI};
 
-use super::manager::ModuloManager;
+use super::manager::{ModuloInvoker, ModuloManager};
 
 pub struct ModuloSearchUI<'a> {
   manager: &'a ModuloManager,
@@ -36,11 +36,19 @@ impl<'a> ModuloSearchUI<'a> {
 }
 
 impl<'a> SearchUI for ModuloSearchUI<'a> {
-  fn show(&self, items: &[SearchItem], hint: Option<&str>) -> anyhow::Result<Option<String>> {
+  fn show(
+    &self,
+    items: &[SearchItem],
+    hint: Option<&str>,
+    case_sensitive: bool,
+    max_results: Option<usize>,
+  ) -> anyhow::Result<Option<String>> {
     let modulo_config = ModuloSearchConfig {
       title: "espanso",
       hint,
       items: convert_items(items),
+      algorithm: search_algorithm(case_sensitive),
+      max_results,
     };
 
     let json_config = serde_json::to_string(&modulo_config)?;
@@ -66,6 +74,17 @@ struct ModuloSearchConfig<'a> {
   title: &'a str,
   hint: Option<&'a str>,
   items: Vec<ModuloSearchItemConfig<'a>>,
+  algorithm: &'a str,
+  max_results: Option<usize>,
+}
+
+// Name of the modulo search algorithm, both match the query keywords
+fn search_algorithm(case_sensitive: bool) -> &'static str {
+  if case_sensitive {
+    "key"
+  } else {
+    "ikey"
+  }
 }
 
 #[derive(Debug, Serialize)]
@@ -73,6 +92,8 @@ struct ModuloSearchItemConfig<'a> {
   id: &'a str,
   label: &'a str,
   trigger: Option<&'a str>,
+  group: Option<&'a str>,
+  search_terms: &'a [String],
   is_builtin: bool,
 }
 
@@ -84,6 +105,8 @@ fn convert_items(items: &[SearchItem]) -> Vec<ModuloSearchItemConfig> {
       id: &item.id,
       label: &item.label,
       trigger: item.tag.as_deref(),
+      group: item.group.as_deref(),
+      search_terms: &item.search_terms,
       is_builtin: item.is_builtin,
     })
     .collect()
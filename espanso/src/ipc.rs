@@ -32,7 +32,20 @@ pub enum IPCEvent {
   ToggleRequest,
   OpenSearchBar,
 
+  // Handled by the daemon, which reloads the config as if it was modified
+  ReloadRequest,
+
   RequestMatchExpansion(RequestMatchExpansionPayload),
+
+  // Request the last N expansions, answered with an ExpansionHistory
+  ExpansionHistoryRequest(usize),
+  ExpansionHistory(Vec<ExpansionRecord>),
+
+  // Add a match for the current session, using the same syntax of the match
+  // files (JSON is accepted too). Answered with an AddMatchResponse, carrying
+  // either the id of the new match or the reason it was rejected
+  AddMatchRequest(String),
+  AddMatchResponse(Result<i32, String>),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +54,14 @@ pub struct RequestMatchExpansionPayload {
   pub args: HashMap<String, String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpansionRecord {
+  pub match_id: i32,
+  pub trigger: Option<String>,
+  // Seconds since the UNIX epoch
+  pub timestamp: u64,
+}
+
 pub fn create_daemon_ipc_server(runtime_dir: &Path) -> Result<impl IPCServer<IPCEvent>> {
   create_ipc_server(runtime_dir, "daemonv2")
 }
@@ -49,6 +70,10 @@ pub fn create_worker_ipc_server(runtime_dir: &Path) -> Result<impl IPCServer<IPC
   create_ipc_server(runtime_dir, "workerv2")
 }
 
+pub fn create_ipc_client_to_daemon(runtime_dir: &Path) -> Result<impl IPCClient<IPCEvent>> {
+  create_ipc_client(runtime_dir, "daemonv2")
+}
+
 pub fn create_ipc_client_to_worker(runtime_dir: &Path) -> Result<impl IPCClient<IPCEvent>> {
   create_ipc_client(runtime_dir, "workerv2")
 }
@@ -61,3 +86,15 @@ fn create_ipc_client(runtime_dir: &Path, target_process: &str) -> Result<impl IP
   let client = espanso_ipc::client(&format!("espanso{}", target_process), runtime_dir)?;
   Ok(client)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reload_request_round_trip() {
+    let serialized = serde_json::to_string(&IPCEvent::ReloadRequest).unwrap();
+    let deserialized: IPCEvent = serde_json::from_str(&serialized).unwrap();
+    assert!(matches!(deserialized, IPCEvent::ReloadRequest));
+  }
+}
@@ -72,7 +72,11 @@ lazy_static! {
     cli::workaround::new(),
     cli::package::new(),
     cli::match_cli::new(),
+    cli::config_cli::new(),
     cli::cmd::new(),
+    cli::reload::new(),
+    cli::test::new(),
+    cli::regex_test::new(),
   ];
   static ref ALIASES: Vec<CliAlias> = vec![
     CliAlias {
@@ -99,12 +103,24 @@ lazy_static! {
       subcommand: "uninstall".to_owned(),
       forward_into: "package".to_owned(),
     },
+    CliAlias {
+      subcommand: "render".to_owned(),
+      forward_into: "test".to_owned(),
+    },
   ];
 }
 
 fn main() {
   util::attach_console();
 
+  let var_override_arg = Arg::with_name("var")
+    .long("var")
+    .help("Override a variable of the match, following the --var 'name=value' format. You can specify multiple ones.")
+    .required(false)
+    .takes_value(true)
+    .multiple(true)
+    .number_of_values(1);
+
   let install_subcommand = SubCommand::with_name("install")
     .about("Install a package")
     .arg(
@@ -196,6 +212,14 @@ fn main() {
         .takes_value(true)
         .help("Specify a custom path from which espanso should read the configuration"),
     )
+    .arg(
+      Arg::with_name("base_config_dir")
+        .long("base_config_dir")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .help("Specify a (read-only) configuration loaded before the main one, which can override and extend it. Can be repeated"),
+    )
     .arg(
       Arg::with_name("package_dir")
         .long("package_dir")
@@ -208,6 +232,17 @@ fn main() {
         .takes_value(true)
         .help("Specify a custom path for the espanso runtime directory"),
     )
+    .arg(
+      Arg::with_name("safe")
+        .long("safe")
+        .help("Start even if some configuration files are invalid, skipping them"),
+    )
+    .arg(
+      Arg::with_name("profile")
+        .long("profile")
+        .takes_value(true)
+        .help("Load the configuration from the given profile, stored in the 'profiles' folder of the config directory"),
+    )
     .subcommand(
       SubCommand::with_name("env-path")
         .arg(
@@ -232,6 +267,10 @@ fn main() {
         .subcommand(SubCommand::with_name("search")
             .about("Open the Espanso's search bar."))
     )
+    .subcommand(
+      SubCommand::with_name("reload")
+        .about("Reload the configuration of the running espanso instance."),
+    )
     .subcommand(SubCommand::with_name("edit")
         .about("Shortcut to open the default text editor to edit config files")
         .arg(Arg::with_name("target_file")
@@ -254,7 +293,20 @@ For example, specifying 'email' is equivalent to 'match/email.yml'."#))
         .about("Start the daemon without spawning a new process."),
     )
     .subcommand(SubCommand::with_name("launcher").setting(AppSettings::Hidden))
-    .subcommand(SubCommand::with_name("log").about("Print the daemon logs."))
+    .subcommand(
+      SubCommand::with_name("log")
+        .about("Print the daemon logs.")
+        .subcommand(
+          SubCommand::with_name("expansions")
+            .about("Print the most recent expansions.")
+            .arg(
+              Arg::with_name("tail")
+                .long("tail")
+                .takes_value(true)
+                .help("Number of expansions to display (defaults to 20)"),
+            ),
+        ),
+    )
     .subcommand(
       SubCommand::with_name("modulo")
         .setting(AppSettings::Hidden)
@@ -323,6 +375,23 @@ For example, specifying 'email' is equivalent to 'match/email.yml'."#))
         )
         .subcommand(SubCommand::with_name("base").about("Print the default match file path.")),
     )
+    .subcommand(
+      SubCommand::with_name("config")
+        .about("Commands to inspect the configuration")
+        .subcommand(SubCommand::with_name("check").about(
+          "Load all the config and match files, report any error and exit with code 2 if some are invalid.",
+        ))
+        .subcommand(
+          SubCommand::with_name("export")
+            .about("Export all the matches and global variables to a single YAML file, with imports resolved.")
+            .arg(
+              Arg::with_name("output")
+                .help("Path of the YAML file to write")
+                .required(true)
+                .takes_value(true),
+            ),
+        ),
+    )
     .subcommand(
       SubCommand::with_name("migrate")
         .about("Automatically migrate legacy config files to the new v2 format.")
@@ -412,6 +481,44 @@ For example, specifying 'email' is equivalent to 'match/email.yml'."#))
             )
         )
     )
+    .subcommand(
+      SubCommand::with_name("test")
+        .about("Render the match associated with the given trigger and print the result, without injecting it. Exits with code 2 if no match is found.")
+        .arg(
+          Arg::with_name("trigger")
+            .help("The trigger of the match to be rendered")
+            .required(true)
+            .takes_value(true),
+        )
+        .arg(var_override_arg.clone()),
+    )
+    .subcommand(
+      SubCommand::with_name("render")
+        .about("Render the match associated with the given trigger, print it to standard output and exit. Useful for scripting.")
+        .arg(
+          Arg::with_name("trigger")
+            .help("The trigger of the match to be rendered")
+            .required(true)
+            .takes_value(true),
+        )
+        .arg(var_override_arg),
+    )
+    .subcommand(
+      SubCommand::with_name("regex-test")
+        .about("Test a regex trigger against the given input, printing the matched text and its captures. Exits with code 2 if the regex does not match.")
+        .arg(
+          Arg::with_name("pattern")
+            .help("The regex to be tested, as specified in the 'regex' field of a match")
+            .required(true)
+            .takes_value(true),
+        )
+        .arg(
+          Arg::with_name("input")
+            .help("The sample text, evaluated as if it was typed")
+            .required(true)
+            .takes_value(true),
+        ),
+    )
     .subcommand(
       SubCommand::with_name("package")
         .about("package-management commands")
@@ -546,8 +653,32 @@ For example, specifying 'email' is equivalent to 'match/email.yml'."#))
 
     let mut cli_args: CliModuleArgs = CliModuleArgs::default();
 
+    if matches.is_present("safe") {
+      std::env::set_var(crate::config::SAFE_MODE_ENV_VAR, "true");
+    }
+
+    if let Some(base_config_dirs) = matches.values_of("base_config_dir") {
+      let base_config_dirs: Vec<PathBuf> = base_config_dirs
+        .map(|path| PathBuf::from(path.trim()))
+        .collect();
+      if let Some(invalid_dir) = base_config_dirs.iter().find(|path| !path.is_dir()) {
+        error_eprintln!("base_config_dir argument was specified, but {:?} doesn't point to a valid directory.", invalid_dir);
+        std::process::exit(1);
+      }
+      match std::env::join_paths(&base_config_dirs) {
+        Ok(value) => std::env::set_var(crate::config::BASE_CONFIG_DIRS_ENV_VAR, value),
+        Err(err) => {
+          error_eprintln!("unable to use the base_config_dir arguments: {}", err);
+          std::process::exit(1);
+        }
+      }
+    }
+
     if handler.requires_paths || handler.requires_config {
       let force_config_path = get_path_override(&matches, "config_dir", "ESPANSO_CONFIG_DIR");
+      // The profile directory is then handled as a config dir override,
+      // so that it's also forwarded to the spawned processes
+      let force_config_path = get_profile_override(&matches, force_config_path);
       let force_package_path = get_path_override(&matches, "package_dir", "ESPANSO_PACKAGE_DIR");
       let force_runtime_path = get_path_override(&matches, "runtime_dir", "ESPANSO_RUNTIME_DIR");
 
@@ -568,8 +699,8 @@ For example, specifying 'email' is equivalent to 'match/email.yml'."#))
       info!("using runtime dir: {:?}", paths.runtime);
 
       if handler.requires_config {
-        let config_result =
-          load_config(&paths.config, &paths.packages).expect("unable to load config");
+        let config_result = load_config(&paths.config, &paths.packages, &paths.runtime)
+          .expect("unable to load config");
 
         cli_args.is_legacy_config = config_result.is_legacy_config;
         cli_args.config_store = Some(config_result.config_store);
@@ -615,6 +746,24 @@ For example, specifying 'email' is equivalent to 'match/email.yml'."#))
   }
 }
 
+fn get_profile_override(
+  matches: &ArgMatches,
+  force_config_path: Option<PathBuf>,
+) -> Option<PathBuf> {
+  if let Some(profile) = matches.value_of("profile") {
+    let config_dir = espanso_path::resolve_config_dir(force_config_path.as_deref());
+    match espanso_path::resolve_profile_dir(&config_dir, profile.trim()) {
+      Ok(profile_dir) => Some(profile_dir),
+      Err(err) => {
+        error_eprintln!("unable to load profile: {}", err);
+        std::process::exit(1);
+      }
+    }
+  } else {
+    force_config_path
+  }
+}
+
 fn get_path_override(matches: &ArgMatches, argument: &str, env_var: &str) -> Option<PathBuf> {
   if let Some(path) = matches.value_of(argument) {
     let path = PathBuf::from(path.trim());
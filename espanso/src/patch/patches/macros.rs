@@ -83,6 +83,18 @@ macro_rules! generate_patchable_config {
           self.base.search_shortcut()
         }
 
+        fn search_key_sequence(&self) -> Option<String> {
+          self.base.search_key_sequence()
+        }
+
+        fn search_case_sensitive(&self) -> bool {
+          self.base.search_case_sensitive()
+        }
+
+        fn search_max_results(&self) -> Option<usize> {
+          self.base.search_max_results()
+        }
+
         fn show_icon(&self) -> bool {
           self.base.show_icon()
         }
@@ -94,6 +106,10 @@ macro_rules! generate_patchable_config {
         fn secure_input_notification(&self) -> bool {
           self.base.secure_input_notification()
         }
+
+        fn reload_notification(&self) -> bool {
+          self.base.reload_notification()
+        }
       }
   };
 }
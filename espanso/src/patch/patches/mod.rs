@@ -17,6 +17,8 @@
  * along with espanso.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use std::collections::BTreeMap;
+
 use espanso_config::config::{Backend, RMLVOConfig, ToggleKey};
 
 #[cfg(target_os = "windows")]
@@ -33,19 +35,45 @@ generate_patchable_config!(
   backend -> Backend,
   enable -> bool,
   clipboard_threshold -> usize,
+  keys_clipboard_fallback -> bool,
+  keys_clipboard_fallback_threshold -> usize,
   pre_paste_delay -> usize,
   paste_shortcut_event_delay -> usize,
   paste_shortcut -> Option<String>,
   disable_x11_fast_inject -> bool,
   toggle_key -> Option<ToggleKey>,
+  toggle_trigger -> Option<String>,
   auto_restart -> bool,
+  watcher_quiet_period -> u64,
   preserve_clipboard -> bool,
   restore_clipboard_delay -> usize,
+  paste_focus_retries -> usize,
   inject_delay -> Option<usize>,
   key_delay -> Option<usize>,
+  inject_chunk_size -> usize,
+  inject_chunk_delay -> usize,
   evdev_modifier_delay -> Option<usize>,
   word_separators -> Vec<String>,
   backspace_limit -> usize,
+  max_backspaces_per_expansion -> usize,
+  post_backspace_delay -> usize,
+  shell_timeout -> usize,
+  form_timeout -> u64,
+  max_propagation_depth -> usize,
+  lint_unused_global_vars -> bool,
+  lint_duplicate_triggers -> bool,
+  injection_failure_dumps -> bool,
+  trace_injected_text -> bool,
+  max_group_matches -> usize,
+  max_total_matches -> usize,
+  trigger_from_filename -> bool,
+  lazy_replace -> bool,
+  trigger_prefix_aliases -> BTreeMap<String, Vec<String>>,
+  regex_max_lookback -> usize,
+  allow_remote_imports -> bool,
+  remote_imports_ttl -> u64,
+  secrets_path -> Option<String>,
+  expansion_sound -> Option<String>,
   apply_patch -> bool,
   undo_backspace -> bool,
   win32_exclude_orphan_events -> bool,
@@ -43,3 +43,46 @@ pub fn attach_console() {
 pub fn attach_console() {
   // Not necessary on Linux and macOS
 }
+
+#[cfg(test)]
+pub mod tests {
+  use espanso_config::{config::ConfigStore, matches::store::MatchStore};
+  use std::path::PathBuf;
+  use tempdir::TempDir;
+
+  // A configuration loaded from a temporary directory, which is deleted
+  // once this struct is dropped
+  pub struct TestConfig {
+    pub dir: TempDir,
+    pub config_store: Box<dyn ConfigStore>,
+    pub match_store: Box<dyn MatchStore>,
+  }
+
+  impl TestConfig {
+    pub fn match_file(&self, name: &str) -> PathBuf {
+      self.dir.path().join("match").join(name)
+    }
+  }
+
+  // Loads a configuration with an empty default.yml and the given base.yml
+  pub fn load_test_config(match_yaml: &str) -> TestConfig {
+    load_test_config_with("", &[("base.yml", match_yaml)])
+  }
+
+  pub fn load_test_config_with(default_yaml: &str, match_files: &[(&str, &str)]) -> TestConfig {
+    let dir = TempDir::new("espanso-test").unwrap();
+    std::fs::create_dir_all(dir.path().join("config")).unwrap();
+    std::fs::create_dir_all(dir.path().join("match")).unwrap();
+    std::fs::write(dir.path().join("config").join("default.yml"), default_yaml).unwrap();
+    for (name, yaml) in match_files {
+      std::fs::write(dir.path().join("match").join(name), yaml).unwrap();
+    }
+
+    let (config_store, match_store, _) = espanso_config::load(dir.path()).unwrap();
+    TestConfig {
+      dir,
+      config_store,
+      match_store,
+    }
+  }
+}